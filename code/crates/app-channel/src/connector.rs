@@ -61,7 +61,9 @@ where
 
                 self.sender.send(AppMsg::ConsensusReady { reply }).await?;
 
-                consensus_ref.cast(rx.await?.into())?;
+                for msg in rx.await?.into_actor_msgs() {
+                    consensus_ref.cast(msg)?;
+                }
             }
 
             HostMsg::StartedRound {
@@ -82,6 +84,7 @@ where
                 height,
                 round,
                 timeout,
+                max_block_size,
                 reply_to,
             } => {
                 let (reply, rx) = oneshot::channel();
@@ -91,6 +94,7 @@ where
                         height,
                         round,
                         timeout,
+                        max_block_size,
                         reply,
                     })
                     .await?;
@@ -118,6 +122,16 @@ where
                 reply_to.send(rx.await?)?;
             }
 
+            HostMsg::ValidateProposal { proposal, reply_to } => {
+                let (reply, rx) = oneshot::channel();
+
+                self.sender
+                    .send(AppMsg::ValidateProposal { proposal, reply })
+                    .await?;
+
+                reply_to.send(rx.await?)?;
+            }
+
             HostMsg::VerifyVoteExtension {
                 height,
                 round,
@@ -209,7 +223,15 @@ where
                     })
                     .await?;
 
-                consensus.cast(rx.await?.into())?;
+                for msg in rx.await?.into_actor_msgs() {
+                    consensus.cast(msg)?;
+                }
+            }
+
+            HostMsg::MisbehaviorEvidence { evidence } => {
+                self.sender
+                    .send(AppMsg::MisbehaviorEvidence { evidence })
+                    .await?
             }
 
             HostMsg::GetDecidedValue { height, reply_to } => {
@@ -244,6 +266,64 @@ where
                 reply_to.send(rx.await?)?;
             }
 
+            HostMsg::ListSnapshots { reply_to } => {
+                let (reply, rx) = oneshot::channel();
+
+                self.sender.send(AppMsg::ListSnapshots { reply }).await?;
+
+                reply_to.send(rx.await?)?;
+            }
+
+            HostMsg::LoadSnapshotChunk {
+                height,
+                format,
+                index,
+                reply_to,
+            } => {
+                let (reply, rx) = oneshot::channel();
+
+                self.sender
+                    .send(AppMsg::LoadSnapshotChunk {
+                        height,
+                        format,
+                        index,
+                        reply,
+                    })
+                    .await?;
+
+                reply_to.send(rx.await?)?;
+            }
+
+            HostMsg::OfferSnapshot { snapshot, reply_to } => {
+                let (reply, rx) = oneshot::channel();
+
+                self.sender
+                    .send(AppMsg::OfferSnapshot { snapshot, reply })
+                    .await?;
+
+                reply_to.send(rx.await?)?;
+            }
+
+            HostMsg::ApplySnapshotChunk {
+                index,
+                chunk,
+                from,
+                reply_to,
+            } => {
+                let (reply, rx) = oneshot::channel();
+
+                self.sender
+                    .send(AppMsg::ApplySnapshotChunk {
+                        index,
+                        chunk,
+                        from,
+                        reply,
+                    })
+                    .await?;
+
+                reply_to.send(rx.await?)?;
+            }
+
             HostMsg::PeerJoined { peer_id } => {
                 self.sender.send(AppMsg::PeerJoined { peer_id }).await?;
             }
@@ -251,6 +331,10 @@ where
             HostMsg::PeerLeft { peer_id } => {
                 self.sender.send(AppMsg::PeerLeft { peer_id }).await?;
             }
+
+            HostMsg::SyncProgress { progress } => {
+                self.sender.send(AppMsg::SyncProgress { progress }).await?;
+            }
         };
 
         Ok(())
@@ -10,10 +10,15 @@ use malachitebft_engine::consensus::Msg as ConsensusActorMsg;
 use malachitebft_engine::network::Msg as NetworkActorMsg;
 use malachitebft_engine::util::events::TxEvent;
 
-use crate::app::types::core::{CommitCertificate, Context, Round, ValueId, VoteExtensions};
+use crate::app::types::config::ByteSize;
+use crate::app::types::core::{
+    CommitCertificate, Context, Evidence, Round, Validity, ValueId, VoteExtensions,
+};
 use crate::app::types::streaming::StreamMessage;
-use crate::app::types::sync::RawDecidedValue;
-use crate::app::types::{LocallyProposedValue, PeerId, ProposedValue};
+use crate::app::types::sync::{
+    ApplySnapshotChunkResult, OfferSnapshotResult, RawDecidedValue, Snapshot, SyncProgress,
+};
+use crate::app::types::{HeightParams, LocallyProposedValue, PeerId, ProposedValue, StateDump};
 
 pub type Reply<T> = oneshot::Sender<T>;
 
@@ -25,6 +30,8 @@ pub struct Channels<Ctx: Context> {
     pub network: mpsc::Sender<NetworkMsg<Ctx>>,
     /// Receiver of events, call `subscribe` to receive them
     pub events: TxEvent<Ctx>,
+    /// Channel for sending requests to consensus
+    pub requests: mpsc::Sender<ConsensusRequest<Ctx>>,
 }
 
 /// Messages sent from consensus to the application.
@@ -35,7 +42,8 @@ pub enum AppMsg<Ctx: Context> {
     /// The application MAY reply with a message to instruct
     /// consensus to start at a given height.
     ConsensusReady {
-        /// Channel for sending a [`ConsensusMsg::StartHeight`] message back to consensus
+        /// Channel for sending a [`ConsensusMsg::StartHeight`] or
+        /// [`ConsensusMsg::StartHeightWithSchedule`] message back to consensus
         reply: Reply<ConsensusMsg<Ctx>>,
     },
 
@@ -60,6 +68,8 @@ pub enum AppMsg<Ctx: Context> {
         round: Round,
         /// Maximum time allowed for the application to respond
         timeout: Duration,
+        /// Maximum size of the value, as per the consensus parameters in effect at that height
+        max_block_size: ByteSize,
         /// Channel for sending back the value just built to consensus
         reply: Reply<LocallyProposedValue<Ctx>>,
     },
@@ -77,6 +87,18 @@ pub enum AppMsg<Ctx: Context> {
         reply: Reply<Option<Ctx::Extension>>,
     },
 
+    /// Validate a proposal received from another validator, before its value is received.
+    ///
+    /// This lets the application reject proposals on its own grounds early, eg. a stale timestamp.
+    /// If the proposal is deemed invalid, consensus will prevote nil for it right away.
+    /// Applications which do not need this should reply with `Validity::Valid`.
+    ValidateProposal {
+        /// The proposal, whose signature has already been verified
+        proposal: Ctx::Proposal,
+        /// Channel for sending back the validity of the proposal
+        reply: Reply<Validity>,
+    },
+
     /// Verify a vote extension
     ///
     /// If the vote extension is deemed invalid, the vote it was part of
@@ -126,6 +148,10 @@ pub enum AppMsg<Ctx: Context> {
     },
 
     /// Requests the validator set for a specific height
+    ///
+    /// Consensus caches the validator sets it receives, and pre-fetches the validator set
+    /// for the next height as soon as a value is decided, unless it was already scheduled
+    /// via [`ConsensusMsg::StartHeightWithSchedule`].
     GetValidatorSet {
         /// Height of the validator set to retrieve
         height: Ctx::Height,
@@ -142,6 +168,14 @@ pub enum AppMsg<Ctx: Context> {
     ///
     /// In response to this message, the application MAY send a [`ConsensusMsg::StartHeight`]
     /// message back to consensus, instructing it to start the next height.
+    ///
+    /// If the application already knows the validator sets for the upcoming heights,
+    /// eg. because validators rotate at epoch boundaries, it SHOULD instead reply with a
+    /// [`ConsensusMsg::StartHeightWithSchedule`] message, so that consensus does not have
+    /// to request them via [`AppMsg::GetValidatorSet`] when it needs them.
+    ///
+    /// To change the consensus parameters from the next height onwards, the application
+    /// MAY instead reply with a [`ConsensusMsg::StartHeightWithParams`] message.
     Decided {
         /// The certificate for the decided value
         certificate: CommitCertificate<Ctx>,
@@ -153,6 +187,17 @@ pub enum AppMsg<Ctx: Context> {
         reply: Reply<ConsensusMsg<Ctx>>,
     },
 
+    /// Notifies the application that consensus has detected misbehavior by a validator,
+    /// ie. two conflicting votes or proposals signed by the same validator
+    /// for the same height and round.
+    ///
+    /// The evidence contains both signed messages, and can be included by the
+    /// application in a block, eg. to slash the offending validator.
+    MisbehaviorEvidence {
+        /// Evidence of the misbehavior
+        evidence: Evidence<Ctx>,
+    },
+
     /// Requests a previously decided value from the application's storage.
     ///
     /// The application MUST respond with that value if available, or `None` otherwise.
@@ -181,6 +226,62 @@ pub enum AppMsg<Ctx: Context> {
         reply: Reply<ProposedValue<Ctx>>,
     },
 
+    /// Requests the snapshots of the application state that the application can serve
+    /// to peers bootstrapping from a snapshot.
+    ///
+    /// The application MUST respond with the snapshots it has, which MAY be empty.
+    ListSnapshots {
+        /// Channel for sending back the available snapshots
+        reply: Reply<Vec<Snapshot<Ctx>>>,
+    },
+
+    /// Requests a chunk of one of the snapshots previously returned by [`AppMsg::ListSnapshots`],
+    /// on behalf of a peer which is restoring that snapshot.
+    ///
+    /// The application MUST respond with the chunk if available, or `None` otherwise.
+    LoadSnapshotChunk {
+        /// Height at which the snapshot was taken
+        height: Ctx::Height,
+        /// Format of the snapshot
+        format: u32,
+        /// Index of the chunk to load
+        index: u32,
+        /// Channel for sending back the chunk
+        reply: Reply<Option<Bytes>>,
+    },
+
+    /// Offers the application a snapshot obtained from peers, to bootstrap its state from.
+    ///
+    /// This only happens when the node starts without any decided value and snapshot sync is enabled.
+    /// The application MUST verify that the snapshot can be trusted, typically by checking its
+    /// commit certificate against the validator set it expects at that height, before accepting it.
+    ///
+    /// If accepted, its chunks are then fetched from peers and passed in order
+    /// to the application via [`AppMsg::ApplySnapshotChunk`].
+    OfferSnapshot {
+        /// The offered snapshot
+        snapshot: Snapshot<Ctx>,
+        /// Channel for accepting or rejecting the snapshot
+        reply: Reply<OfferSnapshotResult>,
+    },
+
+    /// Requests the application to apply the next chunk of the snapshot it accepted.
+    ///
+    /// Once the last chunk is applied, consensus starts at the height following the one
+    /// of the snapshot, and the decided values from there on are synced from peers.
+    /// The application MUST then be ready to process them, as well as to provide the
+    /// validator set for that height via [`AppMsg::GetValidatorSet`].
+    ApplySnapshotChunk {
+        /// Index of the chunk
+        index: u32,
+        /// Content of the chunk
+        chunk: Bytes,
+        /// Peer the chunk was received from
+        from: PeerId,
+        /// Channel for reporting the outcome of applying the chunk
+        reply: Reply<ApplySnapshotChunkResult>,
+    },
+
     /// Notifies the application that a peer has joined our local view of the network.
     ///
     /// In a gossip network, there is no guarantee that we will ever see all peers,
@@ -199,6 +300,16 @@ pub enum AppMsg<Ctx: Context> {
         /// The ID of the peer that left
         peer_id: PeerId,
     },
+
+    /// Notifies the application of the progress of the node in catching up with its peers.
+    ///
+    /// Sent periodically, as well as whenever the node catches up with its peers or falls behind them.
+    /// The application can use it to report its sync status, or eg. to only serve
+    /// requests from its clients once caught up.
+    SyncProgress {
+        /// The current sync progress
+        progress: SyncProgress<Ctx>,
+    },
 }
 
 /// Messages sent from the application to consensus.
@@ -206,29 +317,290 @@ pub enum AppMsg<Ctx: Context> {
 pub enum ConsensusMsg<Ctx: Context> {
     /// Instructs consensus to start a new height with the given validator set.
     StartHeight(Ctx::Height, Ctx::ValidatorSet),
+
+    /// Instructs consensus to start a new height with the given validator set,
+    /// and schedules the validator sets to use at upcoming heights.
+    ///
+    /// Consensus will use the scheduled validator sets when it needs them,
+    /// e.g. to verify votes for a height ahead of ours, instead of requesting
+    /// them from the application via [`AppMsg::GetValidatorSet`].
+    /// Validator sets scheduled for a height at or below the given height are ignored.
+    StartHeightWithSchedule(
+        Ctx::Height,
+        Ctx::ValidatorSet,
+        Vec<(Ctx::Height, Ctx::ValidatorSet)>,
+    ),
+
+    /// Instructs consensus to start a new height with the given validator set,
+    /// using the given consensus parameters from this height onwards.
+    ///
+    /// Useful to change the parameters at a height boundary, eg. as decided by on-chain
+    /// governance, without restarting the nodes. All the parameters take effect together
+    /// when the height starts. The durations of the timeouts are then still computed
+    /// by the timeout strategy of the node, with the given timeouts as a base.
+    ///
+    /// The parameters are ignored if consensus has already started this height.
+    StartHeightWithParams(Ctx::Height, Ctx::ValidatorSet, HeightParams<Ctx>),
 }
 
-impl<Ctx: Context> From<ConsensusMsg<Ctx>> for ConsensusActorMsg<Ctx> {
-    fn from(msg: ConsensusMsg<Ctx>) -> ConsensusActorMsg<Ctx> {
-        match msg {
+impl<Ctx: Context> ConsensusMsg<Ctx> {
+    /// Convert this message into the messages to send to the consensus actor, in order.
+    pub(crate) fn into_actor_msgs(self) -> Vec<ConsensusActorMsg<Ctx>> {
+        match self {
             ConsensusMsg::StartHeight(height, validator_set) => {
-                ConsensusActorMsg::StartHeight(height, validator_set)
+                vec![ConsensusActorMsg::StartHeight(height, validator_set)]
+            }
+            ConsensusMsg::StartHeightWithSchedule(height, validator_set, schedule) => {
+                // Schedule the upcoming validator sets before starting the height,
+                // so that they are available as soon as the height starts.
+                schedule
+                    .into_iter()
+                    .filter(|(next_height, _)| *next_height > height)
+                    .map(|(next_height, validator_set)| {
+                        ConsensusActorMsg::ScheduleValidatorSet(next_height, validator_set)
+                    })
+                    .chain([ConsensusActorMsg::StartHeight(height, validator_set)])
+                    .collect()
+            }
+            ConsensusMsg::StartHeightWithParams(height, validator_set, params) => {
+                vec![
+                    ConsensusActorMsg::ScheduleParams(height, params),
+                    ConsensusActorMsg::StartHeight(height, validator_set),
+                ]
             }
         }
     }
 }
 
+/// Requests sent from the application to consensus.
+#[derive_where(Debug)]
+pub enum ConsensusRequest<Ctx: Context> {
+    /// Requests a snapshot of the state of consensus at the current height,
+    /// including the current round and step, the locked and valid values,
+    /// the proposer and the votes received for each round.
+    ///
+    /// Useful to expose to operators for debugging stuck rounds.
+    DumpState(Reply<StateDump<Ctx>>),
+}
+
 /// Messages sent from the application to the networking layer.
 #[derive_where(Debug)]
 pub enum NetworkMsg<Ctx: Context> {
     /// Publish a proposal part to the network, within a stream.
     PublishProposalPart(StreamMessage<Ctx::ProposalPart>),
+
+    /// Replace the peers allowed to connect to this node, eg. with the peers of
+    /// the validators at the current height. Ignored if the allow-list is disabled.
+    UpdateAllowedPeers(Vec<PeerId>),
 }
 
 impl<Ctx: Context> From<NetworkMsg<Ctx>> for NetworkActorMsg<Ctx> {
     fn from(msg: NetworkMsg<Ctx>) -> NetworkActorMsg<Ctx> {
         match msg {
             NetworkMsg::PublishProposalPart(part) => NetworkActorMsg::PublishProposalPart(part),
+            NetworkMsg::UpdateAllowedPeers(peers) => NetworkActorMsg::UpdateAllowedPeers(peers),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ractor::{Actor, ActorProcessingErr, ActorRef};
+
+    use malachitebft_engine::consensus::{Consensus, ConsensusParams};
+    use malachitebft_engine::host::HostMsg;
+    use malachitebft_engine::util::timeouts::LinearTimeouts;
+    use malachitebft_engine::wal::Msg as WalMsg;
+    use malachitebft_test::utils::validators::make_validators;
+    use malachitebft_test::{Ed25519Provider, Height, TestContext, ValidatorSet};
+
+    use crate::app::types::config::TimeoutConfig;
+    use crate::app::types::metrics::Metrics;
+    use crate::app::types::ValuePayload;
+
+    use super::*;
+
+    /// Reports the timeout and maximum size of the values it is asked to build
+    struct Host(mpsc::UnboundedSender<(Duration, ByteSize)>);
+
+    #[ractor::async_trait]
+    impl Actor for Host {
+        type Msg = HostMsg<TestContext>;
+        type State = ();
+        type Arguments = ();
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            _args: (),
+        ) -> Result<(), ActorProcessingErr> {
+            Ok(())
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            msg: Self::Msg,
+            _state: &mut (),
+        ) -> Result<(), ActorProcessingErr> {
+            if let HostMsg::GetValue {
+                timeout,
+                max_block_size,
+                ..
+            } = msg
+            {
+                let _ = self.0.send((timeout, max_block_size));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Drops all messages
+    struct Network;
+
+    #[ractor::async_trait]
+    impl Actor for Network {
+        type Msg = NetworkActorMsg<TestContext>;
+        type State = ();
+        type Arguments = ();
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            _args: (),
+        ) -> Result<(), ActorProcessingErr> {
+            Ok(())
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            _msg: Self::Msg,
+            _state: &mut (),
+        ) -> Result<(), ActorProcessingErr> {
+            Ok(())
+        }
+    }
+
+    /// Has nothing to replay, and does not persist anything
+    struct Wal;
+
+    #[ractor::async_trait]
+    impl Actor for Wal {
+        type Msg = WalMsg<TestContext>;
+        type State = ();
+        type Arguments = ();
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            _args: (),
+        ) -> Result<(), ActorProcessingErr> {
+            Ok(())
         }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            msg: Self::Msg,
+            _state: &mut (),
+        ) -> Result<(), ActorProcessingErr> {
+            match msg {
+                WalMsg::StartedHeight(_, reply) => {
+                    let _ = reply.send(Ok(None));
+                }
+                WalMsg::Append(_, _, reply) => {
+                    let _ = reply.send(Ok(()));
+                }
+                WalMsg::Flush(reply) => {
+                    let _ = reply.send(Ok(()));
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn start_height_with_params() {
+        let [(validator, private_key)] = make_validators([1]);
+        let validator_set = ValidatorSet::new([validator.clone()]);
+
+        let (tx_get_value, mut rx_get_value) = mpsc::unbounded_channel();
+        let (host, _) = Host::spawn(None, Host(tx_get_value), ()).await.unwrap();
+        let (network, _) = Network::spawn(None, Network, ()).await.unwrap();
+        let (wal, _) = Wal::spawn(None, Wal, ()).await.unwrap();
+
+        // The parameters from the node configuration
+        let initial_params = HeightParams {
+            timeouts: TimeoutConfig::default(),
+            max_block_size: ByteSize::mib(1),
+            vote_extensions_enable_height: None,
+            threshold_params: Default::default(),
+        };
+
+        let consensus_params = ConsensusParams {
+            initial_height: Height::new(1),
+            initial_validator_set: validator_set.clone(),
+            address: validator.address,
+            threshold_params: initial_params.threshold_params,
+            value_payload: ValuePayload::ProposalAndParts,
+            synchrony: None,
+            vote_extensions_enable_height: None,
+        };
+
+        let sign_watermark_path = std::env::temp_dir().join(format!(
+            "malachitebft-app-channel-sign-state-{}",
+            std::process::id()
+        ));
+
+        let consensus = Consensus::spawn(
+            TestContext::new(),
+            consensus_params,
+            initial_params,
+            Box::new(LinearTimeouts),
+            Box::new(Ed25519Provider::new(private_key)),
+            sign_watermark_path,
+            network,
+            host,
+            wal,
+            None,
+            Metrics::new(),
+            TxEvent::new(),
+            tracing::Span::none(),
+        )
+        .await
+        .unwrap();
+
+        // The parameters provided by the application when starting the height
+        let params = HeightParams {
+            timeouts: TimeoutConfig {
+                timeout_propose: Duration::from_secs(7),
+                ..TimeoutConfig::default()
+            },
+            max_block_size: ByteSize::kib(10),
+            vote_extensions_enable_height: None,
+            threshold_params: Default::default(),
+        };
+
+        let msg = ConsensusMsg::StartHeightWithParams(Height::new(1), validator_set, params);
+
+        for msg in msg.into_actor_msgs() {
+            consensus.cast(msg).unwrap();
+        }
+
+        // As the only validator, we are asked to build the value to propose,
+        // within the propose timeout and the maximum size of the new parameters
+        let (timeout, max_block_size) =
+            tokio::time::timeout(Duration::from_secs(5), rx_get_value.recv())
+                .await
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(timeout, Duration::from_secs(7));
+        assert_eq!(max_block_size, ByteSize::kib(10));
+
+        consensus.stop(None);
     }
 }
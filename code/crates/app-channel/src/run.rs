@@ -7,14 +7,14 @@ use malachitebft_engine::util::events::TxEvent;
 
 use crate::app;
 use crate::app::spawn::{
-    spawn_consensus_actor, spawn_node_actor, spawn_sync_actor, spawn_wal_actor,
+    spawn_consensus_actor, spawn_node_actor, spawn_rpc_server, spawn_sync_actor, spawn_wal_actor,
 };
 use crate::app::types::codec::{ConsensusCodec, SyncCodec, WalCodec};
 use crate::app::types::config::Config as NodeConfig;
 use crate::app::types::core::Context;
 use crate::app::types::metrics::{Metrics, SharedRegistry};
 use crate::app::EngineHandle;
-use crate::spawn::{spawn_host_actor, spawn_network_actor};
+use crate::spawn::{spawn_consensus_request_handler, spawn_host_actor, spawn_network_actor};
 use crate::Channels;
 
 #[tracing::instrument("node", skip_all, fields(moniker = %cfg.moniker))]
@@ -46,24 +46,41 @@ where
     let signing_provider = node.get_signing_provider(private_key);
 
     // Spawn consensus gossip
-    let (network, tx_network) =
-        spawn_network_actor(&cfg, keypair, &registry, codec.clone()).await?;
+    let (network, tx_network) = spawn_network_actor(
+        &cfg,
+        &node.get_home_dir(),
+        keypair,
+        &registry,
+        codec.clone(),
+        node.get_message_validator(),
+    )
+    .await?;
 
-    let wal = spawn_wal_actor(&ctx, codec, &node.get_home_dir(), &registry).await?;
+    let wal = spawn_wal_actor(
+        &ctx,
+        codec,
+        &node.get_home_dir(),
+        &cfg.consensus.wal,
+        &registry,
+    )
+    .await?;
 
     // Spawn the host actor
     let (connector, rx_consensus) = spawn_host_actor(metrics.clone()).await?;
 
+    let tx_event = TxEvent::new();
+
     let sync = spawn_sync_actor(
         ctx.clone(),
         network.clone(),
         connector.clone(),
         &cfg.sync,
         &registry,
+        tx_event.clone(),
     )
     .await?;
 
-    let tx_event = TxEvent::new();
+    let rpc_config = cfg.rpc.clone();
 
     // Spawn consensus
     let consensus = spawn_consensus_actor(
@@ -72,7 +89,9 @@ where
         address,
         ctx.clone(),
         cfg,
+        &node.get_home_dir(),
         Box::new(signing_provider),
+        node.get_timeout_strategy(),
         network.clone(),
         connector.clone(),
         wal.clone(),
@@ -82,12 +101,22 @@ where
     )
     .await?;
 
+    let tx_requests = spawn_consensus_request_handler(consensus.clone());
+
+    spawn_rpc_server(
+        &rpc_config,
+        consensus.clone(),
+        network.clone(),
+        tx_event.clone(),
+    );
+
     let (node, handle) = spawn_node_actor(ctx, network, consensus, wal, sync, connector).await?;
 
     let channels = Channels {
         consensus: rx_consensus,
         network: tx_network,
         events: tx_event,
+        requests: tx_requests,
     };
 
     let handle = EngineHandle {
@@ -1,5 +1,7 @@
 //! Utility functions for spawning the actor system and connecting it to the application.
 
+use std::path::Path;
+
 use eyre::Result;
 use tokio::sync::mpsc;
 
@@ -7,15 +9,15 @@ use malachitebft_app as app;
 use malachitebft_app::types::metrics::SharedRegistry;
 use malachitebft_app::types::Keypair;
 use malachitebft_config::Config as NodeConfig;
-use malachitebft_engine::consensus::ConsensusCodec;
+use malachitebft_engine::consensus::{ConsensusCodec, ConsensusRef, Msg as ConsensusActorMsg};
 use malachitebft_engine::host::HostRef;
-use malachitebft_engine::network::NetworkRef;
+use malachitebft_engine::network::{MessageValidator, NetworkRef};
 use malachitebft_engine::sync::SyncCodec;
 
 use crate::app::types::core::Context;
 use crate::app::types::metrics::Metrics;
 use crate::connector::Connector;
-use crate::{AppMsg, NetworkMsg};
+use crate::{AppMsg, ConsensusRequest, NetworkMsg};
 
 pub async fn spawn_host_actor<Ctx>(
     metrics: Metrics,
@@ -30,9 +32,11 @@ where
 
 pub async fn spawn_network_actor<Ctx, Codec>(
     cfg: &NodeConfig,
+    home_dir: &Path,
     keypair: Keypair,
     registry: &SharedRegistry,
     codec: Codec,
+    validator: Box<dyn MessageValidator<Ctx>>,
 ) -> Result<(NetworkRef<Ctx>, mpsc::Sender<NetworkMsg<Ctx>>)>
 where
     Ctx: Context,
@@ -41,7 +45,8 @@ where
 {
     let (tx, mut rx) = mpsc::channel::<NetworkMsg<Ctx>>(1);
 
-    let actor_ref = app::spawn::spawn_network_actor(cfg, keypair, registry, codec).await?;
+    let actor_ref =
+        app::spawn::spawn_network_actor(cfg, home_dir, keypair, registry, codec, validator).await?;
 
     tokio::spawn({
         let actor_ref = actor_ref.clone();
@@ -56,3 +61,31 @@ where
 
     Ok((actor_ref, tx))
 }
+
+pub fn spawn_consensus_request_handler<Ctx>(
+    consensus: ConsensusRef<Ctx>,
+) -> mpsc::Sender<ConsensusRequest<Ctx>>
+where
+    Ctx: Context,
+{
+    let (tx, mut rx) = mpsc::channel::<ConsensusRequest<Ctx>>(16);
+
+    tokio::spawn(async move {
+        while let Some(request) = rx.recv().await {
+            match request {
+                ConsensusRequest::DumpState(reply) => {
+                    match ractor::call!(consensus, ConsensusActorMsg::DumpState) {
+                        Ok(dump) => {
+                            let _ = reply.send(dump);
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to dump consensus state: {e}");
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    tx
+}
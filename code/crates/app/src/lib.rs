@@ -12,6 +12,7 @@ mod node;
 pub use node::{EngineHandle, Node, NodeHandle};
 
 pub mod part_store;
+pub mod rpc;
 pub mod spawn;
 pub mod types;
 
@@ -27,6 +28,10 @@ pub mod consensus {
     pub use malachitebft_core_consensus::*;
 }
 
+pub mod wal {
+    pub use malachitebft_engine::wal::{WalCodec, WalEntry};
+}
+
 pub mod metrics {
     pub use malachitebft_metrics::*;
 }
@@ -8,8 +8,10 @@ use serde::Serialize;
 use tokio::task::JoinHandle;
 
 use malachitebft_core_types::SigningProvider;
+use malachitebft_engine::network::{AcceptAll, MessageValidator};
 use malachitebft_engine::node::NodeRef;
 use malachitebft_engine::util::events::RxEvent;
+use malachitebft_engine::util::timeouts::{LinearTimeouts, TimeoutStrategy};
 
 use crate::types::core::{Context, PrivateKey, PublicKey, VotingPower};
 use crate::types::Keypair;
@@ -60,6 +62,20 @@ pub trait Node {
     fn get_signing_provider(&self, private_key: PrivateKey<Self::Context>)
         -> Self::SigningProvider;
 
+    /// Validator of the messages received over gossip, before they are delivered
+    /// to consensus and forwarded to other peers. Accepts all the messages by default,
+    /// leaving it to consensus to verify the signatures of votes and proposals.
+    fn get_message_validator(&self) -> Box<dyn MessageValidator<Self::Context>> {
+        Box::new(AcceptAll)
+    }
+
+    /// Strategy for computing the duration of the consensus timeouts, eg. adapting them
+    /// to the observed latency of proposals. Increases the configured timeouts linearly
+    /// with the round by default.
+    fn get_timeout_strategy(&self) -> Box<dyn TimeoutStrategy> {
+        Box::new(LinearTimeouts)
+    }
+
     fn load_genesis(&self) -> io::Result<Self::Genesis>;
 
     fn make_genesis(
@@ -0,0 +1,493 @@
+//! HTTP server exposing the status of the node as JSON, for operators and tooling.
+//!
+//! The following endpoints are served:
+//! - `/health`: whether consensus and the network are up and running
+//! - `/net_info`: the peers we are connected to
+//! - `/status`: the current height and round, the sync progress and the latest commit
+//! - `/consensus_state`: the state of consensus at the current height, including the votes per round
+//! - `/commit`: the commit certificate of the latest decided value
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use derive_where::derive_where;
+use serde_json::{json, Value as JsonValue};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use malachitebft_core_consensus::{RoundValue, StateDump, VoteTally};
+use malachitebft_core_types::{
+    CertificateSignature, CommitCertificate, Context, NilOrVal, Validator, ValidatorSet, ValueId,
+};
+use malachitebft_engine::consensus::{ConsensusRef, Msg as ConsensusMsg};
+use malachitebft_engine::network::{Msg as NetworkMsg, NetworkRef};
+use malachitebft_engine::util::events::{Event, TxEvent};
+use malachitebft_sync::SyncProgress;
+
+/// How long to wait for consensus or the network to answer, before reporting them as unavailable
+const CALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What the server learns about the node from its events
+#[derive_where(Default)]
+struct Latest<Ctx: Context> {
+    /// The commit certificate of the latest decided value
+    certificate: Option<CommitCertificate<Ctx>>,
+
+    /// The latest sync progress reported
+    sync: Option<SyncProgress<Ctx>>,
+}
+
+#[derive_where(Clone)]
+struct RpcState<Ctx: Context> {
+    consensus: ConsensusRef<Ctx>,
+    network: NetworkRef<Ctx>,
+    latest: Arc<RwLock<Latest<Ctx>>>,
+}
+
+#[tracing::instrument(name = "rpc", skip_all)]
+pub async fn serve<Ctx>(
+    listen_addr: SocketAddr,
+    consensus: ConsensusRef<Ctx>,
+    network: NetworkRef<Ctx>,
+    tx_event: TxEvent<Ctx>,
+) where
+    Ctx: Context,
+{
+    if let Err(e) = inner(listen_addr, consensus, network, tx_event).await {
+        error!("RPC server failed: {e}");
+    }
+}
+
+async fn inner<Ctx>(
+    listen_addr: SocketAddr,
+    consensus: ConsensusRef<Ctx>,
+    network: NetworkRef<Ctx>,
+    tx_event: TxEvent<Ctx>,
+) -> io::Result<()>
+where
+    Ctx: Context,
+{
+    let latest = Arc::new(RwLock::new(Latest::default()));
+
+    tokio::spawn(track_events(tx_event, Arc::clone(&latest)));
+
+    let state = RpcState {
+        consensus,
+        network,
+        latest,
+    };
+
+    let listener = TcpListener::bind(listen_addr).await?;
+    let local_addr = listener.local_addr()?;
+
+    info!(address = %local_addr, "Serving RPC");
+    axum::serve(listener, router(state)).await?;
+
+    Ok(())
+}
+
+fn router<Ctx: Context>(state: RpcState<Ctx>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/net_info", get(net_info))
+        .route("/status", get(status))
+        .route("/consensus_state", get(consensus_state))
+        .route("/commit", get(commit))
+        .with_state(state)
+}
+
+async fn track_events<Ctx: Context>(tx_event: TxEvent<Ctx>, latest: Arc<RwLock<Latest<Ctx>>>) {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut rx_event = tx_event.subscribe();
+
+    loop {
+        let event = match rx_event.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+
+        let mut latest = latest.write().expect("lock is poisoned");
+
+        match event {
+            Event::Decided(certificate) => latest.certificate = Some(certificate),
+            Event::SyncProgress(progress) => latest.sync = Some(progress),
+            _ => {}
+        }
+    }
+}
+
+fn unavailable(e: impl ToString) -> Response {
+    let body = json!({ "error": e.to_string() });
+    (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response()
+}
+
+async fn dump_state<Ctx: Context>(state: &RpcState<Ctx>) -> Result<StateDump<Ctx>, Response> {
+    ractor::call_t!(
+        state.consensus,
+        ConsensusMsg::DumpState,
+        CALL_TIMEOUT.as_millis() as u64
+    )
+    .map_err(unavailable)
+}
+
+async fn get_peers<Ctx: Context>(state: &RpcState<Ctx>) -> Result<Vec<String>, Response> {
+    let peers = ractor::call_t!(
+        state.network,
+        |reply| NetworkMsg::GetPeers { reply },
+        CALL_TIMEOUT.as_millis() as u64
+    )
+    .map_err(unavailable)?;
+
+    Ok(peers.iter().map(ToString::to_string).collect())
+}
+
+async fn health<Ctx: Context>(State(state): State<RpcState<Ctx>>) -> Response {
+    if let Err(response) = dump_state(&state).await {
+        return response;
+    }
+
+    if let Err(response) = get_peers(&state).await {
+        return response;
+    }
+
+    Json(json!({ "status": "ok" })).into_response()
+}
+
+async fn net_info<Ctx: Context>(State(state): State<RpcState<Ctx>>) -> Response {
+    match get_peers(&state).await {
+        Ok(peers) => Json(json!({ "n_peers": peers.len(), "peers": peers })).into_response(),
+        Err(response) => response,
+    }
+}
+
+async fn status<Ctx: Context>(State(state): State<RpcState<Ctx>>) -> Response {
+    let dump = match dump_state(&state).await {
+        Ok(dump) => dump,
+        Err(response) => return response,
+    };
+
+    let latest = state.latest.read().expect("lock is poisoned");
+
+    Json(json!({
+        "height": dump.height.to_string(),
+        "round": dump.round.as_i64(),
+        "step": format!("{:?}", dump.step),
+        "sync": latest.sync.as_ref().map(sync_json),
+        "latest_commit": latest.certificate.as_ref().map(certificate_json),
+    }))
+    .into_response()
+}
+
+async fn consensus_state<Ctx: Context>(State(state): State<RpcState<Ctx>>) -> Response {
+    match dump_state(&state).await {
+        Ok(dump) => Json(state_dump_json(&dump)).into_response(),
+        Err(response) => response,
+    }
+}
+
+async fn commit<Ctx: Context>(State(state): State<RpcState<Ctx>>) -> Response {
+    let latest = state.latest.read().expect("lock is poisoned");
+
+    match &latest.certificate {
+        Some(certificate) => Json(certificate_json(certificate)).into_response(),
+        None => {
+            let body = json!({ "error": "No value decided yet" });
+            (StatusCode::NOT_FOUND, Json(body)).into_response()
+        }
+    }
+}
+
+fn sync_json<Ctx: Context>(progress: &SyncProgress<Ctx>) -> JsonValue {
+    json!({
+        "tip_height": progress.tip_height.to_string(),
+        "sync_height": progress.sync_height.to_string(),
+        "peers_tip_height": progress.peers_tip_height.map(|height| height.to_string()),
+        "eta_secs": progress.eta.map(|eta| eta.as_secs_f64()),
+        "caught_up": progress.caught_up,
+    })
+}
+
+fn certificate_json<Ctx: Context>(certificate: &CommitCertificate<Ctx>) -> JsonValue {
+    json!({
+        "height": certificate.height.to_string(),
+        "round": certificate.round.as_i64(),
+        "value_id": certificate.value_id.to_string(),
+        "signers_count": certificate.aggregated_signature.signers_count(),
+    })
+}
+
+fn state_dump_json<Ctx: Context>(dump: &StateDump<Ctx>) -> JsonValue {
+    let round_value_json = |round_value: &RoundValue<ValueId<Ctx>>| {
+        json!({
+            "value_id": round_value.value.to_string(),
+            "round": round_value.round.as_i64(),
+        })
+    };
+
+    let validators = (0..dump.validator_set.count())
+        .filter_map(|index| dump.validator_set.get_by_index(index))
+        .map(|validator| {
+            json!({
+                "address": validator.address().to_string(),
+                "voting_power": validator.voting_power(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let votes = dump
+        .votes
+        .iter()
+        .map(|votes| {
+            json!({
+                "round": votes.round.as_i64(),
+                "prevotes": tally_json(&votes.prevotes),
+                "precommits": tally_json(&votes.precommits),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "height": dump.height.to_string(),
+        "round": dump.round.as_i64(),
+        "step": format!("{:?}", dump.step),
+        "proposer": dump.proposer.as_ref().map(ToString::to_string),
+        "locked": dump.locked.as_ref().map(round_value_json),
+        "valid": dump.valid.as_ref().map(round_value_json),
+        "validator_set": {
+            "total_voting_power": dump.validator_set.total_voting_power(),
+            "validators": validators,
+        },
+        "votes": votes,
+    })
+}
+
+fn tally_json<Ctx: Context>(tally: &VoteTally<Ctx>) -> JsonValue {
+    let weights = tally
+        .weights
+        .iter()
+        .map(|(value, weight)| {
+            let value_id = match value {
+                NilOrVal::Nil => None,
+                NilOrVal::Val(value_id) => Some(value_id.to_string()),
+            };
+
+            json!({ "value_id": value_id, "voting_power": weight })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "total_voting_power": tally.total(),
+        "weights": weights,
+        "voters": tally.voters.iter().map(ToString::to_string).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
+    use tower::ServiceExt;
+
+    use malachitebft_core_consensus::Step;
+    use malachitebft_core_types::{AggregatedSignature, Round};
+    use malachitebft_peer::PeerId;
+    use malachitebft_test::utils::validators::make_validators;
+    use malachitebft_test::{Height, TestContext, ValidatorSet, ValueId};
+
+    /// Stands in for the consensus actor, answering with the given state dump, if any
+    struct Consensus(Option<StateDump<TestContext>>);
+
+    #[async_trait]
+    impl Actor for Consensus {
+        type Msg = ConsensusMsg<TestContext>;
+        type State = Vec<RpcReplyPort<StateDump<TestContext>>>;
+        type Arguments = ();
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            _args: (),
+        ) -> Result<Self::State, ActorProcessingErr> {
+            Ok(Vec::new())
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            msg: Self::Msg,
+            unanswered: &mut Self::State,
+        ) -> Result<(), ActorProcessingErr> {
+            if let ConsensusMsg::DumpState(reply) = msg {
+                match &self.0 {
+                    Some(dump) => reply.send(dump.clone())?,
+                    None => unanswered.push(reply),
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Stands in for the network actor, connected to the given peers
+    struct Network(Vec<PeerId>);
+
+    #[async_trait]
+    impl Actor for Network {
+        type Msg = NetworkMsg<TestContext>;
+        type State = ();
+        type Arguments = ();
+
+        async fn pre_start(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            _args: (),
+        ) -> Result<(), ActorProcessingErr> {
+            Ok(())
+        }
+
+        async fn handle(
+            &self,
+            _myself: ActorRef<Self::Msg>,
+            msg: Self::Msg,
+            _state: &mut (),
+        ) -> Result<(), ActorProcessingErr> {
+            if let NetworkMsg::GetPeers { reply } = msg {
+                reply.send(self.0.clone())?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn state_dump() -> StateDump<TestContext> {
+        let validators = make_validators([1, 2, 3]);
+        let validator_set = ValidatorSet::new(validators.iter().map(|(v, _)| v.clone()));
+
+        StateDump {
+            height: Height::new(3),
+            round: Round::new(1),
+            step: Step::Prevote,
+            proposer: Some(validators[0].0.address),
+            locked: None,
+            valid: None,
+            validator_set,
+            votes: Vec::new(),
+        }
+    }
+
+    fn certificate() -> CommitCertificate<TestContext> {
+        CommitCertificate {
+            height: Height::new(2),
+            round: Round::new(0),
+            value_id: ValueId::new(42),
+            aggregated_signature: AggregatedSignature::new(Vec::new()),
+        }
+    }
+
+    async fn rpc_state(
+        dump: Option<StateDump<TestContext>>,
+        latest: Latest<TestContext>,
+    ) -> RpcState<TestContext> {
+        let (consensus, _) = Actor::spawn(None, Consensus(dump), ()).await.unwrap();
+        let peers = vec![PeerId::random(), PeerId::random()];
+        let (network, _) = Actor::spawn(None, Network(peers), ()).await.unwrap();
+
+        RpcState {
+            consensus,
+            network,
+            latest: Arc::new(RwLock::new(latest)),
+        }
+    }
+
+    async fn get(state: &RpcState<TestContext>, path: &str) -> (StatusCode, JsonValue) {
+        let request = Request::get(path).body(Body::empty()).unwrap();
+        let response = router(state.clone()).oneshot(request).await.unwrap();
+
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn health_and_net_info() {
+        let state = rpc_state(Some(state_dump()), Latest::default()).await;
+
+        let (status, body) = get(&state, "/health").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+
+        let (status, body) = get(&state, "/net_info").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["n_peers"], 2);
+        assert_eq!(body["peers"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn status_and_consensus_state() {
+        let latest = Latest {
+            certificate: Some(certificate()),
+            sync: None,
+        };
+
+        let state = rpc_state(Some(state_dump()), latest).await;
+
+        let (status, body) = get(&state, "/status").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["height"], "3");
+        assert_eq!(body["round"], 1);
+        assert_eq!(body["latest_commit"]["height"], "2");
+        assert_eq!(body["sync"], JsonValue::Null);
+
+        let (status, body) = get(&state, "/consensus_state").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["step"], "Prevote");
+        assert_eq!(body["validator_set"]["total_voting_power"], 6);
+        assert_eq!(
+            body["validator_set"]["validators"]
+                .as_array()
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn latest_commit() {
+        let state = rpc_state(Some(state_dump()), Latest::default()).await;
+
+        let (status, _) = get(&state, "/commit").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        state.latest.write().unwrap().certificate = Some(certificate());
+
+        let (status, body) = get(&state, "/commit").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["height"], "2");
+        assert_eq!(body["signers_count"], 0);
+    }
+
+    #[tokio::test]
+    async fn unresponsive_consensus_is_unavailable() {
+        let state = rpc_state(None, Latest::default()).await;
+
+        let (status, _) = get(&state, "/health").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        let (status, _) = get(&state, "/status").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -3,21 +3,30 @@
 use std::path::Path;
 use std::time::Duration;
 
-use eyre::Result;
+use eyre::{bail, Result};
 use tokio::task::JoinHandle;
 use tracing::Span;
 
-use malachitebft_engine::consensus::{Consensus, ConsensusCodec, ConsensusParams, ConsensusRef};
+use malachitebft_engine::consensus::{
+    Consensus, ConsensusCodec, ConsensusParams, ConsensusRef, HeightParams,
+};
 use malachitebft_engine::host::HostRef;
-use malachitebft_engine::network::{Network, NetworkRef};
+use malachitebft_engine::network::{MessageValidator, Network, NetworkRef};
 use malachitebft_engine::node::{Node, NodeRef};
 use malachitebft_engine::sync::{Params as SyncParams, Sync, SyncCodec, SyncRef};
 use malachitebft_engine::util::events::TxEvent;
+use malachitebft_engine::util::sign_watermark::SignWatermark;
+use malachitebft_engine::util::timeouts::TimeoutStrategy;
 use malachitebft_engine::wal::{Wal, WalCodec, WalRef};
-use malachitebft_network::{Config as NetworkConfig, DiscoveryConfig, GossipSubConfig, Keypair};
+use malachitebft_network::{
+    Config as NetworkConfig, DiscoveryConfig, GossipSubConfig, Keypair, PeerScoringConfig,
+};
 
-use crate::types::config::{Config as NodeConfig, PubSubProtocol, SyncConfig, TransportProtocol};
-use crate::types::core::{Context, SigningProvider};
+use crate::rpc;
+use crate::types::config::{
+    Config as NodeConfig, PubSubProtocol, RpcConfig, SyncConfig, TransportProtocol, WalConfig,
+};
+use crate::types::core::{Context, SigningProvider, SynchronyParams};
 use crate::types::metrics::{Metrics, SharedRegistry};
 use crate::types::sync;
 use crate::types::ValuePayload;
@@ -50,20 +59,29 @@ where
 
 pub async fn spawn_network_actor<Ctx, Codec>(
     cfg: &NodeConfig,
+    home_dir: &Path,
     keypair: Keypair,
     registry: &SharedRegistry,
     codec: Codec,
+    validator: Box<dyn MessageValidator<Ctx>>,
 ) -> Result<NetworkRef<Ctx>>
 where
     Ctx: Context,
     Codec: ConsensusCodec<Ctx>,
     Codec: SyncCodec<Ctx>,
 {
-    let config = make_gossip_config(cfg);
+    let config = make_gossip_config(cfg, home_dir);
 
-    Network::spawn(keypair, config, registry.clone(), codec, Span::current())
-        .await
-        .map_err(Into::into)
+    Network::spawn(
+        keypair,
+        config,
+        registry.clone(),
+        codec,
+        validator,
+        Span::current(),
+    )
+    .await
+    .map_err(Into::into)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -73,7 +91,9 @@ pub async fn spawn_consensus_actor<Ctx>(
     address: Ctx::Address,
     ctx: Ctx,
     cfg: NodeConfig,
+    home_dir: &Path,
     signing_provider: Box<dyn SigningProvider<Ctx>>,
+    timeout_strategy: Box<dyn TimeoutStrategy>,
     network: NetworkRef<Ctx>,
     host: HostRef<Ctx>,
     wal: WalRef<Ctx>,
@@ -92,19 +112,45 @@ where
         config::ValuePayload::ProposalAndParts => ValuePayload::ProposalAndParts,
     };
 
+    // The timeliness of a proposal is checked when receiving its proposal message,
+    // which is never sent when values are only carried by proposal parts
+    if cfg.consensus.synchrony.is_some() && !value_payload.include_proposal() {
+        bail!(
+            "Proposer-based timestamps (`consensus.synchrony`) are not supported \
+             with the `parts-only` value payload"
+        );
+    }
+
+    // In effect until the application provides new ones when starting a height.
+    // Like the value payload, the maximum block size and whether vote extensions are enabled
+    // are taken from the `test` section of the configuration.
+    let height_params = HeightParams {
+        timeouts: cfg.consensus.timeouts,
+        max_block_size: cfg.test.max_block_size,
+        vote_extensions_enable_height: cfg.test.vote_extensions.enabled.then_some(initial_height),
+        threshold_params: Default::default(),
+    };
+
     let consensus_params = ConsensusParams {
         initial_height,
         initial_validator_set,
         address,
-        threshold_params: Default::default(),
+        threshold_params: height_params.threshold_params,
         value_payload,
+        synchrony: cfg.consensus.synchrony.map(|synchrony| SynchronyParams {
+            precision: synchrony.precision,
+            message_delay: synchrony.message_delay,
+        }),
+        vote_extensions_enable_height: height_params.vote_extensions_enable_height,
     };
 
     Consensus::spawn(
         ctx,
         consensus_params,
-        cfg.consensus.timeouts,
+        height_params,
+        timeout_strategy,
         signing_provider,
+        SignWatermark::path_in(home_dir),
         network,
         host,
         wal,
@@ -121,6 +167,7 @@ pub async fn spawn_wal_actor<Ctx, Codec>(
     ctx: &Ctx,
     codec: Codec,
     home_dir: &Path,
+    cfg: &WalConfig,
     registry: &SharedRegistry,
 ) -> Result<WalRef<Ctx>>
 where
@@ -132,9 +179,29 @@ where
 
     let wal_file = wal_dir.join("consensus.wal");
 
-    Wal::spawn(ctx, codec, wal_file, registry.clone(), Span::current())
-        .await
-        .map_err(Into::into)
+    Wal::spawn(
+        ctx,
+        codec,
+        wal_file,
+        *cfg,
+        registry.clone(),
+        Span::current(),
+    )
+    .await
+    .map_err(Into::into)
+}
+
+pub fn spawn_rpc_server<Ctx>(
+    config: &RpcConfig,
+    consensus: ConsensusRef<Ctx>,
+    network: NetworkRef<Ctx>,
+    tx_event: TxEvent<Ctx>,
+) where
+    Ctx: Context,
+{
+    if config.enabled {
+        tokio::spawn(rpc::serve(config.listen_addr, consensus, network, tx_event));
+    }
 }
 
 pub async fn spawn_sync_actor<Ctx>(
@@ -143,6 +210,7 @@ pub async fn spawn_sync_actor<Ctx>(
     host: HostRef<Ctx>,
     config: &SyncConfig,
     registry: &SharedRegistry,
+    tx_event: TxEvent<Ctx>,
 ) -> Result<Option<SyncRef<Ctx>>>
 where
     Ctx: Context,
@@ -154,16 +222,31 @@ where
     let params = SyncParams {
         status_update_interval: config.status_update_interval,
         request_timeout: config.request_timeout,
+        batch_size: config.batch_size,
+        parallel_requests: config.parallel_requests,
+        max_response_size: config.max_response_size.as_u64(),
+        peer_ban_duration: config.peer_ban_duration,
+        snapshot_sync: config.snapshot_sync,
+        snapshot_min_peers: config.snapshot_min_peers,
     };
 
     let metrics = sync::Metrics::register(registry);
 
-    let actor_ref = Sync::spawn(ctx, network, host, params, metrics, Span::current()).await?;
+    let actor_ref = Sync::spawn(
+        ctx,
+        network,
+        host,
+        params,
+        metrics,
+        tx_event,
+        Span::current(),
+    )
+    .await?;
 
     Ok(Some(actor_ref))
 }
 
-fn make_gossip_config(cfg: &NodeConfig) -> NetworkConfig {
+fn make_gossip_config(cfg: &NodeConfig, home_dir: &Path) -> NetworkConfig {
     NetworkConfig {
         listen_addr: cfg.consensus.p2p.listen_addr.clone(),
         persistent_peers: cfg.consensus.p2p.persistent_peers.clone(),
@@ -181,15 +264,32 @@ fn make_gossip_config(cfg: &NodeConfig) -> NetworkConfig {
             PubSubProtocol::Broadcast => malachitebft_network::PubSubProtocol::Broadcast,
         },
         gossipsub: match cfg.consensus.p2p.protocol {
-            PubSubProtocol::GossipSub(config) => GossipSubConfig {
-                mesh_n: config.mesh_n(),
-                mesh_n_high: config.mesh_n_high(),
-                mesh_n_low: config.mesh_n_low(),
-                mesh_outbound_min: config.mesh_outbound_min(),
-            },
+            PubSubProtocol::GossipSub(config) => {
+                let peer_scoring = config.peer_scoring();
+
+                GossipSubConfig {
+                    mesh_n: config.mesh_n(),
+                    mesh_n_high: config.mesh_n_high(),
+                    mesh_n_low: config.mesh_n_low(),
+                    mesh_outbound_min: config.mesh_outbound_min(),
+                    peer_scoring: peer_scoring.enabled.then_some(PeerScoringConfig {
+                        gossip_threshold: peer_scoring.gossip_threshold,
+                        publish_threshold: peer_scoring.publish_threshold,
+                        graylist_threshold: peer_scoring.graylist_threshold,
+                        invalid_message_weight: peer_scoring.invalid_message_weight,
+                    }),
+                }
+            }
             PubSubProtocol::Broadcast => GossipSubConfig::default(),
         },
         rpc_max_size: cfg.consensus.p2p.rpc_max_size.as_u64() as usize,
         pubsub_max_size: cfg.consensus.p2p.pubsub_max_size.as_u64() as usize,
+        address_book_path: Some(home_dir.join("data").join("address_book.json")),
+        allowed_peers: cfg
+            .consensus
+            .p2p
+            .allow_list
+            .enabled
+            .then(|| cfg.consensus.p2p.allow_list.peers.clone()),
     }
 }
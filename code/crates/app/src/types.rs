@@ -1,8 +1,10 @@
 //! Re-export of all types required to build a Malachite application.
 
 pub use malachitebft_core_consensus::{
-    ConsensusMsg, ProposedValue, SignedConsensusMsg, ValuePayload,
+    ConsensusMsg, ProposedValue, RoundValue, RoundVotesDump, SignedConsensusMsg, StateDump, Step,
+    ValuePayload, VoteTally,
 };
+pub use malachitebft_engine::consensus::HeightParams;
 pub use malachitebft_engine::host::LocallyProposedValue;
 pub use malachitebft_peer::PeerId;
 
@@ -25,7 +27,22 @@ pub mod streaming {
 }
 
 pub mod sync {
-    pub use malachitebft_sync::{Metrics, RawDecidedValue, Request, Response, Status};
+    pub use malachitebft_sync::{
+        ApplySnapshotChunkResult, Metrics, OfferSnapshotResult, RawDecidedValue, Request, Response,
+        Snapshot, Status, SyncProgress,
+    };
+}
+
+pub mod network {
+    pub use malachitebft_engine::network::{
+        AcceptAll, MessageValidator, NetworkEvent, ValidationResult,
+    };
+}
+
+pub mod timeouts {
+    pub use malachitebft_engine::util::timeouts::{
+        AdaptiveTimeouts, LinearTimeouts, TimeoutStrategy,
+    };
 }
 
 pub mod codec {
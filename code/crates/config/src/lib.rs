@@ -4,9 +4,10 @@ use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
 
-use bytesize::ByteSize;
+pub use bytesize::ByteSize;
 use config as config_rs;
 use malachitebft_core_types::TimeoutKind;
+use malachitebft_peer::PeerId;
 use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +32,10 @@ pub struct Config {
     /// Metrics configuration options
     pub metrics: MetricsConfig,
 
+    /// RPC server configuration options
+    #[serde(default)]
+    pub rpc: RpcConfig,
+
     /// Runtime configuration options
     pub runtime: RuntimeConfig,
 
@@ -75,6 +80,10 @@ pub struct P2pConfig {
 
     /// The maximum size of messages to send over RPC
     pub rpc_max_size: ByteSize,
+
+    /// Restrict the peers allowed to connect to this node
+    #[serde(default)]
+    pub allow_list: AllowListConfig,
 }
 
 impl Default for P2pConfig {
@@ -87,9 +96,23 @@ impl Default for P2pConfig {
             protocol: Default::default(),
             rpc_max_size: ByteSize::mib(10),
             pubsub_max_size: ByteSize::mib(4),
+            allow_list: Default::default(),
         }
     }
 }
+
+/// Peer allow-list configuration options
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+pub struct AllowListConfig {
+    /// Only accept connections from and to the peers in the allow-list
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Peers allowed to connect to this node, when the allow-list is enabled.
+    /// The application may replace this list at runtime, eg. to follow the validator set.
+    #[serde(default)]
+    pub peers: Vec<PeerId>,
+}
 /// Peer Discovery configuration options
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct DiscoveryConfig {
@@ -246,6 +269,9 @@ pub struct GossipSubConfig {
     /// When this value is set to 0 or does not meet the above constraints,
     /// it will be calculated as `max(1, min(mesh_n / 2, mesh_n_low - 1))`
     mesh_outbound_min: usize,
+
+    /// Peer scoring, to penalize the peers sending invalid messages
+    peer_scoring: PeerScoringConfig,
 }
 
 impl Default for GossipSubConfig {
@@ -254,6 +280,40 @@ impl Default for GossipSubConfig {
     }
 }
 
+/// GossipSub peer scoring configuration
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeerScoringConfig {
+    /// Enable peer scoring
+    pub enabled: bool,
+
+    /// Below this score, gossip is neither sent to nor accepted from a peer. Must be negative.
+    pub gossip_threshold: f64,
+
+    /// Below this score, the messages we publish are not sent to a peer.
+    /// Must be lower than the gossip threshold.
+    pub publish_threshold: f64,
+
+    /// Below this score, all the messages from a peer are ignored.
+    /// Must be lower than the publish threshold.
+    pub graylist_threshold: f64,
+
+    /// Weight of the square of the number of invalid messages sent by a peer. Must be negative.
+    pub invalid_message_weight: f64,
+}
+
+impl Default for PeerScoringConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            gossip_threshold: -10.0,
+            publish_threshold: -50.0,
+            graylist_threshold: -80.0,
+            invalid_message_weight: -10.0,
+        }
+    }
+}
+
 impl GossipSubConfig {
     /// Create a new, valid GossipSub configuration.
     pub fn new(
@@ -267,12 +327,21 @@ impl GossipSubConfig {
             mesh_n_high,
             mesh_n_low,
             mesh_outbound_min,
+            peer_scoring: PeerScoringConfig::default(),
         };
 
         result.adjust();
         result
     }
 
+    /// Set the peer scoring configuration.
+    pub fn with_peer_scoring(self, peer_scoring: PeerScoringConfig) -> Self {
+        Self {
+            peer_scoring,
+            ..self
+        }
+    }
+
     /// Adjust the configuration values.
     pub fn adjust(&mut self) {
         use std::cmp::{max, min};
@@ -312,6 +381,10 @@ impl GossipSubConfig {
     pub fn mesh_outbound_min(&self) -> usize {
         self.mesh_outbound_min
     }
+
+    pub fn peer_scoring(&self) -> PeerScoringConfig {
+        self.peer_scoring
+    }
 }
 
 mod gossipsub {
@@ -325,6 +398,8 @@ mod gossipsub {
         mesh_n_low: usize,
         #[serde(default)]
         mesh_outbound_min: usize,
+        #[serde(default)]
+        peer_scoring: super::PeerScoringConfig,
     }
 
     impl From<RawConfig> for super::GossipSubConfig {
@@ -335,6 +410,7 @@ mod gossipsub {
                 raw.mesh_n_low,
                 raw.mesh_outbound_min,
             )
+            .with_peer_scoring(raw.peer_scoring)
         }
     }
 }
@@ -353,6 +429,7 @@ pub struct MempoolConfig {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SyncConfig {
     /// Enable Sync
     pub enabled: bool,
@@ -364,6 +441,26 @@ pub struct SyncConfig {
     /// Timeout duration for sync requests
     #[serde(with = "humantime_serde")]
     pub request_timeout: Duration,
+
+    /// Maximum number of values to request from a peer in a single request
+    pub batch_size: u64,
+
+    /// Maximum number of value requests in flight at the same time, across all peers
+    pub parallel_requests: usize,
+
+    /// Maximum size of the values sent in a single response
+    pub max_response_size: ByteSize,
+
+    /// How long a peer which failed too many requests or sent an invalid certificate is banned
+    #[serde(with = "humantime_serde")]
+    pub peer_ban_duration: Duration,
+
+    /// Bootstrap a node without any state from a snapshot of the application state
+    /// offered by peers, instead of syncing all the decided values from the first height
+    pub snapshot_sync: bool,
+
+    /// Minimum number of peers which must offer the same snapshot before it is restored
+    pub snapshot_min_peers: usize,
 }
 
 impl Default for SyncConfig {
@@ -372,6 +469,12 @@ impl Default for SyncConfig {
             enabled: true,
             status_update_interval: Duration::from_secs(10),
             request_timeout: Duration::from_secs(10),
+            batch_size: 50,
+            parallel_requests: 5,
+            max_response_size: ByteSize::mib(4),
+            peer_ban_duration: Duration::from_secs(60),
+            snapshot_sync: false,
+            snapshot_min_peers: 2,
         }
     }
 }
@@ -385,6 +488,53 @@ pub struct ConsensusConfig {
 
     /// P2P configuration options
     pub p2p: P2pConfig,
+
+    /// Write-Ahead Log configuration options
+    #[serde(default)]
+    pub wal: WalConfig,
+
+    /// Synchrony parameters for proposer-based timestamps (PBTS).
+    /// If not set, the timeliness of proposals is not checked.
+    /// If set, proposals without a timestamp are not timely, so the proposals of the context
+    /// must support timestamps, see `Proposal::with_timestamp`.
+    /// Not supported with the `parts-only` value payload, as proposal messages are then not sent.
+    #[serde(default)]
+    pub synchrony: Option<SynchronyConfig>,
+}
+
+/// Synchrony parameters for proposer-based timestamps (PBTS)
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SynchronyConfig {
+    /// Bound on the difference between the clocks of any two correct validators
+    #[serde(with = "humantime_serde")]
+    pub precision: Duration,
+
+    /// Bound on the time it takes for a proposal to reach all correct validators
+    #[serde(with = "humantime_serde")]
+    pub message_delay: Duration,
+}
+
+/// Write-Ahead Log configuration options
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WalConfig {
+    /// Maximum size of a WAL segment, once reached entries are written to a new segment
+    pub max_segment_size: ByteSize,
+
+    /// Number of past heights for which to keep the WAL around, eg. for post-mortem debugging
+    pub retain_heights: usize,
+
+    /// Maximum total size of the WALs kept for past heights, the oldest ones are pruned first
+    pub max_retained_size: ByteSize,
+}
+
+impl Default for WalConfig {
+    fn default() -> Self {
+        Self {
+            max_segment_size: ByteSize::mib(64),
+            retain_heights: 10,
+            max_retained_size: ByteSize::gib(1),
+        }
+    }
 }
 
 /// Message types required by consensus to deliver the value being proposed
@@ -513,6 +663,24 @@ impl Default for MetricsConfig {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RpcConfig {
+    /// Enable the RPC server
+    pub enabled: bool,
+
+    /// Address at which to serve the RPC endpoints at
+    pub listen_addr: SocketAddr,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        RpcConfig {
+            enabled: false,
+            listen_addr: SocketAddr::new(IpAddr::from([127, 0, 0, 1]), 26657),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "flavor", rename_all = "snake_case")]
 pub enum RuntimeConfig {
@@ -545,6 +713,8 @@ pub struct VoteExtensionsConfig {
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TestConfig {
+    /// Maximum size of the values to propose,
+    /// until the application changes it when starting a height
     pub max_block_size: ByteSize,
     /// Message types that can carry values
     pub value_payload: ValuePayload,
@@ -554,6 +724,8 @@ pub struct TestConfig {
     #[serde(with = "humantime_serde")]
     pub exec_time_per_tx: Duration,
     pub max_retain_blocks: usize,
+    /// Whether vote extensions are enabled from the initial height, and their size,
+    /// until the application changes the height from which they are enabled
     #[serde(default)]
     pub vote_extensions: VoteExtensionsConfig,
 }
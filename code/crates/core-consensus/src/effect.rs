@@ -144,6 +144,22 @@ where
         resume::Continue,
     ),
 
+    /// Notifies the application that a validator has equivocated,
+    /// ie. signed two conflicting votes or proposals for the same height and round.
+    ///
+    /// The evidence includes both signed messages, so that
+    /// the application can include it in a block, eg. for slashing.
+    ///
+    /// Resume with: [`resume::Continue`]
+    ReportEvidence(Evidence<Ctx>, resume::Continue),
+
+    /// Publish evidence of misbehavior detected by this node to peers,
+    /// so that it reaches the next proposers even if they did not
+    /// receive both conflicting messages themselves.
+    ///
+    /// Resume with: [`resume::Continue`]
+    PublishEvidence(Evidence<Ctx>, resume::Continue),
+
     /// Consensus has been stuck in Prevote or Precommit step, ask for vote sets from peers
     ///
     /// Resume with: [`resume::Continue`]
@@ -180,6 +196,15 @@ where
     /// Resume with: [`resume::SignedProposal`]
     SignProposal(Ctx::Proposal, resume::SignedProposal),
 
+    /// Sign an implicit proposal with this node's private key.
+    ///
+    /// Implicit proposals are built internally, on behalf of the actual proposer,
+    /// when running in parts-only mode or when a value is received via sync.
+    /// They are never published, and therefore cannot lead to double-signing.
+    ///
+    /// Resume with: [`resume::SignedProposal`]
+    SignImplicitProposal(Ctx::Proposal, resume::SignedProposal),
+
     /// Verify a signature
     ///
     /// Resume with: [`resume::SignatureValidity`]
@@ -189,6 +214,17 @@ where
         resume::SignatureValidity,
     ),
 
+    /// Verify the signatures of many votes at once
+    ///
+    /// Each vote is paired with the public key of the validator which issued it.
+    /// The validity of each signature must be returned in the same order as the votes.
+    ///
+    /// Resume with: [`resume::SignatureBatchValidity`]
+    VerifySignatureBatch(
+        Vec<(SignedVote<Ctx>, PublicKey<Ctx>)>,
+        resume::SignatureBatchValidity,
+    ),
+
     /// Verify a commit certificate
     ///
     /// Resume with: [`resume::CertificateValidity`]
@@ -199,6 +235,22 @@ where
         resume::CertificateValidity,
     ),
 
+    /// Get the current time, to timestamp our proposals and to check whether a proposal
+    /// was received in a timely manner when proposer-based timestamps (PBTS) are enabled.
+    ///
+    /// Resume with: [`resume::Time`]
+    GetTime(resume::Time),
+
+    /// Validate a proposal received from a peer on application-level grounds,
+    /// eg. its timestamp or proposer-specific fields, before its value is received.
+    ///
+    /// The signature of the proposal has already been verified at this point.
+    /// If the proposal is deemed invalid, consensus prevotes nil for it right away,
+    /// without waiting for the full value.
+    ///
+    /// Resume with: [`resume::ProposalValidity`]
+    ValidateProposal(SignedProposal<Ctx>, resume::ProposalValidity),
+
     /// Verify a vote extension
     ///
     /// If the vote extension is deemed invalid, the vote it was part of
@@ -237,6 +289,9 @@ where
     /// Resume execution with the validity of the signature
     SignatureValidity(bool),
 
+    /// Resume execution with the validity of each signature in a batch
+    SignatureBatchValidity(Vec<bool>),
+
     /// Resume execution with the signed vote
     SignedVote(SignedMessage<Ctx, Ctx::Vote>),
 
@@ -247,6 +302,15 @@ where
     /// See the [`Effect::ExtendVote`] effect for more information.
     VoteExtension(Option<SignedExtension<Ctx>>),
 
+    /// Resume execution with the current time, or `None` if it is not known,
+    /// eg. when replaying the WAL, in which case proposals are considered timely.
+    /// See the [`Effect::GetTime`] effect for more information.
+    Time(Option<Timestamp>),
+
+    /// Resume execution with the validity of the proposal, as judged by the application.
+    /// See the [`Effect::ValidateProposal`] effect for more information.
+    ProposalValidity(Validity),
+
     /// Resume execution with the result of the verification of the [`SignedExtension`]
     VoteExtensionValidity(Result<(), VoteExtensionError>),
 
@@ -292,6 +356,17 @@ pub mod resume {
         }
     }
 
+    #[derive(Debug, Default)]
+    pub struct SignatureBatchValidity;
+
+    impl<Ctx: Context> Resumable<Ctx> for SignatureBatchValidity {
+        type Value = Vec<bool>;
+
+        fn resume_with(self, value: Self::Value) -> Resume<Ctx> {
+            Resume::SignatureBatchValidity(value)
+        }
+    }
+
     #[derive(Debug, Default)]
     pub struct SignedVote;
 
@@ -325,6 +400,28 @@ pub mod resume {
         }
     }
 
+    #[derive(Debug, Default)]
+    pub struct Time;
+
+    impl<Ctx: Context> Resumable<Ctx> for Time {
+        type Value = Option<Timestamp>;
+
+        fn resume_with(self, value: Self::Value) -> Resume<Ctx> {
+            Resume::Time(value)
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct ProposalValidity;
+
+    impl<Ctx: Context> Resumable<Ctx> for ProposalValidity {
+        type Value = Validity;
+
+        fn resume_with(self, value: Self::Value) -> Resume<Ctx> {
+            Resume::ProposalValidity(value)
+        }
+    }
+
     #[derive(Debug, Default)]
     pub struct CertificateValidity;
 
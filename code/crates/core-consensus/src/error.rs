@@ -35,6 +35,10 @@ where
     #[error("Validator set not found at height {0}")]
     ValidatorSetNotFound(Ctx::Height),
 
+    /// The validator was not found in the validator set at the given height.
+    #[error("Validator {1} not found in the validator set at height {0}")]
+    ValidatorNotFound(Ctx::Height, Ctx::Address),
+
     /// The certificate is invalid.
     #[error("Invalid certificate: {1}")]
     InvalidCertificate(CommitCertificate<Ctx>, CertificateError<Ctx>),
@@ -1,5 +1,6 @@
 mod decide;
 mod driver;
+mod evidence;
 mod proposal;
 mod propose;
 mod proposed_value;
@@ -13,6 +14,7 @@ mod validator_set;
 mod vote;
 mod vote_set;
 
+use evidence::on_evidence;
 use proposal::on_proposal;
 use propose::on_propose;
 use proposed_value::on_proposed_value;
@@ -52,7 +54,9 @@ where
             reset_and_start_height(co, state, metrics, height, validator_set).await
         }
         Input::Vote(vote) => on_vote(co, state, metrics, vote).await,
-        Input::Proposal(proposal) => on_proposal(co, state, metrics, proposal).await,
+        Input::Proposal(proposal, received_at) => {
+            on_proposal(co, state, metrics, proposal, received_at).await
+        }
         Input::Propose(value) => on_propose(co, state, metrics, value).await,
         Input::TimeoutElapsed(timeout) => on_timeout_elapsed(co, state, metrics, timeout).await,
         Input::ProposedValue(value, origin) => {
@@ -67,5 +71,6 @@ where
         Input::VoteSetResponse(vote_set) => {
             on_vote_set_response(co, state, metrics, vote_set).await
         }
+        Input::Evidence(evidence) => on_evidence(co, state, metrics, evidence).await,
     }
 }
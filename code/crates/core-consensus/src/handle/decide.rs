@@ -61,7 +61,13 @@ where
             let extensions = extract_vote_extensions(&mut commits);
 
             // TODO: Should we verify we have 2/3rd commits?
-            let certificate = CommitCertificate::new(height, proposal_round, value.id(), commits);
+            let certificate = CommitCertificate::new(
+                height,
+                proposal_round,
+                value.id(),
+                commits,
+                state.driver.validator_set(),
+            );
 
             (certificate, extensions)
         });
@@ -1,6 +1,7 @@
 use malachitebft_core_driver::Input as DriverInput;
 use malachitebft_core_driver::Output as DriverOutput;
 
+use crate::handle::evidence::{mark_evidence, report_new_evidence};
 use crate::handle::on_proposal;
 use crate::handle::signature::sign_proposal;
 use crate::handle::signature::sign_vote;
@@ -82,11 +83,19 @@ where
     // Record the step we were in
     let prev_step = state.driver.step();
 
+    // Record the evidence of equivocation we already have against the sender, if any
+    let evidence_mark = mark_evidence(state, &input);
+
     let outputs = state
         .driver
         .process(input)
         .map_err(|e| Error::DriverProcess(e))?;
 
+    // Report any new evidence of equivocation recorded by the driver
+    if let Some(mark) = evidence_mark {
+        report_new_evidence(co, state, mark).await?;
+    }
+
     // Record the step we are now at
     let new_step = state.driver.step();
 
@@ -207,6 +216,7 @@ where
 
             // Only sign and publish if we're in the validator set
             if state.is_validator() {
+                let proposal = with_timestamp(co, state, proposal).await?;
                 let signed_proposal = sign_proposal(co, proposal).await?;
 
                 if signed_proposal.pol_round().is_defined() {
@@ -223,7 +233,7 @@ where
                     );
                 }
 
-                on_proposal(co, state, metrics, signed_proposal.clone()).await?;
+                on_proposal(co, state, metrics, signed_proposal.clone(), None).await?;
 
                 // Proposal messages should not be broadcasted if they are implicit,
                 // instead they should be inferred from the block parts.
@@ -252,7 +262,7 @@ where
             // Only sign and publish if we're in the validator set
             if state.is_validator() {
                 let vote_type = vote.vote_type();
-                let extended_vote = extend_vote(co, vote).await?;
+                let extended_vote = extend_vote(co, state, vote).await?;
                 let signed_vote = sign_vote(co, extended_vote).await?;
 
                 on_vote(co, state, metrics, signed_vote.clone()).await?;
@@ -318,11 +328,19 @@ where
     }
 }
 
-async fn extend_vote<Ctx: Context>(co: &Co<Ctx>, vote: Ctx::Vote) -> Result<Ctx::Vote, Error<Ctx>> {
+async fn extend_vote<Ctx: Context>(
+    co: &Co<Ctx>,
+    state: &State<Ctx>,
+    vote: Ctx::Vote,
+) -> Result<Ctx::Vote, Error<Ctx>> {
     let VoteType::Precommit = vote.vote_type() else {
         return Ok(vote);
     };
 
+    if !state.vote_extensions_enabled(vote.height()) {
+        return Ok(vote);
+    }
+
     let NilOrVal::Val(value_id) = vote.value().as_ref().cloned() else {
         return Ok(vote);
     };
@@ -340,3 +358,45 @@ async fn extend_vote<Ctx: Context>(co: &Co<Ctx>, vote: Ctx::Vote) -> Result<Ctx:
         Ok(vote)
     }
 }
+
+/// Set the current time as the timestamp of our proposal when proposer-based timestamps (PBTS)
+/// are enabled, so that other validators can check that they receive it in a timely manner.
+///
+/// A value which was already proposed keeps its original timestamp, be it when re-proposing
+/// a value from its valid round, or when proposing again after a restart, once our proposal
+/// was replayed from the WAL. We thus never sign two proposals differing only by their timestamp.
+async fn with_timestamp<Ctx: Context>(
+    co: &Co<Ctx>,
+    state: &State<Ctx>,
+    proposal: Ctx::Proposal,
+) -> Result<Ctx::Proposal, Error<Ctx>> {
+    if state.params.synchrony.is_none() {
+        return Ok(proposal);
+    }
+
+    let value_round = if proposal.pol_round().is_defined() {
+        proposal.pol_round()
+    } else {
+        proposal.round()
+    };
+
+    let timestamp = state
+        .full_proposal_at_round_and_value(&proposal.height(), value_round, proposal.value())
+        .and_then(|full_proposal| full_proposal.proposal.timestamp());
+
+    if let Some(timestamp) = timestamp {
+        return Ok(proposal.with_timestamp(timestamp));
+    }
+
+    let now = perform!(
+        co,
+        Effect::GetTime(Default::default()),
+        Resume::Time(time) => time
+    );
+
+    if let Some(now) = now {
+        Ok(proposal.with_timestamp(now))
+    } else {
+        Ok(proposal)
+    }
+}
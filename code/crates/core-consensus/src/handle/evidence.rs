@@ -0,0 +1,172 @@
+use crate::handle::proposal::verify_signed_proposal;
+use crate::handle::vote::verify_signed_vote;
+use crate::prelude::*;
+
+/// The amount of evidence of equivocation already recorded against the sender of
+/// a vote or proposal, before that vote or proposal is applied to the driver.
+pub enum EvidenceMark<Ctx>
+where
+    Ctx: Context,
+{
+    Vote(Ctx::Address, usize),
+    Proposal(Ctx::Address, usize),
+}
+
+/// Record how much evidence of equivocation we already hold against the sender
+/// of the given input, if it is a vote or a proposal.
+pub fn mark_evidence<Ctx>(state: &State<Ctx>, input: &DriverInput<Ctx>) -> Option<EvidenceMark<Ctx>>
+where
+    Ctx: Context,
+{
+    match input {
+        DriverInput::Vote(vote) => {
+            let address = vote.validator_address();
+            let count = state
+                .driver
+                .votes()
+                .evidence()
+                .get(address)
+                .map_or(0, Vec::len);
+            Some(EvidenceMark::Vote(address.clone(), count))
+        }
+
+        DriverInput::Proposal(proposal, _) => {
+            let address = proposal.validator_address();
+            let count = state.driver.evidence().get(address).map_or(0, Vec::len);
+            Some(EvidenceMark::Proposal(address.clone(), count))
+        }
+
+        _ => None,
+    }
+}
+
+/// Report any evidence of equivocation recorded by the driver since the given mark was taken,
+/// and publish it to our peers.
+pub async fn report_new_evidence<Ctx>(
+    co: &Co<Ctx>,
+    state: &mut State<Ctx>,
+    mark: EvidenceMark<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let new_evidence: Vec<Evidence<Ctx>> = match mark {
+        EvidenceMark::Vote(address, count) => state
+            .driver
+            .votes()
+            .evidence()
+            .get(&address)
+            .into_iter()
+            .flat_map(|evidence| evidence.iter().skip(count))
+            .map(|(first, second)| Evidence::DoubleVote {
+                first: first.clone(),
+                second: second.clone(),
+            })
+            .collect(),
+
+        EvidenceMark::Proposal(address, count) => state
+            .driver
+            .evidence()
+            .get(&address)
+            .into_iter()
+            .flat_map(|evidence| evidence.iter().skip(count))
+            .map(|(first, second)| Evidence::DoubleProposal {
+                first: first.clone(),
+                second: second.clone(),
+            })
+            .collect(),
+    };
+
+    for evidence in new_evidence {
+        if !state.record_evidence(&evidence) {
+            continue;
+        }
+
+        warn!(
+            height = %evidence.height(),
+            round = %evidence.round(),
+            validator = %evidence.validator_address(),
+            "Detected equivocation, reporting evidence"
+        );
+
+        perform!(
+            co,
+            Effect::ReportEvidence(evidence.clone(), Default::default())
+        );
+        perform!(co, Effect::PublishEvidence(evidence, Default::default()));
+    }
+
+    Ok(())
+}
+
+/// Handle evidence of equivocation received from a peer.
+///
+/// The evidence is only reported to the application if both messages actually conflict
+/// and carry a valid signature from a validator in the validator set at that height.
+/// Evidence received from peers is not re-published, as it is already being gossiped.
+pub async fn on_evidence<Ctx>(
+    co: &Co<Ctx>,
+    state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    evidence: Evidence<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    if !evidence.is_conflicting() {
+        warn!(
+            height = %evidence.height(),
+            round = %evidence.round(),
+            validator = %evidence.validator_address(),
+            "Received evidence with non-conflicting messages, dropping"
+        );
+
+        return Ok(());
+    }
+
+    if state.is_evidence_reported(&evidence) {
+        debug!(
+            height = %evidence.height(),
+            round = %evidence.round(),
+            validator = %evidence.validator_address(),
+            "Received evidence that was already reported or is too old, dropping"
+        );
+
+        return Ok(());
+    }
+
+    let valid = match &evidence {
+        Evidence::DoubleVote { first, second } => {
+            verify_signed_vote(co, state, first).await?
+                && verify_signed_vote(co, state, second).await?
+        }
+        Evidence::DoubleProposal { first, second } => {
+            verify_signed_proposal(co, state, first).await?
+                && verify_signed_proposal(co, state, second).await?
+        }
+    };
+
+    if !valid {
+        warn!(
+            height = %evidence.height(),
+            round = %evidence.round(),
+            validator = %evidence.validator_address(),
+            "Received evidence with invalid signatures, dropping"
+        );
+
+        return Ok(());
+    }
+
+    state.record_evidence(&evidence);
+
+    warn!(
+        height = %evidence.height(),
+        round = %evidence.round(),
+        validator = %evidence.validator_address(),
+        "Received valid evidence of equivocation, reporting evidence"
+    );
+
+    perform!(co, Effect::ReportEvidence(evidence, Default::default()));
+
+    Ok(())
+}
@@ -12,6 +12,7 @@ pub async fn on_proposal<Ctx>(
     state: &mut State<Ctx>,
     metrics: &Metrics,
     signed_proposal: SignedProposal<Ctx>,
+    received_at: Option<Timestamp>,
 ) -> Result<(), Error<Ctx>>
 where
     Ctx: Context,
@@ -45,25 +46,71 @@ where
         "Received proposal"
     );
 
+    // Take the time at which the proposal was received before possibly queuing it,
+    // so that its timeliness does not depend on when it is processed
+    let received_at = match received_at {
+        Some(received_at) => Some(received_at),
+        None => receive_time(co, state, &signed_proposal).await?,
+    };
+
     // Queue messages if driver is not initialized, or if they are for higher height.
     // Process messages received for the current height.
     // Drop all others.
     if state.driver.round() == Round::Nil {
         debug!("Received proposal at round -1, queuing for later");
-        state.buffer_input(signed_proposal.height(), Input::Proposal(signed_proposal));
+        state.buffer_input(
+            signed_proposal.height(),
+            Input::Proposal(signed_proposal, received_at),
+        );
 
         return Ok(());
     }
 
     if proposal_height > consensus_height {
         debug!("Received proposal for higher height, queuing for later");
-        state.buffer_input(signed_proposal.height(), Input::Proposal(signed_proposal));
+        state.buffer_input(
+            signed_proposal.height(),
+            Input::Proposal(signed_proposal, received_at),
+        );
 
         return Ok(());
     }
 
     debug_assert_eq!(proposal_height, consensus_height);
 
+    // Let the application reject proposals from other validators early,
+    // so that we can prevote nil without waiting for the full value.
+    // Re-proposals are not validated again, as their value was already validated.
+    let validity = if proposer_address != state.address() && signed_proposal.pol_round().is_nil() {
+        perform!(
+            co,
+            Effect::ValidateProposal(signed_proposal.clone(), Default::default()),
+            Resume::ProposalValidity(validity) => validity
+        )
+    } else {
+        Validity::Valid
+    };
+
+    if !validity.is_valid() {
+        warn!(
+            proposal.height = %proposal_height,
+            proposal.round = %proposal_round,
+            proposer = %proposer_address,
+            "Proposal rejected by the application"
+        );
+    } else if !is_timely(state, &signed_proposal, received_at) {
+        warn!(
+            proposal.height = %proposal_height,
+            proposal.round = %proposal_round,
+            proposer = %proposer_address,
+            "Proposal was not received in a timely manner"
+        );
+
+        state
+            .driver
+            .mark_untimely(proposal_round, signed_proposal.value().id());
+    }
+
     // Store the proposal in the full proposal keeper
     state.store_proposal(signed_proposal.clone());
 
@@ -79,6 +126,16 @@ where
         );
     }
 
+    if !validity.is_valid() {
+        return apply_driver_input(
+            co,
+            state,
+            metrics,
+            DriverInput::Proposal(signed_proposal, Validity::Invalid),
+        )
+        .await;
+    }
+
     if state.params.value_payload.proposal_only() {
         // TODO - pass the received value up to the host that will verify and give back validity and extension.
         // Currently starknet Context defines value as BlockHash, we need a PoC app for this.
@@ -117,6 +174,73 @@ where
     Ok(())
 }
 
+/// Whether a proposal must be checked for timeliness, as per proposer-based timestamps (PBTS).
+///
+/// Only proposals from other validators for a new value are checked, as a proposal for a value
+/// locked in a previous round is backed by a polka for a proposal which was timely.
+fn needs_timeliness_check<Ctx>(state: &State<Ctx>, signed_proposal: &SignedProposal<Ctx>) -> bool
+where
+    Ctx: Context,
+{
+    state.params.synchrony.is_some()
+        && signed_proposal.validator_address() != state.address()
+        && signed_proposal.pol_round().is_nil()
+}
+
+/// Get the current time as the time at which the given proposal was received,
+/// if it needs to be checked for timeliness.
+async fn receive_time<Ctx>(
+    co: &Co<Ctx>,
+    state: &State<Ctx>,
+    signed_proposal: &SignedProposal<Ctx>,
+) -> Result<Option<Timestamp>, Error<Ctx>>
+where
+    Ctx: Context,
+{
+    if !needs_timeliness_check(state, signed_proposal) {
+        return Ok(None);
+    }
+
+    let now = perform!(
+        co,
+        Effect::GetTime(Default::default()),
+        Resume::Time(time) => time
+    );
+
+    Ok(now)
+}
+
+/// Check whether a proposal was received in a timely manner, as per proposer-based timestamps (PBTS).
+///
+/// Proposals without a timestamp are not timely, while proposals received at an unknown time,
+/// eg. when replayed from the WAL, are.
+fn is_timely<Ctx>(
+    state: &State<Ctx>,
+    signed_proposal: &SignedProposal<Ctx>,
+    received_at: Option<Timestamp>,
+) -> bool
+where
+    Ctx: Context,
+{
+    let Some(synchrony) = state.params.synchrony else {
+        return true;
+    };
+
+    if !needs_timeliness_check(state, signed_proposal) {
+        return true;
+    }
+
+    let Some(timestamp) = signed_proposal.timestamp() else {
+        return false;
+    };
+
+    let Some(received_at) = received_at else {
+        return true;
+    };
+
+    synchrony.is_timely(timestamp, received_at, signed_proposal.round())
+}
+
 pub async fn verify_signed_proposal<Ctx>(
     co: &Co<Ctx>,
     state: &State<Ctx>,
@@ -152,7 +276,12 @@ where
         return Ok(false);
     };
 
-    let expected_proposer = state.get_proposer(proposal_height, proposal_round);
+    // The proposer is selected from the validator set at the height of the proposal,
+    // which may differ from the current one, eg. for evidence from a past height
+    let expected_proposer = state
+        .ctx
+        .select_proposer(&validator_set, proposal_height, proposal_round)
+        .address();
 
     if expected_proposer != proposer_address {
         warn!(
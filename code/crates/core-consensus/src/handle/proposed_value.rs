@@ -4,7 +4,7 @@ use crate::handle::driver::apply_driver_input;
 use crate::types::ProposedValue;
 
 use super::decide::decide_current_no_timeout;
-use super::signature::sign_proposal;
+use super::signature::sign_implicit_proposal;
 
 pub async fn on_proposed_value<Ctx>(
     co: &Co<Ctx>,
@@ -48,7 +48,7 @@ where
 
         // TODO: Keep unsigned proposals in keeper.
         // For now we keep all happy by signing all "implicit" proposals with this node's key
-        let signed_proposal = sign_proposal(co, proposal).await?;
+        let signed_proposal = sign_implicit_proposal(co, proposal).await?;
 
         state.store_proposal(signed_proposal);
     }
@@ -18,6 +18,33 @@ where
     Ok(valid)
 }
 
+pub async fn verify_signature_batch<Ctx>(
+    co: &Co<Ctx>,
+    votes: Vec<(SignedVote<Ctx>, PublicKey<Ctx>)>,
+) -> Result<Vec<bool>, Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let count = votes.len();
+
+    let results = perform!(co,
+        Effect::VerifySignatureBatch(votes, Default::default()),
+        Resume::SignatureBatchValidity(results) => results
+    );
+
+    if results.len() != count {
+        warn!(
+            expected = count,
+            actual = results.len(),
+            "Signature batch verification returned an unexpected number of results, rejecting all signatures"
+        );
+
+        return Ok(vec![false; count]);
+    }
+
+    Ok(results)
+}
+
 pub async fn sign_vote<Ctx>(co: &Co<Ctx>, vote: Ctx::Vote) -> Result<SignedVote<Ctx>, Error<Ctx>>
 where
     Ctx: Context,
@@ -45,6 +72,21 @@ where
     Ok(signed_proposal)
 }
 
+pub async fn sign_implicit_proposal<Ctx>(
+    co: &Co<Ctx>,
+    proposal: Ctx::Proposal,
+) -> Result<SignedProposal<Ctx>, Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let signed_proposal = perform!(co,
+        Effect::SignImplicitProposal(proposal, Default::default()),
+        Resume::SignedProposal(signed_proposal) => signed_proposal
+    );
+
+    Ok(signed_proposal)
+}
+
 pub async fn verify_certificate<Ctx>(
     co: &Co<Ctx>,
     certificate: CommitCertificate<Ctx>,
@@ -2,6 +2,9 @@ use crate::prelude::*;
 
 use crate::handle::driver::apply_driver_input;
 use crate::handle::handle_input;
+use crate::handle::signature::verify_signature_batch;
+use crate::handle::vote::{on_verified_vote, verify_vote_extension};
+use crate::util::pretty::PrettyVote;
 
 pub async fn reset_and_start_height<Ctx>(
     co: &Co<Ctx>,
@@ -20,6 +23,7 @@ where
     metrics.step_end(state.driver.step());
 
     state.driver.move_to_height(height, validator_set);
+    state.prune_reported_evidence();
 
     debug_assert_eq!(state.driver.height(), height);
     debug_assert_eq!(state.driver.round(), Round::Nil);
@@ -69,12 +73,103 @@ async fn replay_pending_msgs<Ctx>(
 where
     Ctx: Context,
 {
-    let pending_inputs = std::mem::take(&mut state.input_queue);
+    let pending_inputs = std::mem::take(&mut state.input_queue).into_vec();
     debug!(count = pending_inputs.len(), "Replaying inputs");
 
+    let mut validity = verify_pending_votes(co, state, &pending_inputs)
+        .await?
+        .into_iter();
+
     for pending_input in pending_inputs {
-        handle_input(co, state, metrics, pending_input).await?;
+        let Input::Vote(signed_vote) = pending_input else {
+            handle_input(co, state, metrics, pending_input).await?;
+            continue;
+        };
+
+        match validity.next().flatten() {
+            // The vote was not part of the batch, process it as usual
+            None => handle_input(co, state, metrics, Input::Vote(signed_vote)).await?,
+
+            Some(true) => {
+                let validator = state
+                    .driver
+                    .validator_set()
+                    .get_by_address(signed_vote.validator_address())
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::ValidatorNotFound(
+                            state.driver.height(),
+                            signed_vote.validator_address().clone(),
+                        )
+                    })?;
+
+                if verify_vote_extension(co, state, &signed_vote, &validator).await? {
+                    on_verified_vote(co, state, metrics, signed_vote).await?;
+                }
+            }
+
+            Some(false) => {
+                warn!(
+                    consensus.height = %state.driver.height(),
+                    vote.height = %signed_vote.height(),
+                    vote.round = %signed_vote.round(),
+                    validator = %signed_vote.validator_address(),
+                    "Received vote with invalid signature: {}", PrettyVote::<Ctx>(&signed_vote.message)
+                );
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Verify the signatures of all pending votes for the current height in a single batch.
+///
+/// ## Return
+/// For each pending vote, in order, return `Some(valid)` if the vote was part of the batch,
+/// or `None` if it was not, eg. because it is for another height.
+async fn verify_pending_votes<Ctx>(
+    co: &Co<Ctx>,
+    state: &State<Ctx>,
+    pending_inputs: &[Input<Ctx>],
+) -> Result<Vec<Option<bool>>, Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let height = state.driver.height();
+    let validator_set = state.driver.validator_set();
+
+    let mut batch = Vec::new();
+    let mut in_batch = Vec::new();
+
+    for input in pending_inputs {
+        let Input::Vote(signed_vote) = input else {
+            continue;
+        };
+
+        let validator = (signed_vote.height() == height)
+            .then(|| validator_set.get_by_address(signed_vote.validator_address()))
+            .flatten();
+
+        match validator {
+            Some(validator) => {
+                batch.push((signed_vote.clone(), validator.public_key().clone()));
+                in_batch.push(true);
+            }
+            None => in_batch.push(false),
+        }
+    }
+
+    if batch.is_empty() {
+        return Ok(vec![None; in_batch.len()]);
+    }
+
+    debug!(%height, count = batch.len(), "Verifying signatures of pending votes");
+
+    let mut results = verify_signature_batch(co, batch).await?.into_iter();
+
+    Ok(in_batch
+        .into_iter()
+        .map(|in_batch| if in_batch { results.next() } else { None })
+        .collect())
+}
@@ -7,6 +7,9 @@ use crate::types::ConsensusMsg;
 use crate::util::pretty::PrettyVote;
 use crate::SignedConsensusMsg;
 
+/// Number of queued inputs past which votes are verified before being queued.
+const MAX_UNVERIFIED_QUEUED_INPUTS: usize = 1024;
+
 pub async fn on_vote<Ctx>(
     co: &Co<Ctx>,
     state: &mut State<Ctx>,
@@ -32,22 +35,14 @@ where
         return Ok(());
     }
 
-    if !verify_signed_vote(co, state, &signed_vote).await? {
-        return Ok(());
-    }
-
-    info!(
-        height = %consensus_height,
-        %vote_height,
-        address = %validator_address,
-        message = %PrettyVote::<Ctx>(&signed_vote.message),
-        "Received vote",
-    );
-
     // Queue messages if driver is not initialized, or if they are for higher height.
     // Process messages received for the current height.
     // Drop all others.
     if consensus_round == Round::Nil {
+        if !verify_before_queuing(co, state, &signed_vote).await? {
+            return Ok(());
+        }
+
         debug!(
             consensus.height = %consensus_height,
             vote.height = %vote_height,
@@ -61,6 +56,10 @@ where
     }
 
     if consensus_height < vote_height {
+        if !verify_before_queuing(co, state, &signed_vote).await? {
+            return Ok(());
+        }
+
         debug!(
             consensus.height = %consensus_height,
             vote.height = %vote_height,
@@ -73,6 +72,34 @@ where
         return Ok(());
     }
 
+    if !verify_signed_vote(co, state, &signed_vote).await? {
+        return Ok(());
+    }
+
+    on_verified_vote(co, state, metrics, signed_vote).await
+}
+
+/// Process a vote for the current height whose signature and extension have already been verified.
+pub async fn on_verified_vote<Ctx>(
+    co: &Co<Ctx>,
+    state: &mut State<Ctx>,
+    metrics: &Metrics,
+    signed_vote: SignedVote<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let consensus_height = state.driver.height();
+    let vote_height = signed_vote.height();
+
+    info!(
+        height = %consensus_height,
+        %vote_height,
+        address = %signed_vote.validator_address(),
+        message = %PrettyVote::<Ctx>(&signed_vote.message),
+        "Received vote",
+    );
+
     debug_assert_eq!(consensus_height, vote_height);
 
     // Only append to WAL and store precommits if we're in the validator set
@@ -150,7 +177,75 @@ where
     verify_vote_extension(co, state, signed_vote, validator).await
 }
 
-async fn verify_vote_extension<Ctx>(
+/// Check whether a vote for a height which has not started yet may be queued.
+///
+/// The signature of votes for the next height to start is only checked once they are replayed,
+/// at which point all the votes for that height are verified in a single batch.
+/// Votes for heights further ahead, which would evict the inputs already queued,
+/// and votes received once the queue holds [`MAX_UNVERIFIED_QUEUED_INPUTS`] inputs,
+/// are verified before being queued, so that forged votes can neither evict legitimate
+/// inputs from the queue nor make it grow without bound.
+async fn verify_before_queuing<Ctx>(
+    co: &Co<Ctx>,
+    state: &State<Ctx>,
+    signed_vote: &SignedVote<Ctx>,
+) -> Result<bool, Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let next_height = if state.driver.round() == Round::Nil {
+        state.driver.height()
+    } else {
+        state.driver.height().increment()
+    };
+
+    if signed_vote.height() == next_height && state.input_queue.len() < MAX_UNVERIFIED_QUEUED_INPUTS
+    {
+        is_known_validator(co, state, signed_vote).await
+    } else {
+        verify_signed_vote(co, state, signed_vote).await
+    }
+}
+
+/// Check that the vote was issued by a validator in the validator set at the vote's height,
+/// without verifying its signature.
+async fn is_known_validator<Ctx>(
+    co: &Co<Ctx>,
+    state: &State<Ctx>,
+    signed_vote: &SignedVote<Ctx>,
+) -> Result<bool, Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let Some(validator_set) = get_validator_set(co, state, signed_vote.height()).await? else {
+        debug!(
+            consensus.height = %state.driver.height(),
+            vote.height = %signed_vote.height(),
+            validator = %signed_vote.validator_address(),
+            "Received vote for height without known validator set, dropping"
+        );
+
+        return Ok(false);
+    };
+
+    if validator_set
+        .get_by_address(signed_vote.validator_address())
+        .is_none()
+    {
+        warn!(
+            consensus.height = %state.driver.height(),
+            vote.height = %signed_vote.height(),
+            validator = %signed_vote.validator_address(),
+            "Received vote from unknown validator"
+        );
+
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+pub async fn verify_vote_extension<Ctx>(
     co: &Co<Ctx>,
     state: &State<Ctx>,
     vote: &SignedVote<Ctx>,
@@ -171,6 +266,19 @@ where
         return Ok(true);
     };
 
+    if !state.vote_extensions_enabled(vote.height()) {
+        warn!(
+            consensus.height = %state.driver.height(),
+            vote.height = %vote.height(),
+            vote.round = %vote.round(),
+            validator = %validator.address(),
+            "Received vote with an extension while vote extensions are disabled: {}",
+            PrettyVote::<Ctx>(&vote.message)
+        );
+
+        return Ok(false);
+    }
+
     let result = perform!(
         co,
         Effect::VerifyVoteExtension(
@@ -1,6 +1,7 @@
 use derive_where::derive_where;
 use malachitebft_core_types::{
-    CommitCertificate, Context, Round, SignedProposal, SignedVote, Timeout, ValueOrigin, VoteSet,
+    CommitCertificate, Context, Evidence, Round, SignedProposal, SignedVote, Timeout, Timestamp,
+    ValueOrigin, VoteSet,
 };
 
 use crate::types::ProposedValue;
@@ -20,8 +21,11 @@ where
     /// Process a vote
     Vote(SignedVote<Ctx>),
 
-    /// Process a proposal
-    Proposal(SignedProposal<Ctx>),
+    /// Process a proposal, received at the given time if known.
+    ///
+    /// When proposer-based timestamps are enabled and the time is not given,
+    /// the proposal is considered received when consensus first processes it.
+    Proposal(SignedProposal<Ctx>, Option<Timestamp>),
 
     /// Propose a value
     Propose(LocallyProposedValue<Ctx>),
@@ -41,4 +45,7 @@ where
 
     /// Vote set to be sent to peer
     VoteSetResponse(VoteSet<Ctx>),
+
+    /// Received evidence of misbehavior from a peer
+    Evidence(Evidence<Ctx>),
 }
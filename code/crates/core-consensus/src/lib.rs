@@ -6,6 +6,11 @@ pub use input::Input;
 mod state;
 pub use state::State;
 
+mod state_dump;
+pub use state_dump::{RoundVotesDump, StateDump, VoteTally};
+
+pub use malachitebft_core_state_machine::state::{RoundValue, Step};
+
 mod error;
 pub use error::Error;
 
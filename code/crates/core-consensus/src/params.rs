@@ -1,6 +1,6 @@
 use derive_where::derive_where;
 
-use malachitebft_core_types::{Context, ValuePayload};
+use malachitebft_core_types::{Context, SynchronyParams, ValuePayload};
 
 pub use malachitebft_core_driver::ThresholdParams;
 
@@ -21,4 +21,11 @@ pub struct Params<Ctx: Context> {
 
     /// The messages required to deliver proposals
     pub value_payload: ValuePayload,
+
+    /// The synchrony parameters for proposer-based timestamps (PBTS),
+    /// if the timeliness of proposals is to be checked
+    pub synchrony: Option<SynchronyParams>,
+
+    /// The height from which vote extensions are enabled, if any
+    pub vote_extensions_enable_height: Option<Ctx::Height>,
 }
@@ -8,6 +8,11 @@ use crate::input::Input;
 use crate::util::max_queue::MaxQueue;
 use crate::{FullProposal, FullProposalKeeper, Params, ProposedValue};
 
+/// Number of heights, including the current one, for which we remember the evidence
+/// of misbehavior already reported to the application, so that evidence still being
+/// gossiped after the height it was detected at is not reported twice.
+const EVIDENCE_RETENTION_HEIGHTS: u64 = 10;
+
 /// The state maintained by consensus for processing a [`Input`][crate::Input].
 pub struct State<Ctx>
 where
@@ -39,6 +44,10 @@ where
 
     /// Last precommit broadcasted by this node
     pub last_precommit: Option<SignedVote<Ctx>>,
+
+    /// Evidence of misbehavior reported to the application during the last few heights,
+    /// in canonical form, indexed by the height of the evidence
+    pub reported_evidence: BTreeMap<Ctx::Height, Vec<Evidence<Ctx>>>,
 }
 
 impl<Ctx> State<Ctx>
@@ -64,6 +73,7 @@ where
             decision: Default::default(),
             last_prevote: None,
             last_precommit: None,
+            reported_evidence: BTreeMap::new(),
         }
     }
 
@@ -83,6 +93,25 @@ where
         self.driver.validator_set()
     }
 
+    /// Update the parameters which can change from one height to the next,
+    /// eg. through on-chain governance. They take effect when the next height starts.
+    pub fn update_params(
+        &mut self,
+        threshold_params: ThresholdParams,
+        vote_extensions_enable_height: Option<Ctx::Height>,
+    ) {
+        self.params.threshold_params = threshold_params;
+        self.params.vote_extensions_enable_height = vote_extensions_enable_height;
+        self.driver.set_threshold_params(threshold_params);
+    }
+
+    /// Whether precommits for the given height carry vote extensions.
+    pub fn vote_extensions_enabled(&self, height: Ctx::Height) -> bool {
+        self.params
+            .vote_extensions_enable_height
+            .is_some_and(|enable_height| height >= enable_height)
+    }
+
     pub fn get_proposer(&self, height: Ctx::Height, round: Round) -> &Ctx::Address {
         self.ctx
             .select_proposer(self.validator_set(), height, round)
@@ -96,6 +125,52 @@ where
         }
     }
 
+    /// Whether the given evidence of misbehavior was already reported.
+    ///
+    /// Evidence older than the retention window is considered as reported,
+    /// since we cannot tell anymore whether it was.
+    pub fn is_evidence_reported(&self, evidence: &Evidence<Ctx>) -> bool {
+        if evidence.height() < self.min_evidence_height() {
+            return true;
+        }
+
+        let evidence = evidence.clone().canonical();
+
+        self.reported_evidence
+            .get(&evidence.height())
+            .is_some_and(|reported| reported.contains(&evidence))
+    }
+
+    /// Record evidence of misbehavior as reported,
+    /// returning `false` if it had already been reported.
+    pub fn record_evidence(&mut self, evidence: &Evidence<Ctx>) -> bool {
+        if self.is_evidence_reported(evidence) {
+            return false;
+        }
+
+        self.reported_evidence
+            .entry(evidence.height())
+            .or_default()
+            .push(evidence.clone().canonical());
+
+        true
+    }
+
+    /// Forget the evidence reported for heights that fell out of the retention window.
+    pub fn prune_reported_evidence(&mut self) {
+        let min_height = self.min_evidence_height();
+        self.reported_evidence = self.reported_evidence.split_off(&min_height);
+    }
+
+    /// The lowest height for which reported evidence is remembered.
+    fn min_evidence_height(&self) -> Ctx::Height {
+        let height = self.height();
+
+        height
+            .decrement_by(EVIDENCE_RETENTION_HEIGHTS - 1)
+            .unwrap_or_default()
+    }
+
     pub fn store_signed_precommit(&mut self, precommit: SignedVote<Ctx>) {
         assert_eq!(precommit.vote_type(), VoteType::Precommit);
 
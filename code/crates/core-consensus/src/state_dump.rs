@@ -0,0 +1,113 @@
+use derive_where::derive_where;
+
+use malachitebft_core_state_machine::state::{RoundValue, Step};
+use malachitebft_core_types::{Context, NilOrVal, Round, Validator, Value, ValueId, VotingPower};
+use malachitebft_core_votekeeper::count::VoteCount;
+
+use crate::State;
+
+/// A snapshot of the state of consensus at the current height, for debugging purposes.
+#[derive_where(Clone, Debug)]
+pub struct StateDump<Ctx: Context> {
+    /// The current height
+    pub height: Ctx::Height,
+
+    /// The current round
+    pub round: Round,
+
+    /// The step we are at within the current round
+    pub step: Step,
+
+    /// The proposer for the current round, if the round has started
+    pub proposer: Option<Ctx::Address>,
+
+    /// The value we are locked on, and the round at which we locked on it
+    pub locked: Option<RoundValue<ValueId<Ctx>>>,
+
+    /// The value for which we saw a polka, and the round at which we saw it
+    pub valid: Option<RoundValue<ValueId<Ctx>>>,
+
+    /// The validator set for the current height
+    pub validator_set: Ctx::ValidatorSet,
+
+    /// The votes received for each round of the current height, in order
+    pub votes: Vec<RoundVotesDump<Ctx>>,
+}
+
+/// The votes received for a round.
+#[derive_where(Clone, Debug)]
+pub struct RoundVotesDump<Ctx: Context> {
+    /// The round
+    pub round: Round,
+
+    /// Tally of the prevotes
+    pub prevotes: VoteTally<Ctx>,
+
+    /// Tally of the precommits
+    pub precommits: VoteTally<Ctx>,
+}
+
+/// Tally of the votes of a given type within a round.
+#[derive_where(Clone, Debug)]
+pub struct VoteTally<Ctx: Context> {
+    /// Voting power of the votes for each value, including nil
+    pub weights: Vec<(NilOrVal<ValueId<Ctx>>, VotingPower)>,
+
+    /// Addresses of the validators who voted
+    pub voters: Vec<Ctx::Address>,
+}
+
+impl<Ctx: Context> VoteTally<Ctx> {
+    fn new(count: &VoteCount<Ctx>) -> Self {
+        Self {
+            weights: count
+                .values_weights
+                .iter()
+                .map(|(value, weight)| (value.clone(), weight))
+                .collect(),
+            voters: count.validator_addresses.iter().cloned().collect(),
+        }
+    }
+
+    /// Total voting power of the votes, for any value or nil.
+    pub fn total(&self) -> VotingPower {
+        self.weights.iter().map(|(_, weight)| weight).sum()
+    }
+}
+
+impl<Ctx: Context> State<Ctx> {
+    /// Take a snapshot of the state of consensus at the current height.
+    pub fn dump(&self) -> StateDump<Ctx> {
+        let round_state = self.driver.round_state();
+
+        let value_id = |round_value: &RoundValue<Ctx::Value>| {
+            RoundValue::new(round_value.value.id(), round_value.round)
+        };
+
+        let votes = self
+            .driver
+            .votes()
+            .all_rounds()
+            .map(|(round, per_round)| RoundVotesDump {
+                round,
+                prevotes: VoteTally::new(per_round.votes().prevotes()),
+                precommits: VoteTally::new(per_round.votes().precommits()),
+            })
+            .collect();
+
+        StateDump {
+            height: self.height(),
+            round: self.round(),
+            step: self.driver.step(),
+            proposer: self
+                .driver
+                .get_proposer()
+                .ok()
+                .map(|proposer| proposer.address().clone()),
+            locked: round_state.locked.as_ref().map(value_id),
+            valid: round_state.valid.as_ref().map(value_id),
+            validator_set: self.validator_set().clone(),
+            votes,
+        }
+    }
+}
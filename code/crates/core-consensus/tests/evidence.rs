@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use malachitebft_core_types::{
+    Context, Evidence, NilOrVal, Round, SignedProposal, SignedVote, SigningProvider,
+};
+use malachitebft_metrics::Metrics;
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{
+    Address, Ed25519Provider, Height, PrivateKey, Proposal, TestContext, Validator, ValidatorSet,
+    Value, Vote,
+};
+
+use informalsystems_malachitebft_core_consensus::{
+    process, Effect, Error, Input, Resumable, Resume, State,
+};
+
+mod utils;
+
+/// What the node does in response to the effects
+#[derive(Default)]
+struct Env {
+    validator_sets: BTreeMap<Height, ValidatorSet>,
+    reported: Vec<Evidence<TestContext>>,
+    published: Vec<Evidence<TestContext>>,
+}
+
+impl Env {
+    fn handle_effect(
+        &mut self,
+        effect: Effect<TestContext>,
+    ) -> Result<Resume<TestContext>, Infallible> {
+        let resume = match effect {
+            Effect::GetValidatorSet(height, r) => {
+                r.resume_with(self.validator_sets.get(&height).cloned())
+            }
+            Effect::VerifySignature(_, _, r) => r.resume_with(true),
+            Effect::VerifySignatureBatch(votes, r) => r.resume_with(vec![true; votes.len()]),
+            Effect::ReportEvidence(evidence, r) => {
+                self.reported.push(evidence);
+                r.resume_with(())
+            }
+            Effect::PublishEvidence(evidence, r) => {
+                self.published.push(evidence);
+                r.resume_with(())
+            }
+            _ => Resume::Continue,
+        };
+
+        Ok(resume)
+    }
+}
+
+struct Node {
+    state: State<TestContext>,
+    metrics: Metrics,
+    env: Env,
+    validators: Vec<(Validator, PrivateKey)>,
+}
+
+impl Node {
+    /// Start height 1 as one of three validators.
+    fn new() -> Self {
+        let validators = make_validators([1, 1, 1]).to_vec();
+        let validator_set = ValidatorSet::new(validators.iter().map(|(v, _)| v.clone()));
+        let (us, _) = validators[1].clone();
+
+        let params = utils::params(&validator_set, us.address);
+
+        let mut node = Self {
+            state: State::new(TestContext::new(), params),
+            metrics: Metrics::new(),
+            env: Env::default(),
+            validators,
+        };
+
+        node.start_height(Height::new(1), validator_set);
+        node
+    }
+
+    fn start_height(&mut self, height: Height, validator_set: ValidatorSet) {
+        self.env
+            .validator_sets
+            .insert(height, validator_set.clone());
+        self.process(Input::StartHeight(height, validator_set));
+    }
+
+    fn process(&mut self, input: Input<TestContext>) {
+        let result: Result<(), Error<TestContext>> = process!(
+            input: input,
+            state: &mut self.state,
+            metrics: &self.metrics,
+            with: effect => self.env.handle_effect(effect)
+        );
+
+        result.unwrap();
+    }
+
+    fn signer(&self, address: &Address) -> Ed25519Provider {
+        let (_, private_key) = self
+            .validators
+            .iter()
+            .find(|(v, _)| &v.address == address)
+            .unwrap();
+
+        Ed25519Provider::new(private_key.clone())
+    }
+
+    /// A prevote of the given validator for the given value at round 0
+    fn prevote(&self, address: Address, height: u64, value: u64) -> SignedVote<TestContext> {
+        let vote = Vote::new_prevote(
+            Height::new(height),
+            Round::new(0),
+            NilOrVal::Val(Value::new(value).id()),
+            address,
+        );
+
+        self.signer(&address).sign_vote(vote).unwrap()
+    }
+
+    /// A proposal of the given validator for the given value at round 0
+    fn proposal(&self, address: Address, height: u64, value: u64) -> SignedProposal<TestContext> {
+        let proposal = Proposal::new(
+            Height::new(height),
+            Round::new(0),
+            Value::new(value),
+            Round::Nil,
+            address,
+        );
+
+        self.signer(&address).sign_proposal(proposal).unwrap()
+    }
+
+    fn equivocator(&self) -> Address {
+        self.validators[0].0.address
+    }
+}
+
+#[test]
+fn double_vote_is_reported_and_published() {
+    let mut node = Node::new();
+    let equivocator = node.equivocator();
+
+    let (first, second) = (
+        node.prevote(equivocator, 1, 1),
+        node.prevote(equivocator, 1, 2),
+    );
+    node.process(Input::Vote(first.clone()));
+    node.process(Input::Vote(second.clone()));
+
+    let evidence = Evidence::DoubleVote { first, second };
+    assert_eq!(node.env.reported, vec![evidence.clone()]);
+    assert_eq!(node.env.published, vec![evidence]);
+}
+
+#[test]
+fn evidence_from_peer_is_reported_but_not_published() {
+    let mut node = Node::new();
+    let equivocator = node.equivocator();
+
+    let evidence = Evidence::DoubleVote {
+        first: node.prevote(equivocator, 1, 1),
+        second: node.prevote(equivocator, 1, 2),
+    };
+
+    node.process(Input::Evidence(evidence.clone()));
+
+    assert_eq!(node.env.reported, vec![evidence]);
+    assert!(node.env.published.is_empty());
+}
+
+#[test]
+fn non_conflicting_evidence_is_dropped() {
+    let mut node = Node::new();
+    let equivocator = node.equivocator();
+
+    let evidence = Evidence::DoubleVote {
+        first: node.prevote(equivocator, 1, 1),
+        second: node.prevote(equivocator, 1, 1),
+    };
+
+    node.process(Input::Evidence(evidence));
+
+    assert!(node.env.reported.is_empty());
+}
+
+#[test]
+fn duplicate_evidence_is_reported_once() {
+    let mut node = Node::new();
+    let equivocator = node.equivocator();
+
+    let (first, second) = (
+        node.prevote(equivocator, 1, 1),
+        node.prevote(equivocator, 1, 2),
+    );
+    let evidence = Evidence::DoubleVote {
+        first: first.clone(),
+        second: second.clone(),
+    };
+
+    // Detected locally, then received from several peers
+    node.process(Input::Vote(first));
+    node.process(Input::Vote(second));
+    node.process(Input::Evidence(evidence.clone()));
+    node.process(Input::Evidence(evidence.clone()));
+
+    // Still gossiped after we moved to the next height
+    let validator_set = node.state.validator_set().clone();
+    node.start_height(Height::new(2), validator_set);
+    node.process(Input::Evidence(evidence.clone()));
+
+    assert_eq!(node.env.reported, vec![evidence]);
+}
+
+#[test]
+fn evidence_is_reported_once_in_either_order() {
+    let mut node = Node::new();
+    let equivocator = node.equivocator();
+
+    let (first, second) = (
+        node.prevote(equivocator, 1, 1),
+        node.prevote(equivocator, 1, 2),
+    );
+
+    // Detected locally, then received from a peer which saw the votes in the other order
+    node.process(Input::Vote(first.clone()));
+    node.process(Input::Vote(second.clone()));
+    node.process(Input::Evidence(Evidence::DoubleVote {
+        first: second.clone(),
+        second: first.clone(),
+    }));
+
+    assert_eq!(
+        node.env.reported,
+        vec![Evidence::DoubleVote { first, second }]
+    );
+
+    let proposer = TestContext::new()
+        .select_proposer(node.state.validator_set(), Height::new(1), Round::new(0))
+        .address;
+
+    let (first, second) = (node.proposal(proposer, 1, 1), node.proposal(proposer, 1, 2));
+
+    // Received from peers in both orders
+    node.process(Input::Evidence(Evidence::DoubleProposal {
+        first: first.clone(),
+        second: second.clone(),
+    }));
+    node.process(Input::Evidence(Evidence::DoubleProposal {
+        first: second,
+        second: first,
+    }));
+
+    assert_eq!(node.env.reported.len(), 2);
+}
+
+#[test]
+fn past_height_evidence_is_verified_against_its_validator_set() {
+    let mut node = Node::new();
+
+    let ctx = TestContext::new();
+    let validator_set = node.state.validator_set().clone();
+    let proposer = ctx
+        .select_proposer(&validator_set, Height::new(1), Round::new(0))
+        .address;
+
+    // The proposer at height 1 is not a validator anymore at height 2
+    let next_validator_set = ValidatorSet::new(
+        validator_set
+            .validators
+            .iter()
+            .filter(|v| v.address != proposer)
+            .cloned(),
+    );
+    node.start_height(Height::new(2), next_validator_set);
+
+    let double_proposal = Evidence::DoubleProposal {
+        first: node.proposal(proposer, 1, 1),
+        second: node.proposal(proposer, 1, 2),
+    };
+    let double_vote = Evidence::DoubleVote {
+        first: node.prevote(proposer, 1, 1),
+        second: node.prevote(proposer, 1, 2),
+    };
+
+    node.process(Input::Evidence(double_proposal.clone()));
+    node.process(Input::Evidence(double_vote.clone()));
+
+    assert_eq!(node.env.reported, vec![double_proposal, double_vote]);
+}
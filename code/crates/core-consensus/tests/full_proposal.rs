@@ -16,7 +16,7 @@ fn signed_proposal_pol(
     address: Address,
 ) -> SignedProposal<TestContext> {
     let proposal1 = Proposal::new(height, round, value, pol_round, address);
-    signing_provider.sign_proposal(proposal1)
+    signing_provider.sign_proposal(proposal1).unwrap()
 }
 
 fn prop(
@@ -43,7 +43,10 @@ fn prop_msg(
     value: u64,
     pol_round: i64,
 ) -> Input<TestContext> {
-    Input::Proposal(prop(signing_provider, address, round, value, pol_round))
+    Input::Proposal(
+        prop(signing_provider, address, round, value, pol_round),
+        None,
+    )
 }
 
 fn value(
@@ -275,7 +278,7 @@ fn full_proposal_keeper_tests() {
 
         for m in s.input {
             match m {
-                Input::Proposal(p) => keeper.store_proposal(p),
+                Input::Proposal(p, _) => keeper.store_proposal(p),
                 Input::ProposedValue(v, _) => keeper.store_value(&v),
                 _ => continue,
             }
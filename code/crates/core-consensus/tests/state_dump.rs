@@ -0,0 +1,77 @@
+use malachitebft_core_driver::Input as DriverInput;
+use malachitebft_core_types::{NilOrVal, Round, SigningProvider, ValuePayload};
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{Ed25519Provider, Height, TestContext, ValidatorSet, ValueId, Vote};
+
+use informalsystems_malachitebft_core_consensus::{Params, State, Step};
+
+mod utils;
+
+#[test]
+fn dump_votes_per_round() {
+    let [(v1, sk1), (v2, sk2), (v3, _)] = make_validators([1, 2, 3]);
+    let validator_set = ValidatorSet::new(vec![v1.clone(), v2.clone(), v3.clone()]);
+
+    let params = Params {
+        value_payload: ValuePayload::PartsOnly,
+        ..utils::params(&validator_set, v1.address)
+    };
+
+    let mut state = State::new(TestContext::new(), params);
+
+    let dump = state.dump();
+    assert_eq!(dump.height, Height::new(1));
+    assert_eq!(dump.round, Round::Nil);
+    assert_eq!(dump.step, Step::Unstarted);
+    assert_eq!(dump.proposer, None);
+    assert!(dump.votes.is_empty());
+
+    state
+        .driver
+        .process(DriverInput::NewRound(
+            Height::new(1),
+            Round::new(0),
+            v3.address,
+        ))
+        .unwrap();
+
+    let value_id = ValueId::new(42);
+
+    let prevote1 = Ed25519Provider::new(sk1)
+        .sign_vote(Vote::new_prevote(
+            Height::new(1),
+            Round::new(0),
+            NilOrVal::Val(value_id),
+            v1.address,
+        ))
+        .unwrap();
+
+    let prevote2 = Ed25519Provider::new(sk2)
+        .sign_vote(Vote::new_prevote(
+            Height::new(1),
+            Round::new(0),
+            NilOrVal::Nil,
+            v2.address,
+        ))
+        .unwrap();
+
+    state.driver.process(DriverInput::Vote(prevote1)).unwrap();
+    state.driver.process(DriverInput::Vote(prevote2)).unwrap();
+
+    let dump = state.dump();
+    assert_eq!(dump.round, Round::new(0));
+    assert_eq!(dump.proposer, Some(v3.address));
+    assert_eq!(dump.locked, None);
+    assert_eq!(dump.votes.len(), 1);
+
+    let votes = &dump.votes[0];
+    assert_eq!(votes.round, Round::new(0));
+    assert_eq!(votes.prevotes.total(), 3);
+    assert!(votes
+        .prevotes
+        .weights
+        .contains(&(NilOrVal::Val(value_id), 1)));
+    assert!(votes.prevotes.weights.contains(&(NilOrVal::Nil, 2)));
+    assert_eq!(votes.prevotes.voters.len(), 2);
+    assert_eq!(votes.precommits.total(), 0);
+}
@@ -0,0 +1,288 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use malachitebft_core_types::{
+    Context, NilOrVal, Proposal as _, Round, SignedProposal, SigningProvider, SynchronyParams,
+    Timestamp, Validity, ValueOrigin, Vote as _, VoteType,
+};
+use malachitebft_metrics::Metrics;
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{
+    Address, Ed25519Provider, Height, PrivateKey, Proposal, TestContext, ValidatorSet, Value,
+    ValueId,
+};
+
+use informalsystems_malachitebft_core_consensus::{
+    process, Effect, Error, Input, LocallyProposedValue, Params, ProposedValue, Resumable, Resume,
+    SignedConsensusMsg, State,
+};
+
+mod utils;
+
+const NOW: Timestamp = Timestamp::from_millis(10_000);
+
+const SYNCHRONY: SynchronyParams = SynchronyParams {
+    precision: Duration::from_millis(500),
+    message_delay: Duration::from_secs(2),
+};
+
+/// What the node does in response to the effects
+struct Env {
+    validator_set: ValidatorSet,
+    signing_provider: Ed25519Provider,
+    now: Option<Timestamp>,
+    published: Vec<SignedConsensusMsg<TestContext>>,
+}
+
+impl Env {
+    fn handle_effect(
+        &mut self,
+        effect: Effect<TestContext>,
+    ) -> Result<Resume<TestContext>, Infallible> {
+        let resume = match effect {
+            Effect::GetValidatorSet(_, r) => r.resume_with(Some(self.validator_set.clone())),
+            Effect::VerifySignature(_, _, r) => r.resume_with(true),
+            Effect::ValidateProposal(_, r) => r.resume_with(Validity::Valid),
+            Effect::GetTime(r) => r.resume_with(self.now),
+            Effect::SignVote(vote, r) => {
+                r.resume_with(self.signing_provider.sign_vote(vote).unwrap())
+            }
+            Effect::SignProposal(proposal, r) => {
+                r.resume_with(self.signing_provider.sign_proposal(proposal).unwrap())
+            }
+            Effect::Publish(msg, r) => {
+                self.published.push(msg);
+                r.resume_with(())
+            }
+            _ => Resume::Continue,
+        };
+
+        Ok(resume)
+    }
+}
+
+struct Node {
+    state: State<TestContext>,
+    metrics: Metrics,
+    env: Env,
+}
+
+impl Node {
+    /// Start height 1 as the validator with the given address and private key.
+    fn start(validator_set: &ValidatorSet, address: Address, private_key: PrivateKey) -> Self {
+        let mut node = Node::new(validator_set, address, private_key);
+        node.process(Input::StartHeight(Height::new(1), validator_set.clone()));
+        node
+    }
+
+    /// Set up the validator with the given address and private key, without starting height 1.
+    fn new(validator_set: &ValidatorSet, address: Address, private_key: PrivateKey) -> Self {
+        let params = Params {
+            synchrony: Some(SYNCHRONY),
+            ..utils::params(validator_set, address)
+        };
+
+        Node {
+            state: State::new(TestContext::new(), params),
+            metrics: Metrics::new(),
+            env: Env {
+                validator_set: validator_set.clone(),
+                signing_provider: Ed25519Provider::new(private_key),
+                now: Some(NOW),
+                published: Vec::new(),
+            },
+        }
+    }
+
+    fn process(&mut self, input: Input<TestContext>) {
+        let result: Result<(), Error<TestContext>> = process!(
+            input: input,
+            state: &mut self.state,
+            metrics: &self.metrics,
+            with: effect => self.env.handle_effect(effect)
+        );
+
+        result.unwrap();
+    }
+
+    fn published_prevotes(&self) -> Vec<NilOrVal<ValueId>> {
+        self.env
+            .published
+            .iter()
+            .filter_map(|msg| match msg {
+                SignedConsensusMsg::Vote(vote) if vote.vote_type() == VoteType::Prevote => {
+                    Some(*vote.value())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn published_proposals(&self) -> Vec<SignedProposal<TestContext>> {
+        self.env
+            .published
+            .iter()
+            .filter_map(|msg| match msg {
+                SignedConsensusMsg::Proposal(proposal) => Some(proposal.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Receive the proposal for round 0 of height 1, with the given timestamp, along with its value,
+/// as a validator which is not the proposer, and return the prevotes it published.
+///
+/// If `start_delay` is given, the proposal is received before height 1 is started,
+/// which happens after that delay.
+fn receive_proposal(
+    timestamp: Option<Timestamp>,
+    start_delay: Option<Duration>,
+) -> Vec<NilOrVal<ValueId>> {
+    let validators = make_validators([1, 1, 1]);
+    let validator_set = ValidatorSet::new(validators.iter().map(|(v, _)| v.clone()));
+
+    let proposer = TestContext::new()
+        .select_proposer(&validator_set, Height::new(1), Round::new(0))
+        .address;
+
+    let private_key = |address| {
+        let (_, sk) = validators
+            .iter()
+            .find(|(v, _)| v.address == address)
+            .unwrap();
+        sk.clone()
+    };
+
+    let (us, _) = validators
+        .iter()
+        .find(|(v, _)| v.address != proposer)
+        .unwrap();
+
+    let mut node = match start_delay {
+        Some(_) => Node::new(&validator_set, us.address, private_key(us.address)),
+        None => Node::start(&validator_set, us.address, private_key(us.address)),
+    };
+
+    let proposal = Proposal::new(
+        Height::new(1),
+        Round::new(0),
+        Value::new(42),
+        Round::Nil,
+        proposer,
+    );
+
+    let proposal = match timestamp {
+        Some(timestamp) => proposal.with_timestamp(timestamp),
+        None => proposal,
+    };
+
+    let signed_proposal = Ed25519Provider::new(private_key(proposer))
+        .sign_proposal(proposal)
+        .unwrap();
+
+    node.process(Input::Proposal(signed_proposal, None));
+
+    if let Some(start_delay) = start_delay {
+        node.env.now = Some(NOW.saturating_add(start_delay));
+        node.process(Input::StartHeight(Height::new(1), validator_set.clone()));
+    }
+
+    let value = ProposedValue {
+        height: Height::new(1),
+        round: Round::new(0),
+        valid_round: Round::Nil,
+        proposer,
+        value: Value::new(42),
+        validity: Validity::Valid,
+    };
+
+    node.process(Input::ProposedValue(value, ValueOrigin::Consensus));
+    node.published_prevotes()
+}
+
+#[test]
+fn prevote_timely_proposal() {
+    let prevotes = receive_proposal(Some(NOW.saturating_sub(Duration::from_secs(1))), None);
+    assert_eq!(prevotes, vec![NilOrVal::Val(Value::new(42).id())]);
+}
+
+#[test]
+fn prevote_nil_for_late_proposal() {
+    let prevotes = receive_proposal(Some(NOW.saturating_sub(Duration::from_secs(3))), None);
+    assert_eq!(prevotes, vec![NilOrVal::Nil]);
+}
+
+#[test]
+fn prevote_nil_for_proposal_without_timestamp() {
+    let prevotes = receive_proposal(None, None);
+    assert_eq!(prevotes, vec![NilOrVal::Nil]);
+}
+
+#[test]
+fn prevote_timely_proposal_received_before_start() {
+    // The proposal is timely when received, but would not be by the time the height starts
+    let timestamp = NOW.saturating_sub(Duration::from_secs(1));
+    let prevotes = receive_proposal(Some(timestamp), Some(Duration::from_secs(3)));
+    assert_eq!(prevotes, vec![NilOrVal::Val(Value::new(42).id())]);
+}
+
+/// Start height 1 as the proposer of its first round.
+fn start_proposer() -> Node {
+    let validators = make_validators([1, 1, 1]);
+    let validator_set = ValidatorSet::new(validators.iter().map(|(v, _)| v.clone()));
+
+    let proposer = TestContext::new()
+        .select_proposer(&validator_set, Height::new(1), Round::new(0))
+        .address;
+
+    let (_, private_key) = validators
+        .iter()
+        .find(|(v, _)| v.address == proposer)
+        .unwrap();
+
+    Node::start(&validator_set, proposer, private_key.clone())
+}
+
+fn propose_value() -> Input<TestContext> {
+    Input::Propose(LocallyProposedValue::new(
+        Height::new(1),
+        Round::new(0),
+        Value::new(42),
+    ))
+}
+
+#[test]
+fn timestamp_own_proposal() {
+    let mut node = start_proposer();
+    node.process(propose_value());
+
+    let timestamps = node
+        .published_proposals()
+        .iter()
+        .map(|proposal| proposal.timestamp())
+        .collect::<Vec<_>>();
+
+    assert_eq!(timestamps, vec![Some(NOW)]);
+}
+
+#[test]
+fn restart_proposer() {
+    let mut node = start_proposer();
+    node.process(propose_value());
+
+    let proposals = node.published_proposals();
+    assert_eq!(proposals.len(), 1);
+
+    // Restart, and replay our proposal from the WAL, at which point the time is not known
+    let mut node = start_proposer();
+    node.env.now = None;
+    node.process(Input::Proposal(proposals[0].clone(), None));
+
+    // Get the value to propose from the application again, once recovered
+    node.env.now = Some(NOW.saturating_add(Duration::from_secs(5)));
+    node.process(propose_value());
+
+    // The exact same proposal must be signed again, lest we equivocate
+    assert_eq!(node.published_proposals(), proposals);
+}
@@ -0,0 +1,51 @@
+use malachitebft_core_types::{ThresholdParam, ThresholdParams, ValuePayload};
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{Height, TestContext, ValidatorSet};
+
+use informalsystems_malachitebft_core_consensus::{Params, State};
+
+mod utils;
+
+fn new_state() -> State<TestContext> {
+    let [(v1, _), (v2, _), (v3, _)] = make_validators([1, 1, 1]);
+    let validator_set = ValidatorSet::new(vec![v1.clone(), v2, v3]);
+
+    let params = Params {
+        value_payload: ValuePayload::PartsOnly,
+        ..utils::params(&validator_set, v1.address)
+    };
+
+    State::new(TestContext::new(), params)
+}
+
+#[test]
+fn vote_extensions_enabled_from_height() {
+    let mut state = new_state();
+
+    assert!(!state.vote_extensions_enabled(Height::new(1)));
+    assert!(!state.vote_extensions_enabled(Height::new(100)));
+
+    state.update_params(ThresholdParams::default(), Some(Height::new(5)));
+
+    assert!(!state.vote_extensions_enabled(Height::new(4)));
+    assert!(state.vote_extensions_enabled(Height::new(5)));
+    assert!(state.vote_extensions_enabled(Height::new(6)));
+}
+
+#[test]
+fn threshold_params_apply_from_next_height() {
+    let mut state = new_state();
+
+    let threshold_params = ThresholdParams {
+        quorum: ThresholdParam::new(3, 4),
+        honest: ThresholdParam::new(1, 2),
+    };
+
+    state.update_params(threshold_params, None);
+    assert_eq!(state.params.threshold_params, threshold_params);
+
+    let validator_set = state.validator_set().clone();
+    state.driver.move_to_height(Height::new(2), validator_set);
+
+    assert_eq!(state.driver.votes().threshold_params(), threshold_params);
+}
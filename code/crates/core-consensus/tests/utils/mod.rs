@@ -0,0 +1,18 @@
+use malachitebft_core_types::{ThresholdParams, ValuePayload};
+use malachitebft_test::{Address, Height, TestContext, ValidatorSet};
+
+use informalsystems_malachitebft_core_consensus::Params;
+
+/// Parameters to start consensus at height 1 as the validator with the given address,
+/// with the default thresholds, proposals and parts, and without synchrony nor vote extensions.
+pub fn params(validator_set: &ValidatorSet, address: Address) -> Params<TestContext> {
+    Params {
+        initial_height: Height::new(1),
+        initial_validator_set: validator_set.clone(),
+        address,
+        threshold_params: ThresholdParams::default(),
+        value_payload: ValuePayload::ProposalAndParts,
+        synchrony: None,
+        vote_extensions_enable_height: None,
+    }
+}
@@ -0,0 +1,174 @@
+use std::convert::Infallible;
+
+use malachitebft_core_types::{
+    NilOrVal, Round, SigningProvider, ValidatorSet as _, Validity, Vote as _, VoteType,
+};
+use malachitebft_metrics::Metrics;
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{
+    Ed25519Provider, Height, Proposal, TestContext, ValidatorSet, Value, ValueId,
+};
+
+use informalsystems_malachitebft_core_consensus::{
+    process, Effect, Error, Input, Resumable, Resume, SignedConsensusMsg, State,
+};
+
+mod utils;
+
+/// What the node does in response to the effects
+struct Env {
+    validator_set: ValidatorSet,
+    signing_provider: Ed25519Provider,
+    published: Vec<SignedConsensusMsg<TestContext>>,
+    validated: Vec<Proposal>,
+    wal: Vec<SignedConsensusMsg<TestContext>>,
+}
+
+impl Env {
+    fn handle_effect(
+        &mut self,
+        effect: Effect<TestContext>,
+        validity: Validity,
+    ) -> Result<Resume<TestContext>, Infallible> {
+        let resume = match effect {
+            Effect::GetValidatorSet(_, r) => r.resume_with(Some(self.validator_set.clone())),
+            Effect::VerifySignature(_, _, r) => r.resume_with(true),
+            Effect::ValidateProposal(proposal, r) => {
+                self.validated.push(proposal.message);
+                r.resume_with(validity)
+            }
+            Effect::WalAppendMessage(msg, r) => {
+                self.wal.push(msg);
+                r.resume_with(())
+            }
+            Effect::SignVote(vote, r) => {
+                r.resume_with(self.signing_provider.sign_vote(vote).unwrap())
+            }
+            Effect::Publish(msg, r) => {
+                self.published.push(msg);
+                r.resume_with(())
+            }
+            _ => Resume::Continue,
+        };
+
+        Ok(resume)
+    }
+}
+
+struct Node {
+    state: State<TestContext>,
+    metrics: Metrics,
+    env: Env,
+}
+
+impl Node {
+    fn process(&mut self, input: Input<TestContext>, validity: Validity) {
+        let result: Result<(), Error<TestContext>> = process!(
+            input: input,
+            state: &mut self.state,
+            metrics: &self.metrics,
+            with: effect => self.env.handle_effect(effect, validity)
+        );
+
+        result.unwrap();
+    }
+
+    fn published_prevotes(&self) -> Vec<NilOrVal<ValueId>> {
+        self.env
+            .published
+            .iter()
+            .filter_map(|msg| match msg {
+                SignedConsensusMsg::Vote(vote) if vote.vote_type() == VoteType::Prevote => {
+                    Some(*vote.value())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Start height 1 as a validator which is not the proposer, and receive the proposal
+/// with the given round and POL round, which the application deems to be of the given validity.
+fn receive_proposal(round: Round, pol_round: Round, validity: Validity) -> Node {
+    let validators = make_validators([1, 1, 1]);
+    let validator_set = ValidatorSet::new(validators.iter().map(|(v, _)| v.clone()));
+
+    let proposer = validator_set.get_by_index(0).unwrap().clone();
+    let us = validator_set.get_by_index(1).unwrap().clone();
+
+    let private_key = |address| {
+        let (_, sk) = validators
+            .iter()
+            .find(|(v, _)| v.address == address)
+            .unwrap();
+        sk.clone()
+    };
+
+    let params = utils::params(&validator_set, us.address);
+
+    let mut node = Node {
+        state: State::new(TestContext::new(), params),
+        metrics: Metrics::new(),
+        env: Env {
+            validator_set: validator_set.clone(),
+            signing_provider: Ed25519Provider::new(private_key(us.address)),
+            published: Vec::new(),
+            validated: Vec::new(),
+            wal: Vec::new(),
+        },
+    };
+
+    node.process(
+        Input::StartHeight(Height::new(1), validator_set),
+        Validity::Valid,
+    );
+
+    let proposal = Ed25519Provider::new(private_key(proposer.address))
+        .sign_proposal(Proposal::new(
+            Height::new(1),
+            round,
+            Value::new(42),
+            pol_round,
+            proposer.address,
+        ))
+        .unwrap();
+
+    node.process(Input::Proposal(proposal, None), validity);
+    node
+}
+
+#[test]
+fn prevote_nil_for_rejected_proposal() {
+    let node = receive_proposal(Round::new(0), Round::Nil, Validity::Invalid);
+
+    // The proposal was rejected before its value was received
+    assert_eq!(node.published_prevotes(), vec![NilOrVal::Nil]);
+}
+
+#[test]
+fn persist_rejected_proposal() {
+    let node = receive_proposal(Round::new(0), Round::Nil, Validity::Invalid);
+
+    // The rejected proposal was appended to the WAL before prevoting nil
+    assert!(matches!(
+        node.env.wal.as_slice(),
+        [SignedConsensusMsg::Proposal(proposal)] if proposal.value() == &Value::new(42)
+    ));
+}
+
+#[test]
+fn do_not_validate_reproposal() {
+    let node = receive_proposal(Round::new(1), Round::new(0), Validity::Invalid);
+
+    // The value of a re-proposal was already validated in its POL round
+    assert!(node.env.validated.is_empty());
+    assert!(node.published_prevotes().is_empty());
+}
+
+#[test]
+fn wait_for_value_of_accepted_proposal() {
+    let node = receive_proposal(Round::new(0), Round::Nil, Validity::Valid);
+
+    // The value of the proposal has not been received yet
+    assert!(node.published_prevotes().is_empty());
+}
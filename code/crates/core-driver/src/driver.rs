@@ -95,6 +95,11 @@ where
         }
     }
 
+    /// Set the quorum thresholds to use from the next height onwards.
+    pub fn set_threshold_params(&mut self, threshold_params: ThresholdParams) {
+        self.threshold_params = threshold_params;
+    }
+
     /// Reset votes, round state, pending input
     /// and move to new height with the given validator set.
     pub fn move_to_height(&mut self, height: Ctx::Height, validator_set: Ctx::ValidatorSet) {
@@ -182,6 +187,13 @@ where
         self.proposal_keeper.evidence()
     }
 
+    /// Record that the proposal for the given value at the given round of this height
+    /// was not received in a timely manner, as per proposer-based timestamps (PBTS),
+    /// so that it is not prevoted for once it is applied.
+    pub fn mark_untimely(&mut self, round: Round, value_id: ValueId<Ctx>) {
+        self.proposal_keeper.mark_untimely(round, value_id);
+    }
+
     /// Return the proposer for the current round.
     pub fn get_proposer(&self) -> Result<&Ctx::Validator, Error<Ctx>> {
         if let Some(proposer) = &self.proposer {
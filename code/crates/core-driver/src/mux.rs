@@ -20,6 +20,7 @@
 //! | any             | PrecommitAny          | \*              | PrecommitAny                    | any (unchanged) | L47            | sch\_precommit\_timer              |
 //! | propose         | none                  | InvalidProposal | InvalidProposal                 | prevote         | L22, L26       | prevote\_nil                       |
 //! | propose         | none                  | Proposal        | Proposal                        | prevote         | L22, L24       | prevote(v)                         |
+//! | propose         | none                  | Untimely(v)     | UntimelyProposal                | prevote         | L22, L26       | prevote\_nil                       |
 //! | propose         | PolkaPrevious(v, vr)  | InvalidProposal | InvalidProposalAndPolkaPrevious | prevote         | L28, L33       | prevote\_nil                       |
 //! | propose         | PolkaPrevious(v, vr)  | Proposal(v,vr)  | ProposalAndPolkaPrevious        | prevote         | L28, L30       | prevote(v)                         |
 //! | prevote         | PolkaNil              | \*              | PolkaNil                        | precommit       | L44            | precommit\_nil                     |
@@ -59,11 +60,11 @@ where
     /// 1. Check that there is an ongoing round, otherwise return `None`
     ///
     /// 2. If the proposal is invalid, the method follows these steps:
-    ///    a. If we are at propose step and the proposal's proof-of-lock (POL) round is `Nil`, return
-    ///       `RoundInput::InvalidProposal`.
-    ///    b. If we are at propose step and there is a polka for a prior-round proof-of-lock (POL),
-    ///       return `RoundInput::InvalidProposalAndPolkaPrevious`.
-    ///    c. For other steps or if there is no prior-round POL, return `None`.
+    ///    - If we are at propose step and the proposal's proof-of-lock (POL) round is `Nil`, return
+    ///      `RoundInput::InvalidProposal`.
+    ///    - If we are at propose step and there is a polka for a prior-round proof-of-lock (POL),
+    ///      return `RoundInput::InvalidProposalAndPolkaPrevious`.
+    ///    - For other steps or if there is no prior-round POL, return `None`.
     ///
     /// 3. If a quorum of precommit votes is met for the proposal's value,
     ///    return `RoundInput::ProposalAndPrecommitValue` including the proposal.
@@ -76,7 +77,9 @@ where
     /// 6. If we are at the propose step, and a polka exists for a the propopsal's POL round,
     ///    return `RoundInput::ProposalAndPolkaPrevious`, including the proposal.
     ///
-    /// 7. If none of the above conditions are met, simply wrap the proposal in
+    /// 7. If the proposal was not received in a timely manner, return `RoundInput::UntimelyProposal`.
+    ///
+    /// 8. If none of the above conditions are met, simply wrap the proposal in
     ///    `RoundInput::Proposal` and return it.
     pub(crate) fn multiplex_proposal(
         &mut self,
@@ -162,6 +165,14 @@ where
         }
 
         if proposal.pol_round().is_nil() {
+            // L22 + L26, only proposals received in a timely manner are prevoted for
+            if !self
+                .proposal_keeper
+                .is_timely(proposal.round(), &proposal.value().id())
+            {
+                return Some(RoundInput::UntimelyProposal);
+            }
+
             // L22
             return Some(RoundInput::Proposal(proposal));
         }
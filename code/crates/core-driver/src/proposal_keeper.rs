@@ -1,13 +1,13 @@
 //! For storing proposals.
 
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec;
 use alloc::vec::Vec;
 
 use derive_where::derive_where;
 use thiserror::Error;
 
-use malachitebft_core_types::{Context, Proposal, Round, SignedProposal, Validity};
+use malachitebft_core_types::{Context, Proposal, Round, SignedProposal, Validity, ValueId};
 
 /// Errors can that be yielded when recording a proposal.
 #[derive_where(Debug)]
@@ -42,6 +42,9 @@ where
 {
     /// The proposal received in a given round (proposal.round) if any.
     proposal: Option<(SignedProposal<Ctx>, Validity)>,
+
+    /// The values of the proposals which were not received in a timely manner.
+    untimely: BTreeSet<ValueId<Ctx>>,
 }
 
 impl<Ctx> PerRound<Ctx>
@@ -117,6 +120,22 @@ where
             .and_then(|round_info| round_info.proposal.as_ref())
     }
 
+    /// Record that the proposal for the given value at the round was not received in a timely manner.
+    pub fn mark_untimely(&mut self, round: Round, value_id: ValueId<Ctx>) {
+        self.per_round
+            .entry(round)
+            .or_default()
+            .untimely
+            .insert(value_id);
+    }
+
+    /// Whether the proposal for the given value at the round was received in a timely manner.
+    pub fn is_timely(&self, round: Round, value_id: &ValueId<Ctx>) -> bool {
+        self.per_round
+            .get(&round)
+            .is_none_or(|round_info| !round_info.untimely.contains(value_id))
+    }
+
     /// Return the evidence of equivocation.
     pub fn evidence(&self) -> &EvidenceMap<Ctx> {
         &self.evidence
@@ -66,7 +66,7 @@ pub fn output_to_input(
 ) -> Option<Input<TestContext>> {
     match output {
         Output::NewRound(height, round) => {
-            let proposer = sel.select_proposer(height, round, vs);
+            let proposer = sel.select_proposer(vs, height, round)?.address;
             Some(Input::NewRound(height, round, proposer))
         }
         // Let's consider our own proposal to always be valid
@@ -1,4 +1,5 @@
 pub mod basic;
 pub mod extra;
+pub mod pbts;
 
 mod utils;
@@ -0,0 +1,233 @@
+use malachitebft_core_state_machine::state::{RoundValue, State, Step};
+use malachitebft_core_types::{Round, Validity};
+
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{Height, TestContext, ValidatorSet, Value};
+
+use informalsystems_malachitebft_core_driver::{Driver, Input, Output};
+
+use crate::utils::*;
+
+// The following tests check how untimely proposals are handled, as per proposer-based timestamps (PBTS):
+//
+// - L26 in round 0, untimely proposal without a POL round:
+//     `driver_steps_untimely_proposal_prevote_nil()`
+//
+// - L36 in round 0, via L26, untimely proposal followed by a polka for its value:
+//     `driver_steps_untimely_proposal_then_polka_value()`
+//
+// - L28 in round 1, untimely proposal with a POL round from round 0:
+//     `driver_steps_untimely_proposal_with_pol_round()`
+
+struct TestStep {
+    desc: &'static str,
+    input: Input<TestContext>,
+    expected_outputs: Vec<Output<TestContext>>,
+    expected_round: Round,
+    new_state: State<TestContext>,
+}
+
+// Arrive at L26 with an untimely proposal without a POL round
+//
+// Ev:             NewRound(0)           UntimelyProposal
+// State: NewRound ------------> Propose ----------------> Prevote
+// Msg:            propose_timer         prevote(nil)
+// Alg:            L21                   L22, L26
+//
+// v1=2, v2=3, v3=2, we are v3
+// L21 - v3 is not proposer starts propose timer (step propose)
+// L26 - v3 receives an untimely proposal, prevotes nil (step prevote)
+#[test]
+fn driver_steps_untimely_proposal_prevote_nil() {
+    let value = Value::new(9999);
+
+    let [(v1, _sk1), (v2, _sk2), (v3, _sk3)] = make_validators([2, 3, 2]);
+    let my_addr = v3.address;
+
+    let height = Height::new(1);
+    let ctx = TestContext::new();
+    let vs = ValidatorSet::new(vec![v1.clone(), v2.clone(), v3.clone()]);
+
+    let mut driver = Driver::new(ctx, height, vs, my_addr, Default::default());
+
+    // Consensus marks the proposal as untimely when it receives it
+    driver.mark_untimely(Round::new(0), value.id());
+
+    let steps = vec![
+        TestStep {
+            desc: "Start round 0, we, v3, are not the proposer, start timeout propose",
+            input: new_round_input(Round::new(0), v1.address),
+            expected_outputs: vec![start_propose_timer_output(Round::new(0))],
+            expected_round: Round::new(0),
+            new_state: propose_state(Round::new(0)),
+        },
+        TestStep {
+            desc: "Receive an untimely proposal from v1, prevote nil",
+            input: proposal_input(
+                Round::new(0),
+                value,
+                Round::Nil,
+                Validity::Valid,
+                v1.address,
+            ),
+            expected_outputs: vec![prevote_nil_output(Round::new(0), &my_addr)],
+            expected_round: Round::new(0),
+            new_state: prevote_state(Round::new(0)),
+        },
+    ];
+
+    run_steps(&mut driver, steps);
+}
+
+// Arrive at L36 after prevoting nil for an untimely proposal
+//
+// Ev:             NewRound(0)           UntimelyProposal          <polkaValue>
+// State: NewRound ------------> Propose ----------------> Prevote ------------> Precommit
+// Msg:            propose_timer         prevote(nil)              precommit(v)
+// Alg:            L21                   L22, L26                  L36, L37-L43
+//
+// v1=2, v2=3, v3=2, we are v3
+// L21 - v3 is not proposer starts propose timer (step propose)
+// L26 - v3 receives an untimely proposal, prevotes nil (step prevote)
+// L36 - v3 gets +2/3 prevotes for the value of the proposal (from v1 and v2), precommits it (step precommit)
+#[test]
+fn driver_steps_untimely_proposal_then_polka_value() {
+    let value = Value::new(9999);
+
+    let [(v1, _sk1), (v2, _sk2), (v3, _sk3)] = make_validators([2, 3, 2]);
+    let my_addr = v3.address;
+
+    let height = Height::new(1);
+    let ctx = TestContext::new();
+    let vs = ValidatorSet::new(vec![v1.clone(), v2.clone(), v3.clone()]);
+
+    let mut driver = Driver::new(ctx, height, vs, my_addr, Default::default());
+
+    // Consensus marks the proposal as untimely when it receives it
+    driver.mark_untimely(Round::new(0), value.id());
+
+    let steps = vec![
+        TestStep {
+            desc: "Start round 0, we, v3, are not the proposer, start timeout propose",
+            input: new_round_input(Round::new(0), v1.address),
+            expected_outputs: vec![start_propose_timer_output(Round::new(0))],
+            expected_round: Round::new(0),
+            new_state: propose_state(Round::new(0)),
+        },
+        TestStep {
+            desc: "Receive an untimely proposal from v1, prevote nil",
+            input: proposal_input(
+                Round::new(0),
+                value.clone(),
+                Round::Nil,
+                Validity::Valid,
+                v1.address,
+            ),
+            expected_outputs: vec![prevote_nil_output(Round::new(0), &my_addr)],
+            expected_round: Round::new(0),
+            new_state: prevote_state(Round::new(0)),
+        },
+        TestStep {
+            desc: "v1 prevotes the proposal",
+            input: prevote_input(value.clone(), &v1.address),
+            expected_outputs: vec![],
+            expected_round: Round::new(0),
+            new_state: prevote_state(Round::new(0)),
+        },
+        TestStep {
+            desc: "v2 prevotes the proposal, we get +2/3 prevotes for it, precommit the value",
+            input: prevote_input(value.clone(), &v2.address),
+            expected_outputs: vec![precommit_output(Round::new(0), value.clone(), &my_addr)],
+            expected_round: Round::new(0),
+            new_state: State {
+                height: Height::new(1),
+                round: Round::new(0),
+                step: Step::Precommit,
+                valid: Some(RoundValue::new(value.clone(), Round::new(0))),
+                locked: Some(RoundValue::new(value, Round::new(0))),
+                ..Default::default()
+            },
+        },
+    ];
+
+    run_steps(&mut driver, steps);
+}
+
+// Arrive at L28 with an untimely proposal which has a POL round
+//
+// Ev:             NewRound(1)           <polkaValue(0)>          UntimelyProposal(pol_round=0)
+// State: NewRound ------------> Propose ----------------> Propose ----------------------------> Prevote
+// Msg:            propose_timer         None                     prevote(v)
+// Alg:            L21                                            L28-L30
+//
+// v1=2, v2=3, v3=2, we are v3
+// L21 - v3 is not proposer starts propose timer (step propose)
+// v3 gets +2/3 prevotes for v in round 0 (from v1 and v2), nothing happens (step propose)
+// L28 - v3 receives an untimely proposal for v with POL round 0, timeliness is not checked, prevotes v (step prevote)
+#[test]
+fn driver_steps_untimely_proposal_with_pol_round() {
+    let value = Value::new(9999);
+
+    let [(v1, _sk1), (v2, _sk2), (v3, _sk3)] = make_validators([2, 3, 2]);
+    let my_addr = v3.address;
+
+    let height = Height::new(1);
+    let ctx = TestContext::new();
+    let vs = ValidatorSet::new(vec![v1.clone(), v2.clone(), v3.clone()]);
+
+    let mut driver = Driver::new(ctx, height, vs, my_addr, Default::default());
+
+    // Consensus marks the proposal as untimely when it receives it
+    driver.mark_untimely(Round::new(1), value.id());
+
+    let steps = vec![
+        TestStep {
+            desc: "Start round 1, we, v3, are not the proposer, start timeout propose",
+            input: new_round_input(Round::new(1), v1.address),
+            expected_outputs: vec![start_propose_timer_output(Round::new(1))],
+            expected_round: Round::new(1),
+            new_state: propose_state(Round::new(1)),
+        },
+        TestStep {
+            desc: "v1 prevotes a value in round 0",
+            input: prevote_input_at(Round::new(0), value.clone(), &v1.address),
+            expected_outputs: vec![],
+            expected_round: Round::new(1),
+            new_state: propose_state(Round::new(1)),
+        },
+        TestStep {
+            desc: "v2 prevotes the same value in round 0, we get +2/3 prevotes for it in round 0",
+            input: prevote_input_at(Round::new(0), value.clone(), &v2.address),
+            expected_outputs: vec![],
+            expected_round: Round::new(1),
+            new_state: propose_state(Round::new(1)),
+        },
+        TestStep {
+            desc: "Receive an untimely proposal from v1 with POL round 0, prevote the value",
+            input: proposal_input(
+                Round::new(1),
+                value.clone(),
+                Round::new(0),
+                Validity::Valid,
+                v1.address,
+            ),
+            expected_outputs: vec![prevote_output(Round::new(1), value, &my_addr)],
+            expected_round: Round::new(1),
+            new_state: prevote_state(Round::new(1)),
+        },
+    ];
+
+    run_steps(&mut driver, steps);
+}
+
+fn run_steps(driver: &mut Driver<TestContext>, steps: Vec<TestStep>) {
+    for step in steps {
+        println!("Step: {}", step.desc);
+
+        let outputs = driver.process(step.input).expect("execute succeeded");
+
+        assert_eq!(outputs, step.expected_outputs, "expected outputs");
+        assert_eq!(driver.round(), step.expected_round, "expected round");
+        assert_eq!(driver.round_state(), &step.new_state, "expected state");
+    }
+}
@@ -29,6 +29,11 @@ where
     /// L26 + L32 (invalid)
     InvalidProposal,
 
+    /// Receive a valid proposal which was not received in a timely manner,
+    /// as per proposer-based timestamps (PBTS).
+    /// L22 + L26 (untimely)
+    UntimelyProposal,
+
     /// Received a proposal and a polka value from a previous round.
     /// L28 + L29 (valid)
     ProposalAndPolkaPrevious(Ctx::Proposal),
@@ -124,6 +124,13 @@ where
         // L22 with invalid proposal
         (Step::Propose, Input::InvalidProposal) if this_round => prevote_nil(state, info.address),
 
+        // L22 with untimely proposal
+        (Step::Propose, Input::UntimelyProposal) if this_round => {
+            debug_trace!(state, Line::L26Untimely);
+
+            prevote_nil(state, info.address)
+        }
+
         // L28 with valid proposal
         (Step::Propose, Input::ProposalAndPolkaPrevious(proposal))
             if this_round && is_valid_pol_round(&state, proposal.pol_round()) =>
@@ -36,6 +36,9 @@ pub enum Line {
     /// L26 - prevote nil: valid(v) and lockedValue != v
     L26ValidAndLockedValue,
 
+    /// L26 - prevote nil: valid(v) and not timely(proposal)
+    L26Untimely,
+
     /// L28 - valid proposal
     L28ValidProposal,
 
@@ -1,9 +1,13 @@
+use alloc::collections::BTreeSet;
 use alloc::vec::Vec;
+use core::fmt::Debug;
+
 use derive_where::derive_where;
 use thiserror::Error;
 
 use crate::{
-    Context, NilOrVal, Round, Signature, SignedVote, ValueId, Vote, VoteType, VotingPower,
+    Context, NilOrVal, Round, Signature, SignedVote, SigningProvider, Validator, ValidatorSet,
+    ValueId, Vote, VoteType, VotingPower,
 };
 
 /// Represents a signature for a certificate, including the address and the signature itself.
@@ -22,7 +26,32 @@ impl<Ctx: Context> CommitSignature<Ctx> {
     }
 }
 
-/// Aggregated signature.
+/// Defines the requirements for the signature carried by a [`CommitCertificate`].
+///
+/// The certificate signature is built from the precommit signatures of the validators
+/// that committed to the certified value. It can either keep each signature around,
+/// as [`AggregatedSignature`] does, or combine them into a single signature,
+/// eg. when using a signing scheme which supports signature aggregation, like BLS.
+pub trait CertificateSignature<Ctx>
+where
+    Self: Clone + Debug + Eq + Send + Sync,
+    Ctx: Context,
+{
+    /// Build a certificate signature from the given commit signatures,
+    /// issued by validators in the given validator set.
+    fn aggregate(signatures: Vec<CommitSignature<Ctx>>, validator_set: &Ctx::ValidatorSet) -> Self;
+
+    /// The number of validators whose signature is part of the certificate signature.
+    fn signers_count(&self) -> usize;
+
+    /// The addresses of the validators whose signature is part of the certificate signature,
+    /// given the validator set which issued the certificate.
+    ///
+    /// This does not verify the signature, see [`SigningProvider::verify_certificate_signature`].
+    fn signers(&self, validator_set: &Ctx::ValidatorSet) -> Vec<Ctx::Address>;
+}
+
+/// Aggregated signature, consisting of the list of commit signatures of each signer.
 #[derive_where(Clone, Debug, PartialEq, Eq)]
 pub struct AggregatedSignature<Ctx: Context> {
     /// A collection of commit signatures.
@@ -34,6 +63,91 @@ impl<Ctx: Context> AggregatedSignature<Ctx> {
     pub fn new(signatures: Vec<CommitSignature<Ctx>>) -> Self {
         Self { signatures }
     }
+
+    /// Verify each commit signature against the public key of its validator in the given validator set.
+    ///
+    /// All signatures are verified at once with [`SigningProvider::verify_batch`].
+    ///
+    /// ## Return
+    /// Return the total voting power of the validators that signed, if all signatures are valid
+    /// and no validator signed more than once.
+    pub fn verify<P>(
+        &self,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+        provider: &P,
+    ) -> Result<VotingPower, CertificateError<Ctx>>
+    where
+        P: SigningProvider<Ctx> + ?Sized,
+    {
+        let mut validators = Vec::with_capacity(self.signatures.len());
+        let mut precommits = Vec::with_capacity(self.signatures.len());
+        let mut signers = BTreeSet::new();
+
+        // For each commit signature, reconstruct the signed precommit
+        for commit_sig in &self.signatures {
+            // Abort if validator not in validator set
+            let Some(validator) = validator_set.get_by_address(&commit_sig.address) else {
+                return Err(CertificateError::UnknownValidator(commit_sig.clone()));
+            };
+
+            // Abort if validator already signed, so that its voting power is only counted once
+            if !signers.insert(&commit_sig.address) {
+                return Err(CertificateError::DuplicateVote(commit_sig.clone()));
+            }
+
+            let precommit = Ctx::new_precommit(
+                certificate.height,
+                certificate.round,
+                NilOrVal::Val(certificate.value_id.clone()),
+                commit_sig.address.clone(),
+            );
+
+            validators.push(validator);
+            precommits.push(precommit);
+        }
+
+        let batch = self
+            .signatures
+            .iter()
+            .zip(&precommits)
+            .zip(&validators)
+            .map(|((commit_sig, precommit), validator)| {
+                (precommit, &commit_sig.signature, validator.public_key())
+            })
+            .collect::<Vec<_>>();
+
+        // Verify all signatures at once
+        let results = provider.verify_batch(&batch);
+
+        if let Some(index) = results.iter().position(|valid| !valid) {
+            return Err(CertificateError::InvalidSignature(
+                self.signatures[index].clone(),
+            ));
+        }
+
+        Ok(validators.iter().map(|v| v.voting_power()).sum())
+    }
+}
+
+impl<Ctx: Context> CertificateSignature<Ctx> for AggregatedSignature<Ctx> {
+    fn aggregate(
+        signatures: Vec<CommitSignature<Ctx>>,
+        _validator_set: &Ctx::ValidatorSet,
+    ) -> Self {
+        Self::new(signatures)
+    }
+
+    fn signers_count(&self) -> usize {
+        self.signatures.len()
+    }
+
+    fn signers(&self, _validator_set: &Ctx::ValidatorSet) -> Vec<Ctx::Address> {
+        self.signatures
+            .iter()
+            .map(|commit_sig| commit_sig.address.clone())
+            .collect()
+    }
 }
 
 /// Represents a certificate containing the message (height, round, value_id) and an aggregated signature.
@@ -45,17 +159,19 @@ pub struct CommitCertificate<Ctx: Context> {
     pub round: Round,
     /// The identifier for the value being certified.
     pub value_id: ValueId<Ctx>,
-    /// A vector of signatures that make up the certificate.
-    pub aggregated_signature: AggregatedSignature<Ctx>, // TODO - type in context
+    /// The signature of the validators that committed to the value.
+    pub aggregated_signature: Ctx::CertificateSignature,
 }
 
 impl<Ctx: Context> CommitCertificate<Ctx> {
-    /// Creates a new `CommitCertificate` from a vector of signed votes.
+    /// Creates a new `CommitCertificate` from a vector of signed votes,
+    /// issued by validators in the given validator set.
     pub fn new(
         height: Ctx::Height,
         round: Round,
         value_id: ValueId<Ctx>,
         commits: Vec<SignedVote<Ctx>>,
+        validator_set: &Ctx::ValidatorSet,
     ) -> Self {
         // Collect all commit signatures from the signed votes
         let commit_signatures = commits
@@ -75,7 +191,8 @@ impl<Ctx: Context> CommitCertificate<Ctx> {
             .collect();
 
         // Create the aggregated signature
-        let aggregated_signature = AggregatedSignature::new(commit_signatures);
+        let aggregated_signature =
+            Ctx::CertificateSignature::aggregate(commit_signatures, validator_set);
 
         Self {
             height,
@@ -98,6 +215,19 @@ pub enum CertificateError<Ctx: Context> {
     #[error("A validator in the certificate is not in the validator set: {0:?}")]
     UnknownValidator(CommitSignature<Ctx>),
 
+    /// A validator signed the certificate more than once.
+    #[error("A validator signed the certificate more than once: {0:?}")]
+    DuplicateVote(CommitSignature<Ctx>),
+
+    /// The aggregate signature of the certificate is invalid.
+    #[error("Invalid aggregate signature")]
+    InvalidAggregateSignature,
+
+    /// The signers of the aggregate signature do not match the validator set,
+    /// eg. because the certificate refers to a validator index which is out of bounds.
+    #[error("The signers of the certificate do not match the validator set")]
+    InvalidSigners,
+
     /// Not enough voting power has signed the certificate.
     #[error(
         "Not enough voting power has signed the certificate: \
@@ -112,3 +242,379 @@ pub enum CertificateError<Ctx: Context> {
         expected: VotingPower,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::fmt;
+
+    use super::*;
+    use crate::{
+        Address, Height, Proposal, ProposalPart, SignedExtension, SignedMessage, SigningError,
+        SigningScheme, Value,
+    };
+
+    // A minimal context, whose signatures are the address of their signer.
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Ctx;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Addr(u8);
+
+    impl fmt::Display for Addr {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Address for Addr {}
+
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    struct Ht(u64);
+
+    impl fmt::Display for Ht {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Height for Ht {
+        const ZERO: Self = Ht(0);
+        const INITIAL: Self = Ht(1);
+
+        fn increment_by(&self, n: u64) -> Self {
+            Ht(self.0 + n)
+        }
+
+        fn decrement_by(&self, n: u64) -> Option<Self> {
+            self.0.checked_sub(n).map(Ht)
+        }
+
+        fn as_u64(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Val(u64);
+
+    impl Value for Val {
+        type Id = u64;
+
+        fn id(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Part;
+
+    impl ProposalPart<Ctx> for Part {
+        fn is_first(&self) -> bool {
+            true
+        }
+
+        fn is_last(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Prop {
+        height: Ht,
+        round: Round,
+        value: Val,
+        pol_round: Round,
+        address: Addr,
+    }
+
+    impl Proposal<Ctx> for Prop {
+        fn height(&self) -> Ht {
+            self.height
+        }
+
+        fn round(&self) -> Round {
+            self.round
+        }
+
+        fn value(&self) -> &Val {
+            &self.value
+        }
+
+        fn take_value(self) -> Val {
+            self.value
+        }
+
+        fn pol_round(&self) -> Round {
+            self.pol_round
+        }
+
+        fn validator_address(&self) -> &Addr {
+            &self.address
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Precommit {
+        height: Ht,
+        round: Round,
+        value: NilOrVal<u64>,
+        address: Addr,
+    }
+
+    impl Vote<Ctx> for Precommit {
+        fn height(&self) -> Ht {
+            self.height
+        }
+
+        fn round(&self) -> Round {
+            self.round
+        }
+
+        fn value(&self) -> &NilOrVal<u64> {
+            &self.value
+        }
+
+        fn take_value(self) -> NilOrVal<u64> {
+            self.value
+        }
+
+        fn vote_type(&self) -> VoteType {
+            VoteType::Precommit
+        }
+
+        fn validator_address(&self) -> &Addr {
+            &self.address
+        }
+
+        fn extension(&self) -> Option<&SignedExtension<Ctx>> {
+            None
+        }
+
+        fn take_extension(&mut self) -> Option<SignedExtension<Ctx>> {
+            None
+        }
+
+        fn extend(self, _extension: SignedExtension<Ctx>) -> Self {
+            self
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct TestValidator {
+        address: Addr,
+        voting_power: VotingPower,
+    }
+
+    impl Validator<Ctx> for TestValidator {
+        fn address(&self) -> &Addr {
+            &self.address
+        }
+
+        fn public_key(&self) -> &Addr {
+            &self.address
+        }
+
+        fn voting_power(&self) -> VotingPower {
+            self.voting_power
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct TestValidatorSet(Vec<TestValidator>);
+
+    impl ValidatorSet<Ctx> for TestValidatorSet {
+        fn count(&self) -> usize {
+            self.0.len()
+        }
+
+        fn total_voting_power(&self) -> VotingPower {
+            self.0.iter().map(|v| v.voting_power).sum()
+        }
+
+        fn get_by_address(&self, address: &Addr) -> Option<&TestValidator> {
+            self.0.iter().find(|v| &v.address == address)
+        }
+
+        fn get_by_index(&self, index: usize) -> Option<&TestValidator> {
+            self.0.get(index)
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Scheme;
+
+    impl SigningScheme for Scheme {
+        type DecodingError = &'static str;
+        type Signature = Addr;
+        type PublicKey = Addr;
+        type PrivateKey = Addr;
+
+        fn decode_signature(bytes: &[u8]) -> Result<Addr, Self::DecodingError> {
+            bytes.first().copied().map(Addr).ok_or("empty signature")
+        }
+
+        fn encode_signature(signature: &Addr) -> Vec<u8> {
+            vec![signature.0]
+        }
+    }
+
+    impl Context for Ctx {
+        type Address = Addr;
+        type Height = Ht;
+        type ProposalPart = Part;
+        type Proposal = Prop;
+        type Validator = TestValidator;
+        type ValidatorSet = TestValidatorSet;
+        type Value = Val;
+        type Vote = Precommit;
+        type Extension = ();
+        type SigningScheme = Scheme;
+        type CertificateSignature = AggregatedSignature<Ctx>;
+
+        fn select_proposer<'a>(
+            &self,
+            validator_set: &'a TestValidatorSet,
+            _: Ht,
+            _: Round,
+        ) -> &'a TestValidator {
+            &validator_set.0[0]
+        }
+
+        fn new_proposal(
+            height: Ht,
+            round: Round,
+            value: Val,
+            pol_round: Round,
+            address: Addr,
+        ) -> Prop {
+            Prop {
+                height,
+                round,
+                value,
+                pol_round,
+                address,
+            }
+        }
+
+        fn new_prevote(_: Ht, _: Round, _: NilOrVal<u64>, _: Addr) -> Precommit {
+            unimplemented!()
+        }
+
+        fn new_precommit(
+            height: Ht,
+            round: Round,
+            value: NilOrVal<u64>,
+            address: Addr,
+        ) -> Precommit {
+            Precommit {
+                height,
+                round,
+                value,
+                address,
+            }
+        }
+    }
+
+    struct Provider;
+
+    impl SigningProvider<Ctx> for Provider {
+        fn sign_vote(&self, vote: Precommit) -> Result<SignedVote<Ctx>, SigningError> {
+            let signature = vote.address;
+            Ok(SignedMessage::new(vote, signature))
+        }
+
+        fn verify_signed_vote(
+            &self,
+            vote: &Precommit,
+            signature: &Addr,
+            public_key: &Addr,
+        ) -> bool {
+            &vote.address == signature && signature == public_key
+        }
+
+        fn sign_proposal(&self, _: Prop) -> Result<SignedMessage<Ctx, Prop>, SigningError> {
+            unimplemented!()
+        }
+
+        fn verify_signed_proposal(&self, _: &Prop, _: &Addr, _: &Addr) -> bool {
+            unimplemented!()
+        }
+
+        fn sign_proposal_part(&self, _: Part) -> Result<SignedMessage<Ctx, Part>, SigningError> {
+            unimplemented!()
+        }
+
+        fn verify_signed_proposal_part(&self, _: &Part, _: &Addr, _: &Addr) -> bool {
+            unimplemented!()
+        }
+
+        fn sign_vote_extension(&self, _: ()) -> Result<SignedMessage<Ctx, ()>, SigningError> {
+            unimplemented!()
+        }
+
+        fn verify_signed_vote_extension(&self, _: &(), _: &Addr, _: &Addr) -> bool {
+            unimplemented!()
+        }
+
+        fn verify_certificate_signature(
+            &self,
+            certificate: &CommitCertificate<Ctx>,
+            validator_set: &TestValidatorSet,
+        ) -> Result<VotingPower, CertificateError<Ctx>> {
+            certificate
+                .aggregated_signature
+                .verify(certificate, validator_set, self)
+        }
+    }
+
+    fn validator_set() -> TestValidatorSet {
+        TestValidatorSet(
+            (1..=4)
+                .map(|i| TestValidator {
+                    address: Addr(i),
+                    voting_power: 1,
+                })
+                .collect(),
+        )
+    }
+
+    fn certificate(signers: &[u8]) -> CommitCertificate<Ctx> {
+        let commits = signers
+            .iter()
+            .map(|&i| {
+                let precommit =
+                    Ctx::new_precommit(Ht(1), Round::new(0), NilOrVal::Val(42), Addr(i));
+                Provider.sign_vote(precommit).unwrap()
+            })
+            .collect();
+
+        CommitCertificate::new(Ht(1), Round::new(0), 42, commits, &validator_set())
+    }
+
+    #[test]
+    fn verify_aggregated_signature() {
+        let certificate = certificate(&[1, 2, 3]);
+
+        let signed = Provider
+            .verify_certificate_signature(&certificate, &validator_set())
+            .unwrap();
+
+        assert_eq!(signed, 3);
+    }
+
+    #[test]
+    fn reject_duplicate_signers() {
+        // Validator 1 signs three times, which would otherwise make up for a quorum
+        let certificate = certificate(&[1, 1, 1]);
+
+        assert!(matches!(
+            Provider.verify_certificate_signature(&certificate, &validator_set()),
+            Err(CertificateError::DuplicateVote(CommitSignature {
+                address: Addr(1),
+                ..
+            }))
+        ));
+    }
+}
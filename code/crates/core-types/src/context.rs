@@ -1,6 +1,6 @@
 use crate::{
-    Address, Extension, Height, NilOrVal, Proposal, ProposalPart, Round, SigningScheme, Validator,
-    ValidatorSet, Value, ValueId, Vote,
+    Address, CertificateSignature, Extension, Height, NilOrVal, Proposal, ProposalPart, Round,
+    SigningScheme, Validator, ValidatorSet, Value, ValueId, Vote,
 };
 
 /// This trait allows to abstract over the various datatypes
@@ -40,7 +40,16 @@ where
     /// The signing scheme used to sign consensus messages.
     type SigningScheme: SigningScheme;
 
+    /// The type of signature carried by commit certificates.
+    ///
+    /// Use [`AggregatedSignature`](crate::AggregatedSignature) to keep the signature
+    /// of each validator, or a scheme-specific type to aggregate them into a single one.
+    type CertificateSignature: CertificateSignature<Self>;
+
     /// Select a proposer in the validator set for the given height and round.
+    ///
+    /// See [`ProposerSelector`](crate::ProposerSelector) for reusable implementations,
+    /// such as the stake-weighted [`WeightedRoundRobin`](crate::WeightedRoundRobin).
     fn select_proposer<'a>(
         &self,
         validator_set: &'a Self::ValidatorSet,
@@ -0,0 +1,102 @@
+use derive_where::derive_where;
+
+use crate::{Context, Proposal, Round, SignedProposal, SignedVote, Value, Vote};
+
+/// Evidence of misbehavior by a validator.
+///
+/// Evidence consists of two conflicting messages signed by the same validator,
+/// and can be included by the application in a block, eg. to slash the offending validator.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub enum Evidence<Ctx>
+where
+    Ctx: Context,
+{
+    /// Two votes of the same type, issued by the same validator
+    /// for different values at the same height and round.
+    DoubleVote {
+        /// The vote that was received first
+        first: SignedVote<Ctx>,
+        /// The conflicting vote
+        second: SignedVote<Ctx>,
+    },
+
+    /// Two proposals, issued by the same validator
+    /// for different values at the same height and round.
+    DoubleProposal {
+        /// The proposal that was received first
+        first: SignedProposal<Ctx>,
+        /// The conflicting proposal
+        second: SignedProposal<Ctx>,
+    },
+}
+
+impl<Ctx> Evidence<Ctx>
+where
+    Ctx: Context,
+{
+    /// The height at which the misbehavior occurred.
+    pub fn height(&self) -> Ctx::Height {
+        match self {
+            Self::DoubleVote { first, .. } => first.height(),
+            Self::DoubleProposal { first, .. } => first.height(),
+        }
+    }
+
+    /// The round at which the misbehavior occurred.
+    pub fn round(&self) -> Round {
+        match self {
+            Self::DoubleVote { first, .. } => first.round(),
+            Self::DoubleProposal { first, .. } => first.round(),
+        }
+    }
+
+    /// The address of the misbehaving validator.
+    pub fn validator_address(&self) -> &Ctx::Address {
+        match self {
+            Self::DoubleVote { first, .. } => first.validator_address(),
+            Self::DoubleProposal { first, .. } => first.validator_address(),
+        }
+    }
+
+    /// The same evidence, with its two messages ordered by signature rather than by reception,
+    /// so that evidence received with its messages in either order can be recognized as the same.
+    pub fn canonical(self) -> Self {
+        match self {
+            Self::DoubleVote { first, second } if second.signature < first.signature => {
+                Self::DoubleVote {
+                    first: second,
+                    second: first,
+                }
+            }
+            Self::DoubleProposal { first, second } if second.signature < first.signature => {
+                Self::DoubleProposal {
+                    first: second,
+                    second: first,
+                }
+            }
+            evidence => evidence,
+        }
+    }
+
+    /// Whether the two messages actually conflict with each other, ie. whether they were issued
+    /// by the same validator, for the same height, round and vote type, but for different values.
+    ///
+    /// This does not check the signatures of the messages.
+    pub fn is_conflicting(&self) -> bool {
+        match self {
+            Self::DoubleVote { first, second } => {
+                first.height() == second.height()
+                    && first.round() == second.round()
+                    && first.vote_type() == second.vote_type()
+                    && first.validator_address() == second.validator_address()
+                    && first.value() != second.value()
+            }
+            Self::DoubleProposal { first, second } => {
+                first.height() == second.height()
+                    && first.round() == second.round()
+                    && first.validator_address() == second.validator_address()
+                    && first.value().id() != second.value().id()
+            }
+        }
+    }
+}
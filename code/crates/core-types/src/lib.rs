@@ -15,13 +15,16 @@ extern crate alloc;
 
 mod certificate;
 mod context;
+mod evidence;
 mod height;
 mod proposal;
 mod proposal_part;
+mod proposer_selector;
 mod round;
 mod signed_message;
 mod signing;
 mod threshold;
+mod time;
 mod timeout;
 mod validator_set;
 mod value;
@@ -53,15 +56,20 @@ pub type SignedProposalPart<Ctx> = SignedMessage<Ctx, <Ctx as Context>::Proposal
 /// A signed vote extension
 pub type SignedExtension<Ctx> = SignedMessage<Ctx, <Ctx as Context>::Extension>;
 
-pub use certificate::{AggregatedSignature, CertificateError, CommitCertificate, CommitSignature};
+pub use certificate::{
+    AggregatedSignature, CertificateError, CertificateSignature, CommitCertificate, CommitSignature,
+};
 pub use context::Context;
+pub use evidence::Evidence;
 pub use height::Height;
 pub use proposal::{Proposal, Validity};
 pub use proposal_part::ProposalPart;
+pub use proposer_selector::{ProposerSelector, WeightedRoundRobin};
 pub use round::Round;
 pub use signed_message::SignedMessage;
-pub use signing::{SigningProvider, SigningProviderExt, SigningScheme};
+pub use signing::{SigningError, SigningProvider, SigningProviderExt, SigningScheme};
 pub use threshold::{Threshold, ThresholdParam, ThresholdParams};
+pub use time::{SynchronyParams, Timestamp};
 pub use timeout::{Timeout, TimeoutKind};
 pub use validator_set::{Address, Validator, ValidatorSet, VotingPower};
 pub use value::{NilOrVal, Value, ValueOrigin, ValuePayload};
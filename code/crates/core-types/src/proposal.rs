@@ -1,6 +1,6 @@
 use core::fmt::Debug;
 
-use crate::{Context, Round};
+use crate::{Context, Round, Timestamp};
 
 /// Defines the requirements for a proposal type.
 pub trait Proposal<Ctx>
@@ -25,6 +25,23 @@ where
 
     /// Address of the validator who issued this proposal
     fn validator_address(&self) -> &Ctx::Address;
+
+    /// Time at which the value was proposed, as set by the proposer.
+    ///
+    /// Used to check that the proposal was received in a timely manner when
+    /// proposer-based timestamps are enabled. Proposals without a timestamp
+    /// are then considered untimely.
+    fn timestamp(&self) -> Option<Timestamp> {
+        None
+    }
+
+    /// Set the time at which the value was proposed, when proposer-based timestamps are enabled.
+    ///
+    /// The default implementation ignores the timestamp, so contexts must override it
+    /// along with [`Proposal::timestamp`] for their proposals to be considered timely.
+    fn with_timestamp(self, _timestamp: Timestamp) -> Self {
+        self
+    }
 }
 
 /// Whether or not a proposal is valid.
@@ -0,0 +1,159 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use derive_where::derive_where;
+
+use crate::{Context, Height, Round, Validator, ValidatorSet};
+
+/// Defines how to select a proposer amongst a validator set for a given height and round.
+///
+/// A context can delegate [`Context::select_proposer`] to an implementation of this trait.
+pub trait ProposerSelector<Ctx>
+where
+    Self: Send + Sync,
+    Ctx: Context,
+{
+    /// Select a proposer from the given validator set for the given height and round.
+    ///
+    /// This function is called at the beginning of each round to select the proposer for that
+    /// round. The proposer is responsible for proposing a value for the round.
+    ///
+    /// # Important
+    /// This function must be deterministic!
+    /// For a given height, round and validator set, it must always return the same proposer.
+    ///
+    /// Returns `None` if there is no validator to select from, ie. if the validator set is empty.
+    fn select_proposer<'a>(
+        &self,
+        validator_set: &'a Ctx::ValidatorSet,
+        height: Ctx::Height,
+        round: Round,
+    ) -> Option<&'a Ctx::Validator>;
+}
+
+/// Stake-weighted round-robin proposer selection, using the proposer priorities of CometBFT.
+///
+/// Each validator has a priority, initially zero. At each step, the priority of every validator
+/// is increased by its voting power, and the validator with the highest priority is selected,
+/// ties being broken in favor of the smallest address. The priority of the selected validator
+/// is then decreased by the total voting power. There is one step per height and one per round.
+///
+/// Over any sequence of steps as long as the total voting power, each validator is therefore
+/// selected as many times as its voting power, and the small validators propose in between
+/// the turns of the big ones instead of after all of them. When all validators have the same
+/// voting power, they take turns in the order of their addresses.
+///
+/// CometBFT keeps the priorities in the validator set and updates them at every height.
+/// Here they are instead replayed from the initial priorities, so that the proposer only depends
+/// on the height, the round and the validator set, and is deterministic across nodes regardless
+/// of the heights they have seen. As the priorities are back to zero after as many steps as the
+/// total voting power, once divided by the greatest common divisor of the voting powers, only
+/// the steps since the start of the current cycle are replayed. For the same reason, they never
+/// need to be centered nor rescaled like in CometBFT.
+///
+/// A context opts in by delegating to it, ie. with
+/// `WeightedRoundRobin::<Self>::new().select_proposer(validator_set, height, round)`
+/// in its implementation of [`Context::select_proposer`], which only returns `None`
+/// for an empty validator set.
+#[derive_where(Copy, Clone, Debug, Default)]
+pub struct WeightedRoundRobin<Ctx> {
+    marker: PhantomData<fn() -> Ctx>,
+}
+
+impl<Ctx: Context> WeightedRoundRobin<Ctx> {
+    /// Create a new weighted round-robin proposer selector.
+    pub fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Ctx: Context> ProposerSelector<Ctx> for WeightedRoundRobin<Ctx> {
+    fn select_proposer<'a>(
+        &self,
+        validator_set: &'a Ctx::ValidatorSet,
+        height: Ctx::Height,
+        round: Round,
+    ) -> Option<&'a Ctx::Validator> {
+        let validators = (0..validator_set.count())
+            .filter_map(|index| validator_set.get_by_index(index))
+            .collect::<Vec<_>>();
+
+        let index = select_weighted::<Ctx>(&validators, height, round)?;
+        validators.get(index).copied()
+    }
+}
+
+/// Index of the proposer amongst the given validators, as per [`WeightedRoundRobin`].
+fn select_weighted<Ctx: Context>(
+    validators: &[&Ctx::Validator],
+    height: Ctx::Height,
+    round: Round,
+) -> Option<usize> {
+    if validators.is_empty() {
+        return None;
+    }
+
+    let divisor = validators
+        .iter()
+        .fold(0, |divisor, v| gcd(divisor, v.voting_power()));
+
+    // No voting power at all, fall back to a plain round-robin
+    if divisor == 0 {
+        let index = steps::<Ctx>(height, round, validators.len() as u128);
+        return usize::try_from(index).ok();
+    }
+
+    let powers = validators
+        .iter()
+        .map(|v| i128::from(v.voting_power() / divisor))
+        .collect::<Vec<_>>();
+
+    let total = powers.iter().sum::<i128>();
+    let mut priorities = vec![0; powers.len()];
+    let mut proposer = 0;
+
+    for _ in 0..=steps::<Ctx>(height, round, total.unsigned_abs()) {
+        for (priority, power) in priorities.iter_mut().zip(&powers) {
+            *priority += power;
+        }
+
+        proposer = (0..validators.len()).max_by(|&a, &b| {
+            priorities[a]
+                .cmp(&priorities[b])
+                .then_with(|| validators[b].address().cmp(validators[a].address()))
+        })?;
+
+        priorities[proposer] -= total;
+    }
+
+    Some(proposer)
+}
+
+/// Greatest common divisor of the given voting powers.
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Number of steps since the initial height and round, modulo the given cycle length.
+fn steps<Ctx: Context>(height: Ctx::Height, round: Round, cycle: u128) -> u128 {
+    if cycle == 0 {
+        return 0;
+    }
+
+    let heights = u128::from(
+        height
+            .as_u64()
+            .saturating_sub(Ctx::Height::INITIAL.as_u64()),
+    );
+
+    let rounds = u128::from(round.as_u32().unwrap_or(0));
+
+    (heights % cycle + rounds % cycle) % cycle
+}
@@ -1,10 +1,13 @@
 use alloc::boxed::Box;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt::{Debug, Display};
 
+use thiserror::Error;
+
 use crate::{
-    CertificateError, CommitCertificate, CommitSignature, Context, PublicKey, Signature,
-    SignedMessage, ThresholdParams, VotingPower,
+    CertificateError, CommitCertificate, CommitSignature, Context, NilOrVal, PublicKey, Signature,
+    SignedMessage, ThresholdParams, Validator, VotingPower,
 };
 
 /// A signing scheme that can be used to sign votes and verify such signatures.
@@ -38,6 +41,26 @@ where
     fn encode_signature(signature: &Self::Signature) -> Vec<u8>;
 }
 
+/// An error which can occur when signing a message.
+///
+/// Signing can fail eg. when the private key is held by a remote signer which cannot be reached,
+/// or which refuses to sign a message because doing so could lead to double-signing.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("Failed to sign message: {0}")]
+pub struct SigningError(String);
+
+impl SigningError {
+    /// Create a new `SigningError` with the given description.
+    pub fn new(description: impl Display) -> Self {
+        Self(description.to_string())
+    }
+
+    /// The description of the error.
+    pub fn description(&self) -> &str {
+        &self.0
+    }
+}
+
 /// A provider of signing functionality for the consensus engine.
 ///
 /// This trait defines the core signing operations needed by the engine,
@@ -53,7 +76,7 @@ where
     Self: Send + Sync + 'static,
 {
     /// Sign the given vote with our private key.
-    fn sign_vote(&self, vote: Ctx::Vote) -> SignedMessage<Ctx, Ctx::Vote>;
+    fn sign_vote(&self, vote: Ctx::Vote) -> Result<SignedMessage<Ctx, Ctx::Vote>, SigningError>;
 
     /// Verify the given vote's signature using the given public key.
     fn verify_signed_vote(
@@ -64,7 +87,24 @@ where
     ) -> bool;
 
     /// Sign the given proposal with our private key.
-    fn sign_proposal(&self, proposal: Ctx::Proposal) -> SignedMessage<Ctx, Ctx::Proposal>;
+    fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError>;
+
+    /// Sign the given implicit proposal with our private key.
+    ///
+    /// Implicit proposals are built internally on behalf of the actual proposer,
+    /// and are never published nor verified. They may be signed after we voted in their round,
+    /// so providers which protect against double-signing must not subject them to that protection.
+    ///
+    /// The default implementation signs them like any other proposal.
+    fn sign_implicit_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
+        self.sign_proposal(proposal)
+    }
 
     /// Verify the given proposal's signature using the given public key.
     fn verify_signed_proposal(
@@ -78,7 +118,7 @@ where
     fn sign_proposal_part(
         &self,
         proposal_part: Ctx::ProposalPart,
-    ) -> SignedMessage<Ctx, Ctx::ProposalPart>;
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, SigningError>;
 
     /// Verify the given proposal part signature using the given public key.
     fn verify_signed_proposal_part(
@@ -89,7 +129,10 @@ where
     ) -> bool;
 
     /// Sign the given vote extension with our private key.
-    fn sign_vote_extension(&self, extension: Ctx::Extension) -> SignedMessage<Ctx, Ctx::Extension>;
+    fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, SigningError>;
 
     /// Verify the given vote extension's signature using the given public key.
     fn verify_signed_vote_extension(
@@ -99,8 +142,28 @@ where
         public_key: &PublicKey<Ctx>,
     ) -> bool;
 
+    /// Verify the signatures of a batch of votes, each against the corresponding public key.
+    ///
+    /// Signing schemes which support batch verification should override this method,
+    /// as verifying many signatures at once is significantly faster than verifying them
+    /// one by one. The default implementation verifies each signature individually.
+    ///
+    /// ## Return
+    /// Return whether each signature is valid, in the same order as the given votes.
+    fn verify_batch(&self, votes: &[(&Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)]) -> Vec<bool> {
+        votes
+            .iter()
+            .map(|(vote, signature, public_key)| {
+                self.verify_signed_vote(vote, signature, public_key)
+            })
+            .collect()
+    }
+
     /// Verify a commit signature in a certificate against the public key of its validator.
     ///
+    /// The default implementation reconstructs the precommit signed by the validator
+    /// and verifies its signature with [`SigningProvider::verify_signed_vote`].
+    ///
     /// ## Return
     /// Return the voting power of that validator if the signature is valid.
     fn verify_commit_signature(
@@ -108,6 +171,32 @@ where
         certificate: &CommitCertificate<Ctx>,
         commit_sig: &CommitSignature<Ctx>,
         validator: &Ctx::Validator,
+    ) -> Result<VotingPower, CertificateError<Ctx>> {
+        let precommit = Ctx::new_precommit(
+            certificate.height,
+            certificate.round,
+            NilOrVal::Val(certificate.value_id.clone()),
+            commit_sig.address.clone(),
+        );
+
+        if !self.verify_signed_vote(&precommit, &commit_sig.signature, validator.public_key()) {
+            return Err(CertificateError::InvalidSignature(commit_sig.clone()));
+        }
+
+        Ok(validator.voting_power())
+    }
+
+    /// Verify the signature of a certificate against the validator set at the height of the certificate.
+    ///
+    /// For certificates carrying an [`AggregatedSignature`](crate::AggregatedSignature),
+    /// this can be implemented with [`AggregatedSignature::verify`](crate::AggregatedSignature::verify).
+    ///
+    /// ## Return
+    /// Return the total voting power of the validators that signed the certificate if the signature is valid.
+    fn verify_certificate_signature(
+        &self,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
     ) -> Result<VotingPower, CertificateError<Ctx>>;
 }
 
@@ -115,7 +204,7 @@ impl<Ctx> SigningProvider<Ctx> for Box<dyn SigningProvider<Ctx> + '_>
 where
     Ctx: Context,
 {
-    fn sign_vote(&self, vote: Ctx::Vote) -> SignedMessage<Ctx, Ctx::Vote> {
+    fn sign_vote(&self, vote: Ctx::Vote) -> Result<SignedMessage<Ctx, Ctx::Vote>, SigningError> {
         self.as_ref().sign_vote(vote)
     }
 
@@ -129,10 +218,20 @@ where
             .verify_signed_vote(vote, signature, public_key)
     }
 
-    fn sign_proposal(&self, proposal: Ctx::Proposal) -> SignedMessage<Ctx, Ctx::Proposal> {
+    fn sign_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
         self.as_ref().sign_proposal(proposal)
     }
 
+    fn sign_implicit_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<SignedMessage<Ctx, Ctx::Proposal>, SigningError> {
+        self.as_ref().sign_implicit_proposal(proposal)
+    }
+
     fn verify_signed_proposal(
         &self,
         proposal: &Ctx::Proposal,
@@ -146,7 +245,7 @@ where
     fn sign_proposal_part(
         &self,
         proposal_part: Ctx::ProposalPart,
-    ) -> SignedMessage<Ctx, Ctx::ProposalPart> {
+    ) -> Result<SignedMessage<Ctx, Ctx::ProposalPart>, SigningError> {
         self.as_ref().sign_proposal_part(proposal_part)
     }
 
@@ -160,6 +259,10 @@ where
             .verify_signed_proposal_part(proposal_part, signature, public_key)
     }
 
+    fn verify_batch(&self, votes: &[(&Ctx::Vote, &Signature<Ctx>, &PublicKey<Ctx>)]) -> Vec<bool> {
+        self.as_ref().verify_batch(votes)
+    }
+
     fn verify_commit_signature(
         &self,
         certificate: &CommitCertificate<Ctx>,
@@ -170,7 +273,19 @@ where
             .verify_commit_signature(certificate, commit_sig, validator)
     }
 
-    fn sign_vote_extension(&self, extension: Ctx::Extension) -> SignedMessage<Ctx, Ctx::Extension> {
+    fn verify_certificate_signature(
+        &self,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+    ) -> Result<VotingPower, CertificateError<Ctx>> {
+        self.as_ref()
+            .verify_certificate_signature(certificate, validator_set)
+    }
+
+    fn sign_vote_extension(
+        &self,
+        extension: Ctx::Extension,
+    ) -> Result<SignedMessage<Ctx, Ctx::Extension>, SigningError> {
         self.as_ref().sign_vote_extension(extension)
     }
 
@@ -196,8 +311,7 @@ where
 {
     /// Verify the given certificate against the given validator set.
     ///
-    /// - For each commit signature in the certificate:
-    ///   - Reconstruct the signed precommit and verify its signature
+    /// - Verify the signature of the certificate, see [`SigningProvider::verify_certificate_signature`]
     /// - Check that we have 2/3+ of voting power has signed the certificate
     ///
     /// If any of those steps fail, return a [`CertificateError`].
@@ -212,12 +326,11 @@ where
 impl<Ctx, P> SigningProviderExt<Ctx> for P
 where
     Ctx: Context,
-    P: SigningProvider<Ctx>,
+    P: SigningProvider<Ctx> + ?Sized,
 {
     /// Verify the certificate against the given validator set.
     ///
-    /// - For each commit signature in the certificate:
-    ///   - Reconstruct the signed precommit and verify its signature
+    /// - Verify the signature of the certificate, see [`SigningProvider::verify_certificate_signature`]
     /// - Check that we have 2/3+ of voting power has signed the certificate
     ///
     /// If any of those steps fail, return a [`CertificateError`].
@@ -230,18 +343,7 @@ where
         use crate::ValidatorSet;
 
         let total_voting_power = validator_set.total_voting_power();
-        let mut signed_voting_power = 0;
-
-        // For each commit signature, reconstruct the signed precommit and verify the signature
-        for commit_sig in &certificate.aggregated_signature.signatures {
-            // Abort if validator not in validator set
-            let Some(validator) = validator_set.get_by_address(&commit_sig.address) else {
-                return Err(CertificateError::UnknownValidator(commit_sig.clone()));
-            };
-
-            let voting_power = self.verify_commit_signature(certificate, commit_sig, validator)?;
-            signed_voting_power += voting_power;
-        }
+        let signed_voting_power = self.verify_certificate_signature(certificate, validator_set)?;
 
         // Check if we have 2/3+ voting power
         if thresholds
@@ -0,0 +1,133 @@
+use core::fmt;
+use core::time::Duration;
+
+use crate::Round;
+
+/// A point in time, in milliseconds since the Unix epoch.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// Create a timestamp from a number of milliseconds since the Unix epoch.
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis)
+    }
+
+    /// Create a timestamp from the time elapsed since the Unix epoch.
+    pub fn from_unix_duration(duration: Duration) -> Self {
+        Self(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+    }
+
+    /// The number of milliseconds since the Unix epoch.
+    pub const fn as_millis(&self) -> u64 {
+        self.0
+    }
+
+    /// Add the given duration to the timestamp, saturating at the maximum timestamp.
+    pub fn saturating_add(self, duration: Duration) -> Self {
+        Self(self.0.saturating_add(duration_millis(duration)))
+    }
+
+    /// Subtract the given duration from the timestamp, saturating at the Unix epoch.
+    pub fn saturating_sub(self, duration: Duration) -> Self {
+        Self(self.0.saturating_sub(duration_millis(duration)))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}ms", self.0)
+    }
+}
+
+fn duration_millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Synchrony parameters for proposer-based timestamps (PBTS).
+///
+/// A proposal is only prevoted for if it was received in a timely manner,
+/// ie. if its timestamp is close enough to the time at which it was received,
+/// as per the bounds below.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SynchronyParams {
+    /// Bound on the difference between the clocks of any two correct validators
+    pub precision: Duration,
+
+    /// Bound on the time it takes for a proposal to reach all correct validators
+    pub message_delay: Duration,
+}
+
+impl SynchronyParams {
+    /// The message delay bound at the given round.
+    ///
+    /// The bound grows by 10% every round, so that consensus eventually makes progress
+    /// even if the configured message delay is too small for the network.
+    pub fn message_delay_at(&self, round: Round) -> Duration {
+        let rounds = round.as_u32().unwrap_or(0);
+
+        (0..rounds)
+            .try_fold(self.message_delay, |delay, _| {
+                delay.checked_mul(11).map(|delay| delay / 10)
+            })
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Whether a proposal for the given round with the given timestamp,
+    /// received at the given time, was received in a timely manner.
+    ///
+    /// That is the case if
+    /// `timestamp - precision <= received_at <= timestamp + message_delay + precision`.
+    pub fn is_timely(&self, timestamp: Timestamp, received_at: Timestamp, round: Round) -> bool {
+        let lower = timestamp.saturating_sub(self.precision);
+
+        let upper = timestamp
+            .saturating_add(self.message_delay_at(round))
+            .saturating_add(self.precision);
+
+        lower <= received_at && received_at <= upper
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: SynchronyParams = SynchronyParams {
+        precision: Duration::from_millis(500),
+        message_delay: Duration::from_secs(2),
+    };
+
+    #[test]
+    fn is_timely() {
+        let timestamp = Timestamp::from_millis(10_000);
+        let round = Round::new(0);
+
+        let received_at = |millis| Timestamp::from_millis(millis);
+
+        // Received with a clock slightly behind the proposer's one
+        assert!(PARAMS.is_timely(timestamp, received_at(9_500), round));
+        assert!(!PARAMS.is_timely(timestamp, received_at(9_499), round));
+
+        // Received late
+        assert!(PARAMS.is_timely(timestamp, received_at(12_500), round));
+        assert!(!PARAMS.is_timely(timestamp, received_at(12_501), round));
+
+        // The message delay bound is larger in later rounds
+        assert!(PARAMS.is_timely(timestamp, received_at(12_700), Round::new(1)));
+    }
+
+    #[test]
+    fn message_delay_at() {
+        assert_eq!(PARAMS.message_delay_at(Round::Nil), Duration::from_secs(2));
+        assert_eq!(
+            PARAMS.message_delay_at(Round::new(0)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            PARAMS.message_delay_at(Round::new(2)),
+            Duration::from_millis(2_420)
+        );
+        assert_eq!(PARAMS.message_delay_at(Round::new(u32::MAX)), Duration::MAX);
+    }
+}
@@ -180,6 +180,11 @@ where
         &self.validator_set
     }
 
+    /// Return the quorum and honest thresholds
+    pub fn threshold_params(&self) -> ThresholdParams {
+        self.threshold_params
+    }
+
     /// Return the total weight (ie. voting power) of the network.
     pub fn total_weight(&self) -> Weight {
         self.validator_set.total_voting_power()
@@ -190,6 +195,13 @@ where
         self.per_round.get(&round)
     }
 
+    /// Return the votes for all the rounds we have seen votes for so far, in order.
+    pub fn all_rounds(&self) -> impl Iterator<Item = (Round, &PerRound<Ctx>)> {
+        self.per_round
+            .iter()
+            .map(|(round, per_round)| (*round, per_round))
+    }
+
     /// Return how many rounds we have seen votes for so far.
     pub fn rounds(&self) -> usize {
         self.per_round.len()
@@ -1,4 +1,4 @@
-use malachitebft_core_types::{NilOrVal, Round, SignedVote};
+use malachitebft_core_types::{Evidence, NilOrVal, Round, SignedVote};
 
 use informalsystems_malachitebft_core_votekeeper::keeper::{Output, VoteKeeper};
 
@@ -305,3 +305,46 @@ fn equivocation() {
 
     assert_eq!(keeper.evidence().get(&addr2), Some(&vec![(vote21, vote22)]));
 }
+
+#[test]
+fn equivocation_evidence_is_conflicting() {
+    let ([addr1, addr2, ..], mut keeper) = setup([1, 1, 1]);
+
+    let height = Height::new(1);
+    let round = Round::new(0);
+
+    let val1 = NilOrVal::Val(ValueId::new(1));
+    let val2 = NilOrVal::Val(ValueId::new(2));
+
+    let vote11 = new_signed_prevote(height, round, val1, addr1);
+    keeper.apply_vote(vote11.clone(), round);
+
+    let vote12 = new_signed_prevote(height, round, val2, addr1);
+    keeper.apply_vote(vote12.clone(), round);
+
+    let (first, second) = keeper.evidence().get(&addr1).unwrap()[0].clone();
+    assert!(Evidence::DoubleVote { first, second }.is_conflicting());
+
+    // Same value twice
+    let evidence = Evidence::DoubleVote {
+        first: vote11.clone(),
+        second: vote11.clone(),
+    };
+    assert!(!evidence.is_conflicting());
+
+    // Different validators
+    let vote21 = new_signed_prevote(height, round, val2, addr2);
+    let evidence = Evidence::DoubleVote {
+        first: vote11.clone(),
+        second: vote21,
+    };
+    assert!(!evidence.is_conflicting());
+
+    // Different rounds
+    let vote13 = new_signed_prevote(height, Round::new(1), val2, addr1);
+    let evidence = Evidence::DoubleVote {
+        first: vote11,
+        second: vote13,
+    };
+    assert!(!evidence.is_conflicting());
+}
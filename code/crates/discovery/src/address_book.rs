@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+use libp2p::{Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Maximum number of peers kept in the address book
+const MAX_KNOWN_PEERS: usize = 1000;
+
+/// Number of consecutive failed dials after which a peer is banned
+const BAN_AFTER_FAILURES: u32 = 3;
+
+/// How long a peer stays banned after too many failed dials
+const BAN_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// What we know about a peer from previous connections
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerRecord {
+    pub peer_id: PeerId,
+
+    /// Addresses the peer listens on, as reported by the peer itself
+    pub addrs: Vec<Multiaddr>,
+
+    /// Last time we connected to the peer, in seconds since the Unix epoch
+    pub last_seen: u64,
+
+    /// Number of successful connections to the peer
+    pub successes: u32,
+
+    /// Number of failed dials to the peer, after all retries
+    pub failures: u32,
+
+    /// Number of failed dials since the last successful connection
+    pub consecutive_failures: u32,
+
+    /// The peer is not dialed nor selected until then, in seconds since the Unix epoch
+    pub banned_until: Option<u64>,
+}
+
+impl PeerRecord {
+    fn new(peer_id: PeerId) -> Self {
+        Self {
+            peer_id,
+            addrs: Vec::new(),
+            last_seen: 0,
+            successes: 0,
+            failures: 0,
+            consecutive_failures: 0,
+            banned_until: None,
+        }
+    }
+
+    pub fn is_banned(&self, now: u64) -> bool {
+        self.banned_until.is_some_and(|until| now < until)
+    }
+}
+
+/// On-disk book of the peers we have connected to, used to reconnect to them on startup
+/// instead of rediscovering the network from the bootstrap nodes only.
+#[derive(Debug, Default)]
+pub struct AddressBook {
+    /// Where the address book is stored, if it is persisted at all
+    path: Option<PathBuf>,
+    peers: HashMap<PeerId, PeerRecord>,
+
+    /// Number of changes made to the address book since it was loaded
+    changes: u64,
+
+    /// Number of changes written to disk so far, shared with the saves running in the background
+    saved: Arc<Mutex<u64>>,
+}
+
+impl AddressBook {
+    /// Load the address book from the given file, starting with an empty one
+    /// if the file does not exist yet or cannot be read.
+    pub fn load(path: PathBuf) -> Self {
+        let peers = match Self::read(&path) {
+            Ok(records) => records
+                .into_iter()
+                .map(|record| (record.peer_id, record))
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                warn!("Failed to load address book from {}: {e}", path.display());
+                HashMap::new()
+            }
+        };
+
+        debug!("Loaded {} peers from the address book", peers.len());
+
+        Self {
+            path: Some(path),
+            peers,
+            ..Self::default()
+        }
+    }
+
+    fn read(path: &Path) -> io::Result<Vec<PeerRecord>> {
+        let bytes = fs::read(path)?;
+        serde_json::from_slice(&bytes).map_err(io::Error::from)
+    }
+
+    /// Write the address book to disk, if it is persisted.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let records = self.peers.values().collect::<Vec<_>>();
+        write(&self.saved, self.changes, path, &records)
+    }
+
+    /// Whether the address book is persisted and changed since it was last saved.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.path.is_some() && self.changes > *lock(&self.saved)
+    }
+
+    /// Write the address book to disk on the blocking thread pool, if it changed since
+    /// it was last saved, so as to not stall the caller on file I/O.
+    ///
+    /// Returns a handle to the background save, if any. Must be called within a Tokio runtime.
+    pub fn save_in_background(&self) -> Option<JoinHandle<()>> {
+        if !self.has_unsaved_changes() {
+            return None;
+        }
+
+        let path = self.path.clone()?;
+        let records = self.peers.values().cloned().collect::<Vec<_>>();
+        let (saved, changes) = (Arc::clone(&self.saved), self.changes);
+
+        Some(tokio::task::spawn_blocking(move || {
+            if let Err(e) = write(&saved, changes, &path, &records) {
+                warn!("Failed to save address book: {e}");
+            }
+        }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    pub fn get(&self, peer_id: &PeerId) -> Option<&PeerRecord> {
+        self.peers.get(peer_id)
+    }
+
+    /// Find the peer listening on the given address, if known.
+    pub fn find_by_addr(&self, addr: &Multiaddr) -> Option<PeerId> {
+        self.peers
+            .values()
+            .find(|record| record.addrs.contains(addr))
+            .map(|record| record.peer_id)
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .is_some_and(|record| record.is_banned(unix_now()))
+    }
+
+    /// Peers worth dialing on startup, the most reliable and most recently seen ones first.
+    pub fn peers_to_dial(&self) -> Vec<(PeerId, Multiaddr)> {
+        let now = unix_now();
+
+        let mut records = self
+            .peers
+            .values()
+            .filter(|record| !record.is_banned(now) && !record.addrs.is_empty())
+            .collect::<Vec<_>>();
+
+        records.sort_by_key(|record| {
+            std::cmp::Reverse((record.consecutive_failures == 0, record.last_seen))
+        });
+
+        records
+            .into_iter()
+            .map(|record| (record.peer_id, record.addrs[0].clone()))
+            .collect()
+    }
+
+    /// Record a successful connection to the peer, which lifts any ban on it.
+    pub fn record_success(&mut self, peer_id: PeerId, addrs: Vec<Multiaddr>) {
+        let record = self.entry(peer_id);
+
+        if !addrs.is_empty() {
+            record.addrs = addrs;
+        }
+
+        record.last_seen = unix_now();
+        record.successes = record.successes.saturating_add(1);
+        record.consecutive_failures = 0;
+        record.banned_until = None;
+    }
+
+    /// Record a failed dial to the peer, banning it after too many consecutive failures.
+    pub fn record_failure(&mut self, peer_id: PeerId) {
+        let record = self.entry(peer_id);
+
+        record.failures = record.failures.saturating_add(1);
+        record.consecutive_failures = record.consecutive_failures.saturating_add(1);
+
+        if record.consecutive_failures >= BAN_AFTER_FAILURES {
+            warn!(
+                "Banning peer {peer_id} for {}s after {} consecutive failed dials",
+                BAN_DURATION.as_secs(),
+                record.consecutive_failures
+            );
+
+            self.ban(peer_id, BAN_DURATION);
+        }
+    }
+
+    /// Ban the peer for the given duration.
+    pub fn ban(&mut self, peer_id: PeerId, duration: Duration) {
+        self.entry(peer_id).banned_until = Some(unix_now() + duration.as_secs());
+    }
+
+    fn entry(&mut self, peer_id: PeerId) -> &mut PeerRecord {
+        self.changes += 1;
+
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= MAX_KNOWN_PEERS {
+            self.evict_one();
+        }
+
+        self.peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerRecord::new(peer_id))
+    }
+
+    /// Make room for a new peer by forgetting the least recently seen one.
+    fn evict_one(&mut self) {
+        let oldest = self
+            .peers
+            .values()
+            .min_by_key(|record| record.last_seen)
+            .map(|record| record.peer_id);
+
+        if let Some(peer_id) = oldest {
+            self.peers.remove(&peer_id);
+        }
+    }
+}
+
+/// Write the given records to disk, unless a more recent version of the address book,
+/// with at least `changes` changes, has already been written.
+fn write(
+    saved: &Mutex<u64>,
+    changes: u64,
+    path: &Path,
+    records: &[impl Serialize],
+) -> io::Result<()> {
+    // Hold the lock while writing, so that concurrent saves never interleave
+    let mut saved = lock(saved);
+
+    if *saved >= changes {
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let bytes = serde_json::to_vec_pretty(records)?;
+
+    // Write to a temporary file first so that a crash never leaves a truncated address book
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(&tmp_path, path)?;
+
+    *saved = changes;
+    Ok(())
+}
+
+fn lock(saved: &Mutex<u64>) -> std::sync::MutexGuard<'_, u64> {
+    saved.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("address_book.json");
+
+        let peer_id = PeerId::random();
+        let addr = Multiaddr::from_str("/ip4/127.0.0.1/tcp/27000").unwrap();
+
+        let mut book = AddressBook::load(path.clone());
+        assert!(book.is_empty());
+
+        book.record_success(peer_id, vec![addr.clone()]);
+        book.save().unwrap();
+
+        let loaded = AddressBook::load(path);
+        assert_eq!(loaded.get(&peer_id), book.get(&peer_id));
+        assert_eq!(loaded.find_by_addr(&addr), Some(peer_id));
+        assert_eq!(loaded.peers_to_dial(), vec![(peer_id, addr)]);
+    }
+
+    #[tokio::test]
+    async fn save_changes_in_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("address_book.json");
+
+        let peer_id = PeerId::random();
+        let addr = Multiaddr::from_str("/ip4/127.0.0.1/tcp/27000").unwrap();
+
+        let mut book = AddressBook::load(path.clone());
+        assert!(book.save_in_background().is_none());
+
+        book.record_success(peer_id, vec![addr]);
+        assert!(book.has_unsaved_changes());
+
+        book.save_in_background().unwrap().await.unwrap();
+        assert!(!book.has_unsaved_changes());
+        assert!(book.save_in_background().is_none());
+
+        let loaded = AddressBook::load(path);
+        assert_eq!(loaded.get(&peer_id), book.get(&peer_id));
+    }
+
+    #[test]
+    fn ban_after_consecutive_failures() {
+        let peer_id = PeerId::random();
+        let addr = Multiaddr::from_str("/ip4/127.0.0.1/tcp/27000").unwrap();
+
+        let mut book = AddressBook::default();
+        book.record_success(peer_id, vec![addr]);
+
+        for _ in 0..BAN_AFTER_FAILURES {
+            assert!(!book.is_banned(&peer_id));
+            book.record_failure(peer_id);
+        }
+
+        assert!(book.is_banned(&peer_id));
+        assert!(book.peers_to_dial().is_empty());
+
+        // Connecting to the peer again lifts the ban
+        book.record_success(peer_id, vec![]);
+        assert!(!book.is_banned(&peer_id));
+        assert_eq!(book.get(&peer_id).unwrap().failures, BAN_AFTER_FAILURES);
+        assert_eq!(book.get(&peer_id).unwrap().consecutive_failures, 0);
+    }
+}
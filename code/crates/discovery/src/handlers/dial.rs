@@ -22,6 +22,8 @@ where
             id != swarm.local_peer_id()
             // Is not already connected
             && !swarm.is_connected(id)
+            // Is not banned
+            && !self.address_book.is_banned(id)
         })
             // Has not already dialed, or has dialed but retries are allowed
             && (!check_already_dialed || !self.controller.dial_is_done_on(connection_data) || connection_data.retry.count() != 0)
@@ -137,6 +139,14 @@ where
 
                 self.metrics.increment_total_failed_dials();
 
+                let peer_id = connection_data
+                    .peer_id()
+                    .or_else(|| self.address_book.find_by_addr(&connection_data.multiaddr()));
+
+                if let Some(peer_id) = peer_id {
+                    self.address_book.record_failure(peer_id);
+                }
+
                 self.make_extension_step(swarm);
             }
         }
@@ -156,5 +166,26 @@ where
         for (peer_id, addr) in &self.bootstrap_nodes.clone() {
             self.add_to_dial_queue(swarm, ConnectionData::new(*peer_id, addr.clone()));
         }
+
+        if self.is_enabled() {
+            self.dial_known_peers(swarm);
+        }
+    }
+
+    /// Reconnect to the peers we were connected to before the last restart,
+    /// instead of waiting to rediscover them through the bootstrap nodes.
+    fn dial_known_peers(&mut self, swarm: &Swarm<C>) {
+        let known_peers = self.address_book.peers_to_dial();
+
+        if !known_peers.is_empty() {
+            info!(
+                "Dialing {} known peers from the address book",
+                known_peers.len()
+            );
+        }
+
+        for (peer_id, addr) in known_peers {
+            self.add_to_dial_queue(swarm, ConnectionData::new(Some(peer_id), addr));
+        }
     }
 }
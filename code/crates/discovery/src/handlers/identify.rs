@@ -41,6 +41,9 @@ where
                 .dial_remove_matching_in_progress_connections(&peer_id);
         }
 
+        self.address_book
+            .record_success(peer_id, info.listen_addrs.clone());
+
         match self.discovered_peers.insert(peer_id, info.clone()) {
             Some(_) => {
                 info!("New connection from known peer {peer_id}");
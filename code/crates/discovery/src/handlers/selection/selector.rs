@@ -36,14 +36,15 @@ where
         }
     }
 
-    /// Excluded peers are those that are already outbound connections or have already
-    /// been requested to be so.
+    /// Excluded peers are those that are already outbound connections, have already
+    /// been requested to be so, or are banned in the address book.
     pub(crate) fn get_excluded_peers(&self) -> Vec<PeerId> {
         self.discovered_peers
             .keys()
             .filter(|peer_id| {
                 self.outbound_connections.contains_key(peer_id)
                     || self.controller.connect_request.is_done_on(peer_id)
+                    || self.address_book.is_banned(peer_id)
             })
             .cloned()
             .collect()
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use tracing::{debug, error, info, warn};
 
@@ -8,6 +9,9 @@ use libp2p::{identify, kad, request_response, swarm::ConnectionId, Multiaddr, Pe
 
 mod util;
 
+mod address_book;
+pub use address_book::{AddressBook, PeerRecord};
+
 mod behaviour;
 pub use behaviour::*;
 
@@ -55,6 +59,7 @@ where
     selector: Box<dyn Selector<C>>,
 
     bootstrap_nodes: Vec<(Option<PeerId>, Multiaddr)>,
+    address_book: AddressBook,
     discovered_peers: HashMap<PeerId, identify::Info>,
     active_connections: HashMap<PeerId, Vec<ConnectionId>>,
     outbound_connections: HashMap<PeerId, OutboundConnection>,
@@ -68,7 +73,12 @@ impl<C> Discovery<C>
 where
     C: DiscoveryClient,
 {
-    pub fn new(config: Config, bootstrap_nodes: Vec<Multiaddr>, registry: &mut Registry) -> Self {
+    pub fn new(
+        config: Config,
+        bootstrap_nodes: Vec<Multiaddr>,
+        address_book_path: Option<PathBuf>,
+        registry: &mut Registry,
+    ) -> Self {
         info!(
             "Discovery is {}",
             if config.enabled {
@@ -78,8 +88,14 @@ where
             }
         );
 
-        let state = if config.enabled && bootstrap_nodes.is_empty() {
-            warn!("No bootstrap nodes provided");
+        let address_book = address_book_path.map(AddressBook::load).unwrap_or_default();
+
+        // Peers from the address book are only dialed on startup if discovery is enabled
+        let no_peers_to_dial =
+            bootstrap_nodes.is_empty() && (!config.enabled || address_book.is_empty());
+
+        let state = if config.enabled && no_peers_to_dial {
+            warn!("No bootstrap nodes provided and no known peers in the address book");
             info!("Discovery found 0 peers in 0ms");
             State::Idle
         } else if config.enabled {
@@ -111,13 +127,14 @@ where
                 .into_iter()
                 .map(|addr| (None, addr))
                 .collect(),
+            address_book,
             discovered_peers: HashMap::new(),
             active_connections: HashMap::new(),
             outbound_connections: HashMap::new(),
             inbound_connections: HashMap::new(),
 
             controller: Controller::new(),
-            metrics: Metrics::new(registry, !config.enabled || bootstrap_nodes.is_empty()),
+            metrics: Metrics::new(registry, !config.enabled || no_peers_to_dial),
         }
     }
 
@@ -125,6 +142,13 @@ where
         self.config.enabled
     }
 
+    /// Write the address book to disk in the background, if it changed since it was last saved.
+    ///
+    /// Returns a handle to the background save, if any.
+    pub fn save_address_book(&self) -> Option<tokio::task::JoinHandle<()>> {
+        self.address_book.save_in_background()
+    }
+
     fn active_connections_len(&self) -> usize {
         self.active_connections.values().map(Vec::len).sum()
     }
@@ -1,6 +1,7 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_recursion::async_recursion;
 use async_trait::async_trait;
@@ -11,27 +12,33 @@ use tokio::time::Instant;
 use tracing::{debug, error, error_span, info, warn};
 
 use malachitebft_codec as codec;
-use malachitebft_config::TimeoutConfig;
+use malachitebft_config::{ByteSize, TimeoutConfig};
 use malachitebft_core_consensus::{
-    Effect, PeerId, Resumable, Resume, SignedConsensusMsg, VoteExtensionError,
+    Effect, PeerId, Resumable, Resume, SignedConsensusMsg, StateDump, VoteExtensionError,
 };
 use malachitebft_core_types::{
-    Context, Round, SigningProvider, SigningProviderExt, Timeout, TimeoutKind, ValidatorSet,
-    ValueId, ValueOrigin,
+    Context, Evidence, Height, Proposal, Round, SigningError, SigningProvider, SigningProviderExt,
+    SigningScheme, ThresholdParams, Timeout, TimeoutKind, Timestamp, ValidatorSet, Validity,
+    ValueId, ValueOrigin, Vote, VoteType,
 };
 use malachitebft_metrics::Metrics;
 use malachitebft_sync::{
-    self as sync, InboundRequestId, Response, ValueResponse, VoteSetRequest, VoteSetResponse,
+    self as sync, InboundRequestId, RawDecidedValue, Response, ValueResponse, VoteSetRequest,
+    VoteSetResponse,
 };
 
 use crate::host::{HostMsg, HostRef, LocallyProposedValue, ProposedValue};
-use crate::network::{NetworkEvent, NetworkMsg, NetworkRef, Status};
+use crate::network::{MessageId, NetworkEvent, NetworkMsg, NetworkRef, Status, ValidationResult};
 use crate::sync::Msg as SyncMsg;
 use crate::sync::SyncRef;
 use crate::util::events::{Event, TxEvent};
 use crate::util::msg_buffer::MessageBuffer;
+use crate::util::scheduled::Scheduled;
+use crate::util::sign_watermark::{SignStep, SignWatermark, Signed};
 use crate::util::streaming::StreamMessage;
+use crate::util::timeouts::{TimeoutStrategy, Timeouts};
 use crate::util::timers::{TimeoutElapsed, TimerScheduler};
+use crate::util::validator_sets::ValidatorSetCache;
 use crate::wal::{Msg as WalMsg, WalEntry, WalRef};
 
 pub use malachitebft_core_consensus::Error as ConsensusError;
@@ -44,12 +51,14 @@ pub use malachitebft_core_consensus::State as ConsensusState;
 /// - [`codec::Codec<Ctx::ProposalPart>`]
 /// - [`codec::Codec<SignedConsensusMsg<Ctx>>`]
 /// - [`codec::Codec<StreamMessage<Ctx::ProposalPart>>`]
+/// - [`codec::Codec<Evidence<Ctx>>`]
 pub trait ConsensusCodec<Ctx>
 where
     Ctx: Context,
     Self: codec::Codec<Ctx::ProposalPart>,
     Self: codec::Codec<SignedConsensusMsg<Ctx>>,
     Self: codec::Codec<StreamMessage<Ctx::ProposalPart>>,
+    Self: codec::Codec<Evidence<Ctx>>,
 {
 }
 
@@ -59,9 +68,30 @@ where
     Self: codec::Codec<Ctx::ProposalPart>,
     Self: codec::Codec<SignedConsensusMsg<Ctx>>,
     Self: codec::Codec<StreamMessage<Ctx::ProposalPart>>,
+    Self: codec::Codec<Evidence<Ctx>>,
 {
 }
 
+/// Consensus parameters which can change from one height to the next,
+/// eg. as decided through on-chain governance.
+///
+/// Defaults to the node configuration, until the application provides new ones when starting
+/// a height. They then take effect together for that height and the following ones.
+#[derive_where(Clone, Debug, PartialEq)]
+pub struct HeightParams<Ctx: Context> {
+    /// The consensus timeouts, used as a base by the timeout strategy
+    pub timeouts: TimeoutConfig,
+
+    /// The maximum size of the values to propose, ie. of the blocks
+    pub max_block_size: ByteSize,
+
+    /// The height from which vote extensions are enabled, if any
+    pub vote_extensions_enable_height: Option<Ctx::Height>,
+
+    /// The quorum and honest thresholds
+    pub threshold_params: ThresholdParams,
+}
+
 pub type ConsensusRef<Ctx> = ActorRef<Msg<Ctx>>;
 
 pub struct Consensus<Ctx>
@@ -70,8 +100,10 @@ where
 {
     ctx: Ctx,
     params: ConsensusParams<Ctx>,
-    timeout_config: TimeoutConfig,
-    signing_provider: Box<dyn SigningProvider<Ctx>>,
+    height_params: HeightParams<Ctx>,
+    timeout_strategy: Arc<dyn TimeoutStrategy>,
+    signing_provider: Arc<dyn SigningProvider<Ctx>>,
+    sign_watermark_path: PathBuf,
     network: NetworkRef<Ctx>,
     host: HostRef<Ctx>,
     wal: WalRef<Ctx>,
@@ -88,6 +120,13 @@ pub enum Msg<Ctx: Context> {
     /// Start consensus for the given height with the given validator set
     StartHeight(Ctx::Height, Ctx::ValidatorSet),
 
+    /// Schedule the validator set to use at the given (typically future) height,
+    /// which is ignored if that height has already started
+    ScheduleValidatorSet(Ctx::Height, Ctx::ValidatorSet),
+
+    /// Schedule the consensus parameters to use from the given (typically future) height onwards
+    ScheduleParams(Ctx::Height, HeightParams<Ctx>),
+
     /// Received an event from the gossip layer
     NetworkEvent(NetworkEvent<Ctx>),
 
@@ -102,6 +141,9 @@ pub enum Msg<Ctx: Context> {
 
     /// Get the status of the consensus state machine
     GetStatus(RpcReplyPort<Status<Ctx>>),
+
+    /// Get a snapshot of the state of consensus at the current height
+    DumpState(RpcReplyPort<StateDump<Ctx>>),
 }
 
 impl<Ctx: Context> From<NetworkEvent<Ctx>> for Msg<Ctx> {
@@ -120,47 +162,6 @@ impl<Ctx: Context> From<TimeoutElapsed<Timeout>> for Msg<Ctx> {
 
 type Timers = TimerScheduler<Timeout>;
 
-struct Timeouts {
-    config: TimeoutConfig,
-}
-
-impl Timeouts {
-    pub fn new(config: TimeoutConfig) -> Self {
-        Self { config }
-    }
-
-    fn reset(&mut self, config: TimeoutConfig) {
-        self.config = config;
-    }
-
-    fn duration_for(&self, step: TimeoutKind) -> Duration {
-        match step {
-            TimeoutKind::Propose => self.config.timeout_propose,
-            TimeoutKind::Prevote => self.config.timeout_prevote,
-            TimeoutKind::Precommit => self.config.timeout_precommit,
-            TimeoutKind::Commit => self.config.timeout_commit,
-            TimeoutKind::PrevoteTimeLimit => self.config.timeout_step,
-            TimeoutKind::PrecommitTimeLimit => self.config.timeout_step,
-            TimeoutKind::PrevoteRebroadcast => self.config.timeout_prevote,
-            TimeoutKind::PrecommitRebroadcast => self.config.timeout_precommit,
-        }
-    }
-
-    fn increase_timeout(&mut self, step: TimeoutKind) {
-        let c = &mut self.config;
-        match step {
-            TimeoutKind::Propose => c.timeout_propose += c.timeout_propose_delta,
-            TimeoutKind::Prevote => c.timeout_prevote += c.timeout_prevote_delta,
-            TimeoutKind::Precommit => c.timeout_precommit += c.timeout_precommit_delta,
-            TimeoutKind::Commit => (),
-            TimeoutKind::PrevoteTimeLimit => (),
-            TimeoutKind::PrecommitTimeLimit => (),
-            TimeoutKind::PrevoteRebroadcast => (),
-            TimeoutKind::PrecommitRebroadcast => (),
-        };
-    }
-}
-
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum Phase {
     Unstarted,
@@ -172,6 +173,10 @@ enum Phase {
 /// in the `Unstarted` or `Recovering` phase
 const MAX_BUFFER_SIZE: usize = 1024;
 
+/// Maximum number of validator sets to keep in the cache,
+/// including the one for the current height
+const MAX_CACHED_VALIDATOR_SETS: usize = 16;
+
 pub struct State<Ctx: Context> {
     /// Scheduler for timers
     timers: Timers,
@@ -179,6 +184,12 @@ pub struct State<Ctx: Context> {
     /// Timeouts configuration
     timeouts: Timeouts,
 
+    /// Consensus parameters in effect at the current height
+    params: HeightParams<Ctx>,
+
+    /// Consensus parameters to use from the given heights onwards, as scheduled by the application
+    scheduled_params: Scheduled<Ctx::Height, HeightParams<Ctx>>,
+
     /// The state of the consensus state machine
     consensus: ConsensusState<Ctx>,
 
@@ -191,6 +202,17 @@ pub struct State<Ctx: Context> {
     /// A buffer of messages that were received while
     /// consensus was `Unstarted` or in the `Recovering` phase
     msg_buffer: MessageBuffer<Ctx>,
+
+    /// Validator sets for the current and upcoming heights,
+    /// either scheduled by the application or pre-fetched
+    validator_sets: ValidatorSetCache<Ctx::Height, Ctx::ValidatorSet>,
+
+    /// The last proposal and vote signed by this node, persisted to disk
+    sign_watermark: SignWatermark,
+
+    /// Values received via sync for the current and upcoming heights,
+    /// along with the peer they were received from
+    synced_values: BTreeMap<Ctx::Height, (PeerId, RawDecidedValue<Ctx>)>,
 }
 
 impl<Ctx> State<Ctx>
@@ -200,6 +222,18 @@ where
     pub fn height(&self) -> Ctx::Height {
         self.consensus.height()
     }
+
+    /// Use the given consensus parameters, before starting a new height.
+    fn apply_params(&mut self, params: HeightParams<Ctx>) {
+        self.timeouts.set_config(params.timeouts);
+
+        self.consensus.update_params(
+            params.threshold_params,
+            params.vote_extensions_enable_height,
+        );
+
+        self.params = params;
+    }
 }
 
 impl<Ctx> Consensus<Ctx>
@@ -210,8 +244,10 @@ where
     pub async fn spawn(
         ctx: Ctx,
         params: ConsensusParams<Ctx>,
-        timeout_config: TimeoutConfig,
+        height_params: HeightParams<Ctx>,
+        timeout_strategy: Box<dyn TimeoutStrategy>,
         signing_provider: Box<dyn SigningProvider<Ctx>>,
+        sign_watermark_path: PathBuf,
         network: NetworkRef<Ctx>,
         host: HostRef<Ctx>,
         wal: WalRef<Ctx>,
@@ -223,8 +259,10 @@ where
         let node = Self {
             ctx,
             params,
-            timeout_config,
-            signing_provider,
+            height_params,
+            timeout_strategy: Arc::from(timeout_strategy),
+            signing_provider: Arc::from(signing_provider),
+            sign_watermark_path,
             network,
             host,
             wal,
@@ -243,6 +281,50 @@ where
         myself: &ActorRef<Msg<Ctx>>,
         state: &mut State<Ctx>,
         input: ConsensusInput<Ctx>,
+    ) -> Result<(), ConsensusError<Ctx>> {
+        self.process_input_and_verify(myself, state, input, &mut None)
+            .await
+    }
+
+    /// Process a vote or proposal received over gossip, and report to the network whether
+    /// its signature is valid, so that only valid messages are forwarded to other peers.
+    async fn process_gossip_input(
+        &self,
+        myself: &ActorRef<Msg<Ctx>>,
+        state: &mut State<Ctx>,
+        message_id: MessageId,
+        input: ConsensusInput<Ctx>,
+    ) -> Result<(), ConsensusError<Ctx>> {
+        let mut verified = None;
+
+        let result = self
+            .process_input_and_verify(myself, state, input, &mut verified)
+            .await;
+
+        // Messages which were not verified, eg. because they are for a past height, are not invalid
+        let validation = match verified {
+            Some(false) => ValidationResult::Reject,
+            _ => ValidationResult::Accept,
+        };
+
+        if let Err(e) = self
+            .network
+            .cast(NetworkMsg::ReportValidationResult(message_id, validation))
+        {
+            error!("Error when reporting validation result to network: {e}");
+        }
+
+        result
+    }
+
+    /// Process the given input, recording in `verified` the outcome of the first
+    /// signature verification, which is the one of the message being processed, if any.
+    async fn process_input_and_verify(
+        &self,
+        myself: &ActorRef<Msg<Ctx>>,
+        state: &mut State<Ctx>,
+        input: ConsensusInput<Ctx>,
+        verified: &mut Option<bool>,
     ) -> Result<(), ConsensusError<Ctx>> {
         let height = state.height();
 
@@ -256,7 +338,11 @@ where
                     height,
                     &mut state.timers,
                     &mut state.timeouts,
+                    &state.params,
+                    &mut state.validator_sets,
+                    &mut state.sign_watermark,
                     state.phase,
+                    verified,
                     effect
                 ).await
             }
@@ -288,6 +374,15 @@ where
     ) -> Result<(), ActorProcessingErr> {
         match msg {
             Msg::StartHeight(height, validator_set) => {
+                state.validator_sets.prune(height);
+                state.validator_sets.insert(height, validator_set.clone());
+
+                // Switch to the latest parameters scheduled at or below this height, if any
+                if let Some((since, params)) = state.scheduled_params.take_due(&height) {
+                    debug!(%height, %since, "Using scheduled consensus parameters");
+                    state.apply_params(params);
+                }
+
                 let result = self
                     .process_input(
                         &myself,
@@ -317,6 +412,46 @@ where
 
                 state.phase = Phase::Running;
 
+                self.process_next_synced_value(&myself, state).await
+            }
+
+            Msg::ScheduleValidatorSet(height, validator_set) => {
+                if height < state.height() {
+                    debug!(%height, "Ignoring validator set scheduled for a past height");
+                    return Ok(());
+                }
+
+                if height == state.height() && state.phase != Phase::Unstarted {
+                    warn!(
+                        %height,
+                        "Ignoring validator set scheduled for the current height, which has already started"
+                    );
+                    return Ok(());
+                }
+
+                debug!(%height, "Scheduled validator set");
+                state.validator_sets.insert(height, validator_set);
+
+                Ok(())
+            }
+
+            Msg::ScheduleParams(height, params) => {
+                if height < state.height() {
+                    debug!(%height, "Ignoring consensus parameters scheduled for a past height");
+                    return Ok(());
+                }
+
+                if height == state.height() && state.phase != Phase::Unstarted {
+                    warn!(
+                        %height,
+                        "Ignoring consensus parameters scheduled for the current height, which has already started"
+                    );
+                    return Ok(());
+                }
+
+                debug!(%height, "Scheduled consensus parameters");
+                state.scheduled_params.insert(height, params);
+
                 Ok(())
             }
 
@@ -349,6 +484,10 @@ where
                 match event {
                     NetworkEvent::Listening(address) => {
                         info!(%address, "Listening");
+                        if let Some(sync) = &self.sync {
+                            sync.cast(SyncMsg::ConsensusReady(myself.clone()))?;
+                        }
+
                         self.host.cast(HostMsg::ConsensusReady(myself.clone()))?;
                     }
 
@@ -389,45 +528,35 @@ where
                             return Ok(());
                         };
 
-                        self.host.call_and_forward(
-                            |reply_to| HostMsg::ProcessSyncedValue {
-                                height: value.certificate.height,
-                                round: value.certificate.round,
-                                validator_address: state.consensus.address().clone(),
-                                value_bytes: value.value_bytes.clone(),
-                                reply_to,
-                            },
-                            &myself,
-                            |proposed| {
-                                Msg::<Ctx>::ReceivedProposedValue(proposed, ValueOrigin::Sync)
-                            },
-                            None,
-                        )?;
+                        self.process_synced_value(&myself, state, peer, value)
+                            .await?;
+                    }
 
-                        if let Err(e) = self
-                            .process_input(
-                                &myself,
-                                state,
-                                ConsensusInput::CommitCertificate(value.certificate),
-                            )
-                            .await
-                        {
-                            error!(%height, %request_id, "Error when processing received synced block: {e}");
-
-                            let Some(sync) = self.sync.as_ref() else {
-                                warn!("Received sync response but sync actor is not available");
-                                return Ok(());
-                            };
-
-                            if let ConsensusError::InvalidCertificate(certificate, e) = e {
-                                sync.cast(SyncMsg::InvalidCertificate(peer, certificate, e))
-                                    .map_err(|e| {
-                                        eyre!(
-                                            "Error when notifying sync of invalid certificate: {e}"
-                                        )
-                                    })?;
-                            }
+                    NetworkEvent::Response(
+                        request_id,
+                        peer,
+                        sync::Response::ValueRangeResponse(response),
+                    ) => {
+                        debug!(
+                            start = %response.start, count = response.values.len(), %request_id,
+                            "Received sync range response"
+                        );
+
+                        let height = state.height();
+
+                        // Only keep the values for consecutive heights, from the current height onwards
+                        let values = response
+                            .consecutive_values()
+                            .iter()
+                            .filter(|value| value.certificate.height >= height);
+
+                        for value in values {
+                            state
+                                .synced_values
+                                .insert(value.certificate.height, (peer, value.clone()));
                         }
+
+                        self.process_next_synced_value(&myself, state).await?;
                     }
 
                     NetworkEvent::Request(
@@ -481,29 +610,52 @@ where
                         }
                     }
 
-                    NetworkEvent::Vote(from, vote) => {
+                    NetworkEvent::Vote(from, message_id, vote) => {
                         if let Err(e) = self
-                            .process_input(&myself, state, ConsensusInput::Vote(vote))
+                            .process_gossip_input(
+                                &myself,
+                                state,
+                                message_id,
+                                ConsensusInput::Vote(vote),
+                            )
                             .await
                         {
                             error!(%from, "Error when processing vote: {e}");
                         }
                     }
 
-                    NetworkEvent::Proposal(from, proposal) => {
+                    NetworkEvent::Proposal(from, message_id, proposal) => {
                         if state.consensus.params.value_payload.parts_only() {
                             error!(%from, "Properly configured peer should never send proposal messages in BlockPart mode");
+                            self.network.cast(NetworkMsg::ReportValidationResult(
+                                message_id,
+                                ValidationResult::Ignore,
+                            ))?;
                             return Ok(());
                         }
 
                         if let Err(e) = self
-                            .process_input(&myself, state, ConsensusInput::Proposal(proposal))
+                            .process_gossip_input(
+                                &myself,
+                                state,
+                                message_id,
+                                ConsensusInput::Proposal(proposal, None),
+                            )
                             .await
                         {
                             error!(%from, "Error when processing proposal: {e}");
                         }
                     }
 
+                    NetworkEvent::Evidence(from, evidence) => {
+                        if let Err(e) = self
+                            .process_input(&myself, state, ConsensusInput::Evidence(evidence))
+                            .await
+                        {
+                            error!(%from, "Error when processing evidence: {e}");
+                        }
+                    }
+
                     NetworkEvent::ProposalPart(from, part) => {
                         if state.consensus.params.value_payload.proposal_only() {
                             error!(%from, "Properly configured peer should never send proposal part messages in Proposal mode");
@@ -538,7 +690,7 @@ where
                     return Ok(());
                 };
 
-                state.timeouts.increase_timeout(timeout.kind);
+                state.timeouts.elapsed(timeout);
 
                 if matches!(
                     timeout.kind,
@@ -588,7 +740,81 @@ where
 
                 Ok(())
             }
+
+            Msg::DumpState(reply_to) => {
+                if let Err(e) = reply_to.send(state.consensus.dump()) {
+                    error!("Error when replying to DumpState message: {e}");
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Process the value received via sync for the current height, if any,
+    /// discarding the ones received for heights which have already been decided.
+    async fn process_next_synced_value(
+        &self,
+        myself: &ActorRef<Msg<Ctx>>,
+        state: &mut State<Ctx>,
+    ) -> Result<(), ActorProcessingErr> {
+        let height = state.height();
+
+        state.synced_values = state.synced_values.split_off(&height);
+
+        let Some((peer, value)) = state.synced_values.remove(&height) else {
+            return Ok(());
+        };
+
+        self.process_synced_value(myself, state, peer, value).await
+    }
+
+    /// Hand over a value received via sync to the application,
+    /// and commit it at the height of its certificate.
+    async fn process_synced_value(
+        &self,
+        myself: &ActorRef<Msg<Ctx>>,
+        state: &mut State<Ctx>,
+        peer: PeerId,
+        value: RawDecidedValue<Ctx>,
+    ) -> Result<(), ActorProcessingErr> {
+        let height = value.certificate.height;
+
+        self.host.call_and_forward(
+            |reply_to| HostMsg::ProcessSyncedValue {
+                height,
+                round: value.certificate.round,
+                validator_address: state.consensus.address().clone(),
+                value_bytes: value.value_bytes.clone(),
+                reply_to,
+            },
+            myself,
+            |proposed| Msg::<Ctx>::ReceivedProposedValue(proposed, ValueOrigin::Sync),
+            None,
+        )?;
+
+        if let Err(e) = self
+            .process_input(
+                myself,
+                state,
+                ConsensusInput::CommitCertificate(value.certificate),
+            )
+            .await
+        {
+            error!(%height, %peer, "Error when processing received synced block: {e}");
+
+            let Some(sync) = self.sync.as_ref() else {
+                warn!("Received sync response but sync actor is not available");
+                return Ok(());
+            };
+
+            if let ConsensusError::InvalidCertificate(certificate, e) = e {
+                sync.cast(SyncMsg::InvalidCertificate(peer, certificate, e))
+                    .map_err(|e| eyre!("Error when notifying sync of invalid certificate: {e}"))?;
+            }
         }
+
+        Ok(())
     }
 
     async fn timeout_elapsed(
@@ -600,8 +826,8 @@ where
         // Make sure the associated timer is cancelled
         state.timers.cancel(&timeout);
 
-        // Increase the timeout for the next round
-        state.timeouts.increase_timeout(timeout.kind);
+        // Let the timeout strategy know that the timeout elapsed
+        state.timeouts.elapsed(timeout);
 
         // Print debug information if the timeout is for a prevote or precommit
         if matches!(timeout.kind, TimeoutKind::Prevote | TimeoutKind::Precommit) {
@@ -681,7 +907,7 @@ where
                         .send(|| Event::WalReplayConsensus(Proposal(proposal.clone())));
 
                     if let Err(e) = self
-                        .process_input(myself, state, ConsensusInput::Proposal(proposal))
+                        .process_input(myself, state, ConsensusInput::Proposal(proposal, None))
                         .await
                     {
                         error!("Error when replaying Proposal: {e}");
@@ -709,6 +935,7 @@ where
         height: Ctx::Height,
         round: Round,
         timeout: Duration,
+        max_block_size: ByteSize,
     ) -> Result<(), ActorProcessingErr> {
         // Call `GetValue` on the Host actor, and forward the reply
         // to the current actor, wrapping it in `Msg::ProposeValue`.
@@ -717,6 +944,7 @@ where
                 height,
                 round,
                 timeout,
+                max_block_size,
                 reply_to,
             },
             myself,
@@ -742,6 +970,23 @@ where
         Ok(validator_set)
     }
 
+    fn prefetch_validator_set(
+        &self,
+        myself: &ActorRef<Msg<Ctx>>,
+        height: Ctx::Height,
+    ) -> Result<(), ActorProcessingErr> {
+        // Call `GetValidatorSet` on the Host actor, and forward the reply
+        // to the current actor, wrapping it in `Msg::ScheduleValidatorSet`.
+        self.host.call_and_forward(
+            |reply_to| HostMsg::GetValidatorSet { height, reply_to },
+            myself,
+            move |validator_set| Msg::<Ctx>::ScheduleValidatorSet(height, validator_set),
+            None,
+        )?;
+
+        Ok(())
+    }
+
     async fn extend_vote(
         &self,
         height: Ctx::Height,
@@ -757,6 +1002,17 @@ where
         .map_err(|e| eyre!("Failed to get earliest block height: {e:?}").into())
     }
 
+    async fn validate_proposal(
+        &self,
+        proposal: Ctx::Proposal,
+    ) -> Result<Validity, ActorProcessingErr> {
+        ractor::call!(self.host, |reply_to| HostMsg::ValidateProposal {
+            proposal,
+            reply_to
+        })
+        .map_err(|e| eyre!("Failed to validate proposal: {e:?}").into())
+    }
+
     async fn verify_vote_extension(
         &self,
         height: Ctx::Height,
@@ -830,34 +1086,71 @@ where
         Ok(())
     }
 
+    /// Record the given signed message in the watermark before it is released,
+    /// refusing to release it if it conflicts with a previously signed message.
+    fn update_sign_watermark(
+        &self,
+        sign_watermark: &mut SignWatermark,
+        signed: Signed,
+    ) -> Result<(), ActorProcessingErr> {
+        sign_watermark.update(signed).map_err(|e| {
+            error!(
+                path = %sign_watermark.path().display(),
+                "Refusing to sign message, to prevent double-signing: {e}"
+            );
+
+            eyre!(e).into()
+        })
+    }
+
+    /// Sign a message on the blocking thread pool, so that a signing provider
+    /// waiting on a remote signer does not stall the runtime.
+    async fn sign<T, F>(&self, sign: F) -> Result<T, ActorProcessingErr>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn SigningProvider<Ctx>) -> Result<T, SigningError> + Send + 'static,
+    {
+        let signing_provider = Arc::clone(&self.signing_provider);
+        let signed = tokio::task::spawn_blocking(move || sign(signing_provider.as_ref())).await??;
+        Ok(signed)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn handle_effect(
         &self,
         myself: &ActorRef<Msg<Ctx>>,
         height: Ctx::Height,
         timers: &mut Timers,
         timeouts: &mut Timeouts,
+        params: &HeightParams<Ctx>,
+        validator_sets: &mut ValidatorSetCache<Ctx::Height, Ctx::ValidatorSet>,
+        sign_watermark: &mut SignWatermark,
         phase: Phase,
+        verified: &mut Option<bool>,
         effect: Effect<Ctx>,
     ) -> Result<Resume<Ctx>, ActorProcessingErr> {
         match effect {
             Effect::ResetTimeouts(r) => {
-                timeouts.reset(self.timeout_config);
+                timeouts.reset();
                 Ok(r.resume_with(()))
             }
 
             Effect::CancelAllTimeouts(r) => {
                 timers.cancel_all();
+                timeouts.reset();
                 Ok(r.resume_with(()))
             }
 
             Effect::CancelTimeout(timeout, r) => {
                 timers.cancel(&timeout);
+                timeouts.cancelled(timeout);
                 Ok(r.resume_with(()))
             }
 
             Effect::ScheduleTimeout(timeout, r) => {
-                let duration = timeouts.duration_for(timeout.kind);
+                let duration = timeouts.duration_for(timeout);
                 timers.start_timer(timeout, duration);
+                timeouts.started(timeout);
 
                 Ok(r.resume_with(()))
             }
@@ -879,7 +1172,33 @@ where
             Effect::SignProposal(proposal, r) => {
                 let start = Instant::now();
 
-                let signed_proposal = self.signing_provider.sign_proposal(proposal);
+                let signed_proposal = self
+                    .sign(move |signer| signer.sign_proposal(proposal))
+                    .await?;
+
+                self.metrics
+                    .signature_signing_time
+                    .observe(start.elapsed().as_secs_f64());
+
+                self.update_sign_watermark(
+                    sign_watermark,
+                    Signed {
+                        height: signed_proposal.height().as_u64(),
+                        round: signed_proposal.round().as_i64(),
+                        step: SignStep::Propose,
+                        signature: Ctx::SigningScheme::encode_signature(&signed_proposal.signature),
+                    },
+                )?;
+
+                Ok(r.resume_with(signed_proposal))
+            }
+
+            Effect::SignImplicitProposal(proposal, r) => {
+                let start = Instant::now();
+
+                let signed_proposal = self
+                    .sign(move |signer| signer.sign_implicit_proposal(proposal))
+                    .await?;
 
                 self.metrics
                     .signature_signing_time
@@ -891,12 +1210,27 @@ where
             Effect::SignVote(vote, r) => {
                 let start = Instant::now();
 
-                let signed_vote = self.signing_provider.sign_vote(vote);
+                let signed_vote = self.sign(move |signer| signer.sign_vote(vote)).await?;
 
                 self.metrics
                     .signature_signing_time
                     .observe(start.elapsed().as_secs_f64());
 
+                let step = match signed_vote.vote_type() {
+                    VoteType::Prevote => SignStep::Prevote,
+                    VoteType::Precommit => SignStep::Precommit,
+                };
+
+                self.update_sign_watermark(
+                    sign_watermark,
+                    Signed {
+                        height: signed_vote.height().as_u64(),
+                        round: signed_vote.round().as_i64(),
+                        step,
+                        signature: Ctx::SigningScheme::encode_signature(&signed_vote.signature),
+                    },
+                )?;
+
                 Ok(r.resume_with(signed_vote))
             }
 
@@ -916,6 +1250,25 @@ where
                     }
                 };
 
+                self.metrics
+                    .signature_verification_time
+                    .observe(start.elapsed().as_secs_f64());
+
+                verified.get_or_insert(valid);
+
+                Ok(r.resume_with(valid))
+            }
+
+            Effect::VerifySignatureBatch(votes, r) => {
+                let start = Instant::now();
+
+                let batch = votes
+                    .iter()
+                    .map(|(vote, pk)| (&vote.message, &vote.signature, pk))
+                    .collect::<Vec<_>>();
+
+                let valid = self.signing_provider.verify_batch(&batch);
+
                 self.metrics
                     .signature_verification_time
                     .observe(start.elapsed().as_secs_f64());
@@ -935,13 +1288,34 @@ where
 
             Effect::ExtendVote(height, round, value_id, r) => {
                 if let Some(extension) = self.extend_vote(height, round, value_id).await? {
-                    let signed_extension = self.signing_provider.sign_vote_extension(extension);
+                    let signed_extension = self
+                        .sign(move |signer| signer.sign_vote_extension(extension))
+                        .await?;
                     Ok(r.resume_with(Some(signed_extension)))
                 } else {
                     Ok(r.resume_with(None))
                 }
             }
 
+            Effect::GetTime(r) => {
+                // The time at which the proposals replayed from the WAL were received is not known,
+                // in which case consensus considers them timely
+                if phase == Phase::Recovering {
+                    return Ok(r.resume_with(None));
+                }
+
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+
+                Ok(r.resume_with(Some(Timestamp::from_unix_duration(now))))
+            }
+
+            Effect::ValidateProposal(signed_proposal, r) => {
+                let validity = self.validate_proposal(signed_proposal.message).await?;
+                Ok(r.resume_with(validity))
+            }
+
             Effect::VerifyVoteExtension(height, round, value_id, signed_extension, pk, r) => {
                 let valid = self.signing_provider.verify_signed_vote_extension(
                     &signed_extension.message,
@@ -992,21 +1366,36 @@ where
             }
 
             Effect::GetValue(height, round, timeout, r) => {
-                let timeout_duration = timeouts.duration_for(timeout.kind);
+                let timeout_duration = timeouts.duration_for(timeout);
+                timeouts.proposing(timeout);
 
-                self.get_value(myself, height, round, timeout_duration)
-                    .map_err(|e| eyre!("Error when asking for value to be built: {e:?}"))?;
+                self.get_value(
+                    myself,
+                    height,
+                    round,
+                    timeout_duration,
+                    params.max_block_size,
+                )
+                .map_err(|e| eyre!("Error when asking for value to be built: {e:?}"))?;
 
                 Ok(r.resume_with(()))
             }
 
             Effect::GetValidatorSet(height, r) => {
+                if let Some(validator_set) = validator_sets.get(&height) {
+                    return Ok(r.resume_with(Some(validator_set.clone())));
+                }
+
                 let validator_set = self
                     .get_validator_set(height)
                     .await
                     .map_err(|e| warn!("No validator set found for height {height}: {e:?}"))
                     .ok();
 
+                if let Some(validator_set) = &validator_set {
+                    validator_sets.insert(height, validator_set.clone());
+                }
+
                 Ok(r.resume_with(validator_set))
             }
 
@@ -1039,6 +1428,13 @@ where
                     })
                     .map_err(|e| eyre!("Error when sending decided value to host: {e:?}"))?;
 
+                // Pre-fetch the validator set for the next height, unless the application
+                // already scheduled it, so that starting the next height does not block on it
+                let next_height = height.increment();
+                if !validator_sets.contains(&next_height) {
+                    self.prefetch_validator_set(myself, next_height)?;
+                }
+
                 if let Some(sync) = &self.sync {
                     sync.cast(SyncMsg::Decided(height))
                         .map_err(|e| eyre!("Error when sending decided height to sync: {e:?}"))?;
@@ -1047,6 +1443,25 @@ where
                 Ok(r.resume_with(()))
             }
 
+            Effect::ReportEvidence(evidence, r) => {
+                self.tx_event
+                    .send(|| Event::MisbehaviorEvidence(evidence.clone()));
+
+                self.host
+                    .cast(HostMsg::MisbehaviorEvidence { evidence })
+                    .map_err(|e| eyre!("Error when sending evidence to host: {e:?}"))?;
+
+                Ok(r.resume_with(()))
+            }
+
+            Effect::PublishEvidence(evidence, r) => {
+                self.network
+                    .cast(NetworkMsg::PublishEvidence(evidence))
+                    .map_err(|e| eyre!("Error when publishing evidence: {e:?}"))?;
+
+                Ok(r.resume_with(()))
+            }
+
             Effect::GetVoteSet(height, round, r) => {
                 if let Some(sync) = &self.sync {
                     debug!(%height, %round, "Request sync to obtain the vote set from peers");
@@ -1126,13 +1541,33 @@ where
         self.network
             .cast(NetworkMsg::Subscribe(Box::new(myself.clone())))?;
 
+        let sign_watermark =
+            SignWatermark::load(self.sign_watermark_path.clone()).map_err(|e| {
+                eyre!(
+                    "Failed to load last signed watermark from {}: {e}",
+                    self.sign_watermark_path.display()
+                )
+            })?;
+
+        if let Some(last) = sign_watermark.last_vote() {
+            info!(path = %self.sign_watermark_path.display(), "Last signed vote: {last}");
+        }
+
         Ok(State {
             timers: Timers::new(Box::new(myself)),
-            timeouts: Timeouts::new(self.timeout_config),
+            timeouts: Timeouts::new(
+                self.height_params.timeouts,
+                Arc::clone(&self.timeout_strategy),
+            ),
+            params: self.height_params.clone(),
+            scheduled_params: Scheduled::new(),
             consensus: ConsensusState::new(self.ctx.clone(), self.params.clone()),
             connected_peers: BTreeSet::new(),
             phase: Phase::Unstarted,
             msg_buffer: MessageBuffer::new(MAX_BUFFER_SIZE),
+            validator_sets: ValidatorSetCache::new(MAX_CACHED_VALIDATOR_SETS),
+            sign_watermark,
+            synced_values: BTreeMap::new(),
         })
     }
 
@@ -1187,7 +1622,10 @@ fn should_buffer<Ctx: Context>(msg: &Msg<Ctx>) -> bool {
     !matches!(
         msg,
         Msg::StartHeight(..)
+            | Msg::ScheduleValidatorSet(..)
+            | Msg::ScheduleParams(..)
             | Msg::GetStatus(..)
+            | Msg::DumpState(..)
             | Msg::NetworkEvent(NetworkEvent::Listening(..))
             | Msg::NetworkEvent(NetworkEvent::PeerConnected(..))
             | Msg::NetworkEvent(NetworkEvent::PeerDisconnected(..))
@@ -4,9 +4,14 @@ use std::time::Duration;
 use derive_where::derive_where;
 use ractor::{ActorRef, RpcReplyPort};
 
+use malachitebft_config::ByteSize;
 use malachitebft_core_consensus::{PeerId, VoteExtensionError};
-use malachitebft_core_types::{CommitCertificate, Context, Round, ValueId, VoteExtensions};
-use malachitebft_sync::RawDecidedValue;
+use malachitebft_core_types::{
+    CommitCertificate, Context, Evidence, Round, Validity, ValueId, VoteExtensions,
+};
+use malachitebft_sync::{
+    ApplySnapshotChunkResult, OfferSnapshotResult, RawDecidedValue, Snapshot, SyncProgress,
+};
 
 use crate::consensus::ConsensusRef;
 use crate::util::streaming::StreamMessage;
@@ -29,11 +34,12 @@ pub enum HostMsg<Ctx: Context> {
         proposer: Ctx::Address,
     },
 
-    /// Request to build a local value to propose
+    /// Request to build a local value to propose, of at most the given size
     GetValue {
         height: Ctx::Height,
         round: Round,
         timeout: Duration,
+        max_block_size: ByteSize,
         reply_to: RpcReplyPort<LocallyProposedValue<Ctx>>,
     },
 
@@ -50,6 +56,15 @@ pub enum HostMsg<Ctx: Context> {
         reply_to: RpcReplyPort<Option<Ctx::Extension>>,
     },
 
+    /// Validate a proposal received from another validator, before its value is received.
+    ///
+    /// If the proposal is deemed invalid, consensus will prevote nil for it right away.
+    /// Applications which do not need to reject proposals early should reply with `Validity::Valid`.
+    ValidateProposal {
+        proposal: Ctx::Proposal,
+        reply_to: RpcReplyPort<Validity>,
+    },
+
     /// Verify a vote extension
     ///
     /// If the vote extension is deemed invalid, the vote it was part of
@@ -101,6 +116,12 @@ pub enum HostMsg<Ctx: Context> {
         consensus: ConsensusRef<Ctx>,
     },
 
+    /// Consensus has detected that a validator equivocated.
+    MisbehaviorEvidence {
+        /// Evidence of the misbehavior, including both conflicting signed messages.
+        evidence: Evidence<Ctx>,
+    },
+
     // Retrieve decided value from the block store
     GetDecidedValue {
         height: Ctx::Height,
@@ -117,6 +138,33 @@ pub enum HostMsg<Ctx: Context> {
         reply_to: RpcReplyPort<ProposedValue<Ctx>>,
     },
 
+    /// Request the snapshots of the application state available to serve to peers
+    ListSnapshots {
+        reply_to: RpcReplyPort<Vec<Snapshot<Ctx>>>,
+    },
+
+    /// Request a chunk of the snapshot taken at the given height, in the given format
+    LoadSnapshotChunk {
+        height: Ctx::Height,
+        format: u32,
+        index: u32,
+        reply_to: RpcReplyPort<Option<Bytes>>,
+    },
+
+    /// Offer a snapshot obtained from peers to restore the application state from
+    OfferSnapshot {
+        snapshot: Snapshot<Ctx>,
+        reply_to: RpcReplyPort<OfferSnapshotResult>,
+    },
+
+    /// Apply the chunk at the given index of the snapshot previously accepted
+    ApplySnapshotChunk {
+        index: u32,
+        chunk: Bytes,
+        from: PeerId,
+        reply_to: RpcReplyPort<ApplySnapshotChunkResult>,
+    },
+
     /// A peer joined our local view of the network.
     /// In a gossip network, there is no guarantee that we will ever see all peers,
     /// as we are typically only connected to a subset of the network (i.e. in our mesh).
@@ -133,4 +181,11 @@ pub enum HostMsg<Ctx: Context> {
         /// The ID of the peer that left
         peer_id: PeerId,
     },
+
+    /// Progress of the node in catching up with its peers, reported periodically
+    /// as well as whenever the node catches up with its peers or falls behind them.
+    SyncProgress {
+        /// The current sync progress
+        progress: SyncProgress<Ctx>,
+    },
 }
@@ -1,5 +1,4 @@
 use std::collections::{BTreeSet, HashMap};
-use std::marker::PhantomData;
 
 use async_trait::async_trait;
 use derive_where::derive_where;
@@ -9,7 +8,7 @@ use libp2p::request_response;
 use ractor::port::OutputPortSubscriber;
 use ractor::{Actor, ActorProcessingErr, ActorRef, OutputPort, RpcReplyPort};
 use tokio::task::JoinHandle;
-use tracing::{error, trace};
+use tracing::{error, trace, warn};
 
 use malachitebft_sync::{
     self as sync, InboundRequestId, OutboundRequestId, RawMessage, Request, Response,
@@ -17,11 +16,13 @@ use malachitebft_sync::{
 
 use malachitebft_codec as codec;
 use malachitebft_core_consensus::SignedConsensusMsg;
-use malachitebft_core_types::{Context, SignedProposal, SignedVote};
+use malachitebft_core_types::{Context, Evidence, SignedProposal, SignedVote};
 use malachitebft_metrics::SharedRegistry;
 use malachitebft_network::handle::CtrlHandle;
 use malachitebft_network::{Channel, Config, Event, Multiaddr, PeerId};
 
+pub use malachitebft_network::{MessageId, ValidationResult};
+
 use crate::consensus::ConsensusCodec;
 use crate::sync::SyncCodec;
 use crate::util::streaming::StreamMessage;
@@ -29,18 +30,43 @@ use crate::util::streaming::StreamMessage;
 pub type NetworkRef<Ctx> = ActorRef<Msg<Ctx>>;
 pub type NetworkMsg<Ctx> = Msg<Ctx>;
 
-pub struct Network<Ctx, Codec> {
+/// Validates the messages received over gossip once decoded, before they are
+/// delivered to consensus and forwarded to other peers.
+///
+/// Rejected messages are dropped and penalize the peer which forwarded them,
+/// when GossipSub peer scoring is enabled.
+///
+/// Accepted votes and proposals are only forwarded once consensus has verified their signature.
+pub trait MessageValidator<Ctx: Context>: Send + Sync + 'static {
+    fn validate(&self, event: &NetworkEvent<Ctx>) -> ValidationResult;
+}
+
+/// Accepts all the messages which can be decoded.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AcceptAll;
+
+impl<Ctx: Context> MessageValidator<Ctx> for AcceptAll {
+    fn validate(&self, _event: &NetworkEvent<Ctx>) -> ValidationResult {
+        ValidationResult::Accept
+    }
+}
+
+pub struct Network<Ctx: Context, Codec> {
     codec: Codec,
+    validator: Box<dyn MessageValidator<Ctx>>,
     span: tracing::Span,
-    marker: PhantomData<Ctx>,
 }
 
-impl<Ctx, Codec> Network<Ctx, Codec> {
-    pub fn new(codec: Codec, span: tracing::Span) -> Self {
+impl<Ctx: Context, Codec> Network<Ctx, Codec> {
+    pub fn new(
+        codec: Codec,
+        validator: Box<dyn MessageValidator<Ctx>>,
+        span: tracing::Span,
+    ) -> Self {
         Self {
             codec,
+            validator,
             span,
-            marker: PhantomData,
         }
     }
 }
@@ -56,6 +82,7 @@ where
         config: Config,
         metrics: SharedRegistry,
         codec: Codec,
+        validator: Box<dyn MessageValidator<Ctx>>,
         span: tracing::Span,
     ) -> Result<ActorRef<Msg<Ctx>>, ractor::SpawnErr> {
         let args = Args {
@@ -64,7 +91,7 @@ where
             metrics,
         };
 
-        let (actor_ref, _) = Actor::spawn(None, Self::new(codec, span), args).await?;
+        let (actor_ref, _) = Actor::spawn(None, Self::new(codec, validator, span), args).await?;
         Ok(actor_ref)
     }
 }
@@ -82,11 +109,13 @@ pub enum NetworkEvent<Ctx: Context> {
     PeerConnected(PeerId),
     PeerDisconnected(PeerId),
 
-    Vote(PeerId, SignedVote<Ctx>),
+    Vote(PeerId, MessageId, SignedVote<Ctx>),
 
-    Proposal(PeerId, SignedProposal<Ctx>),
+    Proposal(PeerId, MessageId, SignedProposal<Ctx>),
     ProposalPart(PeerId, StreamMessage<Ctx::ProposalPart>),
 
+    Evidence(PeerId, Evidence<Ctx>),
+
     Status(PeerId, Status<Ctx>),
 
     Request(InboundRequestId, PeerId, Request<Ctx>),
@@ -129,6 +158,9 @@ pub enum Msg<Ctx: Context> {
     /// Publish a proposal part
     PublishProposalPart(StreamMessage<Ctx::ProposalPart>),
 
+    /// Publish evidence of misbehavior
+    PublishEvidence(Evidence<Ctx>),
+
     /// Broadcast status to all direct peers
     BroadcastStatus(Status<Ctx>),
 
@@ -138,14 +170,57 @@ pub enum Msg<Ctx: Context> {
     /// Send a response for a request to a peer
     OutgoingResponse(InboundRequestId, Response<Ctx>),
 
+    /// Replace the peers allowed to connect to this node, if the allow-list is enabled
+    UpdateAllowedPeers(Vec<PeerId>),
+
+    /// Report the validity of a vote or proposal received over gossip, once consensus has verified it
+    ReportValidationResult(MessageId, ValidationResult),
+
     /// Request for number of peers from gossip
     GetState { reply: RpcReplyPort<usize> },
 
+    /// Request for the list of peers we are connected to
+    GetPeers { reply: RpcReplyPort<Vec<PeerId>> },
+
     // Event emitted by the gossip layer
     #[doc(hidden)]
     NewEvent(Event),
 }
 
+impl<Ctx: Context, Codec> Network<Ctx, Codec> {
+    /// Validate a decoded gossip message, report the outcome to the network layer
+    /// so that only valid messages are forwarded to other peers, and deliver it if valid.
+    ///
+    /// The outcome for accepted votes and proposals is reported by consensus instead,
+    /// once it has verified their signature.
+    async fn validate_and_deliver(
+        &self,
+        ctrl_handle: &CtrlHandle,
+        output_port: &OutputPort<NetworkEvent<Ctx>>,
+        message_id: MessageId,
+        event: NetworkEvent<Ctx>,
+    ) -> Result<(), ActorProcessingErr> {
+        let result = self.validator.validate(&event);
+
+        let verified_by_consensus =
+            matches!(event, NetworkEvent::Vote(..) | NetworkEvent::Proposal(..));
+
+        if result != ValidationResult::Accept || !verified_by_consensus {
+            ctrl_handle
+                .report_validation_result(message_id, result)
+                .await?;
+        }
+
+        match result {
+            ValidationResult::Accept => output_port.send(event),
+            ValidationResult::Reject => warn!(?event, "Rejected invalid gossip message"),
+            ValidationResult::Ignore => trace!(?event, "Ignored gossip message"),
+        }
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl<Ctx, Codec> Actor for Network<Ctx, Codec>
 where
@@ -154,6 +229,7 @@ where
     Codec: codec::Codec<Ctx::ProposalPart>,
     Codec: codec::Codec<SignedConsensusMsg<Ctx>>,
     Codec: codec::Codec<StreamMessage<Ctx::ProposalPart>>,
+    Codec: codec::Codec<Evidence<Ctx>>,
     Codec: codec::Codec<sync::Status<Ctx>>,
     Codec: codec::Codec<sync::Request<Ctx>>,
     Codec: codec::Codec<sync::Response<Ctx>>,
@@ -237,6 +313,11 @@ where
                 }
             }
 
+            Msg::PublishEvidence(evidence) => match self.codec.encode(&evidence) {
+                Ok(data) => ctrl_handle.publish(Channel::Evidence, data).await?,
+                Err(e) => error!("Failed to encode evidence: {e:?}"),
+            },
+
             Msg::BroadcastStatus(status) => {
                 let status = sync::Status {
                     peer_id: ctrl_handle.peer_id(),
@@ -281,6 +362,14 @@ where
                 };
             }
 
+            Msg::UpdateAllowedPeers(peers) => ctrl_handle.update_allowed_peers(peers).await?,
+
+            Msg::ReportValidationResult(message_id, result) => {
+                ctrl_handle
+                    .report_validation_result(message_id, result)
+                    .await?
+            }
+
             Msg::NewEvent(Event::Listening(addr)) => {
                 output_port.send(NetworkEvent::Listening(addr));
             }
@@ -295,30 +384,39 @@ where
                 output_port.send(NetworkEvent::PeerDisconnected(peer_id));
             }
 
-            Msg::NewEvent(Event::Message(Channel::Consensus, from, data)) => {
+            Msg::NewEvent(Event::Message(Channel::Consensus, from, message_id, data)) => {
                 let msg = match self.codec.decode(data) {
                     Ok(msg) => msg,
                     Err(e) => {
                         error!(%from, "Failed to decode gossip message: {e:?}");
+                        ctrl_handle
+                            .report_validation_result(message_id, ValidationResult::Reject)
+                            .await?;
                         return Ok(());
                     }
                 };
 
                 let event = match msg {
-                    SignedConsensusMsg::Vote(vote) => NetworkEvent::Vote(from, vote),
+                    SignedConsensusMsg::Vote(vote) => {
+                        NetworkEvent::Vote(from, message_id.clone(), vote)
+                    }
                     SignedConsensusMsg::Proposal(proposal) => {
-                        NetworkEvent::Proposal(from, proposal)
+                        NetworkEvent::Proposal(from, message_id.clone(), proposal)
                     }
                 };
 
-                output_port.send(event);
+                self.validate_and_deliver(ctrl_handle, output_port, message_id, event)
+                    .await?;
             }
 
-            Msg::NewEvent(Event::Message(Channel::ProposalParts, from, data)) => {
+            Msg::NewEvent(Event::Message(Channel::ProposalParts, from, message_id, data)) => {
                 let msg: StreamMessage<Ctx::ProposalPart> = match self.codec.decode(data) {
                     Ok(stream_msg) => stream_msg,
                     Err(e) => {
                         error!(%from, "Failed to decode stream message: {e:?}");
+                        ctrl_handle
+                            .report_validation_result(message_id, ValidationResult::Reject)
+                            .await?;
                         return Ok(());
                     }
                 };
@@ -330,29 +428,64 @@ where
                     "Received proposal part"
                 );
 
-                output_port.send(NetworkEvent::ProposalPart(from, msg));
+                let event = NetworkEvent::ProposalPart(from, msg);
+                self.validate_and_deliver(ctrl_handle, output_port, message_id, event)
+                    .await?;
+            }
+
+            Msg::NewEvent(Event::Message(Channel::Evidence, from, message_id, data)) => {
+                let evidence: Evidence<Ctx> = match self.codec.decode(data) {
+                    Ok(evidence) => evidence,
+                    Err(e) => {
+                        error!(%from, "Failed to decode evidence: {e:?}");
+                        ctrl_handle
+                            .report_validation_result(message_id, ValidationResult::Reject)
+                            .await?;
+                        return Ok(());
+                    }
+                };
+
+                trace!(
+                    %from,
+                    height = %evidence.height(),
+                    validator = %evidence.validator_address(),
+                    "Received evidence"
+                );
+
+                let event = NetworkEvent::Evidence(from, evidence);
+                self.validate_and_deliver(ctrl_handle, output_port, message_id, event)
+                    .await?;
             }
 
-            Msg::NewEvent(Event::Message(Channel::Sync, from, data)) => {
+            Msg::NewEvent(Event::Message(Channel::Sync, from, message_id, data)) => {
                 let status: sync::Status<Ctx> = match self.codec.decode(data) {
                     Ok(status) => status,
                     Err(e) => {
                         error!(%from, "Failed to decode status message: {e:?}");
+                        ctrl_handle
+                            .report_validation_result(message_id, ValidationResult::Reject)
+                            .await?;
                         return Ok(());
                     }
                 };
 
                 if from != status.peer_id {
                     error!(%from, %status.peer_id, "Mismatched peer ID in status message");
+                    ctrl_handle
+                        .report_validation_result(message_id, ValidationResult::Reject)
+                        .await?;
                     return Ok(());
                 }
 
                 trace!(%from, height = %status.height, "Received status");
 
-                output_port.send(NetworkEvent::Status(
+                let event = NetworkEvent::Status(
                     status.peer_id,
                     Status::new(status.height, status.history_min_height),
-                ));
+                );
+
+                self.validate_and_deliver(ctrl_handle, output_port, message_id, event)
+                    .await?;
             }
 
             Msg::NewEvent(Event::Sync(raw_msg)) => match raw_msg {
@@ -406,6 +539,14 @@ where
                 };
                 reply.send(number_peers)?;
             }
+
+            Msg::GetPeers { reply } => {
+                let peers = match state {
+                    State::Stopped => Vec::new(),
+                    State::Running { peers, .. } => peers.iter().copied().collect(),
+                };
+                reply.send(peers)?;
+            }
         }
 
         Ok(())
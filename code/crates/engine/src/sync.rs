@@ -15,10 +15,15 @@ use malachitebft_codec as codec;
 use malachitebft_core_consensus::PeerId;
 use malachitebft_core_types::{CertificateError, CommitCertificate, Context, Height, Round};
 use malachitebft_sync::{self as sync, InboundRequestId, OutboundRequestId, Response};
-use malachitebft_sync::{RawDecidedValue, Request};
+use malachitebft_sync::{
+    ApplySnapshotChunkResult, OfferSnapshotResult, RawDecidedValue, Request, Snapshot,
+    SnapshotChunkResponse, SnapshotListRequest, ValueRangeRequest,
+};
 
+use crate::consensus::{ConsensusRef, Msg as ConsensusMsg};
 use crate::host::{HostMsg, HostRef};
 use crate::network::{NetworkEvent, NetworkMsg, NetworkRef, Status};
+use crate::util::events::{Event, TxEvent};
 use crate::util::ticker::ticker;
 use crate::util::timers::{TimeoutElapsed, TimerScheduler};
 
@@ -88,6 +93,9 @@ pub enum Msg<Ctx: Context> {
     /// Host has a response for the blocks request
     GotDecidedBlock(InboundRequestId, Ctx::Height, Option<RawDecidedValue<Ctx>>),
 
+    /// Host has a response for the request for a range of blocks, starting at the given height
+    GotDecidedBlocks(InboundRequestId, Ctx::Height, Vec<RawDecidedValue<Ctx>>),
+
     /// A timeout has elapsed
     TimeoutElapsed(TimeoutElapsed<Timeout>),
 
@@ -99,6 +107,21 @@ pub enum Msg<Ctx: Context> {
 
     /// Consensus has sent a vote set response to a peer
     SentVoteSetResponse(InboundRequestId, Ctx::Height, Round),
+
+    /// Consensus is ready, and can be instructed to start at the height following a restored snapshot
+    ConsensusReady(ConsensusRef<Ctx>),
+
+    /// Host has a response for the request for the snapshots it has
+    GotSnapshots(InboundRequestId, Vec<Snapshot<Ctx>>),
+
+    /// Host has a response for the request for a snapshot chunk
+    GotSnapshotChunk(InboundRequestId, SnapshotChunkResponse<Ctx>),
+
+    /// Host has accepted or rejected the snapshot offered to it
+    SnapshotOffered(Snapshot<Ctx>, OfferSnapshotResult),
+
+    /// Host has applied the snapshot chunk at the given index, received from the given peer
+    SnapshotChunkApplied(PeerId, u32, ApplySnapshotChunkResult),
 }
 
 impl<Ctx: Context> From<NetworkEvent<Ctx>> for Msg<Ctx> {
@@ -117,13 +140,39 @@ impl<Ctx: Context> From<TimeoutElapsed<Timeout>> for Msg<Ctx> {
 pub struct Params {
     pub status_update_interval: Duration,
     pub request_timeout: Duration,
+
+    /// Maximum number of values to request from a peer at once
+    pub batch_size: u64,
+
+    /// Maximum number of value requests in flight at the same time
+    pub parallel_requests: usize,
+
+    /// Maximum size in bytes of the values sent in a single response
+    pub max_response_size: u64,
+
+    /// How long a peer which failed too many requests or sent invalid certificates is banned
+    pub peer_ban_duration: Duration,
+
+    /// Whether to bootstrap a node without any state from a snapshot offered by peers
+    pub snapshot_sync: bool,
+
+    /// Minimum number of peers which must offer the same snapshot before restoring it
+    pub snapshot_min_peers: usize,
 }
 
 impl Default for Params {
     fn default() -> Self {
+        let range = sync::RangeParams::default();
+
         Self {
             status_update_interval: Duration::from_secs(5),
             request_timeout: Duration::from_secs(10),
+            batch_size: range.batch_size,
+            parallel_requests: range.parallel_requests,
+            max_response_size: range.max_response_size,
+            peer_ban_duration: sync::ScoringParams::default().ban_duration,
+            snapshot_sync: false,
+            snapshot_min_peers: sync::SnapshotParams::default().min_peers,
         }
     }
 }
@@ -140,6 +189,9 @@ pub struct State<Ctx: Context> {
 
     /// Task for sending status updates
     ticker: JoinHandle<()>,
+
+    /// The consensus actor, once it is ready
+    consensus: Option<ConsensusRef<Ctx>>,
 }
 
 #[allow(dead_code)]
@@ -149,6 +201,7 @@ pub struct Sync<Ctx: Context> {
     host: HostRef<Ctx>,
     params: Params,
     metrics: sync::Metrics,
+    tx_event: TxEvent<Ctx>,
     span: tracing::Span,
 }
 
@@ -162,6 +215,7 @@ where
         host: HostRef<Ctx>,
         params: Params,
         metrics: sync::Metrics,
+        tx_event: TxEvent<Ctx>,
         span: tracing::Span,
     ) -> Self {
         Self {
@@ -170,6 +224,7 @@ where
             host,
             params,
             metrics,
+            tx_event,
             span,
         }
     }
@@ -180,9 +235,10 @@ where
         host: HostRef<Ctx>,
         params: Params,
         metrics: sync::Metrics,
+        tx_event: TxEvent<Ctx>,
         span: tracing::Span,
     ) -> Result<SyncRef<Ctx>, ractor::SpawnErr> {
-        let actor = Self::new(ctx, gossip, host, params, metrics, span);
+        let actor = Self::new(ctx, gossip, host, params, metrics, tx_event, span);
         let (actor_ref, _) = Actor::spawn(None, actor, ()).await?;
         Ok(actor_ref)
     }
@@ -198,7 +254,14 @@ where
             state: &mut state.sync,
             metrics: &self.metrics,
             with: effect => {
-                self.handle_effect(myself, &mut state.timers, &mut state.inflight, effect).await
+                self.handle_effect(
+                    myself,
+                    &mut state.timers,
+                    &mut state.inflight,
+                    state.consensus.as_ref(),
+                    effect,
+                )
+                .await
             }
         )
     }
@@ -210,11 +273,74 @@ where
         .map_err(|e| eyre!("Failed to get earliest history height: {e:?}").into())
     }
 
+    async fn send_request(
+        &self,
+        peer_id: PeerId,
+        request: Request<Ctx>,
+        timers: &mut Timers,
+        inflight: &mut InflightRequests<Ctx>,
+    ) -> Option<OutboundRequestId> {
+        let result = ractor::call!(self.gossip, |reply_to| {
+            NetworkMsg::OutgoingRequest(peer_id, request.clone(), reply_to)
+        });
+
+        match result {
+            Ok(request_id) => {
+                let request_id = OutboundRequestId::new(request_id);
+
+                timers.start_timer(
+                    Timeout::Request(request_id.clone()),
+                    self.params.request_timeout,
+                );
+
+                inflight.insert(
+                    request_id.clone(),
+                    InflightRequest {
+                        peer_id,
+                        request_id: request_id.clone(),
+                        request,
+                    },
+                );
+
+                Some(request_id)
+            }
+            Err(e) => {
+                error!("Failed to send request to gossip layer: {e}");
+                None
+            }
+        }
+    }
+
+    /// Start consensus at the height following the one of the snapshot the application
+    /// state was just restored from, with the validator set for that height.
+    async fn start_after_snapshot(
+        &self,
+        consensus: Option<&ConsensusRef<Ctx>>,
+        snapshot: Snapshot<Ctx>,
+    ) -> Result<(), ActorProcessingErr> {
+        let Some(consensus) = consensus else {
+            return Err(eyre!("Restored a snapshot before consensus was ready").into());
+        };
+
+        let height = snapshot.height().increment();
+
+        let validator_set = ractor::call!(self.host, |reply_to| HostMsg::GetValidatorSet {
+            height,
+            reply_to
+        })
+        .map_err(|e| eyre!("Failed to get validator set at height {height}: {e:?}"))?;
+
+        consensus.cast(ConsensusMsg::StartHeight(height, validator_set))?;
+
+        Ok(())
+    }
+
     async fn handle_effect(
         &self,
         myself: &ActorRef<Msg<Ctx>>,
         timers: &mut Timers,
         inflight: &mut InflightRequests<Ctx>,
+        consensus: Option<&ConsensusRef<Ctx>>,
         effect: sync::Effect<Ctx>,
     ) -> Result<sync::Resume<Ctx>, ActorProcessingErr> {
         use sync::Effect;
@@ -229,34 +355,11 @@ where
                 )))?;
             }
 
-            Effect::SendValueRequest(peer_id, value_request) => {
-                let request = Request::ValueRequest(value_request);
-                let result = ractor::call!(self.gossip, |reply_to| {
-                    NetworkMsg::OutgoingRequest(peer_id, request.clone(), reply_to)
-                });
+            Effect::SendValueRangeRequest(peer_id, range_request) => {
+                let request = Request::ValueRangeRequest(range_request);
+                let request_id = self.send_request(peer_id, request, timers, inflight).await;
 
-                match result {
-                    Ok(request_id) => {
-                        let request_id = OutboundRequestId::new(request_id);
-
-                        timers.start_timer(
-                            Timeout::Request(request_id.clone()),
-                            self.params.request_timeout,
-                        );
-
-                        inflight.insert(
-                            request_id.clone(),
-                            InflightRequest {
-                                peer_id,
-                                request_id,
-                                request,
-                            },
-                        );
-                    }
-                    Err(e) => {
-                        error!("Failed to send request to gossip layer: {e}");
-                    }
-                }
+                return Ok(sync::Resume::RequestSent(request_id));
             }
 
             Effect::SendValueResponse(request_id, value_response) => {
@@ -265,6 +368,28 @@ where
                     .cast(NetworkMsg::OutgoingResponse(request_id, response))?;
             }
 
+            Effect::SendValueRangeResponse(request_id, range_response) => {
+                let response = Response::ValueRangeResponse(range_response);
+                self.gossip
+                    .cast(NetworkMsg::OutgoingResponse(request_id, response))?;
+            }
+
+            Effect::GetDecidedValues(request_id, range_request) => {
+                let host = self.host.clone();
+                let myself = myself.clone();
+
+                // Fetch the values in the background, so as to not block the sync actor
+                // while the host is retrieving a potentially large number of values.
+                tokio::spawn(async move {
+                    let start = range_request.start;
+                    let values = get_decided_values(&host, range_request).await;
+
+                    if let Err(e) = myself.cast(Msg::GotDecidedBlocks(request_id, start, values)) {
+                        error!("Failed to send decided values to sync actor: {e}");
+                    }
+                });
+            }
+
             Effect::GetDecidedValue(request_id, height) => {
                 self.host.call_and_forward(
                     |reply_to| HostMsg::GetDecidedValue { height, reply_to },
@@ -282,30 +407,109 @@ where
                 );
 
                 let request = Request::VoteSetRequest(vote_set_request);
+                self.send_request(peer_id, request, timers, inflight).await;
+            }
 
-                let result = ractor::call!(self.gossip, |reply_to| {
-                    NetworkMsg::OutgoingRequest(peer_id, request.clone(), reply_to)
-                });
-                match result {
-                    Ok(request_id) => {
-                        timers.start_timer(
-                            Timeout::Request(request_id.clone()),
-                            self.params.request_timeout,
-                        );
-
-                        inflight.insert(
-                            request_id.clone(),
-                            InflightRequest {
-                                peer_id,
-                                request_id,
-                                request,
-                            },
-                        );
-                    }
-                    Err(e) => {
-                        error!("Failed to send request to gossip layer: {e}");
-                    }
-                }
+            Effect::SendSnapshotListRequest(peer_id) => {
+                let request = Request::SnapshotListRequest(SnapshotListRequest);
+                self.send_request(peer_id, request, timers, inflight).await;
+            }
+
+            Effect::SendSnapshotListResponse(request_id, list_response) => {
+                let response = Response::SnapshotListResponse(list_response);
+                self.gossip
+                    .cast(NetworkMsg::OutgoingResponse(request_id, response))?;
+            }
+
+            Effect::GetSnapshots(request_id) => {
+                self.host.call_and_forward(
+                    |reply_to| HostMsg::ListSnapshots { reply_to },
+                    myself,
+                    move |snapshots| Msg::<Ctx>::GotSnapshots(request_id, snapshots),
+                    None,
+                )?;
+            }
+
+            Effect::SendSnapshotChunkRequest(peer_id, chunk_request) => {
+                let request = Request::SnapshotChunkRequest(chunk_request);
+                self.send_request(peer_id, request, timers, inflight).await;
+            }
+
+            Effect::SendSnapshotChunkResponse(request_id, chunk_response) => {
+                let response = Response::SnapshotChunkResponse(chunk_response);
+                self.gossip
+                    .cast(NetworkMsg::OutgoingResponse(request_id, response))?;
+            }
+
+            Effect::GetSnapshotChunk(request_id, chunk_request) => {
+                let (height, format, index) = (
+                    chunk_request.height,
+                    chunk_request.format,
+                    chunk_request.index,
+                );
+
+                self.host.call_and_forward(
+                    |reply_to| HostMsg::LoadSnapshotChunk {
+                        height,
+                        format,
+                        index,
+                        reply_to,
+                    },
+                    myself,
+                    move |chunk| {
+                        let response = SnapshotChunkResponse::new(height, format, index, chunk);
+                        Msg::<Ctx>::GotSnapshotChunk(request_id, response)
+                    },
+                    None,
+                )?;
+            }
+
+            Effect::OfferSnapshot(snapshot) => {
+                let offered = snapshot.clone();
+
+                self.host.call_and_forward(
+                    |reply_to| HostMsg::OfferSnapshot {
+                        snapshot: offered,
+                        reply_to,
+                    },
+                    myself,
+                    move |result| Msg::<Ctx>::SnapshotOffered(snapshot, result),
+                    None,
+                )?;
+            }
+
+            Effect::ApplySnapshotChunk(peer_id, index, chunk) => {
+                self.host.call_and_forward(
+                    |reply_to| HostMsg::ApplySnapshotChunk {
+                        index,
+                        chunk,
+                        from: peer_id,
+                        reply_to,
+                    },
+                    myself,
+                    move |result| Msg::<Ctx>::SnapshotChunkApplied(peer_id, index, result),
+                    None,
+                )?;
+            }
+
+            Effect::SnapshotRestored(snapshot) => {
+                self.start_after_snapshot(consensus, snapshot).await?;
+            }
+
+            Effect::ReportProgress(progress) => {
+                self.tx_event.send(|| Event::SyncProgress(progress.clone()));
+                self.host.cast(HostMsg::SyncProgress { progress })?;
+            }
+
+            Effect::CaughtUp(progress) => {
+                self.tx_event.send(|| Event::CaughtUp(progress.tip_height));
+                self.host.cast(HostMsg::SyncProgress { progress })?;
+            }
+
+            Effect::FellBehind(progress) => {
+                self.tx_event
+                    .send(|| Event::FellBehind(progress.tip_height));
+                self.host.cast(HostMsg::SyncProgress { progress })?;
             }
         }
 
@@ -369,6 +573,14 @@ where
                         )
                         .await?;
                     }
+                    Request::ValueRangeRequest(range_request) => {
+                        self.process_input(
+                            &myself,
+                            state,
+                            sync::Input::ValueRangeRequest(request_id, from, range_request),
+                        )
+                        .await?;
+                    }
                     Request::VoteSetRequest(vote_set_request) => {
                         self.process_input(
                             &myself,
@@ -377,12 +589,29 @@ where
                         )
                         .await?;
                     }
+                    Request::SnapshotListRequest(list_request) => {
+                        self.process_input(
+                            &myself,
+                            state,
+                            sync::Input::SnapshotListRequest(request_id, from, list_request),
+                        )
+                        .await?;
+                    }
+                    Request::SnapshotChunkRequest(chunk_request) => {
+                        self.process_input(
+                            &myself,
+                            state,
+                            sync::Input::SnapshotChunkRequest(request_id, from, chunk_request),
+                        )
+                        .await?;
+                    }
                 };
             }
 
             Msg::NetworkEvent(NetworkEvent::Response(request_id, peer, response)) => {
                 // Cancel the timer associated with the request for which we just received a response
                 state.timers.cancel(&Timeout::Request(request_id.clone()));
+                state.inflight.remove(&request_id);
 
                 match response {
                     Response::ValueResponse(value_response) => {
@@ -393,6 +622,14 @@ where
                         )
                         .await?;
                     }
+                    Response::ValueRangeResponse(range_response) => {
+                        self.process_input(
+                            &myself,
+                            state,
+                            sync::Input::ValueRangeResponse(request_id, peer, range_response),
+                        )
+                        .await?;
+                    }
                     Response::VoteSetResponse(vote_set_response) => {
                         self.process_input(
                             &myself,
@@ -401,6 +638,22 @@ where
                         )
                         .await?;
                     }
+                    Response::SnapshotListResponse(list_response) => {
+                        self.process_input(
+                            &myself,
+                            state,
+                            sync::Input::SnapshotListResponse(request_id, peer, list_response),
+                        )
+                        .await?;
+                    }
+                    Response::SnapshotChunkResponse(chunk_response) => {
+                        self.process_input(
+                            &myself,
+                            state,
+                            sync::Input::SnapshotChunkResponse(request_id, peer, chunk_response),
+                        )
+                        .await?;
+                    }
                 }
             }
 
@@ -432,6 +685,55 @@ where
                 .await?;
             }
 
+            Msg::GotDecidedBlocks(request_id, start, blocks) => {
+                self.process_input(
+                    &myself,
+                    state,
+                    sync::Input::GotDecidedValues(request_id, start, blocks),
+                )
+                .await?;
+            }
+
+            Msg::ConsensusReady(consensus) => {
+                state.consensus = Some(consensus);
+            }
+
+            Msg::GotSnapshots(request_id, snapshots) => {
+                self.process_input(
+                    &myself,
+                    state,
+                    sync::Input::GotSnapshots(request_id, snapshots),
+                )
+                .await?;
+            }
+
+            Msg::GotSnapshotChunk(request_id, response) => {
+                self.process_input(
+                    &myself,
+                    state,
+                    sync::Input::GotSnapshotChunk(request_id, response),
+                )
+                .await?;
+            }
+
+            Msg::SnapshotOffered(snapshot, result) => {
+                self.process_input(
+                    &myself,
+                    state,
+                    sync::Input::SnapshotOffered(snapshot, result),
+                )
+                .await?;
+            }
+
+            Msg::SnapshotChunkApplied(peer, index, result) => {
+                self.process_input(
+                    &myself,
+                    state,
+                    sync::Input::SnapshotChunkApplied(peer, index, result),
+                )
+                .await?;
+            }
+
             Msg::InvalidCertificate(peer, certificate, error) => {
                 self.process_input(
                     &myself,
@@ -456,6 +758,7 @@ where
                                 &myself,
                                 state,
                                 sync::Input::SyncRequestTimedOut(
+                                    request_id,
                                     inflight.peer_id,
                                     inflight.request,
                                 ),
@@ -473,6 +776,53 @@ where
     }
 }
 
+/// Fetch from the host the decided values for consecutive heights of the requested range,
+/// stopping at the first missing value or once their total size would exceed `max_bytes`.
+///
+/// The value at the start of the range is always included if the host has it,
+/// even if it is larger than `max_bytes`.
+async fn get_decided_values<Ctx: Context>(
+    host: &HostRef<Ctx>,
+    request: ValueRangeRequest<Ctx>,
+) -> Vec<RawDecidedValue<Ctx>> {
+    let mut values = Vec::new();
+    let mut total_bytes = 0;
+    let mut height = request.start;
+
+    while height <= request.end {
+        let value = match ractor::call!(host, |reply_to| HostMsg::GetDecidedValue {
+            height,
+            reply_to
+        }) {
+            Ok(Some(value)) => value,
+            Ok(None) => break,
+            Err(e) => {
+                error!(%height, "Failed to get decided value from host: {e}");
+                break;
+            }
+        };
+
+        if value.certificate.height != height {
+            error!(
+                %height, value.height = %value.certificate.height,
+                "Received value for wrong height"
+            );
+            break;
+        }
+
+        total_bytes += value.value_bytes.len() as u64;
+
+        if total_bytes > request.max_bytes && !values.is_empty() {
+            break;
+        }
+
+        values.push(value);
+        height = height.increment();
+    }
+
+    values
+}
+
 #[async_trait]
 impl<Ctx> Actor for Sync<Ctx>
 where
@@ -498,11 +848,28 @@ where
 
         let rng = Box::new(rand::rngs::StdRng::from_entropy());
 
+        let range_params = sync::RangeParams {
+            batch_size: self.params.batch_size,
+            parallel_requests: self.params.parallel_requests,
+            max_response_size: self.params.max_response_size,
+        };
+
+        let scoring_params = sync::ScoringParams {
+            ban_duration: self.params.peer_ban_duration,
+            ..Default::default()
+        };
+
+        let snapshot_params = sync::SnapshotParams {
+            enabled: self.params.snapshot_sync,
+            min_peers: self.params.snapshot_min_peers,
+        };
+
         Ok(State {
-            sync: sync::State::new(rng),
+            sync: sync::State::new(rng, range_params, scoring_params, snapshot_params),
             timers: Timers::new(Box::new(myself.clone())),
             inflight: HashMap::new(),
             ticker,
+            consensus: None,
         })
     }
 
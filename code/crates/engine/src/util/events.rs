@@ -7,8 +7,9 @@ use tokio::sync::broadcast;
 
 use malachitebft_core_consensus::{LocallyProposedValue, ProposedValue, SignedConsensusMsg};
 use malachitebft_core_types::{
-    CommitCertificate, Context, Round, SignedVote, Timeout, ValueOrigin,
+    CommitCertificate, Context, Evidence, Round, SignedVote, Timeout, ValueOrigin,
 };
+use malachitebft_sync::SyncProgress;
 
 pub type RxEvent<Ctx> = broadcast::Receiver<Event<Ctx>>;
 
@@ -48,6 +49,7 @@ pub enum Event<Ctx: Context> {
     ProposedValue(LocallyProposedValue<Ctx>),
     ReceivedProposedValue(ProposedValue<Ctx>, ValueOrigin),
     Decided(CommitCertificate<Ctx>),
+    MisbehaviorEvidence(Evidence<Ctx>),
     Rebroadcast(SignedVote<Ctx>),
     RequestedVoteSet(Ctx::Height, Round),
     SentVoteSetResponse(Ctx::Height, Round, usize),
@@ -56,6 +58,9 @@ pub enum Event<Ctx: Context> {
     WalReplayTimeout(Timeout),
     WalReplayDone(Ctx::Height),
     WalReplayError(Arc<ActorProcessingErr>),
+    SyncProgress(SyncProgress<Ctx>),
+    CaughtUp(Ctx::Height),
+    FellBehind(Ctx::Height),
 }
 
 impl<Ctx: Context> fmt::Display for Event<Ctx> {
@@ -74,6 +79,9 @@ impl<Ctx: Context> fmt::Display for Event<Ctx> {
                 )
             }
             Event::Decided(cert) => write!(f, "Decided(value: {})", cert.value_id),
+            Event::MisbehaviorEvidence(evidence) => {
+                write!(f, "MisbehaviorEvidence(evidence: {evidence:?})")
+            }
             Event::Rebroadcast(msg) => write!(f, "Rebroadcast(msg: {msg:?})"),
             Event::RequestedVoteSet(height, round) => {
                 write!(f, "RequestedVoteSet(height: {height}, round: {round})")
@@ -91,6 +99,18 @@ impl<Ctx: Context> fmt::Display for Event<Ctx> {
             Event::WalReplayTimeout(timeout) => write!(f, "WalReplayTimeout(timeout: {timeout:?})"),
             Event::WalReplayDone(height) => write!(f, "WalReplayDone(height: {height})"),
             Event::WalReplayError(error) => write!(f, "WalReplayError({error})"),
+            Event::SyncProgress(progress) => {
+                write!(
+                    f,
+                    "SyncProgress(tip_height: {}, peers_tip_height: {:?}, eta: {:?}, caught_up: {})",
+                    progress.tip_height,
+                    progress.peers_tip_height,
+                    progress.eta,
+                    progress.caught_up
+                )
+            }
+            Event::CaughtUp(height) => write!(f, "CaughtUp(height: {height})"),
+            Event::FellBehind(height) => write!(f, "FellBehind(height: {height})"),
         }
     }
 }
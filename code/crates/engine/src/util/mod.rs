@@ -1,5 +1,9 @@
 pub mod events;
 pub mod msg_buffer;
+pub mod scheduled;
+pub mod sign_watermark;
 pub mod streaming;
 pub mod ticker;
+pub mod timeouts;
 pub mod timers;
+pub mod validator_sets;
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+/// Values scheduled by the application to take effect from a given height onwards,
+/// eg. the consensus parameters.
+pub struct Scheduled<Height, T> {
+    values: BTreeMap<Height, T>,
+}
+
+impl<Height, T> Scheduled<Height, T>
+where
+    Height: Ord,
+{
+    pub fn new() -> Self {
+        Self {
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Schedule the value to use from the given height onwards,
+    /// replacing any value previously scheduled for that height.
+    pub fn insert(&mut self, height: Height, value: T) {
+        self.values.insert(height, value);
+    }
+
+    /// Take the value to use at the given height, ie. the latest one scheduled
+    /// at or below that height, along with the height it was scheduled for.
+    ///
+    /// Values scheduled for lower heights are superseded by it, and dropped.
+    pub fn take_due(&mut self, height: &Height) -> Option<(Height, T)> {
+        let mut due = None;
+
+        while let Some(entry) = self.values.first_entry() {
+            if entry.key() > height {
+                break;
+            }
+
+            due = Some(entry.remove_entry());
+        }
+
+        due
+    }
+}
+
+impl<Height: Ord, T> Default for Scheduled<Height, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduled(heights: &[u64]) -> Scheduled<u64, &'static str> {
+        let mut scheduled = Scheduled::new();
+        for &height in heights {
+            scheduled.insert(height, "scheduled");
+        }
+        scheduled
+    }
+
+    fn heights(scheduled: &Scheduled<u64, &'static str>) -> Vec<u64> {
+        scheduled.values.keys().copied().collect()
+    }
+
+    #[test]
+    fn nothing_due_before_scheduled_height() {
+        let mut scheduled = scheduled(&[5]);
+
+        assert_eq!(scheduled.take_due(&4), None);
+        assert_eq!(heights(&scheduled), [5]);
+    }
+
+    #[test]
+    fn take_value_scheduled_for_height() {
+        let mut scheduled = scheduled(&[5, 6]);
+
+        assert_eq!(scheduled.take_due(&5), Some((5, "scheduled")));
+        assert_eq!(heights(&scheduled), [6]);
+
+        // Already taken
+        assert_eq!(scheduled.take_due(&5), None);
+    }
+
+    #[test]
+    fn take_latest_value_scheduled_below_height() {
+        let mut scheduled = scheduled(&[2, 3, 7]);
+        scheduled.insert(3, "replaced");
+
+        // Heights 2 and 3 were skipped, eg. because they were synced
+        assert_eq!(scheduled.take_due(&5), Some((3, "replaced")));
+        assert_eq!(heights(&scheduled), [7]);
+    }
+}
@@ -0,0 +1,394 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, BE};
+use tracing::debug;
+
+/// Version of the on-disk format of the watermark file
+const VERSION: u8 = 1;
+
+/// Maximum size of an encoded signature, guards against reading a corrupted file
+const MAX_SIGNATURE_SIZE: u32 = 1024;
+
+/// The kind of message signed by this node.
+///
+/// Variants are ordered by the order in which they are signed within a round.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SignStep {
+    Propose,
+    Prevote,
+    Precommit,
+}
+
+impl SignStep {
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Propose => 0,
+            Self::Prevote => 1,
+            Self::Precommit => 2,
+        }
+    }
+
+    fn from_u8(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(Self::Propose),
+            1 => Ok(Self::Prevote),
+            2 => Ok(Self::Precommit),
+            _ => Err(invalid_data(format!("invalid step: {byte}"))),
+        }
+    }
+}
+
+/// A message signed by this node, identified by its height, round and step,
+/// along with the encoded signature over it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signed {
+    pub height: u64,
+    pub round: i64,
+    pub step: SignStep,
+    pub signature: Vec<u8>,
+}
+
+impl Signed {
+    fn position(&self) -> (u64, i64, SignStep) {
+        (self.height, self.round, self.step)
+    }
+}
+
+impl fmt::Display for Signed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} at height {}, round {}",
+            self.step, self.height, self.round
+        )
+    }
+}
+
+/// An error which prevents a message from being signed.
+#[derive(Debug)]
+pub enum WatermarkError {
+    /// Signing the message could lead to double-signing, as it is either a different
+    /// message for the same height, round and step as the last signed one, or is
+    /// for an earlier height, round or step.
+    Conflict { attempted: Signed, last: Signed },
+
+    /// The updated watermark could not be persisted to disk
+    Io(io::Error),
+}
+
+impl fmt::Display for WatermarkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict { attempted, last } => write!(
+                f,
+                "Refusing to sign {attempted}, as it conflicts with the last signed {last}"
+            ),
+            Self::Io(e) => write!(f, "Failed to persist the last signed watermark: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WatermarkError {}
+
+/// Watermark of the last proposal, prevote and precommit signed by this node, persisted to disk.
+///
+/// It is consulted before signing any proposal or vote, and guards against double-signing
+/// even if the write-ahead log is lost, eg. after the node's `wal` directory was wiped.
+///
+/// Signing the exact same message as the last one of its kind is allowed, as happens when
+/// replaying the write-ahead log after a restart, since signatures are deterministic.
+/// This is why the last prevote is kept alongside the last precommit, as a node re-signs
+/// its prevote for a round after having already precommitted in it.
+pub struct SignWatermark {
+    path: PathBuf,
+    last_proposal: Option<Signed>,
+    last_prevote: Option<Signed>,
+    last_precommit: Option<Signed>,
+}
+
+impl SignWatermark {
+    /// Load the watermark from the given file, or start from an empty one if the file does not exist.
+    pub fn load(path: PathBuf) -> io::Result<Self> {
+        let [last_proposal, last_prevote, last_precommit] = match File::open(&path) {
+            Ok(mut file) => decode(&mut file)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => [None, None, None],
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            path,
+            last_proposal,
+            last_prevote,
+            last_precommit,
+        })
+    }
+
+    /// Location of the watermark file in the given home directory of the node.
+    ///
+    /// It is kept outside of the WAL directory, so that it survives the WAL being wiped.
+    pub fn path_in(home_dir: &Path) -> PathBuf {
+        home_dir.join("data").join("sign_state")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn last_proposal(&self) -> Option<&Signed> {
+        self.last_proposal.as_ref()
+    }
+
+    /// The latest of the last signed prevote and precommit.
+    pub fn last_vote(&self) -> Option<&Signed> {
+        match (&self.last_prevote, &self.last_precommit) {
+            (Some(prevote), Some(precommit)) if prevote.position() > precommit.position() => {
+                Some(prevote)
+            }
+            (_, Some(precommit)) => Some(precommit),
+            (prevote, None) => prevote.as_ref(),
+        }
+    }
+
+    fn last(&self, step: SignStep) -> Option<&Signed> {
+        match step {
+            SignStep::Propose => self.last_proposal.as_ref(),
+            SignStep::Prevote => self.last_prevote.as_ref(),
+            SignStep::Precommit => self.last_precommit.as_ref(),
+        }
+    }
+
+    /// Check whether the given signed message may be released, and if so persist it
+    /// as the new watermark before returning.
+    pub fn update(&mut self, signed: Signed) -> Result<(), WatermarkError> {
+        if let Some(last) = self.last(signed.step) {
+            if signed.position() == last.position() && signed.signature == last.signature {
+                debug!("Signing the same message as the last signed {last}");
+                return Ok(());
+            }
+        }
+
+        let last = match signed.step {
+            SignStep::Propose => self.last_proposal(),
+            SignStep::Prevote | SignStep::Precommit => self.last_vote(),
+        };
+
+        if let Some(last) = last {
+            if signed.position() <= last.position() {
+                return Err(WatermarkError::Conflict {
+                    attempted: signed,
+                    last: last.clone(),
+                });
+            }
+        }
+
+        let mut updated = [
+            self.last_proposal.as_ref(),
+            self.last_prevote.as_ref(),
+            self.last_precommit.as_ref(),
+        ];
+        updated[signed.step.to_u8() as usize] = Some(&signed);
+
+        self.persist(updated).map_err(WatermarkError::Io)?;
+
+        match signed.step {
+            SignStep::Propose => self.last_proposal = Some(signed),
+            SignStep::Prevote => self.last_prevote = Some(signed),
+            SignStep::Precommit => self.last_precommit = Some(signed),
+        }
+
+        Ok(())
+    }
+
+    /// Write the watermark to a temporary file and atomically move it in place,
+    /// so that a crash never leaves a partially written watermark behind.
+    fn persist(&self, last: [Option<&Signed>; 3]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+
+        let mut file = File::create(&tmp_path)?;
+        encode(&mut file, last)?;
+        file.sync_all()?;
+
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+/// Encode the last signed proposal, prevote and precommit, in that order.
+fn encode(writer: &mut impl Write, last: [Option<&Signed>; 3]) -> io::Result<()> {
+    writer.write_u8(VERSION)?;
+    for signed in last {
+        encode_signed(writer, signed)?;
+    }
+    writer.flush()
+}
+
+fn encode_signed(writer: &mut impl Write, signed: Option<&Signed>) -> io::Result<()> {
+    let Some(signed) = signed else {
+        return writer.write_u8(0);
+    };
+
+    writer.write_u8(1)?;
+    writer.write_u64::<BE>(signed.height)?;
+    writer.write_i64::<BE>(signed.round)?;
+    writer.write_u8(signed.step.to_u8())?;
+    writer.write_u32::<BE>(signed.signature.len() as u32)?;
+    writer.write_all(&signed.signature)
+}
+
+fn decode(reader: &mut impl Read) -> io::Result<[Option<Signed>; 3]> {
+    let version = reader.read_u8()?;
+    if version != VERSION {
+        return Err(invalid_data(format!("unsupported version: {version}")));
+    }
+
+    Ok([
+        decode_signed(reader)?,
+        decode_signed(reader)?,
+        decode_signed(reader)?,
+    ])
+}
+
+fn decode_signed(reader: &mut impl Read) -> io::Result<Option<Signed>> {
+    if reader.read_u8()? == 0 {
+        return Ok(None);
+    }
+
+    let height = reader.read_u64::<BE>()?;
+    let round = reader.read_i64::<BE>()?;
+    let step = SignStep::from_u8(reader.read_u8()?)?;
+
+    let len = reader.read_u32::<BE>()?;
+    if len > MAX_SIGNATURE_SIZE {
+        return Err(invalid_data(format!("signature too large: {len} bytes")));
+    }
+
+    let mut signature = vec![0; len as usize];
+    reader.read_exact(&mut signature)?;
+
+    Ok(Some(Signed {
+        height,
+        round,
+        step,
+        signature,
+    }))
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(height: u64, round: i64, step: SignStep, signature: &[u8]) -> Signed {
+        Signed {
+            height,
+            round,
+            step,
+            signature: signature.to_vec(),
+        }
+    }
+
+    fn assert_conflict(result: Result<(), WatermarkError>) {
+        assert!(
+            matches!(result, Err(WatermarkError::Conflict { .. })),
+            "expected conflict, got {result:?}"
+        );
+    }
+
+    #[test]
+    fn refuses_conflicting_votes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watermark = SignWatermark::load(dir.path().join("sign_state")).unwrap();
+
+        watermark
+            .update(signed(2, 1, SignStep::Prevote, b"prevote"))
+            .unwrap();
+
+        // Signing the same vote again is allowed
+        watermark
+            .update(signed(2, 1, SignStep::Prevote, b"prevote"))
+            .unwrap();
+
+        // A different vote at the same height, round and step is refused
+        assert_conflict(watermark.update(signed(2, 1, SignStep::Prevote, b"other")));
+
+        // So are votes for an earlier round or height
+        assert_conflict(watermark.update(signed(2, 0, SignStep::Precommit, b"precommit")));
+        assert_conflict(watermark.update(signed(1, 5, SignStep::Precommit, b"precommit")));
+
+        // Proposals are tracked separately from votes
+        watermark
+            .update(signed(2, 1, SignStep::Propose, b"proposal"))
+            .unwrap();
+        assert_conflict(watermark.update(signed(2, 0, SignStep::Propose, b"proposal")));
+
+        watermark
+            .update(signed(2, 1, SignStep::Precommit, b"precommit"))
+            .unwrap();
+        watermark
+            .update(signed(3, 0, SignStep::Prevote, b"prevote"))
+            .unwrap();
+    }
+
+    #[test]
+    fn survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = SignWatermark::path_in(dir.path());
+
+        let mut watermark = SignWatermark::load(path.clone()).unwrap();
+        watermark
+            .update(signed(3, 0, SignStep::Propose, b"proposal"))
+            .unwrap();
+        watermark
+            .update(signed(3, 0, SignStep::Prevote, b"prevote"))
+            .unwrap();
+        watermark
+            .update(signed(3, 0, SignStep::Precommit, b"precommit"))
+            .unwrap();
+
+        let mut watermark = SignWatermark::load(path).unwrap();
+        assert_eq!(
+            watermark.last_proposal(),
+            Some(&signed(3, 0, SignStep::Propose, b"proposal"))
+        );
+        assert_eq!(
+            watermark.last_vote(),
+            Some(&signed(3, 0, SignStep::Precommit, b"precommit"))
+        );
+
+        // Replaying the messages signed in the round is allowed
+        watermark
+            .update(signed(3, 0, SignStep::Propose, b"proposal"))
+            .unwrap();
+        watermark
+            .update(signed(3, 0, SignStep::Prevote, b"prevote"))
+            .unwrap();
+        watermark
+            .update(signed(3, 0, SignStep::Precommit, b"precommit"))
+            .unwrap();
+
+        // But not signing different ones
+        assert_conflict(watermark.update(signed(3, 0, SignStep::Prevote, b"other")));
+        assert_conflict(watermark.update(signed(3, 0, SignStep::Propose, b"other")));
+    }
+
+    #[test]
+    fn rejects_corrupted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sign_state");
+
+        fs::write(&path, [VERSION, 1, 0, 0]).unwrap();
+        assert!(SignWatermark::load(path.clone()).is_err());
+
+        fs::write(&path, [VERSION + 1, 0, 0, 0]).unwrap();
+        assert!(SignWatermark::load(path).is_err());
+    }
+}
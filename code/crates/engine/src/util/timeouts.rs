@@ -0,0 +1,360 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use malachitebft_config::TimeoutConfig;
+use malachitebft_core_types::{Round, Timeout, TimeoutKind};
+
+/// Computes the duration of the consensus timeouts, eg. to adapt them to the load of the network.
+///
+/// The timeouts configured for the current height, either in the node configuration or by the
+/// application when starting the height, are given as a base for the computation.
+///
+/// Consensus reports to the strategy how long it took to receive the proposal of each round,
+/// or whether the propose timeout elapsed before, so that it can adapt the timeouts of the
+/// following rounds and heights.
+pub trait TimeoutStrategy: Send + Sync + 'static {
+    /// Duration of the given timeout, given the timeouts configured for the current height.
+    fn duration_for(&self, config: &TimeoutConfig, timeout: Timeout) -> Duration;
+
+    /// The proposal for the given round was received, `latency` after the propose timeout started.
+    fn proposal_received(&self, _round: Round, _latency: Duration) {}
+
+    /// The propose timeout of the given round elapsed, `elapsed` after it started,
+    /// before a proposal was received.
+    fn proposal_missed(&self, _round: Round, _elapsed: Duration) {}
+}
+
+/// Increases the propose, prevote and precommit timeouts linearly with the round,
+/// by their configured delta. The other timeouts are used as configured.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LinearTimeouts;
+
+impl TimeoutStrategy for LinearTimeouts {
+    fn duration_for(&self, config: &TimeoutConfig, timeout: Timeout) -> Duration {
+        linear(config.timeout_duration(timeout.kind), config, timeout)
+    }
+}
+
+fn linear(base: Duration, config: &TimeoutConfig, timeout: Timeout) -> Duration {
+    let Some(delta) = config.delta_duration(timeout.kind) else {
+        return base;
+    };
+
+    let round = timeout.round.as_u32().unwrap_or(0);
+    base.saturating_add(delta.saturating_mul(round))
+}
+
+/// Adapts the propose timeout to the latency of the proposals observed in the previous rounds.
+///
+/// The base propose timeout is a multiple of the moving average of the latency of the proposals,
+/// bounded by a minimum and a maximum. Under low load, proposals arrive quickly and the timeout
+/// shrinks, so that rounds with an unresponsive proposer are skipped sooner. Under high load,
+/// the timeout grows so that slow proposals are not needlessly missed. When a propose timeout
+/// elapses, the time waited is accounted as the latency of the proposal.
+///
+/// The timeouts then increase with the round as with [`LinearTimeouts`].
+/// Until the first proposal has been received, the configured propose timeout is used.
+#[derive(Debug)]
+pub struct AdaptiveTimeouts {
+    min: Duration,
+    max: Duration,
+    factor: f64,
+    smoothing: f64,
+    latency: Mutex<Option<Duration>>,
+}
+
+impl AdaptiveTimeouts {
+    /// Create a new adaptive strategy, with the propose timeout bounded by `min` and `max`.
+    ///
+    /// By default, the propose timeout is twice the average latency, and each new
+    /// observation weighs for a fifth of the average.
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max: max.max(min),
+            factor: 2.0,
+            smoothing: 0.2,
+            latency: Mutex::new(None),
+        }
+    }
+
+    /// Set the multiple of the average latency to use as the base propose timeout.
+    pub fn with_factor(self, factor: f64) -> Self {
+        Self { factor, ..self }
+    }
+
+    /// Set the weight of each new observation in the average latency, between 0 and 1.
+    pub fn with_smoothing(self, smoothing: f64) -> Self {
+        Self {
+            smoothing: smoothing.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// The current moving average of the latency of the proposals, if any was observed.
+    pub fn latency(&self) -> Option<Duration> {
+        *self.latency.lock().unwrap()
+    }
+
+    fn observe(&self, sample: Duration) {
+        let mut latency = self.latency.lock().unwrap();
+
+        let average = match *latency {
+            None => sample,
+            Some(average) => average.mul_f64(1.0 - self.smoothing) + sample.mul_f64(self.smoothing),
+        };
+
+        *latency = Some(average);
+    }
+}
+
+impl TimeoutStrategy for AdaptiveTimeouts {
+    fn duration_for(&self, config: &TimeoutConfig, timeout: Timeout) -> Duration {
+        let base = match (timeout.kind, self.latency()) {
+            (TimeoutKind::Propose, Some(latency)) => latency
+                .mul_f64(self.factor.max(0.0))
+                .clamp(self.min, self.max),
+            _ => config.timeout_duration(timeout.kind),
+        };
+
+        linear(base, config, timeout)
+    }
+
+    fn proposal_received(&self, _round: Round, latency: Duration) {
+        self.observe(latency);
+    }
+
+    fn proposal_missed(&self, _round: Round, elapsed: Duration) {
+        self.observe(elapsed);
+    }
+}
+
+/// Timeouts of consensus at the current height, along with the strategy computing their duration.
+///
+/// Tracks the propose timeout while it is running, to report to the strategy how long it took
+/// to receive the proposal of each round from another validator.
+pub struct Timeouts {
+    /// Timeouts configured for the current height
+    config: TimeoutConfig,
+
+    /// Strategy for computing the duration of the timeouts
+    strategy: Arc<dyn TimeoutStrategy>,
+
+    /// The round and start time of the propose timeout, while it is running,
+    /// to measure how long it takes to receive the proposal
+    propose_started: Option<(Round, Instant)>,
+}
+
+impl Timeouts {
+    pub fn new(config: TimeoutConfig, strategy: Arc<dyn TimeoutStrategy>) -> Self {
+        Self {
+            config,
+            strategy,
+            propose_started: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.propose_started = None;
+    }
+
+    pub fn set_config(&mut self, config: TimeoutConfig) {
+        self.config = config;
+    }
+
+    pub fn duration_for(&self, timeout: Timeout) -> Duration {
+        self.strategy.duration_for(&self.config, timeout)
+    }
+
+    /// The given timeout was scheduled.
+    pub fn started(&mut self, timeout: Timeout) {
+        if timeout.kind == TimeoutKind::Propose {
+            self.propose_started = Some((timeout.round, Instant::now()));
+        }
+    }
+
+    /// We are the proposer for the round of the given propose timeout,
+    /// so the time it takes to build our own value is not the latency of a proposal.
+    pub fn proposing(&mut self, timeout: Timeout) {
+        self.take_propose_started(timeout);
+    }
+
+    /// The given timeout was cancelled, eg. because the proposal was received.
+    pub fn cancelled(&mut self, timeout: Timeout) {
+        if let Some((round, started)) = self.take_propose_started(timeout) {
+            self.strategy.proposal_received(round, started.elapsed());
+        }
+    }
+
+    /// The given timeout elapsed.
+    pub fn elapsed(&mut self, timeout: Timeout) {
+        if let Some((round, started)) = self.take_propose_started(timeout) {
+            self.strategy.proposal_missed(round, started.elapsed());
+        }
+    }
+
+    fn take_propose_started(&mut self, timeout: Timeout) -> Option<(Round, Instant)> {
+        self.propose_started
+            .take_if(|(round, _)| timeout.kind == TimeoutKind::Propose && *round == timeout.round)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TimeoutConfig {
+        TimeoutConfig {
+            timeout_propose: Duration::from_secs(3),
+            timeout_propose_delta: Duration::from_millis(500),
+            ..TimeoutConfig::default()
+        }
+    }
+
+    #[test]
+    fn linear_increases_with_round() {
+        let config = config();
+
+        let propose =
+            |round| LinearTimeouts.duration_for(&config, Timeout::propose(Round::new(round)));
+        let commit =
+            |round| LinearTimeouts.duration_for(&config, Timeout::commit(Round::new(round)));
+
+        assert_eq!(propose(0), Duration::from_secs(3));
+        assert_eq!(propose(2), Duration::from_secs(4));
+        assert_eq!(commit(0), commit(5));
+    }
+
+    #[test]
+    fn adaptive_follows_latency() {
+        let config = config();
+
+        let strategy = AdaptiveTimeouts::new(Duration::from_millis(500), Duration::from_secs(10))
+            .with_smoothing(0.5);
+
+        let propose = |round| strategy.duration_for(&config, Timeout::propose(Round::new(round)));
+
+        // No latency observed yet, use the configured timeout
+        assert_eq!(propose(0), Duration::from_secs(3));
+
+        // Low load
+        strategy.proposal_received(Round::new(0), Duration::from_millis(400));
+        assert_eq!(propose(0), Duration::from_millis(800));
+        assert_eq!(propose(1), Duration::from_millis(1300));
+
+        // High load
+        strategy.proposal_missed(Round::new(0), Duration::from_millis(1600));
+        assert_eq!(propose(0), Duration::from_secs(2));
+
+        // Bounded by the minimum and maximum
+        strategy.proposal_received(Round::new(0), Duration::ZERO);
+        strategy.proposal_received(Round::new(0), Duration::ZERO);
+        assert_eq!(propose(0), Duration::from_millis(500));
+
+        for _ in 0..10 {
+            strategy.proposal_missed(Round::new(0), Duration::from_secs(60));
+        }
+        assert_eq!(propose(0), Duration::from_secs(10));
+
+        // Other timeouts are not affected
+        assert_eq!(
+            strategy.duration_for(&config, Timeout::prevote(Round::new(1))),
+            LinearTimeouts.duration_for(&config, Timeout::prevote(Round::new(1)))
+        );
+    }
+
+    /// Records the proposals reported to it
+    #[derive(Default)]
+    struct Recorder {
+        received: Mutex<Vec<Round>>,
+        missed: Mutex<Vec<Round>>,
+    }
+
+    impl TimeoutStrategy for Recorder {
+        fn duration_for(&self, config: &TimeoutConfig, timeout: Timeout) -> Duration {
+            LinearTimeouts.duration_for(config, timeout)
+        }
+
+        fn proposal_received(&self, round: Round, _latency: Duration) {
+            self.received.lock().unwrap().push(round);
+        }
+
+        fn proposal_missed(&self, round: Round, _elapsed: Duration) {
+            self.missed.lock().unwrap().push(round);
+        }
+    }
+
+    fn timeouts() -> (Timeouts, Arc<Recorder>) {
+        let recorder = Arc::new(Recorder::default());
+        let timeouts = Timeouts::new(config(), Arc::clone(&recorder) as Arc<dyn TimeoutStrategy>);
+        (timeouts, recorder)
+    }
+
+    #[test]
+    fn report_received_and_missed_proposals() {
+        let (mut timeouts, recorder) = timeouts();
+
+        // The proposal is received before the propose timeout elapses
+        timeouts.started(Timeout::propose(Round::new(0)));
+        timeouts.cancelled(Timeout::propose(Round::new(0)));
+
+        // The propose timeout elapses before the proposal is received
+        timeouts.started(Timeout::propose(Round::new(1)));
+        timeouts.elapsed(Timeout::propose(Round::new(1)));
+
+        // Only reported once
+        timeouts.cancelled(Timeout::propose(Round::new(1)));
+
+        assert_eq!(*recorder.received.lock().unwrap(), [Round::new(0)]);
+        assert_eq!(*recorder.missed.lock().unwrap(), [Round::new(1)]);
+    }
+
+    #[test]
+    fn ignore_other_timeouts_and_rounds() {
+        let (mut timeouts, recorder) = timeouts();
+
+        timeouts.started(Timeout::prevote(Round::new(0)));
+        timeouts.elapsed(Timeout::prevote(Round::new(0)));
+
+        timeouts.started(Timeout::propose(Round::new(0)));
+        timeouts.cancelled(Timeout::propose(Round::new(1)));
+        timeouts.elapsed(Timeout::precommit(Round::new(0)));
+
+        // A new round resets the timeouts
+        timeouts.reset();
+        timeouts.elapsed(Timeout::propose(Round::new(0)));
+
+        assert!(recorder.received.lock().unwrap().is_empty());
+        assert!(recorder.missed.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn ignore_own_proposals() {
+        let (mut timeouts, recorder) = timeouts();
+
+        // We are the proposer, and the propose timeout is cancelled once we have built a value
+        timeouts.started(Timeout::propose(Round::new(0)));
+        timeouts.proposing(Timeout::propose(Round::new(0)));
+        timeouts.cancelled(Timeout::propose(Round::new(0)));
+
+        assert!(recorder.received.lock().unwrap().is_empty());
+        assert!(recorder.missed.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn use_config_of_current_height() {
+        let (mut timeouts, _) = timeouts();
+
+        let propose = Timeout::propose(Round::new(0));
+        assert_eq!(timeouts.duration_for(propose), Duration::from_secs(3));
+
+        timeouts.set_config(TimeoutConfig {
+            timeout_propose: Duration::from_secs(1),
+            ..config()
+        });
+
+        assert_eq!(timeouts.duration_for(propose), Duration::from_secs(1));
+    }
+}
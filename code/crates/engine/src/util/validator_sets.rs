@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use tracing::debug;
+
+/// Cache of validator sets for the current and upcoming heights.
+///
+/// Validator sets are either scheduled ahead of time by the application,
+/// or pre-fetched by consensus once a height has been decided, so that
+/// starting a new height or verifying messages from a height slightly
+/// ahead of ours does not require a round-trip to the application.
+pub struct ValidatorSetCache<Height, ValidatorSet> {
+    sets: BTreeMap<Height, ValidatorSet>,
+    max_size: usize,
+}
+
+impl<Height, ValidatorSet> ValidatorSetCache<Height, ValidatorSet>
+where
+    Height: Ord + Display,
+{
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            sets: BTreeMap::new(),
+            max_size,
+        }
+    }
+
+    /// Store the validator set to use at the given height,
+    /// replacing any previously scheduled set for that height.
+    ///
+    /// If the cache is full, the set for the highest height is evicted
+    /// to make room, as it is the one least likely to be needed soon.
+    pub fn insert(&mut self, height: Height, validator_set: ValidatorSet) {
+        self.sets.insert(height, validator_set);
+
+        while self.sets.len() > self.max_size {
+            if let Some((evicted, _)) = self.sets.pop_last() {
+                debug!(%evicted, "Validator set cache is full, evicting validator set");
+            }
+        }
+    }
+
+    pub fn get(&self, height: &Height) -> Option<&ValidatorSet> {
+        self.sets.get(height)
+    }
+
+    pub fn contains(&self, height: &Height) -> bool {
+        self.sets.contains_key(height)
+    }
+
+    /// Remove all validator sets for heights strictly below the given height.
+    pub fn prune(&mut self, min_height: Height) {
+        self.sets = self.sets.split_off(&min_height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_size: usize, heights: &[u64]) -> ValidatorSetCache<u64, &'static str> {
+        let mut cache = ValidatorSetCache::new(max_size);
+        for &height in heights {
+            cache.insert(height, "scheduled");
+        }
+        cache
+    }
+
+    fn heights(cache: &ValidatorSetCache<u64, &'static str>) -> Vec<u64> {
+        cache.sets.keys().copied().collect()
+    }
+
+    #[test]
+    fn insert_replaces_existing_set() {
+        let mut cache = cache(4, &[1]);
+
+        cache.insert(1, "replaced");
+        assert_eq!(cache.get(&1), Some(&"replaced"));
+        assert_eq!(heights(&cache), [1]);
+    }
+
+    #[test]
+    fn insert_evicts_highest_height_when_full() {
+        let mut cache = cache(3, &[1, 2, 5]);
+
+        cache.insert(3, "scheduled");
+        assert_eq!(heights(&cache), [1, 2, 3]);
+
+        // The inserted set is itself evicted if it is for the highest height
+        cache.insert(10, "scheduled");
+        assert_eq!(heights(&cache), [1, 2, 3]);
+        assert!(!cache.contains(&10));
+    }
+
+    #[test]
+    fn prune_removes_heights_below() {
+        let mut cache = cache(8, &[1, 2, 3, 4]);
+
+        cache.prune(3);
+        assert_eq!(heights(&cache), [3, 4]);
+
+        cache.prune(10);
+        assert_eq!(heights(&cache), [] as [u64; 0]);
+    }
+
+    #[test]
+    fn scheduled_sets_are_kept_when_starting_a_height() {
+        // The application scheduled the sets for the next heights ahead of time
+        let mut cache = cache(8, &[3, 4, 5]);
+
+        // Starting height 3 prunes past heights but keeps the scheduled ones
+        cache.prune(3);
+        cache.insert(3, "started");
+        assert_eq!(cache.get(&3), Some(&"started"));
+        assert_eq!(heights(&cache), [3, 4, 5]);
+    }
+
+    #[test]
+    fn prefetch_only_missing_heights() {
+        let mut cache = cache(8, &[1, 2]);
+
+        // Height 2 is decided while the set for height 3 has not been scheduled
+        assert!(cache.contains(&2));
+        assert!(!cache.contains(&3));
+
+        // The pre-fetched set is then served from the cache when starting height 3
+        cache.insert(3, "prefetched");
+        cache.prune(3);
+        assert_eq!(cache.get(&3), Some(&"prefetched"));
+        assert_eq!(cache.get(&2), None);
+    }
+}
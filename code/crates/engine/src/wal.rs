@@ -6,6 +6,7 @@ use ractor::{async_trait, Actor, ActorProcessingErr, ActorRef, RpcReplyPort, Spa
 use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, info};
 
+use malachitebft_config::WalConfig;
 use malachitebft_core_types::{Context, Height};
 use malachitebft_metrics::SharedRegistry;
 use malachitebft_wal as wal;
@@ -39,10 +40,17 @@ where
         _ctx: &Ctx,
         codec: Codec,
         path: PathBuf,
+        config: WalConfig,
         _metrics: SharedRegistry,
         span: tracing::Span,
     ) -> Result<WalRef<Ctx>, SpawnErr> {
-        let (actor_ref, _) = Actor::spawn(None, Self::new(span), Args { path, codec }).await?;
+        let args = Args {
+            path,
+            config,
+            codec,
+        };
+
+        let (actor_ref, _) = Actor::spawn(None, Self::new(span), args).await?;
         Ok(actor_ref)
     }
 }
@@ -57,6 +65,7 @@ pub enum Msg<Ctx: Context> {
 
 pub struct Args<Codec> {
     pub path: PathBuf,
+    pub config: WalConfig,
     pub codec: Codec,
 }
 
@@ -195,7 +204,13 @@ where
         _myself: WalRef<Ctx>,
         args: Self::Arguments,
     ) -> Result<Self::State, ActorProcessingErr> {
-        let log = wal::Log::open(&args.path)?;
+        let options = wal::SegmentOptions {
+            max_segment_size: args.config.max_segment_size.as_u64(),
+            retain_logs: args.config.retain_heights,
+            max_retained_size: Some(args.config.max_retained_size.as_u64()),
+        };
+
+        let log = wal::SegmentedLog::open_with(&args.path, options)?;
         info!("Opened WAL at {}", args.path.display());
 
         let (tx, rx) = mpsc::channel(100);
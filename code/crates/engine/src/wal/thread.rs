@@ -22,7 +22,7 @@ pub enum WalMsg<Ctx: Context> {
 
 pub fn spawn<Ctx, Codec>(
     span: tracing::Span,
-    mut log: wal::Log,
+    mut log: wal::SegmentedLog,
     codec: Codec,
     mut rx: mpsc::Receiver<WalMsg<Ctx>>,
 ) -> JoinHandle<()>
@@ -48,7 +48,7 @@ where
 fn process_msg<Ctx, Codec>(
     msg: WalMsg<Ctx>,
     span: &tracing::Span,
-    log: &mut wal::Log,
+    log: &mut wal::SegmentedLog,
     codec: &Codec,
 ) -> Result<ControlFlow<()>>
 where
@@ -135,7 +135,10 @@ where
     Ok(ControlFlow::Continue(()))
 }
 
-fn fetch_entries<Ctx, Codec>(log: &mut wal::Log, codec: &Codec) -> Result<Vec<WalEntry<Ctx>>>
+fn fetch_entries<Ctx, Codec>(
+    log: &mut wal::SegmentedLog,
+    codec: &Codec,
+) -> Result<Vec<WalEntry<Ctx>>>
 where
     Ctx: Context,
     Codec: WalCodec<Ctx>,
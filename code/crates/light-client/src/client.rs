@@ -0,0 +1,104 @@
+use alloc::vec;
+
+use malachitebft_core_types::{Context, Height, SigningProvider};
+
+use crate::{Error, LightBlock, TransitionProof, Verifier};
+
+/// Follows the chain from a trusted light block, trusting each light block it verifies in turn.
+pub struct LightClient<Ctx: Context, P, S> {
+    verifier: Verifier<Ctx, S>,
+    trusted: LightBlock<Ctx, P>,
+}
+
+impl<Ctx, P, S> LightClient<Ctx, P, S>
+where
+    Ctx: Context,
+    P: TransitionProof<Ctx>,
+    S: SigningProvider<Ctx>,
+{
+    /// Create a new light client, trusting the given light block.
+    ///
+    /// The trusted light block is typically obtained from the genesis of the chain,
+    /// or from a source trusted by the user of the light client.
+    pub fn new(verifier: Verifier<Ctx, S>, trusted: LightBlock<Ctx, P>) -> Self {
+        Self { verifier, trusted }
+    }
+
+    /// The latest trusted light block.
+    pub fn trusted(&self) -> &LightBlock<Ctx, P> {
+        &self.trusted
+    }
+
+    /// Verify the given light block against the latest trusted one,
+    /// see [`Verifier::verify`], and trust it if it is valid.
+    pub fn update(&mut self, untrusted: LightBlock<Ctx, P>) -> Result<(), Error<Ctx>> {
+        self.verifier.verify(&self.trusted, &untrusted)?;
+        self.trusted = untrusted;
+        Ok(())
+    }
+
+    /// Verify the given light blocks one after the other, each at the height following the previous one,
+    /// see [`Verifier::verify_sequential`].
+    ///
+    /// The light blocks verified before an invalid one are trusted.
+    pub fn update_sequential(
+        &mut self,
+        light_blocks: impl IntoIterator<Item = LightBlock<Ctx, P>>,
+    ) -> Result<(), Error<Ctx>> {
+        for untrusted in light_blocks {
+            self.verifier.verify_sequential(&self.trusted, &untrusted)?;
+            self.trusted = untrusted;
+        }
+
+        Ok(())
+    }
+
+    /// Move trust to the light block at the given height, fetched with `fetch`.
+    ///
+    /// Verification skips directly to the target height if enough of the trusted validators signed its
+    /// certificate, otherwise it first verifies the light block halfway there, and so on (bisection).
+    ///
+    /// The intermediate light blocks verified before failing to verify another one are trusted.
+    pub fn verify_to_height(
+        &mut self,
+        target: Ctx::Height,
+        mut fetch: impl FnMut(Ctx::Height) -> Option<LightBlock<Ctx, P>>,
+    ) -> Result<&LightBlock<Ctx, P>, Error<Ctx>> {
+        if target == self.trusted.height() {
+            return Ok(&self.trusted);
+        }
+
+        if target < self.trusted.height() {
+            return Err(Error::NonIncreasingHeight {
+                trusted: self.trusted.height(),
+                untrusted: target,
+            });
+        }
+
+        let target_block = fetch(target).ok_or(Error::MissingLightBlock(target))?;
+        let mut pending = vec![target_block];
+
+        while let Some(untrusted) = pending.pop() {
+            match self.verifier.verify(&self.trusted, &untrusted) {
+                Ok(()) => {
+                    self.trusted = untrusted;
+                }
+
+                // Light blocks following the trusted one are verified sequentially,
+                // so the pivot below is always strictly between the trusted and untrusted heights.
+                Err(Error::NotEnoughTrust { .. }) => {
+                    let distance = untrusted.height().as_u64() - self.trusted.height().as_u64();
+                    let pivot = self.trusted.height().increment_by(distance / 2);
+                    let pivot_block = fetch(pivot).ok_or(Error::MissingLightBlock(pivot))?;
+
+                    pending.push(untrusted);
+                    pending.push(pivot_block);
+                }
+
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(&self.trusted)
+    }
+}
@@ -0,0 +1,57 @@
+use derive_where::derive_where;
+use thiserror::Error;
+
+use malachitebft_core_types::{CertificateError, Context, VotingPower};
+
+/// The reasons why a light block can fail to verify.
+#[derive_where(Clone, Debug)]
+#[derive(Error)]
+pub enum Error<Ctx: Context> {
+    /// The light block is not above the trusted one.
+    #[error("Light block at height {untrusted} is not above the trusted height {trusted}")]
+    NonIncreasingHeight {
+        /// Height of the trusted light block
+        trusted: Ctx::Height,
+        /// Height of the light block being verified
+        untrusted: Ctx::Height,
+    },
+
+    /// The light block does not directly follow the trusted one.
+    #[error("Light block at height {untrusted} does not follow the trusted height {trusted}")]
+    NonAdjacentHeight {
+        /// Height of the trusted light block
+        trusted: Ctx::Height,
+        /// Height of the light block being verified
+        untrusted: Ctx::Height,
+    },
+
+    /// The validator set of the light block is not the one designated by the trusted light block.
+    #[error("Validator set does not match the next validator set of the trusted light block")]
+    ValidatorSetMismatch,
+
+    /// The proof that the decided value designates the validator sets of the light block is invalid.
+    #[error("Invalid validator set transition proof")]
+    InvalidTransitionProof,
+
+    /// The certificate of the light block is invalid.
+    #[error("Invalid certificate: {0}")]
+    InvalidCertificate(#[from] CertificateError<Ctx>),
+
+    /// Not enough of the trusted validators signed the certificate of the light block.
+    #[error(
+        "Not enough trusted voting power has signed the certificate: \
+         signed={signed}, total={total}, expected={expected}"
+    )]
+    NotEnoughTrust {
+        /// Trusted voting power which signed the certificate
+        signed: VotingPower,
+        /// Total trusted voting power
+        total: VotingPower,
+        /// Expected trusted voting power
+        expected: VotingPower,
+    },
+
+    /// The light block at the given height could not be fetched.
+    #[error("Missing light block at height {0}")]
+    MissingLightBlock(Ctx::Height),
+}
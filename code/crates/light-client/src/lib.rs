@@ -0,0 +1,29 @@
+//! Light client verification of the values decided by a Malachite BFT network.
+//!
+//! Starting from a trusted [`LightBlock`], a light client follows the chain without running consensus,
+//! by verifying the [`CommitCertificate`](malachitebft_core_types::CommitCertificate)s of later heights.
+//! Light blocks can be verified sequentially, height after height, or by skipping over many heights
+//! at once, provided enough of the trusted validators signed the certificate of the later height.
+
+#![no_std]
+#![forbid(unsafe_code)]
+#![deny(trivial_casts, trivial_numeric_casts)]
+#![warn(
+    missing_docs,
+    rustdoc::broken_intra_doc_links,
+    rustdoc::private_intra_doc_links,
+    variant_size_differences
+)]
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::panic))]
+
+extern crate alloc;
+
+mod client;
+mod error;
+mod light_block;
+mod verifier;
+
+pub use client::LightClient;
+pub use error::Error;
+pub use light_block::{LightBlock, TransitionProof};
+pub use verifier::Verifier;
@@ -0,0 +1,58 @@
+use derive_where::derive_where;
+
+use malachitebft_core_types::{CommitCertificate, Context};
+
+/// Proof that the value decided at some height designates the validator set which decided it,
+/// as well as the one deciding the value at the next height.
+///
+/// Malachite does not interpret the values it decides on, so it is up to the application
+/// to commit to its validator sets in its values, eg. by including their hash in its block headers,
+/// and to provide light clients with the corresponding proofs.
+pub trait TransitionProof<Ctx: Context> {
+    /// Check that the value certified by `certificate` designates `validator_set` as the validator set
+    /// which decided it, and `next_validator_set` as the one deciding the value at the next height.
+    fn verify(
+        &self,
+        certificate: &CommitCertificate<Ctx>,
+        validator_set: &Ctx::ValidatorSet,
+        next_validator_set: &Ctx::ValidatorSet,
+    ) -> bool;
+}
+
+/// The value decided at some height, as seen by a light client.
+#[derive_where(Clone, Debug; P)]
+pub struct LightBlock<Ctx: Context, P> {
+    /// The certificate for the value decided at this height
+    pub certificate: CommitCertificate<Ctx>,
+
+    /// The validator set which decided the value
+    pub validator_set: Ctx::ValidatorSet,
+
+    /// The validator set deciding the value at the next height
+    pub next_validator_set: Ctx::ValidatorSet,
+
+    /// Proof that the decided value designates these validator sets
+    pub proof: P,
+}
+
+impl<Ctx: Context, P> LightBlock<Ctx, P> {
+    /// Create a new light block.
+    pub fn new(
+        certificate: CommitCertificate<Ctx>,
+        validator_set: Ctx::ValidatorSet,
+        next_validator_set: Ctx::ValidatorSet,
+        proof: P,
+    ) -> Self {
+        Self {
+            certificate,
+            validator_set,
+            next_validator_set,
+            proof,
+        }
+    }
+
+    /// The height at which the value was decided.
+    pub fn height(&self) -> Ctx::Height {
+        self.certificate.height
+    }
+}
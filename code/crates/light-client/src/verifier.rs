@@ -0,0 +1,165 @@
+use alloc::collections::BTreeSet;
+use core::marker::PhantomData;
+
+use malachitebft_core_types::{
+    CertificateSignature, Context, Height, SigningProvider, SigningProviderExt, ThresholdParams,
+    Validator, ValidatorSet, VotingPower,
+};
+
+use crate::{Error, LightBlock, TransitionProof};
+
+/// Verifies light blocks against a trusted light block.
+///
+/// - The quorum threshold is the voting power of the validator set of a light block
+///   which must have signed its certificate.
+/// - The honest threshold is the voting power of the trusted validator set which must
+///   have signed the certificate of a light block for it to be verified by skipping.
+pub struct Verifier<Ctx, S> {
+    provider: S,
+    thresholds: ThresholdParams,
+    _ctx: PhantomData<Ctx>,
+}
+
+impl<Ctx, S> Verifier<Ctx, S>
+where
+    Ctx: Context,
+    S: SigningProvider<Ctx>,
+{
+    /// Create a new verifier, using the given signing provider to verify certificate signatures.
+    pub fn new(provider: S, thresholds: ThresholdParams) -> Self {
+        Self {
+            provider,
+            thresholds,
+            _ctx: PhantomData,
+        }
+    }
+
+    /// Verify the given light block against the trusted one, sequentially if it is at the next height,
+    /// or by skipping otherwise.
+    pub fn verify<P>(
+        &self,
+        trusted: &LightBlock<Ctx, P>,
+        untrusted: &LightBlock<Ctx, P>,
+    ) -> Result<(), Error<Ctx>>
+    where
+        P: TransitionProof<Ctx>,
+    {
+        if untrusted.height() == trusted.height().increment() {
+            self.verify_sequential(trusted, untrusted)
+        } else {
+            self.verify_skipping(trusted, untrusted)
+        }
+    }
+
+    /// Verify a light block at the height following the trusted one.
+    ///
+    /// The light block must have been decided by the validator set designated by the trusted light block.
+    pub fn verify_sequential<P>(
+        &self,
+        trusted: &LightBlock<Ctx, P>,
+        untrusted: &LightBlock<Ctx, P>,
+    ) -> Result<(), Error<Ctx>>
+    where
+        P: TransitionProof<Ctx>,
+    {
+        if untrusted.height() != trusted.height().increment() {
+            return Err(Error::NonAdjacentHeight {
+                trusted: trusted.height(),
+                untrusted: untrusted.height(),
+            });
+        }
+
+        if untrusted.validator_set != trusted.next_validator_set {
+            return Err(Error::ValidatorSetMismatch);
+        }
+
+        self.verify_light_block(untrusted)
+    }
+
+    /// Verify a light block at any height above the trusted one.
+    ///
+    /// As the validator set of the light block cannot be traced back to the trusted one,
+    /// validators of the trusted validator set holding more than the honest threshold
+    /// of its voting power must have signed the certificate of the light block.
+    pub fn verify_skipping<P>(
+        &self,
+        trusted: &LightBlock<Ctx, P>,
+        untrusted: &LightBlock<Ctx, P>,
+    ) -> Result<(), Error<Ctx>>
+    where
+        P: TransitionProof<Ctx>,
+    {
+        if untrusted.height() <= trusted.height() {
+            return Err(Error::NonIncreasingHeight {
+                trusted: trusted.height(),
+                untrusted: untrusted.height(),
+            });
+        }
+
+        self.verify_light_block(untrusted)?;
+        self.verify_trust(&trusted.next_validator_set, untrusted)
+    }
+
+    /// Verify that the light block is consistent, ie. that its decided value designates its validator sets,
+    /// and that a quorum of distinct validators of its validator set signed its certificate.
+    fn verify_light_block<P>(&self, untrusted: &LightBlock<Ctx, P>) -> Result<(), Error<Ctx>>
+    where
+        P: TransitionProof<Ctx>,
+    {
+        if !untrusted.proof.verify(
+            &untrusted.certificate,
+            &untrusted.validator_set,
+            &untrusted.next_validator_set,
+        ) {
+            return Err(Error::InvalidTransitionProof);
+        }
+
+        self.provider.verify_certificate(
+            &untrusted.certificate,
+            &untrusted.validator_set,
+            self.thresholds,
+        )?;
+
+        Ok(())
+    }
+
+    /// Verify that validators of the trusted validator set holding more than the honest threshold
+    /// of its voting power signed the (already verified) certificate of the light block.
+    ///
+    /// Signers are only counted once, and only if their public key in the trusted validator set
+    /// is the one their signature was verified against.
+    fn verify_trust<P>(
+        &self,
+        trusted_validators: &Ctx::ValidatorSet,
+        untrusted: &LightBlock<Ctx, P>,
+    ) -> Result<(), Error<Ctx>> {
+        let signers: BTreeSet<_> = untrusted
+            .certificate
+            .aggregated_signature
+            .signers(&untrusted.validator_set)
+            .into_iter()
+            .collect();
+
+        let signed: VotingPower = signers
+            .iter()
+            .filter_map(|address| {
+                let trusted = trusted_validators.get_by_address(address)?;
+                let untrusted = untrusted.validator_set.get_by_address(address)?;
+
+                (trusted.public_key() == untrusted.public_key()).then(|| trusted.voting_power())
+            })
+            .sum();
+
+        let total = trusted_validators.total_voting_power();
+
+        if !self.thresholds.honest.is_met(signed, total) {
+            return Err(Error::NotEnoughTrust {
+                signed,
+                total,
+                expected: self.thresholds.honest.min_expected(total),
+            });
+        }
+
+        Ok(())
+    }
+}
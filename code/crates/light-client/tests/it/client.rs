@@ -0,0 +1,67 @@
+use malachitebft_test::Height;
+
+use informalsystems_malachitebft_light_client::{Error, LightClient};
+
+use crate::utils::{Chain, EPOCH_LENGTH};
+
+#[test]
+fn update_sequential() {
+    let chain = Chain::new();
+    let mut client = LightClient::new(chain.verifier(), chain.light_block(1));
+
+    let target = EPOCH_LENGTH * 2 + 1;
+    let light_blocks = (2..=target).map(|height| chain.light_block(height));
+
+    client.update_sequential(light_blocks).unwrap();
+    assert_eq!(client.trusted().height().as_u64(), target);
+}
+
+#[test]
+fn update_keeps_trust_on_failure() {
+    let chain = Chain::new();
+    let mut client = LightClient::new(chain.verifier(), chain.light_block(1));
+
+    let result = client.update(chain.light_block_signed_by(2, 1));
+    assert!(matches!(result, Err(Error::InvalidCertificate(_))));
+    assert_eq!(client.trusted().height().as_u64(), 1);
+
+    client.update(chain.light_block(2)).unwrap();
+    assert_eq!(client.trusted().height().as_u64(), 2);
+}
+
+#[test]
+fn verify_to_height_bisects() {
+    let chain = Chain::new();
+    let mut client = LightClient::new(chain.verifier(), chain.light_block(1));
+
+    // Skipping straight to the target fails, as only one of the trusted validators
+    // is still in the validator set, but the light block halfway there can be trusted,
+    // and from there the target one.
+    let target = EPOCH_LENGTH * 3 + 10;
+    let mut fetched = Vec::new();
+
+    let trusted = client
+        .verify_to_height(Height::new(target), |height| {
+            fetched.push(height.as_u64());
+            Some(chain.light_block(height.as_u64()))
+        })
+        .unwrap();
+
+    assert_eq!(trusted.height().as_u64(), target);
+    assert_eq!(fetched, [target, 1 + (target - 1) / 2]);
+}
+
+#[test]
+fn verify_to_height_missing_light_block() {
+    let chain = Chain::new();
+    let mut client = LightClient::new(chain.verifier(), chain.light_block(1));
+
+    let target = EPOCH_LENGTH * 3 + 10;
+
+    let result = client.verify_to_height(Height::new(target), |height| {
+        (height.as_u64() == target).then(|| chain.light_block(target))
+    });
+
+    assert!(matches!(result, Err(Error::MissingLightBlock(_))));
+    assert_eq!(client.trusted().height().as_u64(), 1);
+}
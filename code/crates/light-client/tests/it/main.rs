@@ -0,0 +1,4 @@
+pub mod client;
+pub mod verifier;
+
+mod utils;
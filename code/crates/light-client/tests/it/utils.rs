@@ -0,0 +1,121 @@
+use malachitebft_core_types::{
+    CommitCertificate, NilOrVal, Round, SigningProvider, ThresholdParams,
+};
+use malachitebft_test::utils::validators::make_validators;
+use malachitebft_test::{
+    Ed25519Provider, Height, PrivateKey, TestContext, Validator, ValidatorSet, ValueId, Vote,
+};
+
+use informalsystems_malachitebft_light_client::{LightBlock, TransitionProof, Verifier};
+
+/// Number of heights decided by each validator set
+pub const EPOCH_LENGTH: u64 = 10;
+
+/// Number of validators in each validator set
+pub const VALIDATORS_PER_EPOCH: usize = 4;
+
+/// Stands in for the commitment of a decided value to its validator sets,
+/// eg. a hash of the validator sets in a block header.
+#[derive(Clone, Debug)]
+pub struct Commitment {
+    pub validator_set: ValidatorSet,
+    pub next_validator_set: ValidatorSet,
+}
+
+impl TransitionProof<TestContext> for Commitment {
+    fn verify(
+        &self,
+        _certificate: &CommitCertificate<TestContext>,
+        validator_set: &ValidatorSet,
+        next_validator_set: &ValidatorSet,
+    ) -> bool {
+        &self.validator_set == validator_set && &self.next_validator_set == next_validator_set
+    }
+}
+
+pub type TestLightBlock = LightBlock<TestContext, Commitment>;
+
+/// A chain where, at each epoch, the first validator of the previous validator set
+/// is replaced by a new one.
+pub struct Chain {
+    validators: [(Validator, PrivateKey); 8],
+}
+
+impl Chain {
+    pub fn new() -> Self {
+        Self {
+            validators: make_validators([10; 8]),
+        }
+    }
+
+    pub fn verifier(&self) -> Verifier<TestContext, Ed25519Provider> {
+        let provider = Ed25519Provider::new(self.validators[0].1.clone());
+        Verifier::new(provider, ThresholdParams::default())
+    }
+
+    /// The validator set deciding the value at the given height.
+    pub fn validator_set(&self, height: u64) -> ValidatorSet {
+        let epoch = ((height - 1) / EPOCH_LENGTH) as usize;
+
+        ValidatorSet::new(
+            self.validators[epoch..epoch + VALIDATORS_PER_EPOCH]
+                .iter()
+                .map(|(validator, _)| validator.clone()),
+        )
+    }
+
+    /// The light block at the given height, with a certificate signed by the given number of validators.
+    pub fn light_block_signed_by(&self, height: u64, signers: usize) -> TestLightBlock {
+        let validator_set = self.validator_set(height);
+        let value_id = ValueId::new(height);
+
+        let commits = validator_set
+            .validators
+            .iter()
+            .take(signers)
+            .map(|validator| {
+                let (_, private_key) = self
+                    .validators
+                    .iter()
+                    .find(|(v, _)| v.address == validator.address)
+                    .unwrap();
+
+                let precommit = Vote::new_precommit(
+                    Height::new(height),
+                    Round::new(0),
+                    NilOrVal::Val(value_id),
+                    validator.address,
+                );
+
+                Ed25519Provider::new(private_key.clone())
+                    .sign_vote(precommit)
+                    .unwrap()
+            })
+            .collect();
+
+        let certificate = CommitCertificate::new(
+            Height::new(height),
+            Round::new(0),
+            value_id,
+            commits,
+            &validator_set,
+        );
+
+        let next_validator_set = self.validator_set(height + 1);
+
+        LightBlock::new(
+            certificate,
+            validator_set.clone(),
+            next_validator_set.clone(),
+            Commitment {
+                validator_set,
+                next_validator_set,
+            },
+        )
+    }
+
+    /// The light block at the given height, with a certificate signed by all its validators.
+    pub fn light_block(&self, height: u64) -> TestLightBlock {
+        self.light_block_signed_by(height, VALIDATORS_PER_EPOCH)
+    }
+}
@@ -0,0 +1,139 @@
+use informalsystems_malachitebft_light_client::Error;
+
+use malachitebft_core_types::CertificateError;
+
+use crate::utils::{Chain, EPOCH_LENGTH};
+
+#[test]
+fn sequential() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    // Within an epoch
+    let trusted = chain.light_block(1);
+    let untrusted = chain.light_block(2);
+    assert!(verifier.verify_sequential(&trusted, &untrusted).is_ok());
+
+    // Across a validator set change
+    let trusted = chain.light_block(EPOCH_LENGTH);
+    let untrusted = chain.light_block(EPOCH_LENGTH + 1);
+    assert!(verifier.verify_sequential(&trusted, &untrusted).is_ok());
+}
+
+#[test]
+fn sequential_requires_adjacent_heights() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    let trusted = chain.light_block(1);
+    let untrusted = chain.light_block(3);
+
+    assert!(matches!(
+        verifier.verify_sequential(&trusted, &untrusted),
+        Err(Error::NonAdjacentHeight { .. })
+    ));
+}
+
+#[test]
+fn sequential_requires_designated_validator_set() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    let trusted = chain.light_block(1);
+
+    // The light block claims to be decided by the validator set of the next epoch
+    let mut untrusted = chain.light_block(2);
+    untrusted.validator_set = chain.validator_set(EPOCH_LENGTH + 1);
+
+    assert!(matches!(
+        verifier.verify_sequential(&trusted, &untrusted),
+        Err(Error::ValidatorSetMismatch)
+    ));
+}
+
+#[test]
+fn rejects_certificate_without_quorum() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    let trusted = chain.light_block(1);
+    let untrusted = chain.light_block_signed_by(2, 2);
+
+    assert!(matches!(
+        verifier.verify(&trusted, &untrusted),
+        Err(Error::InvalidCertificate(_))
+    ));
+}
+
+#[test]
+fn rejects_repeated_signer() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    let trusted = chain.light_block(1);
+
+    // Half of the validators signed, one of them counting for the other half
+    let mut untrusted = chain.light_block_signed_by(2, 2);
+    let signatures = &mut untrusted.certificate.aggregated_signature.signatures;
+    let repeated = signatures[0].clone();
+    signatures.extend([repeated.clone(), repeated.clone()]);
+
+    assert!(matches!(
+        verifier.verify(&trusted, &untrusted),
+        Err(Error::InvalidCertificate(CertificateError::DuplicateVote(signature)))
+            if signature.address == repeated.address
+    ));
+}
+
+#[test]
+fn rejects_invalid_transition_proof() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    let trusted = chain.light_block(1);
+
+    // The decided value does not designate the claimed next validator set
+    let mut untrusted = chain.light_block(2);
+    untrusted.next_validator_set = chain.validator_set(EPOCH_LENGTH * 3 + 1);
+
+    assert!(matches!(
+        verifier.verify(&trusted, &untrusted),
+        Err(Error::InvalidTransitionProof)
+    ));
+}
+
+#[test]
+fn skipping() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    // 3 out of 4 of the trusted validators are still in the validator set
+    let trusted = chain.light_block(1);
+    let untrusted = chain.light_block(EPOCH_LENGTH + 5);
+    assert!(verifier.verify_skipping(&trusted, &untrusted).is_ok());
+
+    // Only 1 out of 4 of the trusted validators is still in the validator set
+    let untrusted = chain.light_block(EPOCH_LENGTH * 3 + 5);
+    assert!(matches!(
+        verifier.verify_skipping(&trusted, &untrusted),
+        Err(Error::NotEnoughTrust {
+            signed: 10,
+            total: 40,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn skipping_requires_increasing_heights() {
+    let chain = Chain::new();
+    let verifier = chain.verifier();
+
+    let trusted = chain.light_block(5);
+    let untrusted = chain.light_block(3);
+
+    assert!(matches!(
+        verifier.verify_skipping(&trusted, &untrusted),
+        Err(Error::NonIncreasingHeight { .. })
+    ));
+}
@@ -1,7 +1,11 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
 use std::time::Duration;
 
+use libp2p::allow_block_list::{self, AllowedPeers};
 use libp2p::kad::{Addresses, KBucketKey, KBucketRef};
 use libp2p::request_response::{OutboundRequestId, ResponseChannel};
+use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::NetworkBehaviour;
 use libp2p::{gossipsub, identify, ping};
 use libp2p_broadcast as broadcast;
@@ -13,7 +17,7 @@ use malachitebft_discovery as discovery;
 use malachitebft_metrics::Registry;
 use malachitebft_sync as sync;
 
-use crate::{Config, GossipSubConfig, PROTOCOL};
+use crate::{Channel, Config, GossipSubConfig, PeerIdExt, PeerScoringConfig, PROTOCOL};
 
 #[derive(Debug)]
 pub enum NetworkEvent {
@@ -25,6 +29,12 @@ pub enum NetworkEvent {
     Discovery(discovery::NetworkEvent),
 }
 
+impl From<Infallible> for NetworkEvent {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
 impl From<identify::Event> for NetworkEvent {
     fn from(event: identify::Event) -> Self {
         Self::Identify(event)
@@ -64,6 +74,7 @@ impl From<discovery::NetworkEvent> for NetworkEvent {
 #[derive(NetworkBehaviour)]
 #[behaviour(to_swarm = "NetworkEvent")]
 pub struct Behaviour {
+    pub allow_list: Toggle<allow_block_list::Behaviour<AllowedPeers>>,
     pub identify: identify::Behaviour,
     pub ping: ping::Behaviour,
     pub gossipsub: gossipsub::Behaviour,
@@ -118,6 +129,11 @@ fn message_id(message: &gossipsub::Message) -> gossipsub::MessageId {
     gossipsub::MessageId::new(hasher.finish().to_be_bytes().as_slice())
 }
 
+/// Identifier of a message received over the broadcast protocol, which has none of its own
+pub(crate) fn broadcast_message_id(data: &[u8]) -> gossipsub::MessageId {
+    gossipsub::MessageId::new(seahash::hash(data).to_be_bytes().as_slice())
+}
+
 fn gossipsub_config(config: GossipSubConfig, max_transmit_size: usize) -> gossipsub::Config {
     gossipsub::ConfigBuilder::default()
         .max_transmit_size(max_transmit_size)
@@ -131,10 +147,45 @@ fn gossipsub_config(config: GossipSubConfig, max_transmit_size: usize) -> gossip
         .mesh_outbound_min(config.mesh_outbound_min)
         .mesh_n(config.mesh_n)
         .message_id_fn(message_id)
+        // Messages are only forwarded once they have been validated by the application
+        .validate_messages()
         .build()
         .unwrap()
 }
 
+fn peer_score_params(config: PeerScoringConfig) -> gossipsub::PeerScoreParams {
+    let topic_params = gossipsub::TopicScoreParams {
+        topic_weight: 1.0,
+        time_in_mesh_quantum: Duration::from_secs(1),
+        // Some channels, eg. evidence, are quiet most of the time,
+        // so peers are not penalized for delivering few messages in the mesh
+        mesh_message_deliveries_weight: 0.0,
+        mesh_failure_penalty_weight: 0.0,
+        invalid_message_deliveries_weight: config.invalid_message_weight,
+        invalid_message_deliveries_decay: 0.9,
+        ..Default::default()
+    };
+
+    let topics = Channel::consensus()
+        .iter()
+        .map(|channel| (channel.to_gossipsub_topic().hash(), topic_params.clone()))
+        .collect();
+
+    gossipsub::PeerScoreParams {
+        topics,
+        ..Default::default()
+    }
+}
+
+fn peer_score_thresholds(config: PeerScoringConfig) -> gossipsub::PeerScoreThresholds {
+    gossipsub::PeerScoreThresholds {
+        gossip_threshold: config.gossip_threshold,
+        publish_threshold: config.publish_threshold,
+        graylist_threshold: config.graylist_threshold,
+        ..Default::default()
+    }
+}
+
 impl Behaviour {
     pub fn new_with_metrics(config: &Config, keypair: &Keypair, registry: &mut Registry) -> Self {
         let identify = identify::Behaviour::new(identify::Config::new(
@@ -144,7 +195,7 @@ impl Behaviour {
 
         let ping = ping::Behaviour::new(ping::Config::new().with_interval(Duration::from_secs(5)));
 
-        let gossipsub = gossipsub::Behaviour::new_with_metrics(
+        let mut gossipsub = gossipsub::Behaviour::new_with_metrics(
             gossipsub::MessageAuthenticity::Signed(keypair.clone()),
             gossipsub_config(config.gossipsub, config.pubsub_max_size),
             registry.sub_registry_with_prefix("gossipsub"),
@@ -152,6 +203,15 @@ impl Behaviour {
         )
         .unwrap();
 
+        if let Some(peer_scoring) = config.gossipsub.peer_scoring {
+            gossipsub
+                .with_peer_score(
+                    peer_score_params(peer_scoring),
+                    peer_score_thresholds(peer_scoring),
+                )
+                .unwrap();
+        }
+
         let broadcast = broadcast::Behaviour::new_with_metrics(
             broadcast::Config {
                 max_buf_size: config.pubsub_max_size,
@@ -166,7 +226,16 @@ impl Behaviour {
 
         let discovery = discovery::Behaviour::new(keypair, config.discovery);
 
+        let allow_list = config.allowed_peers.as_ref().map(|peers| {
+            let mut allow_list = allow_block_list::Behaviour::default();
+            for peer_id in peers {
+                allow_list.allow_peer(peer_id.to_libp2p());
+            }
+            allow_list
+        });
+
         Self {
+            allow_list: Toggle::from(allow_list),
             identify,
             ping,
             gossipsub,
@@ -175,4 +244,33 @@ impl Behaviour {
             discovery,
         }
     }
+
+    /// Replace the peers in the allow-list, closing the connections to the peers
+    /// which are not allowed anymore. Returns `false` if the allow-list is disabled.
+    pub fn set_allowed_peers(&mut self, peers: &[crate::PeerId]) -> bool {
+        let Some(allow_list) = self.allow_list.as_mut() else {
+            return false;
+        };
+
+        let peers = peers
+            .iter()
+            .map(|peer_id| peer_id.to_libp2p())
+            .collect::<HashSet<_>>();
+
+        let disallowed = allow_list
+            .allowed_peers()
+            .difference(&peers)
+            .copied()
+            .collect::<Vec<_>>();
+
+        for peer_id in disallowed {
+            allow_list.disallow_peer(peer_id);
+        }
+
+        for peer_id in peers {
+            allow_list.allow_peer(peer_id);
+        }
+
+        true
+    }
 }
@@ -8,16 +8,26 @@ use serde::{Deserialize, Serialize};
 pub enum Channel {
     Consensus,
     ProposalParts,
+    Evidence,
     Sync,
 }
 
 impl Channel {
     pub fn all() -> &'static [Channel] {
-        &[Channel::Consensus, Channel::ProposalParts, Channel::Sync]
+        &[
+            Channel::Consensus,
+            Channel::ProposalParts,
+            Channel::Evidence,
+            Channel::Sync,
+        ]
     }
 
     pub fn consensus() -> &'static [Channel] {
-        &[Channel::Consensus, Channel::ProposalParts]
+        &[
+            Channel::Consensus,
+            Channel::ProposalParts,
+            Channel::Evidence,
+        ]
     }
 
     pub fn to_gossipsub_topic(self) -> gossipsub::IdentTopic {
@@ -32,6 +42,7 @@ impl Channel {
         match self {
             Channel::Consensus => "/consensus",
             Channel::ProposalParts => "/proposal_parts",
+            Channel::Evidence => "/evidence",
             Channel::Sync => "/sync",
         }
     }
@@ -52,6 +63,7 @@ impl Channel {
         match topic.as_str() {
             "/consensus" => Some(Channel::Consensus),
             "/proposal_parts" => Some(Channel::ProposalParts),
+            "/evidence" => Some(Channel::Evidence),
             "/sync" => Some(Channel::Sync),
             _ => None,
         }
@@ -61,6 +73,7 @@ impl Channel {
         match topic.as_ref() {
             b"/consensus" => Some(Channel::Consensus),
             b"/proposal_parts" => Some(Channel::ProposalParts),
+            b"/evidence" => Some(Channel::Evidence),
             b"/sync" => Some(Channel::Sync),
             _ => None,
         }
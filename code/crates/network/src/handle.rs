@@ -5,7 +5,7 @@ use tokio::task;
 
 use malachitebft_peer::PeerId;
 
-use crate::{Channel, CtrlMsg, Event};
+use crate::{Channel, CtrlMsg, Event, MessageId, ValidationResult};
 
 pub struct RecvHandle {
     peer_id: PeerId,
@@ -68,6 +68,24 @@ impl CtrlHandle {
         Ok(())
     }
 
+    pub async fn update_allowed_peers(&self, peers: Vec<PeerId>) -> Result<(), eyre::Report> {
+        self.tx_ctrl
+            .send(CtrlMsg::UpdateAllowedPeers(peers))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn report_validation_result(
+        &self,
+        message_id: MessageId,
+        result: ValidationResult,
+    ) -> Result<(), eyre::Report> {
+        self.tx_ctrl
+            .send(CtrlMsg::ReportValidationResult(message_id, result))
+            .await?;
+        Ok(())
+    }
+
     pub async fn wait_shutdown(self) -> Result<(), eyre::Report> {
         self.shutdown().await?;
         self.join().await?;
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::ops::ControlFlow;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use futures::StreamExt;
 use libp2p::metrics::{Metrics, Recorder};
@@ -13,7 +14,7 @@ use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, error_span, trace, warn, Instrument};
 
 use malachitebft_discovery::{self as discovery};
-use malachitebft_metrics::SharedRegistry;
+use malachitebft_metrics::{Registry, SharedRegistry};
 use malachitebft_sync::{self as sync};
 
 pub use malachitebft_peer::PeerId;
@@ -37,6 +38,13 @@ const PROTOCOL: &str = "/malachitebft-core-consensus/v1beta1";
 const METRICS_PREFIX: &str = "malachitebft_network";
 const DISCOVERY_METRICS_PREFIX: &str = "malachitebft_discovery";
 
+/// How often to write the address book to disk, if it changed
+const ADDRESS_BOOK_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long to wait for the validation result of a GossipSub message,
+/// after which GossipSub has dropped the message from its cache anyway
+const PENDING_VALIDATION_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Copy, Clone, Debug, Default)]
 pub enum PubSubProtocol {
     /// GossipSub: a pubsub protocol based on epidemic broadcast trees
@@ -63,6 +71,7 @@ pub struct GossipSubConfig {
     pub mesh_n_high: usize,
     pub mesh_n_low: usize,
     pub mesh_outbound_min: usize,
+    pub peer_scoring: Option<PeerScoringConfig>,
 }
 
 impl Default for GossipSubConfig {
@@ -73,6 +82,52 @@ impl Default for GossipSubConfig {
             mesh_n_high: 12,
             mesh_n_low: 4,
             mesh_outbound_min: 2,
+            peer_scoring: None,
+        }
+    }
+}
+
+/// GossipSub peer scoring parameters, see the GossipSub v1.1 spec for their meaning
+#[derive(Copy, Clone, Debug)]
+pub struct PeerScoringConfig {
+    /// Below this score, gossip is neither sent to nor accepted from the peer
+    pub gossip_threshold: f64,
+    /// Below this score, messages we publish are not sent to the peer
+    pub publish_threshold: f64,
+    /// Below this score, all messages from the peer are ignored
+    pub graylist_threshold: f64,
+    /// Weight of the square of the number of invalid messages sent by the peer (P4), must be negative
+    pub invalid_message_weight: f64,
+}
+
+impl Default for PeerScoringConfig {
+    fn default() -> Self {
+        Self {
+            gossip_threshold: -10.0,
+            publish_threshold: -50.0,
+            graylist_threshold: -80.0,
+            invalid_message_weight: -10.0,
+        }
+    }
+}
+
+/// Outcome of the validation of a message received over GossipSub
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ValidationResult {
+    /// The message is valid, deliver it and forward it to other peers
+    Accept,
+    /// The message is invalid, drop it and penalize the peer which sent it
+    Reject,
+    /// The message is useless but not malicious, drop it without penalizing the peer
+    Ignore,
+}
+
+impl ValidationResult {
+    fn to_gossipsub(self) -> gossipsub::MessageAcceptance {
+        match self {
+            Self::Accept => gossipsub::MessageAcceptance::Accept,
+            Self::Reject => gossipsub::MessageAcceptance::Reject,
+            Self::Ignore => gossipsub::MessageAcceptance::Ignore,
         }
     }
 }
@@ -94,6 +149,8 @@ pub struct Config {
     pub pubsub_protocol: PubSubProtocol,
     pub rpc_max_size: usize,
     pub pubsub_max_size: usize,
+    pub address_book_path: Option<PathBuf>,
+    pub allowed_peers: Option<Vec<PeerId>>,
 }
 
 impl Config {
@@ -133,7 +190,7 @@ pub enum Event {
     Listening(Multiaddr),
     PeerConnected(PeerId),
     PeerDisconnected(PeerId),
-    Message(Channel, PeerId, Bytes),
+    Message(Channel, PeerId, MessageId, Bytes),
     Sync(sync::RawMessage),
 }
 
@@ -143,6 +200,8 @@ pub enum CtrlMsg {
     Broadcast(Channel, Bytes),
     SyncRequest(PeerId, Bytes, oneshot::Sender<OutboundRequestId>),
     SyncReply(InboundRequestId, Bytes),
+    UpdateAllowedPeers(Vec<PeerId>),
+    ReportValidationResult(MessageId, ValidationResult),
     Shutdown,
 }
 
@@ -150,6 +209,9 @@ pub enum CtrlMsg {
 pub struct State {
     pub sync_channels: HashMap<InboundRequestId, sync::ResponseChannel>,
     pub discovery: discovery::Discovery<Behaviour>,
+    /// Peers which forwarded us the GossipSub messages pending validation,
+    /// along with the time at which the messages were received
+    pub pending_validations: HashMap<MessageId, (libp2p::PeerId, Instant)>,
 }
 
 impl State {
@@ -157,8 +219,21 @@ impl State {
         Self {
             sync_channels: Default::default(),
             discovery,
+            pending_validations: Default::default(),
         }
     }
+
+    /// Forget about the messages whose validation result has not been reported in time
+    fn prune_pending_validations(&mut self, now: Instant) {
+        self.pending_validations
+            .retain(|message_id, (_, received_at)| {
+                let pending = now.duration_since(*received_at) < PENDING_VALIDATION_TIMEOUT;
+                if !pending {
+                    trace!(%message_id, "Validation result of message was not reported in time");
+                }
+                pending
+            });
+    }
 }
 
 pub async fn spawn(
@@ -166,28 +241,8 @@ pub async fn spawn(
     config: Config,
     registry: SharedRegistry,
 ) -> Result<Handle, eyre::Report> {
-    let swarm = registry.with_prefix(METRICS_PREFIX, |registry| -> Result<_, eyre::Report> {
-        let builder = SwarmBuilder::with_existing_identity(keypair).with_tokio();
-        match config.transport {
-            TransportProtocol::Tcp => Ok(builder
-                .with_tcp(
-                    libp2p::tcp::Config::new().nodelay(true), // Disable Nagle's algorithm
-                    libp2p::noise::Config::new,
-                    libp2p::yamux::Config::default,
-                )?
-                .with_dns()?
-                .with_bandwidth_metrics(registry)
-                .with_behaviour(|kp| Behaviour::new_with_metrics(&config, kp, registry))?
-                .with_swarm_config(|cfg| config.apply_to_swarm(cfg))
-                .build()),
-            TransportProtocol::Quic => Ok(builder
-                .with_quic_config(|cfg| config.apply_to_quic(cfg))
-                .with_dns()?
-                .with_bandwidth_metrics(registry)
-                .with_behaviour(|kp| Behaviour::new_with_metrics(&config, kp, registry))?
-                .with_swarm_config(|cfg| config.apply_to_swarm(cfg))
-                .build()),
-        }
+    let swarm = registry.with_prefix(METRICS_PREFIX, |registry| {
+        build_swarm(keypair, &config, registry)
     })?;
 
     let metrics = registry.with_prefix(METRICS_PREFIX, Metrics::new);
@@ -196,7 +251,12 @@ pub async fn spawn(
     let (tx_ctrl, rx_ctrl) = mpsc::channel(32);
 
     let discovery = registry.with_prefix(DISCOVERY_METRICS_PREFIX, |reg| {
-        discovery::Discovery::new(config.discovery, config.persistent_peers.clone(), reg)
+        discovery::Discovery::new(
+            config.discovery,
+            config.persistent_peers.clone(),
+            config.address_book_path.clone(),
+            reg,
+        )
     });
 
     let state = State::new(discovery);
@@ -209,6 +269,34 @@ pub async fn spawn(
     Ok(Handle::new(peer_id, tx_ctrl, rx_event, task_handle))
 }
 
+fn build_swarm(
+    keypair: Keypair,
+    config: &Config,
+    registry: &mut Registry,
+) -> Result<swarm::Swarm<Behaviour>, eyre::Report> {
+    let builder = SwarmBuilder::with_existing_identity(keypair).with_tokio();
+    match config.transport {
+        TransportProtocol::Tcp => Ok(builder
+            .with_tcp(
+                libp2p::tcp::Config::new().nodelay(true), // Disable Nagle's algorithm
+                libp2p::noise::Config::new,
+                libp2p::yamux::Config::default,
+            )?
+            .with_dns()?
+            .with_bandwidth_metrics(registry)
+            .with_behaviour(|kp| Behaviour::new_with_metrics(config, kp, registry))?
+            .with_swarm_config(|cfg| config.apply_to_swarm(cfg))
+            .build()),
+        TransportProtocol::Quic => Ok(builder
+            .with_quic_config(|cfg| config.apply_to_quic(cfg))
+            .with_dns()?
+            .with_bandwidth_metrics(registry)
+            .with_behaviour(|kp| Behaviour::new_with_metrics(config, kp, registry))?
+            .with_swarm_config(|cfg| config.apply_to_swarm(cfg))
+            .build()),
+    }
+}
+
 async fn run(
     config: Config,
     metrics: Metrics,
@@ -234,6 +322,9 @@ async fn run(
         return;
     };
 
+    let mut save_address_book = tokio::time::interval(ADDRESS_BOOK_SAVE_INTERVAL);
+    let mut prune_pending_validations = tokio::time::interval(PENDING_VALIDATION_TIMEOUT);
+
     loop {
         let result = tokio::select! {
             event = swarm.select_next_some() => {
@@ -263,6 +354,16 @@ async fn run(
             Some(ctrl) = rx_ctrl.recv() => {
                 handle_ctrl_msg(&mut swarm, &mut state, &config, ctrl).await
             }
+
+            _ = save_address_book.tick() => {
+                state.discovery.save_address_book();
+                ControlFlow::Continue(())
+            }
+
+            _ = prune_pending_validations.tick() => {
+                state.prune_pending_validations(Instant::now());
+                ControlFlow::Continue(())
+            }
         };
 
         match result {
@@ -270,6 +371,11 @@ async fn run(
             ControlFlow::Break(()) => break,
         }
     }
+
+    // Make sure the latest changes to the address book are written before shutting down
+    if let Some(save) = state.discovery.save_address_book() {
+        let _ = save.await;
+    }
 }
 
 async fn handle_ctrl_msg(
@@ -332,6 +438,37 @@ async fn handle_ctrl_msg(
             ControlFlow::Continue(())
         }
 
+        CtrlMsg::UpdateAllowedPeers(peers) => {
+            if swarm.behaviour_mut().set_allowed_peers(&peers) {
+                debug!(count = %peers.len(), "Updated allowed peers");
+            } else {
+                warn!("Ignoring update of allowed peers, the allow-list is disabled");
+            }
+
+            ControlFlow::Continue(())
+        }
+
+        CtrlMsg::ReportValidationResult(message_id, result) => {
+            // Messages received over the broadcast protocol are not pending validation
+            let Some((propagation_source, _)) = state.pending_validations.remove(&message_id)
+            else {
+                return ControlFlow::Continue(());
+            };
+
+            trace!(%message_id, ?result, "Reporting validation result of message");
+
+            swarm
+                .behaviour_mut()
+                .gossipsub
+                .report_message_validation_result(
+                    &message_id,
+                    &propagation_source,
+                    result.to_gossipsub(),
+                );
+
+            ControlFlow::Continue(())
+        }
+
         CtrlMsg::Shutdown => ControlFlow::Break(()),
     }
 }
@@ -492,8 +629,8 @@ async fn handle_swarm_event(
 async fn handle_gossipsub_event(
     event: gossipsub::Event,
     _metrics: &Metrics,
-    _swarm: &mut swarm::Swarm<Behaviour>,
-    _state: &mut State,
+    swarm: &mut swarm::Swarm<Behaviour>,
+    state: &mut State,
     tx_event: &mpsc::Sender<Event>,
 ) -> ControlFlow<()> {
     match event {
@@ -516,11 +653,20 @@ async fn handle_gossipsub_event(
         }
 
         gossipsub::Event::Message {
+            propagation_source,
             message_id,
             message,
-            ..
         } => {
             let Some(peer_id) = message.source else {
+                swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .report_message_validation_result(
+                        &message_id,
+                        &propagation_source,
+                        gossipsub::MessageAcceptance::Reject,
+                    );
+
                 return ControlFlow::Continue(());
             };
 
@@ -530,6 +676,15 @@ async fn handle_gossipsub_event(
                     message.topic
                 );
 
+                swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .report_message_validation_result(
+                        &message_id,
+                        &propagation_source,
+                        gossipsub::MessageAcceptance::Ignore,
+                    );
+
                 return ControlFlow::Continue(());
             };
 
@@ -538,14 +693,21 @@ async fn handle_gossipsub_event(
                 message.data.len()
             );
 
+            // The message is only forwarded to other peers once it has been validated
+            state
+                .pending_validations
+                .insert(message_id.clone(), (propagation_source, Instant::now()));
+
             let event = Event::Message(
                 channel,
                 PeerId::from_libp2p(&peer_id),
+                message_id.clone(),
                 Bytes::from(message.data),
             );
 
             if let Err(e) = tx_event.send(event).await {
                 error!("Error sending message to handle: {e}");
+                state.pending_validations.remove(&message_id);
                 return ControlFlow::Break(());
             }
         }
@@ -608,6 +770,7 @@ async fn handle_broadcast_event(
             let event = Event::Message(
                 channel,
                 PeerId::from_libp2p(&peer_id),
+                behaviour::broadcast_message_id(&message),
                 Bytes::copy_from_slice(message.as_ref()),
             );
 
@@ -692,3 +855,167 @@ impl PeerIdExt for PeerId {
         Self::from_bytes(&peer_id.to_bytes()).expect("valid PeerId")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestNode {
+        config: Config,
+        metrics: Metrics,
+        state: State,
+        swarm: swarm::Swarm<Behaviour>,
+        tx_event: mpsc::Sender<Event>,
+        rx_event: mpsc::Receiver<Event>,
+    }
+
+    impl TestNode {
+        fn new() -> Self {
+            let config = Config {
+                listen_addr: "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+                persistent_peers: vec![],
+                discovery: DiscoveryConfig {
+                    enabled: false,
+                    ..Default::default()
+                },
+                idle_connection_timeout: Duration::from_secs(60),
+                transport: TransportProtocol::Tcp,
+                gossipsub: GossipSubConfig {
+                    peer_scoring: Some(PeerScoringConfig::default()),
+                    ..Default::default()
+                },
+                pubsub_protocol: PubSubProtocol::GossipSub,
+                rpc_max_size: 1024 * 1024,
+                pubsub_max_size: 1024 * 1024,
+                address_book_path: None,
+                allowed_peers: None,
+            };
+
+            let mut registry = Registry::default();
+            let keypair = Keypair::generate_ed25519();
+            let mut swarm = build_swarm(keypair, &config, &mut registry).unwrap();
+            pubsub::subscribe(&mut swarm, config.pubsub_protocol, Channel::consensus()).unwrap();
+
+            let metrics = Metrics::new(&mut registry);
+            let discovery =
+                discovery::Discovery::new(config.discovery, vec![], None, &mut registry);
+            let (tx_event, rx_event) = mpsc::channel(32);
+
+            Self {
+                config,
+                metrics,
+                state: State::new(discovery),
+                swarm,
+                tx_event,
+                rx_event,
+            }
+        }
+
+        async fn handle(&mut self, event: SwarmEvent<NetworkEvent>) {
+            let flow = handle_swarm_event(
+                event,
+                &self.config,
+                &self.metrics,
+                &mut self.swarm,
+                &mut self.state,
+                &self.tx_event,
+            )
+            .await;
+
+            assert_eq!(flow, ControlFlow::Continue(()));
+        }
+    }
+
+    /// Score given by a node to the peer which published a consensus message to it,
+    /// once the node reported the given validation result for that message.
+    async fn score_after_validation(result: ValidationResult) -> f64 {
+        let mut sender = TestNode::new();
+        let mut receiver = TestNode::new();
+
+        let sender_id = *sender.swarm.local_peer_id();
+        let receiver_id = *receiver.swarm.local_peer_id();
+        let topic = Channel::Consensus.to_gossipsub_topic().hash();
+
+        sender
+            .swarm
+            .listen_on(sender.config.listen_addr.clone())
+            .unwrap();
+
+        let message_id = loop {
+            tokio::select! {
+                event = sender.swarm.select_next_some() => match event {
+                    SwarmEvent::NewListenAddr { address, .. } => {
+                        receiver.swarm.dial(address).unwrap();
+                    }
+
+                    // Publish once the receiver is known to listen to the channel
+                    SwarmEvent::Behaviour(NetworkEvent::GossipSub(gossipsub::Event::Subscribed {
+                        peer_id,
+                        topic: subscribed,
+                    })) if peer_id == receiver_id && subscribed == topic => {
+                        let data = Bytes::from_static(b"vote");
+                        pubsub::publish(&mut sender.swarm, sender.config.pubsub_protocol, Channel::Consensus, data).unwrap();
+                    }
+
+                    _ => {}
+                },
+
+                event = receiver.swarm.select_next_some() => receiver.handle(event).await,
+
+                Some(event) = receiver.rx_event.recv() => {
+                    if let Event::Message(Channel::Consensus, _, message_id, _) = event {
+                        break message_id;
+                    }
+                }
+            }
+        };
+
+        let msg = CtrlMsg::ReportValidationResult(message_id, result);
+        let flow = handle_ctrl_msg(
+            &mut receiver.swarm,
+            &mut receiver.state,
+            &receiver.config,
+            msg,
+        )
+        .await;
+        assert_eq!(flow, ControlFlow::Continue(()));
+
+        receiver
+            .swarm
+            .behaviour()
+            .gossipsub
+            .peer_score(&sender_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn prune_expired_pending_validations() {
+        let mut node = TestNode::new();
+        let now = Instant::now();
+
+        let expired = MessageId::new(b"expired");
+        let pending = MessageId::new(b"pending");
+        let peer_id = libp2p::PeerId::random();
+
+        node.state
+            .pending_validations
+            .insert(expired.clone(), (peer_id, now - PENDING_VALIDATION_TIMEOUT));
+        node.state
+            .pending_validations
+            .insert(pending.clone(), (peer_id, now));
+
+        node.state.prune_pending_validations(now);
+
+        assert!(!node.state.pending_validations.contains_key(&expired));
+        assert!(node.state.pending_validations.contains_key(&pending));
+    }
+
+    #[tokio::test]
+    async fn rejected_message_penalizes_peer() {
+        let rejected = score_after_validation(ValidationResult::Reject).await;
+        assert!(rejected < 0.0, "score is {rejected}");
+
+        let accepted = score_after_validation(ValidationResult::Accept).await;
+        assert!(accepted >= 0.0, "score is {accepted}");
+    }
+}
@@ -5,7 +5,8 @@ use libp2p_identity::{ecdsa, PeerId};
 use malachitebft_config::TransportProtocol;
 use malachitebft_metrics::SharedRegistry;
 use malachitebft_network::{
-    spawn, BootstrapProtocol, Config, DiscoveryConfig, Keypair, PeerIdExt, Selector,
+    spawn, BootstrapProtocol, Config, DiscoveryConfig, Keypair, PeerId as MalachitePeerId,
+    PeerIdExt, Selector,
 };
 use malachitebft_starknet_host::types::PrivateKey;
 use rand::{rngs::StdRng, Rng, SeedableRng};
@@ -146,6 +147,10 @@ impl<const N: usize> Test<N> {
         })
     }
 
+    fn peer_id(&self, node: usize) -> PeerId {
+        PeerId::from_public_key(&self.keypairs[node].public())
+    }
+
     fn generate_default_configs(&self) -> [Config; N] {
         std::array::from_fn(|i| Config {
             listen_addr: TransportProtocol::Quic
@@ -169,6 +174,13 @@ impl<const N: usize> Test<N> {
             pubsub_protocol: malachitebft_network::PubSubProtocol::default(),
             rpc_max_size: 10 * 1024 * 1024,   // 10 MiB
             pubsub_max_size: 4 * 1024 * 1024, // 4 MiB
+            address_book_path: None,
+            allowed_peers: self.nodes[i].allowed_peers.as_ref().map(|peers| {
+                peers
+                    .iter()
+                    .map(|j| MalachitePeerId::from_libp2p(&self.peer_id(*j)))
+                    .collect()
+            }),
         })
     }
 
@@ -277,6 +289,7 @@ pub enum Fault {
 pub struct TestNode {
     _id: usize,
     bootstrap_nodes: Vec<usize>,
+    allowed_peers: Option<Vec<usize>>,
     faults: Vec<Fault>,
 }
 
@@ -285,6 +298,7 @@ impl TestNode {
         Self {
             _id: id,
             bootstrap_nodes,
+            allowed_peers: None,
             faults: Vec::new(),
         }
     }
@@ -293,10 +307,17 @@ impl TestNode {
         Self {
             _id: id,
             bootstrap_nodes,
+            allowed_peers: None,
             faults,
         }
     }
 
+    /// Only allow the given nodes to connect to this node
+    pub fn with_allowed_peers(mut self, allowed_peers: Vec<usize>) -> Self {
+        self.allowed_peers = Some(allowed_peers);
+        self
+    }
+
     pub fn bootstrap_nodes(&self) -> &[usize] {
         &self.bootstrap_nodes
     }
@@ -54,3 +54,28 @@ pub async fn circular_graph_n() {
 
     test.run().await
 }
+
+// Testing a validator behind a sentry node, which only allows the sentry to connect:
+//     0 ---> 1 <--- 2 <--- 3
+// The other nodes discover the validator through the sentry, but cannot connect to it.
+#[tokio::test]
+pub async fn sentry_node() {
+    let test = Test::new(
+        [
+            TestNode::correct(0, vec![1]).with_allowed_peers(vec![1]),
+            TestNode::correct(1, vec![]),
+            TestNode::correct(2, vec![1]),
+            TestNode::correct(3, vec![2]),
+        ],
+        [
+            Expected::Exactly(vec![1]),
+            Expected::Exactly(vec![0, 2, 3]),
+            Expected::Exactly(vec![1, 3]),
+            Expected::Exactly(vec![1, 2]),
+        ],
+        Duration::from_secs(0),
+        Duration::from_secs(10),
+    );
+
+    test.run().await
+}
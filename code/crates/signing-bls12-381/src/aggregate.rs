@@ -0,0 +1,228 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use blst::min_pk;
+use blst::BLST_ERROR;
+use malachitebft_core_types::{
+    CertificateError, CertificateSignature, CommitCertificate, CommitSignature, Context, Validator,
+    ValidatorSet, VotingPower,
+};
+
+use crate::{Bls12381, Error, PublicKey, Signature, SignersBitmap, SIGNATURE_DST};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A single BLS signature aggregating the signatures of several validators,
+/// along with a bitmap recording which validators of the validator set signed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AggregateSignature {
+    /// The aggregate of the signatures of all signers, or `None` if there are no signers.
+    pub signature: Option<Signature>,
+
+    /// The validators whose signature is part of the aggregate.
+    pub signers: SignersBitmap,
+}
+
+impl AggregateSignature {
+    pub fn new(signature: Option<Signature>, signers: SignersBitmap) -> Self {
+        Self { signature, signers }
+    }
+
+    /// Aggregate the given signatures, each issued by the validator
+    /// at the given index in a validator set of the given size.
+    ///
+    /// Signatures from validators which already signed, or whose index
+    /// is out of bounds, are ignored.
+    pub fn aggregate<'a>(
+        validator_count: usize,
+        signatures: impl IntoIterator<Item = (usize, &'a Signature)>,
+    ) -> Result<Self, Error> {
+        let mut signers = SignersBitmap::new(validator_count);
+        let mut to_aggregate = Vec::new();
+
+        for (index, signature) in signatures {
+            if index >= validator_count || signers.get(index) {
+                continue;
+            }
+
+            signers.set(index);
+            to_aggregate.push(signature.inner());
+        }
+
+        if to_aggregate.is_empty() {
+            return Ok(Self::new(None, signers));
+        }
+
+        let aggregate = min_pk::AggregateSignature::aggregate(&to_aggregate, true)?;
+
+        Ok(Self::new(Some(aggregate.to_signature().into()), signers))
+    }
+
+    /// Verify the aggregate signature, assuming all signers signed the same message.
+    ///
+    /// The public keys must be the ones of the signers, in the order of the bitmap,
+    /// and must each have been checked for proof of possession beforehand.
+    pub fn verify(&self, msg: &[u8], public_keys: &[&PublicKey]) -> bool {
+        let Some(signature) = &self.signature else {
+            return false;
+        };
+
+        if public_keys.len() != self.signers.count_ones() {
+            return false;
+        }
+
+        let public_keys = public_keys.iter().map(|pk| pk.inner()).collect::<Vec<_>>();
+
+        let result =
+            signature
+                .inner()
+                .fast_aggregate_verify(true, msg, SIGNATURE_DST, &public_keys);
+
+        result == BLST_ERROR::BLST_SUCCESS
+    }
+
+    /// Verify the aggregate signature, where each signer signed its own message.
+    ///
+    /// The messages and public keys must be the ones of the signers, in the order of the bitmap.
+    pub fn verify_distinct(&self, msgs: &[&[u8]], public_keys: &[&PublicKey]) -> bool {
+        let Some(signature) = &self.signature else {
+            return false;
+        };
+
+        if public_keys.len() != self.signers.count_ones() || msgs.len() != public_keys.len() {
+            return false;
+        }
+
+        let public_keys = public_keys.iter().map(|pk| pk.inner()).collect::<Vec<_>>();
+
+        let result =
+            signature
+                .inner()
+                .aggregate_verify(true, msgs, SIGNATURE_DST, &public_keys, true);
+
+        result == BLST_ERROR::BLST_SUCCESS
+    }
+}
+
+impl<Ctx> CertificateSignature<Ctx> for AggregateSignature
+where
+    Ctx: Context<SigningScheme = Bls12381>,
+{
+    fn aggregate(signatures: Vec<CommitSignature<Ctx>>, validator_set: &Ctx::ValidatorSet) -> Self {
+        let indices = (0..validator_set.count())
+            .filter_map(|index| {
+                let validator = validator_set.get_by_index(index)?;
+                Some((validator.address(), index))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let signatures = signatures.iter().filter_map(|commit_sig| {
+            let index = indices.get(&commit_sig.address)?;
+            Some((*index, &commit_sig.signature))
+        });
+
+        // Signatures are only aggregated once their votes have been verified,
+        // so they are all valid points and aggregation cannot fail.
+        Self::aggregate(validator_set.count(), signatures)
+            .unwrap_or_else(|_| Self::new(None, SignersBitmap::new(validator_set.count())))
+    }
+
+    fn signers_count(&self) -> usize {
+        self.signers.count_ones()
+    }
+
+    fn signers(&self, validator_set: &Ctx::ValidatorSet) -> Vec<Ctx::Address> {
+        self.signers
+            .iter_ones()
+            .filter_map(|index| validator_set.get_by_index(index))
+            .map(|validator| validator.address().clone())
+            .collect()
+    }
+}
+
+/// Error returned when the aggregate signature of a certificate fails to verify.
+///
+/// Converts into the corresponding [`CertificateError`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AggregateError {
+    /// The signers do not match the validator set.
+    InvalidSigners,
+
+    /// The aggregate signature is invalid.
+    InvalidSignature,
+}
+
+impl fmt::Display for AggregateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSigners => write!(f, "signers do not match the validator set"),
+            Self::InvalidSignature => write!(f, "invalid aggregate signature"),
+        }
+    }
+}
+
+impl<Ctx: Context> From<AggregateError> for CertificateError<Ctx> {
+    fn from(error: AggregateError) -> Self {
+        match error {
+            AggregateError::InvalidSigners => Self::InvalidSigners,
+            AggregateError::InvalidSignature => Self::InvalidAggregateSignature,
+        }
+    }
+}
+
+/// Verify the aggregate signature of a certificate against the validator set at its height.
+///
+/// The `sign_bytes` function must return the bytes that the given validator signed
+/// when precommitting to the certified value. If all signers signed the same bytes,
+/// the signature is verified with a single pairing check over the aggregated public key.
+///
+/// This is meant to be used to implement
+/// [`SigningProvider::verify_certificate_signature`](malachitebft_core_types::SigningProvider::verify_certificate_signature)
+/// for contexts using [`AggregateSignature`] as their certificate signature.
+///
+/// ## Return
+/// Return the total voting power of the validators that signed the certificate if the signature is valid.
+pub fn verify_certificate_signature<Ctx>(
+    certificate: &CommitCertificate<Ctx>,
+    validator_set: &Ctx::ValidatorSet,
+    sign_bytes: impl Fn(&Ctx::Validator) -> Vec<u8>,
+) -> Result<VotingPower, AggregateError>
+where
+    Ctx: Context<SigningScheme = Bls12381, CertificateSignature = AggregateSignature>,
+{
+    let aggregate = &certificate.aggregated_signature;
+
+    if aggregate.signers.len() != validator_set.count() {
+        return Err(AggregateError::InvalidSigners);
+    }
+
+    let signers = aggregate
+        .signers
+        .iter_ones()
+        .map(|index| validator_set.get_by_index(index))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(AggregateError::InvalidSigners)?;
+
+    if signers.is_empty() {
+        return Ok(0);
+    }
+
+    let public_keys = signers.iter().map(|v| v.public_key()).collect::<Vec<_>>();
+    let msgs = signers.iter().map(|v| sign_bytes(v)).collect::<Vec<_>>();
+
+    let valid = if msgs.windows(2).all(|w| w[0] == w[1]) {
+        aggregate.verify(&msgs[0], &public_keys)
+    } else {
+        let msgs = msgs.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        aggregate.verify_distinct(&msgs, &public_keys)
+    };
+
+    if !valid {
+        return Err(AggregateError::InvalidSignature);
+    }
+
+    Ok(signers.iter().map(|v| v.voting_power()).sum())
+}
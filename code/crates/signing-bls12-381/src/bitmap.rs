@@ -0,0 +1,105 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A bitmap recording which validators of a validator set signed a certificate,
+/// where the bit at index `i` corresponds to the validator at index `i` in the set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SignersBitmap {
+    len: usize,
+    bits: Vec<u8>,
+}
+
+impl SignersBitmap {
+    /// Create an empty bitmap for a validator set of the given size.
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            bits: vec![0; len.div_ceil(8)],
+        }
+    }
+
+    /// Decode a bitmap for a validator set of the given size from its byte representation.
+    ///
+    /// Return `None` if the number of bytes does not match the size of the validator set,
+    /// or if any bit past the end of the validator set is set.
+    pub fn from_bytes(len: usize, bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != len.div_ceil(8) {
+            return None;
+        }
+
+        let bitmap = Self {
+            len,
+            bits: bytes.to_vec(),
+        };
+
+        let padding = bitmap.bits.len() * 8 - len;
+        if padding > 0 && bitmap.bits[bitmap.bits.len() - 1] >> (8 - padding) != 0 {
+            return None;
+        }
+
+        Some(bitmap)
+    }
+
+    /// The byte representation of the bitmap, least significant bit first.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// The size of the validator set this bitmap refers to.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the validator at the given index signed.
+    pub fn get(&self, index: usize) -> bool {
+        index < self.len && self.bits[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Mark the validator at the given index as a signer.
+    ///
+    /// ## Panics
+    /// If the index is out of bounds.
+    pub fn set(&mut self, index: usize) {
+        assert!(index < self.len, "signer index out of bounds");
+        self.bits[index / 8] |= 1 << (index % 8);
+    }
+
+    /// The number of signers.
+    pub fn count_ones(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
+    /// The indices of the signers, in increasing order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|&index| self.get(index))
+    }
+}
+
+/// Deserialization goes through [`SignersBitmap::from_bytes`],
+/// so that the size of the bitmap is checked against the size of the validator set.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SignersBitmap {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            len: usize,
+            bits: Vec<u8>,
+        }
+
+        let raw = Raw::deserialize(de)?;
+
+        Self::from_bytes(raw.len, &raw.bits)
+            .ok_or_else(|| serde::de::Error::custom("invalid signers bitmap"))
+    }
+}
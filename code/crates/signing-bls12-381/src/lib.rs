@@ -0,0 +1,266 @@
+//! BLS12-381 signing scheme for the consensus engine, with support for signature aggregation.
+//!
+//! Signatures live in G2 and public keys in G1 (the "min-pk" variant), and messages are hashed
+//! with the proof-of-possession ciphersuite. Because aggregate signatures over the same message
+//! are only secure against rogue-key attacks if every public key has proven possession of its
+//! private key, applications must check [`PublicKey::verify_possession`] before admitting
+//! a validator to the validator set.
+
+#![cfg_attr(coverage_nightly, feature(coverage_attribute))]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use blst::min_pk;
+use blst::BLST_ERROR;
+use malachitebft_core_types::SigningScheme;
+use signature::{Keypair, Signer, Verifier};
+
+#[cfg(feature = "rand")]
+use rand::{CryptoRng, RngCore};
+
+#[cfg(feature = "serde")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod serializers;
+
+mod aggregate;
+mod bitmap;
+
+pub use aggregate::{verify_certificate_signature, AggregateError, AggregateSignature};
+pub use bitmap::SignersBitmap;
+
+/// Domain separation tag for signatures over consensus messages.
+const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Domain separation tag for proofs of possession.
+const POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bls12381;
+
+impl Bls12381 {
+    #[cfg(feature = "rand")]
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn generate_keypair<R>(rng: R) -> PrivateKey
+    where
+        R: RngCore + CryptoRng,
+    {
+        PrivateKey::generate(rng)
+    }
+}
+
+impl SigningScheme for Bls12381 {
+    type DecodingError = Error;
+
+    type Signature = Signature;
+    type PublicKey = PublicKey;
+    type PrivateKey = PrivateKey;
+
+    fn encode_signature(signature: &Signature) -> Vec<u8> {
+        signature.to_bytes().to_vec()
+    }
+
+    fn decode_signature(bytes: &[u8]) -> Result<Self::Signature, Self::DecodingError> {
+        Signature::try_from(bytes)
+    }
+}
+
+/// Error returned when decoding or aggregating keys and signatures.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Error(BLST_ERROR);
+
+impl Error {
+    pub fn inner(&self) -> BLST_ERROR {
+        self.0
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            BLST_ERROR::BLST_SUCCESS => write!(f, "success"),
+            BLST_ERROR::BLST_BAD_ENCODING => write!(f, "bad encoding"),
+            BLST_ERROR::BLST_POINT_NOT_ON_CURVE => write!(f, "point not on curve"),
+            BLST_ERROR::BLST_POINT_NOT_IN_GROUP => write!(f, "point not in group"),
+            BLST_ERROR::BLST_AGGR_TYPE_MISMATCH => write!(f, "aggregate type mismatch"),
+            BLST_ERROR::BLST_VERIFY_FAIL => write!(f, "verification failed"),
+            BLST_ERROR::BLST_PK_IS_INFINITY => write!(f, "public key is infinity"),
+            BLST_ERROR::BLST_BAD_SCALAR => write!(f, "bad scalar"),
+        }
+    }
+}
+
+impl From<BLST_ERROR> for Error {
+    fn from(error: BLST_ERROR) -> Self {
+        Self(error)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Signature(min_pk::Signature);
+
+impl Signature {
+    pub fn inner(&self) -> &min_pk::Signature {
+        &self.0
+    }
+
+    /// The compressed representation of the signature.
+    pub fn to_bytes(&self) -> [u8; 96] {
+        self.0.to_bytes()
+    }
+
+    /// Decode a compressed signature, checking that it is a valid point in G2.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self(min_pk::Signature::sig_validate(bytes, true)?))
+    }
+}
+
+impl From<min_pk::Signature> for Signature {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn from(signature: min_pk::Signature) -> Self {
+        Self(signature)
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = Error;
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl PartialOrd for Signature {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Signature {
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.to_bytes().cmp(&other.to_bytes())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PrivateKey(min_pk::SecretKey);
+
+impl PrivateKey {
+    #[cfg(feature = "rand")]
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn generate<R>(mut rng: R) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut seed = [0; 32];
+        rng.fill_bytes(&mut seed);
+
+        Self::from_seed(seed)
+    }
+
+    /// Deterministically derive a private key from the given key material.
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        let secret_key =
+            min_pk::SecretKey::key_gen(&seed, &[]).expect("32 bytes of key material is enough");
+
+        Self(secret_key)
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.0.sk_to_pk())
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        Signature(self.0.sign(msg, SIGNATURE_DST, &[]))
+    }
+
+    /// Prove possession of this private key, by signing the corresponding public key.
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn prove_possession(&self) -> Signature {
+        let public_key = self.public_key();
+        Signature(self.0.sign(&public_key.to_bytes(), POP_DST, &[]))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self(min_pk::SecretKey::from_bytes(bytes)?))
+    }
+
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub fn inner(&self) -> &min_pk::SecretKey {
+        &self.0
+    }
+}
+
+impl Signer<Signature> for PrivateKey {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(self.sign(msg))
+    }
+}
+
+impl Keypair for PrivateKey {
+    type VerifyingKey = PublicKey;
+
+    fn verifying_key(&self) -> Self::VerifyingKey {
+        self.public_key()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(min_pk::PublicKey);
+
+impl PublicKey {
+    pub fn new(key: impl Into<min_pk::PublicKey>) -> Self {
+        Self(key.into())
+    }
+
+    /// The compressed representation of the public key.
+    pub fn to_bytes(&self) -> [u8; 48] {
+        self.0.to_bytes()
+    }
+
+    /// Decode a compressed public key, checking that it is a valid point in G1.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self(min_pk::PublicKey::key_validate(bytes)?))
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), signature::Error> {
+        match signature
+            .0
+            .verify(true, msg, SIGNATURE_DST, &[], &self.0, true)
+        {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            _ => Err(signature::Error::new()),
+        }
+    }
+
+    /// Verify a proof of possession of the private key corresponding to this public key.
+    pub fn verify_possession(&self, proof: &Signature) -> bool {
+        let result = proof
+            .0
+            .verify(true, &self.to_bytes(), POP_DST, &[], &self.0, true);
+
+        result == BLST_ERROR::BLST_SUCCESS
+    }
+
+    pub fn inner(&self) -> &min_pk::PublicKey {
+        &self.0
+    }
+}
+
+impl Verifier<Signature> for PublicKey {
+    fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), signature::Error> {
+        PublicKey::verify(self, msg, signature)
+    }
+}
@@ -0,0 +1,69 @@
+//! Serialize/deserialize keys and signatures as base64-encoded strings of their compressed form.
+
+use alloc::string::{String, ToString};
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{PrivateKey, PublicKey, Signature};
+
+fn serialize_bytes<S>(bytes: &[u8], ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.serialize_str(BASE64_STANDARD.encode(bytes).as_str())
+}
+
+fn deserialize_with<'de, D, T, E>(
+    de: D,
+    decode: impl FnOnce(&[u8]) -> Result<T, E>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    E: ToString,
+{
+    let s = String::deserialize(de)?;
+
+    let bytes = BASE64_STANDARD
+        .decode(s)
+        .map_err(|e| serde::de::Error::custom(e.to_string()))?;
+
+    decode(&bytes).map_err(|e| serde::de::Error::custom(e.to_string()))
+}
+
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&self.to_bytes(), ser)
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        deserialize_with(de, Signature::from_bytes)
+    }
+}
+
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&self.to_bytes(), ser)
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        deserialize_with(de, PublicKey::from_bytes)
+    }
+}
+
+impl Serialize for PrivateKey {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(&self.to_bytes(), ser)
+    }
+}
+
+impl<'de> Deserialize<'de> for PrivateKey {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        deserialize_with(de, PrivateKey::from_bytes)
+    }
+}
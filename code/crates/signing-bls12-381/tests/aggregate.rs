@@ -0,0 +1,146 @@
+use informalsystems_malachitebft_signing_bls12_381::{
+    AggregateSignature, PrivateKey, PublicKey, Signature, SignersBitmap,
+};
+
+fn keys<const N: usize>() -> [PrivateKey; N] {
+    core::array::from_fn(|i| PrivateKey::from_seed([i as u8 + 1; 32]))
+}
+
+#[test]
+fn sign_and_verify() {
+    let [sk] = keys::<1>();
+    let pk = sk.public_key();
+
+    let signature = sk.sign(b"hello");
+    assert!(pk.verify(b"hello", &signature).is_ok());
+    assert!(pk.verify(b"world", &signature).is_err());
+
+    let decoded = Signature::from_bytes(&signature.to_bytes()).unwrap();
+    assert_eq!(decoded, signature);
+
+    let decoded = PublicKey::from_bytes(&pk.to_bytes()).unwrap();
+    assert_eq!(decoded, pk);
+}
+
+#[test]
+fn proof_of_possession() {
+    let [sk1, sk2] = keys::<2>();
+
+    let proof = sk1.prove_possession();
+    assert!(sk1.public_key().verify_possession(&proof));
+    assert!(!sk2.public_key().verify_possession(&proof));
+
+    // A proof of possession is not a valid signature over the public key, and vice versa
+    let pk1 = sk1.public_key();
+    assert!(pk1.verify(&pk1.to_bytes(), &proof).is_err());
+    assert!(!pk1.verify_possession(&sk1.sign(&pk1.to_bytes())));
+}
+
+#[test]
+fn aggregate_same_message() {
+    let sks = keys::<4>();
+    let pks = sks.each_ref().map(|sk| sk.public_key());
+
+    let msg = b"commit";
+    let signatures = sks.each_ref().map(|sk| sk.sign(msg));
+
+    // Validators 0, 2 and 3 signed
+    let aggregate =
+        AggregateSignature::aggregate(4, [0, 2, 3].map(|i| (i, &signatures[i]))).unwrap();
+
+    assert_eq!(aggregate.signers.iter_ones().collect::<Vec<_>>(), [0, 2, 3]);
+    assert!(aggregate.verify(msg, &[&pks[0], &pks[2], &pks[3]]));
+
+    // Wrong message
+    assert!(!aggregate.verify(b"other", &[&pks[0], &pks[2], &pks[3]]));
+
+    // Wrong signers
+    assert!(!aggregate.verify(msg, &[&pks[0], &pks[1], &pks[3]]));
+    assert!(!aggregate.verify(msg, &[&pks[0], &pks[2]]));
+}
+
+#[test]
+fn aggregate_distinct_messages() {
+    let sks = keys::<3>();
+    let pks = sks.each_ref().map(|sk| sk.public_key());
+
+    let msgs: [&[u8]; 3] = [b"one", b"two", b"three"];
+    let signatures = [0, 1, 2].map(|i| sks[i].sign(msgs[i]));
+
+    let aggregate = AggregateSignature::aggregate(3, signatures.iter().enumerate()).unwrap();
+
+    assert!(aggregate.verify_distinct(&msgs, &[&pks[0], &pks[1], &pks[2]]));
+    assert!(!aggregate.verify_distinct(&[msgs[0], msgs[2], msgs[1]], &[&pks[0], &pks[1], &pks[2]]));
+}
+
+#[test]
+fn aggregate_ignores_duplicates_and_out_of_bounds() {
+    let sks = keys::<2>();
+    let pks = sks.each_ref().map(|sk| sk.public_key());
+
+    let signatures = sks.each_ref().map(|sk| sk.sign(b"commit"));
+
+    let aggregate = AggregateSignature::aggregate(
+        2,
+        [
+            (0, &signatures[0]),
+            (0, &signatures[0]),
+            (1, &signatures[1]),
+            (5, &signatures[1]),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(aggregate.signers.count_ones(), 2);
+    assert!(aggregate.verify(b"commit", &[&pks[0], &pks[1]]));
+}
+
+#[test]
+fn empty_aggregate() {
+    let aggregate = AggregateSignature::aggregate(3, []).unwrap();
+
+    assert_eq!(aggregate.signature, None);
+    assert_eq!(aggregate.signers.count_ones(), 0);
+    assert!(!aggregate.verify(b"commit", &[]));
+}
+
+#[test]
+fn signers_bitmap() {
+    let mut bitmap = SignersBitmap::new(10);
+    bitmap.set(1);
+    bitmap.set(9);
+
+    assert_eq!(bitmap.as_bytes(), &[0b0000_0010, 0b0000_0010]);
+    assert_eq!(bitmap.iter_ones().collect::<Vec<_>>(), [1, 9]);
+
+    let decoded = SignersBitmap::from_bytes(10, bitmap.as_bytes()).unwrap();
+    assert_eq!(decoded, bitmap);
+
+    // Wrong number of bytes
+    assert_eq!(SignersBitmap::from_bytes(10, &[0]), None);
+
+    // Bit set past the end of the validator set
+    assert_eq!(SignersBitmap::from_bytes(10, &[0, 0b0000_0100]), None);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn signers_bitmap_deserialize() {
+    let mut bitmap = SignersBitmap::new(10);
+    bitmap.set(9);
+
+    let json = serde_json::to_string(&bitmap).unwrap();
+    let decoded: SignersBitmap = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, bitmap);
+
+    let decode = |json: &str| serde_json::from_str::<SignersBitmap>(json);
+
+    // Truncated
+    assert!(decode(r#"{"len":10,"bits":[0]}"#).is_err());
+
+    // Padded with an extra byte
+    assert!(decode(r#"{"len":10,"bits":[0,2,0]}"#).is_err());
+
+    // Bit set past the end of the validator set
+    assert!(decode(r#"{"len":10,"bits":[0,4]}"#).is_err());
+}
@@ -0,0 +1,462 @@
+use core::fmt;
+
+use malachitebft_core_types::{
+    Address, CertificateSignature, CommitCertificate, CommitSignature, Context, Height, NilOrVal,
+    Proposal, ProposalPart, Round, SignedExtension, Validator, ValidatorSet, Value, Vote, VoteType,
+    VotingPower,
+};
+
+use informalsystems_malachitebft_signing_bls12_381::{
+    verify_certificate_signature, AggregateError, AggregateSignature, Bls12381, PrivateKey,
+    PublicKey, Signature, SignersBitmap,
+};
+
+// A minimal context using BLS signatures aggregated in certificates.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Ctx;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Addr(u8);
+
+impl fmt::Display for Addr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Address for Addr {}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Ht(u64);
+
+impl fmt::Display for Ht {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Height for Ht {
+    const ZERO: Self = Ht(0);
+    const INITIAL: Self = Ht(1);
+
+    fn increment_by(&self, n: u64) -> Self {
+        Ht(self.0 + n)
+    }
+
+    fn decrement_by(&self, n: u64) -> Option<Self> {
+        self.0.checked_sub(n).map(Ht)
+    }
+
+    fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Val(u64);
+
+impl Value for Val {
+    type Id = u64;
+
+    fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Part;
+
+impl ProposalPart<Ctx> for Part {
+    fn is_first(&self) -> bool {
+        true
+    }
+
+    fn is_last(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Prop {
+    height: Ht,
+    round: Round,
+    value: Val,
+    pol_round: Round,
+    address: Addr,
+}
+
+impl Proposal<Ctx> for Prop {
+    fn height(&self) -> Ht {
+        self.height
+    }
+
+    fn round(&self) -> Round {
+        self.round
+    }
+
+    fn value(&self) -> &Val {
+        &self.value
+    }
+
+    fn take_value(self) -> Val {
+        self.value
+    }
+
+    fn pol_round(&self) -> Round {
+        self.pol_round
+    }
+
+    fn validator_address(&self) -> &Addr {
+        &self.address
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Precommit {
+    height: Ht,
+    round: Round,
+    value: NilOrVal<u64>,
+    address: Addr,
+}
+
+impl Precommit {
+    /// The bytes signed by the validator, which include its address.
+    fn to_sign_bytes(&self) -> Vec<u8> {
+        let value = match self.value {
+            NilOrVal::Nil => 0,
+            NilOrVal::Val(id) => id,
+        };
+
+        [
+            &self.height.0.to_be_bytes()[..],
+            &self.round.as_i64().to_be_bytes(),
+            &value.to_be_bytes(),
+            &[self.address.0],
+        ]
+        .concat()
+    }
+}
+
+impl Vote<Ctx> for Precommit {
+    fn height(&self) -> Ht {
+        self.height
+    }
+
+    fn round(&self) -> Round {
+        self.round
+    }
+
+    fn value(&self) -> &NilOrVal<u64> {
+        &self.value
+    }
+
+    fn take_value(self) -> NilOrVal<u64> {
+        self.value
+    }
+
+    fn vote_type(&self) -> VoteType {
+        VoteType::Precommit
+    }
+
+    fn validator_address(&self) -> &Addr {
+        &self.address
+    }
+
+    fn extension(&self) -> Option<&SignedExtension<Ctx>> {
+        None
+    }
+
+    fn take_extension(&mut self) -> Option<SignedExtension<Ctx>> {
+        None
+    }
+
+    fn extend(self, _extension: SignedExtension<Ctx>) -> Self {
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BlsValidator {
+    address: Addr,
+    public_key: PublicKey,
+    voting_power: VotingPower,
+}
+
+impl Validator<Ctx> for BlsValidator {
+    fn address(&self) -> &Addr {
+        &self.address
+    }
+
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn voting_power(&self) -> VotingPower {
+        self.voting_power
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BlsValidatorSet(Vec<BlsValidator>);
+
+impl ValidatorSet<Ctx> for BlsValidatorSet {
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn total_voting_power(&self) -> VotingPower {
+        self.0.iter().map(|v| v.voting_power).sum()
+    }
+
+    fn get_by_address(&self, address: &Addr) -> Option<&BlsValidator> {
+        self.0.iter().find(|v| &v.address == address)
+    }
+
+    fn get_by_index(&self, index: usize) -> Option<&BlsValidator> {
+        self.0.get(index)
+    }
+}
+
+impl Context for Ctx {
+    type Address = Addr;
+    type Height = Ht;
+    type ProposalPart = Part;
+    type Proposal = Prop;
+    type Validator = BlsValidator;
+    type ValidatorSet = BlsValidatorSet;
+    type Value = Val;
+    type Vote = Precommit;
+    type Extension = ();
+    type SigningScheme = Bls12381;
+    type CertificateSignature = AggregateSignature;
+
+    fn select_proposer<'a>(
+        &self,
+        validator_set: &'a BlsValidatorSet,
+        _: Ht,
+        _: Round,
+    ) -> &'a BlsValidator {
+        &validator_set.0[0]
+    }
+
+    fn new_proposal(height: Ht, round: Round, value: Val, pol_round: Round, address: Addr) -> Prop {
+        Prop {
+            height,
+            round,
+            value,
+            pol_round,
+            address,
+        }
+    }
+
+    fn new_prevote(_: Ht, _: Round, _: NilOrVal<u64>, _: Addr) -> Precommit {
+        unimplemented!()
+    }
+
+    fn new_precommit(height: Ht, round: Round, value: NilOrVal<u64>, address: Addr) -> Precommit {
+        Precommit {
+            height,
+            round,
+            value,
+            address,
+        }
+    }
+}
+
+const VALUE_ID: u64 = 42;
+
+fn keys<const N: usize>() -> [PrivateKey; N] {
+    core::array::from_fn(|i| PrivateKey::from_seed([i as u8 + 1; 32]))
+}
+
+/// Validators with voting powers 1, 2, 3 and so on.
+fn validator_set(public_keys: impl IntoIterator<Item = PublicKey>) -> BlsValidatorSet {
+    BlsValidatorSet(
+        public_keys
+            .into_iter()
+            .zip(1..)
+            .map(|(public_key, i)| BlsValidator {
+                address: Addr(i as u8),
+                public_key,
+                voting_power: i,
+            })
+            .collect(),
+    )
+}
+
+/// The precommit for the certified value of the validator with the given address.
+fn precommit(address: Addr) -> Precommit {
+    Ctx::new_precommit(Ht(1), Round::new(0), NilOrVal::Val(VALUE_ID), address)
+}
+
+fn sign_bytes(validator: &BlsValidator) -> Vec<u8> {
+    precommit(validator.address).to_sign_bytes()
+}
+
+fn certificate(aggregated_signature: AggregateSignature) -> CommitCertificate<Ctx> {
+    CommitCertificate {
+        height: Ht(1),
+        round: Round::new(0),
+        value_id: VALUE_ID,
+        aggregated_signature,
+    }
+}
+
+/// Certificate aggregating the precommits signed with the given keys, by the given validators.
+fn sign_certificate(
+    validator_set: &BlsValidatorSet,
+    signers: &[(&PrivateKey, Addr)],
+) -> CommitCertificate<Ctx> {
+    let signatures = signers
+        .iter()
+        .map(|(sk, address)| {
+            let signature = sk.sign(&precommit(*address).to_sign_bytes());
+            CommitSignature::<Ctx>::new(*address, signature)
+        })
+        .collect();
+
+    certificate(CertificateSignature::aggregate(signatures, validator_set))
+}
+
+/// A public key such that the aggregate of `victim` and the rogue key is `attacker`,
+/// which the attacker can compute without knowing the corresponding private key.
+fn rogue_key(attacker: &PublicKey, victim: &PublicKey) -> PublicKey {
+    let attacker: &blst::blst_p1_affine = attacker.inner().into();
+    let victim: &blst::blst_p1_affine = victim.inner().into();
+
+    let mut attacker_point = blst::blst_p1::default();
+    let mut victim_point = blst::blst_p1::default();
+    let mut rogue_point = blst::blst_p1::default();
+    let mut rogue = blst::blst_p1_affine::default();
+
+    unsafe {
+        blst::blst_p1_from_affine(&mut attacker_point, attacker);
+        blst::blst_p1_from_affine(&mut victim_point, victim);
+        blst::blst_p1_cneg(&mut victim_point, true);
+        blst::blst_p1_add_or_double(&mut rogue_point, &attacker_point, &victim_point);
+        blst::blst_p1_to_affine(&mut rogue, &rogue_point);
+    }
+
+    PublicKey::new(rogue)
+}
+
+#[test]
+fn verify_signed_certificate() {
+    let sks = keys::<4>();
+    let validator_set = validator_set(sks.each_ref().map(|sk| sk.public_key()));
+
+    let certificate = sign_certificate(
+        &validator_set,
+        &[(&sks[0], Addr(1)), (&sks[2], Addr(3)), (&sks[3], Addr(4))],
+    );
+
+    let signed = verify_certificate_signature(&certificate, &validator_set, sign_bytes);
+    assert_eq!(signed, Ok(1 + 3 + 4));
+
+    // Signed over other bytes than the precommits for the certified value
+    let other_value = |validator: &BlsValidator| {
+        Ctx::new_precommit(Ht(1), Round::new(0), NilOrVal::Val(0), validator.address)
+            .to_sign_bytes()
+    };
+
+    let signed = verify_certificate_signature(&certificate, &validator_set, other_value);
+    assert_eq!(signed, Err(AggregateError::InvalidSignature));
+
+    // Signed by another validator than the one it claims to be from
+    let forged = sign_certificate(&validator_set, &[(&sks[0], Addr(1)), (&sks[0], Addr(2))]);
+
+    let signed = verify_certificate_signature(&forged, &validator_set, sign_bytes);
+    assert_eq!(signed, Err(AggregateError::InvalidSignature));
+}
+
+#[test]
+fn verify_certificate_signed_over_same_bytes() {
+    let sks = keys::<3>();
+    let validator_set = validator_set(sks.each_ref().map(|sk| sk.public_key()));
+
+    let same_bytes = |_: &BlsValidator| b"commit".to_vec();
+    let signatures = sks.each_ref().map(|sk| sk.sign(b"commit"));
+
+    let aggregate = AggregateSignature::aggregate(3, signatures.iter().enumerate()).unwrap();
+    let certificate = certificate(aggregate);
+
+    let signed = verify_certificate_signature(&certificate, &validator_set, same_bytes);
+    assert_eq!(signed, Ok(1 + 2 + 3));
+}
+
+#[test]
+fn count_duplicate_signer_once() {
+    let sks = keys::<3>();
+    let validator_set = validator_set(sks.each_ref().map(|sk| sk.public_key()));
+
+    // Validator 3 signs three times, which would otherwise make up for a quorum
+    let certificate = sign_certificate(
+        &validator_set,
+        &[(&sks[2], Addr(3)), (&sks[2], Addr(3)), (&sks[2], Addr(3))],
+    );
+
+    assert_eq!(certificate.aggregated_signature.signers.count_ones(), 1);
+
+    let signed = verify_certificate_signature(&certificate, &validator_set, sign_bytes);
+    assert_eq!(signed, Ok(3));
+
+    // The signature of validator 3 is aggregated three times but its bit is only set once
+    let signature = sks[2].sign(&precommit(Addr(3)).to_sign_bytes());
+    let inner = signature.inner();
+
+    let aggregate = blst::min_pk::AggregateSignature::aggregate(&[inner, inner, inner], true)
+        .unwrap()
+        .to_signature();
+
+    let mut signers = SignersBitmap::new(3);
+    signers.set(2);
+
+    let forged = self::certificate(AggregateSignature::new(
+        Some(Signature::from(aggregate)),
+        signers,
+    ));
+
+    let signed = verify_certificate_signature(&forged, &validator_set, sign_bytes);
+    assert_eq!(signed, Err(AggregateError::InvalidSignature));
+
+    // The bitmap does not match the validator set
+    let mut signers = SignersBitmap::new(4);
+    signers.set(2);
+
+    let forged = self::certificate(AggregateSignature::new(Some(signature), signers));
+
+    let signed = verify_certificate_signature(&forged, &validator_set, sign_bytes);
+    assert_eq!(signed, Err(AggregateError::InvalidSigners));
+}
+
+#[test]
+fn rogue_key_fails_proof_of_possession() {
+    let [honest, attacker] = keys::<2>();
+
+    // The attacker registers a rogue key crafted from the public key of the honest validator
+    let rogue = rogue_key(&attacker.public_key(), &honest.public_key());
+    let validator_set = validator_set([honest.public_key(), rogue]);
+
+    // The attacker alone can then forge a certificate signed by both validators
+    let same_bytes = |_: &BlsValidator| b"commit".to_vec();
+    let signature = attacker.sign(b"commit");
+
+    let mut signers = SignersBitmap::new(2);
+    signers.set(0);
+    signers.set(1);
+
+    let forged = certificate(AggregateSignature::new(Some(signature), signers));
+
+    let signed = verify_certificate_signature(&forged, &validator_set, same_bytes);
+    assert_eq!(signed, Ok(1 + 2));
+
+    // Which is why validators must prove possession of their private key before being admitted,
+    // which the attacker cannot do for the rogue key
+    assert!(honest
+        .public_key()
+        .verify_possession(&honest.prove_possession()));
+
+    assert!(!rogue.verify_possession(&attacker.prove_possession()));
+    assert!(!rogue.verify_possession(&honest.prove_possession()));
+}
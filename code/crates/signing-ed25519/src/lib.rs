@@ -190,3 +190,37 @@ impl Verifier<Signature> for PublicKey {
         PublicKey::verify(self, msg, signature)
     }
 }
+
+/// Verifies many signatures at once, which is significantly faster than verifying them one by one.
+///
+/// Batch verification only tells whether all queued signatures are valid,
+/// callers need to verify signatures one by one to find out which ones are invalid.
+#[cfg(feature = "rand")]
+pub struct BatchVerifier(ed25519_consensus::batch::Verifier);
+
+#[cfg(feature = "rand")]
+impl BatchVerifier {
+    pub fn new() -> Self {
+        Self(ed25519_consensus::batch::Verifier::new())
+    }
+
+    /// Queue a signature over the given message for verification against the given public key.
+    pub fn queue(&mut self, public_key: &PublicKey, msg: &[u8], signature: &Signature) {
+        self.0.queue((public_key.0.into(), signature.0, msg));
+    }
+
+    /// Verify all queued signatures at once.
+    pub fn verify<R>(self, rng: R) -> Result<(), signature::Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        self.0.verify(rng).map_err(|_| signature::Error::new())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl Default for BatchVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
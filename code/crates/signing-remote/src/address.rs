@@ -0,0 +1,41 @@
+use core::fmt;
+use core::str::FromStr;
+use std::path::PathBuf;
+
+/// The address at which a remote signer can be reached.
+///
+/// Parsed from and displayed as `unix://<path>`.
+///
+/// The signer protocol does not authenticate the node, so the signer is only
+/// reachable over a Unix socket, whose access is restricted by its file permissions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignerAddress {
+    /// Path to a Unix domain socket
+    Unix(PathBuf),
+}
+
+impl fmt::Display for SignerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+impl FromStr for SignerAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix://") {
+            if path.is_empty() {
+                return Err(format!("missing socket path in signer address: {s}"));
+            }
+
+            Ok(Self::Unix(PathBuf::from(path)))
+        } else {
+            Err(format!(
+                "invalid signer address, expected `unix://<path>`: {s}"
+            ))
+        }
+    }
+}
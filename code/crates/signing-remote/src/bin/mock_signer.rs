@@ -0,0 +1,82 @@
+//! A remote signer holding an Ed25519 private key, meant for testing nodes
+//! configured with a remote signer locally.
+//!
+//! The private key is read from a `priv_validator_key.json` file, as generated by the test CLI.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::{eyre, Context};
+use tracing::info;
+
+use informalsystems_malachitebft_signing_remote::{
+    SignStateFile, SignerAddress, SignerListener, SignerServer,
+};
+use malachitebft_signing_ed25519::PrivateKey;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Mock remote signer for Ed25519 keys")]
+struct Args {
+    /// Path to the private key file
+    #[arg(long, value_name = "FILE")]
+    key: PathBuf,
+
+    /// Path to the file in which to persist the last signed height, round and step
+    #[arg(long, value_name = "FILE")]
+    state: PathBuf,
+
+    /// Address to listen on, as `unix://<path>`
+    #[arg(long, value_name = "ADDRESS")]
+    listen: SignerAddress,
+}
+
+fn main() -> eyre::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    let args = Args::parse();
+
+    let key = std::fs::read_to_string(&args.key)
+        .wrap_err_with(|| eyre!("Failed to read private key file {}", args.key.display()))?;
+
+    let private_key: PrivateKey = serde_json::from_str(&key)
+        .wrap_err_with(|| eyre!("Failed to parse private key file {}", args.key.display()))?;
+
+    let state = SignStateFile::load(&args.state)
+        .wrap_err_with(|| eyre!("Failed to load sign state {}", args.state.display()))?;
+
+    let last_signed = [
+        state.state().last_proposal.as_ref(),
+        state.state().last_vote(),
+    ];
+
+    if last_signed.iter().all(Option::is_none) {
+        info!("No sign state found, starting from scratch");
+    }
+
+    for last in last_signed.into_iter().flatten() {
+        info!(
+            height = last.height,
+            round = last.round,
+            step = ?last.step,
+            "Loaded sign state"
+        );
+    }
+
+    let listener = SignerListener::bind(&args.listen)
+        .wrap_err_with(|| eyre!("Failed to listen on {}", args.listen))?;
+
+    info!(
+        address = %args.listen,
+        public_key = %hex::encode(private_key.public_key().as_bytes()),
+        "Mock signer listening"
+    );
+
+    listener.run(SignerServer::new(private_key, state))?;
+
+    Ok(())
+}
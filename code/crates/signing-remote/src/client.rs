@@ -0,0 +1,221 @@
+use std::io;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::framing::{read_message, write_message, Stream};
+use crate::proto::{self, signer_request, signer_response};
+use crate::SignerAddress;
+
+/// An error which can occur when requesting a signature from the remote signer.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Failed to connect to the signer, or to exchange messages with it.
+    #[error("Failed to communicate with remote signer at {address}: {source}")]
+    Io {
+        address: SignerAddress,
+        source: io::Error,
+    },
+
+    /// The signer refused to sign the message or failed to do so.
+    #[error("Remote signer returned an error ({code:?}): {description}")]
+    Remote {
+        code: proto::ErrorCode,
+        description: String,
+    },
+
+    /// The signer replied with a response which does not match the request.
+    #[error("Unexpected response from remote signer: {0:?}")]
+    UnexpectedResponse(proto::SignerResponse),
+}
+
+impl Error {
+    /// Whether the signer refused to sign the message because doing so could lead to double-signing.
+    pub fn is_double_sign(&self) -> bool {
+        matches!(self, Self::Remote { code, .. } if *code == proto::ErrorCode::DoubleSign)
+    }
+}
+
+/// Client for a remote signer.
+///
+/// The connection to the signer is established lazily on the first request,
+/// and re-established on the next request if it fails.
+///
+/// Requests are blocking, and are sent one at a time over a single connection.
+/// From async code, they should be sent from a blocking thread, eg. with `tokio::task::spawn_blocking`.
+pub struct RemoteSignerClient {
+    address: SignerAddress,
+    timeout: Duration,
+    connection: Mutex<Option<Stream>>,
+}
+
+impl RemoteSignerClient {
+    /// Create a new client for the signer at the given address.
+    ///
+    /// The timeout applies to each read and write on the connection to the signer.
+    pub fn new(address: SignerAddress, timeout: Duration) -> Self {
+        Self {
+            address,
+            timeout,
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// The address of the remote signer.
+    pub fn address(&self) -> &SignerAddress {
+        &self.address
+    }
+
+    /// Get the public key of the validator whose private key is held by the signer.
+    pub fn public_key(&self) -> Result<Vec<u8>, Error> {
+        let request = signer_request::Request::PublicKey(proto::PublicKeyRequest {});
+
+        match self.request(request)? {
+            signer_response::Response::PublicKey(response) => Ok(response.public_key),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Sign a vote of the given type, for the given height and round.
+    pub fn sign_vote(
+        &self,
+        height: u64,
+        round: u32,
+        vote_type: proto::VoteType,
+        sign_bytes: Vec<u8>,
+    ) -> Result<Vec<u8>, Error> {
+        let request = signer_request::Request::SignVote(proto::SignVoteRequest {
+            height,
+            round,
+            vote_type: vote_type.into(),
+            sign_bytes,
+        });
+
+        self.request_signature(request)
+    }
+
+    /// Sign a proposal for the given height and round.
+    pub fn sign_proposal(
+        &self,
+        height: u64,
+        round: u32,
+        sign_bytes: Vec<u8>,
+    ) -> Result<Vec<u8>, Error> {
+        let request = signer_request::Request::SignProposal(proto::SignProposalRequest {
+            height,
+            round,
+            sign_bytes,
+        });
+
+        self.request_signature(request)
+    }
+
+    /// Sign an implicit proposal, which is not subject to double-sign protection.
+    ///
+    /// The signature is over the sign bytes prefixed with [`IMPLICIT_PROPOSAL_DOMAIN`](crate::IMPLICIT_PROPOSAL_DOMAIN),
+    /// and is therefore not valid for the proposal itself.
+    pub fn sign_implicit_proposal(&self, sign_bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let request =
+            signer_request::Request::SignImplicitProposal(proto::SignImplicitProposalRequest {
+                sign_bytes,
+            });
+
+        self.request_signature(request)
+    }
+
+    /// Sign a proposal part.
+    ///
+    /// The signature is over the sign bytes prefixed with [`PROPOSAL_PART_DOMAIN`](crate::PROPOSAL_PART_DOMAIN).
+    pub fn sign_proposal_part(&self, sign_bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let request = signer_request::Request::SignProposalPart(proto::SignProposalPartRequest {
+            sign_bytes,
+        });
+
+        self.request_signature(request)
+    }
+
+    /// Sign a vote extension.
+    ///
+    /// The signature is over the extension prefixed with [`VOTE_EXTENSION_DOMAIN`](crate::VOTE_EXTENSION_DOMAIN).
+    pub fn sign_vote_extension(&self, extension: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let request = signer_request::Request::SignVoteExtension(proto::SignVoteExtensionRequest {
+            extension,
+        });
+
+        self.request_signature(request)
+    }
+
+    fn request_signature(&self, request: signer_request::Request) -> Result<Vec<u8>, Error> {
+        match self.request(request)? {
+            signer_response::Response::Signature(response) => Ok(response.signature),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    fn request(
+        &self,
+        request: signer_request::Request,
+    ) -> Result<signer_response::Response, Error> {
+        let request = proto::SignerRequest {
+            request: Some(request),
+        };
+
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+
+        // If the connection to the signer was lost, retry once over a new connection.
+        // This is safe even for signing requests, as the signer returns
+        // the same signature when asked to sign the exact same message twice.
+        let response = match self.send(&mut connection, &request) {
+            Ok(response) => response,
+            Err(e) => {
+                warn!(address = %self.address, "Request to remote signer failed, reconnecting: {e}");
+                self.send(&mut connection, &request)
+                    .map_err(|source| Error::Io {
+                        address: self.address.clone(),
+                        source,
+                    })?
+            }
+        };
+
+        match response.response {
+            Some(signer_response::Response::Error(error)) => Err(Error::Remote {
+                code: error.code(),
+                description: error.description,
+            }),
+            Some(response) => Ok(response),
+            None => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+
+    fn send(
+        &self,
+        connection: &mut Option<Stream>,
+        request: &proto::SignerRequest,
+    ) -> io::Result<proto::SignerResponse> {
+        if connection.is_none() {
+            debug!(address = %self.address, "Connecting to remote signer");
+            *connection = Some(Stream::connect(&self.address, self.timeout)?);
+        }
+
+        let stream = connection
+            .as_mut()
+            .expect("connection was just established");
+
+        let result = write_message(stream, request).and_then(|()| read_message(stream));
+
+        if result.is_err() {
+            // Drop the connection, it will be re-established on the next request
+            *connection = None;
+        }
+
+        result
+    }
+}
+
+fn unexpected(response: signer_response::Response) -> Error {
+    Error::UnexpectedResponse(proto::SignerResponse {
+        response: Some(response),
+    })
+}
@@ -0,0 +1,20 @@
+/// Domain tag prepended by the signer to the sign bytes of an implicit proposal before signing them.
+pub const IMPLICIT_PROPOSAL_DOMAIN: &[u8] = b"\x00malachitebft/implicit-proposal";
+
+/// Domain tag prepended by the signer to the sign bytes of a proposal part before signing them.
+pub const PROPOSAL_PART_DOMAIN: &[u8] = b"\x00malachitebft/proposal-part";
+
+/// Domain tag prepended by the signer to a vote extension before signing it.
+pub const VOTE_EXTENSION_DOMAIN: &[u8] = b"\x00malachitebft/vote-extension";
+
+/// The bytes signed by the signer for a message of the given domain,
+/// against which its signature must be verified.
+pub fn domain_separated(domain: &[u8], bytes: &[u8]) -> Vec<u8> {
+    [domain, bytes].concat()
+}
+
+/// Whether the given sign bytes of a vote or proposal start like a domain tag,
+/// in which case the signer refuses to sign them.
+pub(crate) fn is_reserved(sign_bytes: &[u8]) -> bool {
+    sign_bytes.first() == Some(&0)
+}
@@ -0,0 +1,122 @@
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+use prost::Message;
+
+use crate::SignerAddress;
+
+/// Maximum size of a message exchanged with the signer, in bytes.
+pub const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Write the given message to the given writer, prefixed with its length.
+pub fn write_message<M, W>(writer: &mut W, message: &M) -> io::Result<()>
+where
+    M: Message,
+    W: Write + ?Sized,
+{
+    let bytes = message.encode_length_delimited_to_vec();
+    writer.write_all(&bytes)?;
+    writer.flush()
+}
+
+/// Read a length-prefixed message from the given reader.
+///
+/// Fails with [`io::ErrorKind::UnexpectedEof`] if the connection
+/// was closed before a message could be read in full.
+pub fn read_message<M, R>(reader: &mut R) -> io::Result<M>
+where
+    M: Message + Default,
+    R: Read + ?Sized,
+{
+    let len = read_varint(reader)?;
+
+    if len > MAX_MESSAGE_SIZE as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {len} bytes exceeds maximum size of {MAX_MESSAGE_SIZE} bytes"),
+        ));
+    }
+
+    let mut buf = vec![0; len as usize];
+    reader.read_exact(&mut buf)?;
+
+    M::decode(buf.as_slice()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn read_varint<R: Read + ?Sized>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0;
+
+    // A varint encoding a `u64` is at most 10 bytes long
+    for i in 0..10 {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+
+        value |= u64::from(byte[0] & 0x7f) << (7 * i);
+
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "invalid message length prefix",
+    ))
+}
+
+/// A connection between the node and the signer.
+pub(crate) enum Stream {
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    /// Connect to the signer at the given address.
+    ///
+    /// The timeout applies to each read and write on the connection.
+    pub(crate) fn connect(address: &SignerAddress, timeout: Duration) -> io::Result<Self> {
+        match address {
+            #[cfg(unix)]
+            SignerAddress::Unix(path) => {
+                let stream = UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                Ok(Self::Unix(stream))
+            }
+
+            #[cfg(not(unix))]
+            SignerAddress::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Unix sockets are not supported on this platform",
+            )),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush(),
+        }
+    }
+}
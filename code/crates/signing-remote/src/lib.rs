@@ -0,0 +1,45 @@
+//! Protocol for signing consensus messages with a private key held by a remote signer,
+//! eg. a key management system (KMS), instead of in the node's process.
+//!
+//! The node connects to the signer over a Unix socket, and exchanges
+//! length-prefixed Protobuf messages with it, as defined in `proto/signer.proto`.
+//! Connections are not authenticated, access to the signer is restricted
+//! by the permissions of the socket file.
+//!
+//! The signer keeps track of the height, round and step of the last proposal, prevote and precommit
+//! it signed, and refuses to sign any message which could lead its validator to equivocate.
+//! This double-sign protection lives in the signer rather than in the node,
+//! so that it still holds if the node loses its state or if several nodes share the same signer.
+//!
+//! The protection is advisory only: the encoding of the sign bytes is defined by the application,
+//! so the signer cannot decode them and relies on the height, round and step the node sends along.
+//! It therefore does not protect against a compromised node, which can misreport them.
+//!
+//! Implicit proposals, proposal parts and vote extensions are signed prefixed with a domain tag
+//! (see [`IMPLICIT_PROPOSAL_DOMAIN`], [`PROPOSAL_PART_DOMAIN`] and [`VOTE_EXTENSION_DOMAIN`]), which the sign bytes
+//! of votes and proposals may not start with, so that their signatures cannot be confused.
+//!
+//! This crate provides:
+//! - [`RemoteSignerClient`], the client used by the node to talk to the signer
+//! - [`SignerServer`], a signer which serves requests using a [`RawSigner`] and a [`SignStateFile`]
+//! - the `malachitebft-mock-signer` binary, a signer for Ed25519 keys meant for local testing
+
+mod address;
+mod client;
+mod domain;
+mod framing;
+mod server;
+mod state;
+
+pub mod proto {
+    include!(concat!(env!("OUT_DIR"), "/signer.rs"));
+}
+
+pub use address::SignerAddress;
+pub use client::{Error, RemoteSignerClient};
+pub use domain::{
+    domain_separated, IMPLICIT_PROPOSAL_DOMAIN, PROPOSAL_PART_DOMAIN, VOTE_EXTENSION_DOMAIN,
+};
+pub use framing::{read_message, write_message, MAX_MESSAGE_SIZE};
+pub use server::{RawSigner, SignerListener, SignerServer};
+pub use state::{SignState, SignStateFile, Signed, Step};
@@ -0,0 +1,286 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+
+use malachitebft_signing_ed25519::PrivateKey;
+use tracing::{debug, error, info, warn};
+
+use crate::domain::{domain_separated, is_reserved};
+use crate::framing::{read_message, write_message, Stream};
+use crate::proto::{self, signer_request, signer_response};
+use crate::{
+    SignState, SignStateFile, Signed, SignerAddress, Step, IMPLICIT_PROPOSAL_DOMAIN,
+    PROPOSAL_PART_DOMAIN, VOTE_EXTENSION_DOMAIN,
+};
+
+/// Signs arbitrary bytes with the private key held by the signer.
+pub trait RawSigner: Send + 'static {
+    /// The public key corresponding to the private key held by the signer, as bytes.
+    fn public_key(&self) -> Vec<u8>;
+
+    /// Sign the given bytes.
+    fn sign(&self, msg: &[u8]) -> Vec<u8>;
+}
+
+impl RawSigner for PrivateKey {
+    fn public_key(&self) -> Vec<u8> {
+        PrivateKey::public_key(self).as_bytes().to_vec()
+    }
+
+    fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        PrivateKey::sign(self, msg).to_bytes().to_vec()
+    }
+}
+
+/// A remote signer, which signs the messages requested by the node with a [`RawSigner`],
+/// while protecting it against double-signing by keeping track of the last messages it signed.
+pub struct SignerServer<S> {
+    signer: S,
+    state: SignStateFile,
+}
+
+impl<S> SignerServer<S>
+where
+    S: RawSigner,
+{
+    pub fn new(signer: S, state: SignStateFile) -> Self {
+        Self { signer, state }
+    }
+
+    /// The last proposal and votes signed.
+    pub fn state(&self) -> &SignState {
+        self.state.state()
+    }
+
+    /// Handle a single request from the node.
+    pub fn handle(&mut self, request: proto::SignerRequest) -> proto::SignerResponse {
+        let response = match request.request {
+            Some(signer_request::Request::PublicKey(_)) => Ok(
+                signer_response::Response::PublicKey(proto::PublicKeyResponse {
+                    public_key: self.signer.public_key(),
+                }),
+            ),
+
+            Some(signer_request::Request::SignVote(request)) => {
+                let step = match request.vote_type() {
+                    proto::VoteType::Prevote => Step::Prevote,
+                    proto::VoteType::Precommit => Step::Precommit,
+                };
+
+                self.sign_guarded(request.height, request.round, step, request.sign_bytes)
+            }
+
+            Some(signer_request::Request::SignProposal(request)) => self.sign_guarded(
+                request.height,
+                request.round,
+                Step::Propose,
+                request.sign_bytes,
+            ),
+
+            Some(signer_request::Request::SignImplicitProposal(request)) => {
+                let msg = domain_separated(IMPLICIT_PROPOSAL_DOMAIN, &request.sign_bytes);
+                Ok(signature_response(self.signer.sign(&msg)))
+            }
+
+            Some(signer_request::Request::SignProposalPart(request)) => {
+                let msg = domain_separated(PROPOSAL_PART_DOMAIN, &request.sign_bytes);
+                Ok(signature_response(self.signer.sign(&msg)))
+            }
+
+            Some(signer_request::Request::SignVoteExtension(request)) => {
+                let msg = domain_separated(VOTE_EXTENSION_DOMAIN, &request.extension);
+                Ok(signature_response(self.signer.sign(&msg)))
+            }
+
+            None => Err(signer_error(
+                proto::ErrorCode::InvalidRequest,
+                "empty request".to_string(),
+            )),
+        };
+
+        proto::SignerResponse {
+            response: Some(response.unwrap_or_else(signer_response::Response::Error)),
+        }
+    }
+
+    /// Sign a vote or proposal, unless it could lead to double-signing.
+    ///
+    /// The sign bytes are opaque, so the given height, round and step are trusted to match them.
+    fn sign_guarded(
+        &mut self,
+        height: u64,
+        round: u32,
+        step: Step,
+        sign_bytes: Vec<u8>,
+    ) -> Result<signer_response::Response, proto::SignerError> {
+        if is_reserved(&sign_bytes) {
+            warn!(
+                height,
+                round,
+                ?step,
+                "Refusing to sign: sign bytes start with a domain tag"
+            );
+
+            return Err(signer_error(
+                proto::ErrorCode::InvalidRequest,
+                "sign bytes of votes and proposals may not start with a zero byte".to_string(),
+            ));
+        }
+
+        match self.state.state().check(height, round, step, &sign_bytes) {
+            Ok(None) => (),
+            Ok(Some(signature)) => {
+                debug!(
+                    height,
+                    round,
+                    ?step,
+                    "Message already signed, returning same signature"
+                );
+                return Ok(signature_response(signature.to_vec()));
+            }
+            Err(description) => {
+                warn!(height, round, ?step, "Refusing to sign: {description}");
+                return Err(signer_error(proto::ErrorCode::DoubleSign, description));
+            }
+        }
+
+        let signature = self.signer.sign(&sign_bytes);
+
+        // Persist the new state before handing out the signature
+        let mut state = self.state.state().clone();
+        state.record(Signed {
+            height,
+            round,
+            step,
+            sign_bytes,
+            signature: signature.clone(),
+        });
+
+        if let Err(e) = self.state.update(state) {
+            error!(height, round, ?step, "Failed to persist sign state: {e}");
+
+            return Err(signer_error(
+                proto::ErrorCode::SigningFailed,
+                format!("failed to persist sign state: {e}"),
+            ));
+        }
+
+        debug!(height, round, ?step, "Signed message");
+
+        Ok(signature_response(signature))
+    }
+}
+
+fn signature_response(signature: Vec<u8>) -> signer_response::Response {
+    signer_response::Response::Signature(proto::SignatureResponse { signature })
+}
+
+fn signer_error(code: proto::ErrorCode, description: String) -> proto::SignerError {
+    proto::SignerError {
+        code: code.into(),
+        description,
+    }
+}
+
+/// Listens for connections from nodes on a Unix socket.
+///
+/// Connections are not authenticated, any process which can connect to the socket
+/// can request signatures. The socket is therefore created accessible to its owner only.
+pub enum SignerListener {
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl SignerListener {
+    /// Listen on the given address.
+    ///
+    /// A stale socket file left over by a previous signer is removed first.
+    pub fn bind(address: &SignerAddress) -> io::Result<Self> {
+        match address {
+            #[cfg(unix)]
+            SignerAddress::Unix(path) => {
+                use std::fs::{self, Permissions};
+                use std::os::unix::fs::PermissionsExt;
+
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+
+                let listener = UnixListener::bind(path)?;
+                fs::set_permissions(path, Permissions::from_mode(0o600))?;
+
+                Ok(Self::Unix(listener))
+            }
+
+            #[cfg(not(unix))]
+            SignerAddress::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Unix sockets are not supported on this platform",
+            )),
+        }
+    }
+
+    /// The address this listener is bound to.
+    pub fn local_addr(&self) -> io::Result<SignerAddress> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                let addr = listener.local_addr()?;
+                let path = addr
+                    .as_pathname()
+                    .ok_or_else(|| io::Error::other("unnamed Unix socket"))?;
+
+                Ok(SignerAddress::Unix(path.to_path_buf()))
+            }
+        }
+    }
+
+    /// Accept connections and serve them with the given signer, each on its own thread.
+    ///
+    /// Requests from all connections are handled one at a time,
+    /// so that the double-sign protection holds across connections.
+    pub fn run<S: RawSigner>(self, server: SignerServer<S>) -> io::Result<()> {
+        let server = Arc::new(Mutex::new(server));
+
+        loop {
+            let mut stream = match &self {
+                #[cfg(unix)]
+                Self::Unix(listener) => {
+                    let (stream, _) = listener.accept()?;
+                    Stream::Unix(stream)
+                }
+            };
+
+            info!("Accepted connection");
+
+            let server = Arc::clone(&server);
+
+            thread::spawn(move || {
+                let result = loop {
+                    let request = match read_message::<proto::SignerRequest, _>(&mut stream) {
+                        Ok(request) => request,
+                        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break Ok(()),
+                        Err(e) => break Err(e),
+                    };
+
+                    let response = server
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .handle(request);
+
+                    if let Err(e) = write_message(&mut stream, &response) {
+                        break Err(e);
+                    }
+                };
+
+                match result {
+                    Ok(()) => info!("Connection closed"),
+                    Err(e) => warn!("Connection failed: {e}"),
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The step at which a message is signed, in the order in which they happen within a round.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Propose,
+    Prevote,
+    Precommit,
+}
+
+/// A vote or proposal signed by the signer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signed {
+    pub height: u64,
+    pub round: u32,
+    pub step: Step,
+    #[serde(with = "hex::serde")]
+    pub sign_bytes: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    pub signature: Vec<u8>,
+}
+
+impl Signed {
+    fn position(&self) -> (u64, u32, Step) {
+        (self.height, self.round, self.step)
+    }
+}
+
+impl fmt::Display for Signed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} at height {}, round {}",
+            self.step, self.height, self.round
+        )
+    }
+}
+
+/// The last proposal, prevote and precommit signed by the signer.
+///
+/// Signing the exact same message as the last one of its kind returns the same signature,
+/// as happens when the node replays its write-ahead log after a restart. This is why the last
+/// prevote is kept alongside the last precommit, as the node re-signs its prevote for a round
+/// after having already precommitted in it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignState {
+    pub last_proposal: Option<Signed>,
+    pub last_prevote: Option<Signed>,
+    pub last_precommit: Option<Signed>,
+}
+
+impl SignState {
+    /// The last message signed at the given step, if any.
+    pub fn last(&self, step: Step) -> Option<&Signed> {
+        match step {
+            Step::Propose => self.last_proposal.as_ref(),
+            Step::Prevote => self.last_prevote.as_ref(),
+            Step::Precommit => self.last_precommit.as_ref(),
+        }
+    }
+
+    /// The latest of the last signed prevote and precommit.
+    pub fn last_vote(&self) -> Option<&Signed> {
+        match (&self.last_prevote, &self.last_precommit) {
+            (Some(prevote), Some(precommit)) if prevote.position() > precommit.position() => {
+                Some(prevote)
+            }
+            (_, Some(precommit)) => Some(precommit),
+            (prevote, None) => prevote.as_ref(),
+        }
+    }
+
+    /// Check whether signing the given message at the given height, round and step
+    /// could lead to double-signing.
+    ///
+    /// Proposals are checked against the last signed proposal,
+    /// and votes against the latest of the last signed prevote and precommit.
+    ///
+    /// ## Return
+    /// - `Ok(None)` if the message is for a later height, round or step, and can be signed
+    /// - `Ok(Some(signature))` if the exact same message was the last one signed at its step,
+    ///   with its signature
+    /// - `Err(description)` if signing the message could lead to double-signing
+    pub fn check(
+        &self,
+        height: u64,
+        round: u32,
+        step: Step,
+        sign_bytes: &[u8],
+    ) -> Result<Option<&[u8]>, String> {
+        let next = (height, round, step);
+
+        if let Some(last) = self.last(step) {
+            if next == last.position() && sign_bytes == last.sign_bytes {
+                return Ok(Some(&last.signature));
+            }
+        }
+
+        let last = match step {
+            Step::Propose => self.last_proposal.as_ref(),
+            Step::Prevote | Step::Precommit => self.last_vote(),
+        };
+
+        match last {
+            Some(last) if next == last.position() => Err(format!(
+                "already signed a different message at height {height}, round {round}, step {step:?}"
+            )),
+            Some(last) if next < last.position() => Err(format!(
+                "height {height}, round {round}, step {step:?} is lower than last signed {last}"
+            )),
+            _ => Ok(None),
+        }
+    }
+
+    /// Record the given message as the last one signed at its step.
+    pub fn record(&mut self, signed: Signed) {
+        match signed.step {
+            Step::Propose => self.last_proposal = Some(signed),
+            Step::Prevote => self.last_prevote = Some(signed),
+            Step::Precommit => self.last_precommit = Some(signed),
+        }
+    }
+}
+
+/// The last proposal and votes signed by the signer, persisted to a JSON file.
+///
+/// The state is written to disk before the corresponding signature is returned to the node,
+/// so that the signer never forgets about a message it signed, even if it crashes.
+#[derive(Debug)]
+pub struct SignStateFile {
+    path: PathBuf,
+    state: SignState,
+}
+
+impl SignStateFile {
+    /// Load the state from the given file, or start from an empty state if the file does not exist.
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+
+        let state = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => SignState::default(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self { path, state })
+    }
+
+    /// The path of the file in which the state is persisted.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The last proposal and votes signed.
+    pub fn state(&self) -> &SignState {
+        &self.state
+    }
+
+    /// Persist the given state, atomically replacing the previous one.
+    pub fn update(&mut self, state: SignState) -> io::Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+
+        let bytes = serde_json::to_vec_pretty(&state)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+
+        fs::rename(&tmp_path, &self.path)?;
+
+        self.state = state;
+
+        Ok(())
+    }
+}
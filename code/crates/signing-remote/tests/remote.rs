@@ -0,0 +1,229 @@
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use informalsystems_malachitebft_signing_remote::proto::{ErrorCode, VoteType};
+use informalsystems_malachitebft_signing_remote::{
+    domain_separated, Error, RemoteSignerClient, SignStateFile, SignerAddress, SignerListener,
+    SignerServer, Step, IMPLICIT_PROPOSAL_DOMAIN, PROPOSAL_PART_DOMAIN, VOTE_EXTENSION_DOMAIN,
+};
+use malachitebft_signing_ed25519::{PrivateKey, Signature};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn private_key() -> PrivateKey {
+    PrivateKey::generate(StdRng::seed_from_u64(0x42))
+}
+
+fn spawn_signer(address: &SignerAddress, state_path: &Path) -> SignerAddress {
+    let state = SignStateFile::load(state_path).unwrap();
+    let server = SignerServer::new(private_key(), state);
+
+    let listener = SignerListener::bind(address).unwrap();
+    let address = listener.local_addr().unwrap();
+
+    thread::spawn(move || listener.run(server));
+
+    address
+}
+
+fn assert_valid(msg: &[u8], signature: &[u8]) {
+    let signature = Signature::try_from(signature).unwrap();
+    private_key().public_key().verify(msg, &signature).unwrap();
+}
+
+fn assert_double_sign(result: Result<Vec<u8>, Error>) {
+    match result {
+        Err(e) => assert!(e.is_double_sign(), "unexpected error: {e}"),
+        Ok(_) => panic!("expected double-sign error"),
+    }
+}
+
+#[test]
+fn sign_over_unix_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let address = SignerAddress::Unix(dir.path().join("signer.sock"));
+    let address = spawn_signer(&address, &dir.path().join("state.json"));
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+
+    let public_key = client.public_key().unwrap();
+    assert_eq!(public_key, private_key().public_key().as_bytes());
+
+    let signature = client.sign_proposal(1, 0, b"proposal".to_vec()).unwrap();
+    assert_valid(b"proposal", &signature);
+
+    let signature = client
+        .sign_vote(1, 0, VoteType::Prevote, b"prevote".to_vec())
+        .unwrap();
+    assert_valid(b"prevote", &signature);
+
+    let signature = client.sign_proposal_part(b"part".to_vec()).unwrap();
+    assert_valid(&domain_separated(PROPOSAL_PART_DOMAIN, b"part"), &signature);
+
+    let signature = client.sign_vote_extension(b"extension".to_vec()).unwrap();
+    assert_valid(
+        &domain_separated(VOTE_EXTENSION_DOMAIN, b"extension"),
+        &signature,
+    );
+}
+
+#[test]
+fn refuse_sign_bytes_with_domain_tag() {
+    let dir = tempfile::tempdir().unwrap();
+    let address = SignerAddress::Unix(dir.path().join("signer.sock"));
+    let address = spawn_signer(&address, &dir.path().join("state.json"));
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+
+    // A proposal part signature cannot be obtained for the sign bytes of a vote
+    let sign_bytes = domain_separated(PROPOSAL_PART_DOMAIN, b"part");
+
+    match client.sign_vote(1, 0, VoteType::Prevote, sign_bytes) {
+        Err(Error::Remote { code, .. }) => assert_eq!(code, ErrorCode::InvalidRequest),
+        other => panic!("expected invalid request error, got {other:?}"),
+    }
+}
+
+#[test]
+fn refuse_double_sign() {
+    let dir = tempfile::tempdir().unwrap();
+    let address = SignerAddress::Unix(dir.path().join("signer.sock"));
+    let address = spawn_signer(&address, &dir.path().join("state.json"));
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+
+    client.sign_proposal(2, 1, b"proposal".to_vec()).unwrap();
+
+    let signature = client
+        .sign_vote(2, 1, VoteType::Precommit, b"precommit".to_vec())
+        .unwrap();
+
+    // Signing the exact same vote again returns the same signature
+    let again = client
+        .sign_vote(2, 1, VoteType::Precommit, b"precommit".to_vec())
+        .unwrap();
+    assert_eq!(signature, again);
+
+    // Signing a different vote at the same height, round and step is refused
+    assert_double_sign(client.sign_vote(2, 1, VoteType::Precommit, b"other".to_vec()));
+
+    // Signing at an earlier step, round or height is refused
+    assert_double_sign(client.sign_vote(2, 1, VoteType::Prevote, b"prevote".to_vec()));
+    assert_double_sign(client.sign_vote(1, 5, VoteType::Precommit, b"precommit".to_vec()));
+    assert_double_sign(client.sign_proposal(2, 0, b"proposal".to_vec()));
+
+    // Signing a different proposal at the same height and round is refused
+    assert_double_sign(client.sign_proposal(2, 1, b"other".to_vec()));
+
+    // Signing in a later round is allowed
+    client.sign_proposal(2, 2, b"proposal".to_vec()).unwrap();
+
+    // Implicit proposals are not subject to double-sign protection
+    let signature = client.sign_implicit_proposal(b"implicit".to_vec()).unwrap();
+    assert_valid(
+        &domain_separated(IMPLICIT_PROPOSAL_DOMAIN, b"implicit"),
+        &signature,
+    );
+}
+
+#[test]
+fn sign_state_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("state.json");
+
+    let address = SignerAddress::Unix(dir.path().join("first.sock"));
+    let address = spawn_signer(&address, &state_path);
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+    client
+        .sign_vote(3, 0, VoteType::Prevote, b"prevote".to_vec())
+        .unwrap();
+
+    let state = SignStateFile::load(&state_path).unwrap();
+    let last = state.state().last_vote().unwrap();
+    assert_eq!((last.height, last.round, last.step), (3, 0, Step::Prevote));
+
+    // A new signer, loading the same state, keeps refusing to double-sign
+    let address = SignerAddress::Unix(dir.path().join("second.sock"));
+    let address = spawn_signer(&address, &state_path);
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+    assert_double_sign(client.sign_vote(3, 0, VoteType::Prevote, b"other".to_vec()));
+
+    client
+        .sign_vote(3, 0, VoteType::Precommit, b"precommit".to_vec())
+        .unwrap();
+}
+
+#[test]
+fn sign_again_when_replaying_wal() {
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("state.json");
+
+    let address = SignerAddress::Unix(dir.path().join("first.sock"));
+    let address = spawn_signer(&address, &state_path);
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+
+    let proposal = client.sign_proposal(4, 0, b"proposal".to_vec()).unwrap();
+    let prevote = client
+        .sign_vote(4, 0, VoteType::Prevote, b"prevote".to_vec())
+        .unwrap();
+    let precommit = client
+        .sign_vote(4, 0, VoteType::Precommit, b"precommit".to_vec())
+        .unwrap();
+
+    // After a restart, the node replays its WAL and signs the same messages again, in order
+    let address = SignerAddress::Unix(dir.path().join("second.sock"));
+    let address = spawn_signer(&address, &state_path);
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+
+    assert_eq!(
+        client.sign_proposal(4, 0, b"proposal".to_vec()).unwrap(),
+        proposal
+    );
+    assert_eq!(
+        client
+            .sign_vote(4, 0, VoteType::Prevote, b"prevote".to_vec())
+            .unwrap(),
+        prevote
+    );
+    assert_eq!(
+        client
+            .sign_vote(4, 0, VoteType::Precommit, b"precommit".to_vec())
+            .unwrap(),
+        precommit
+    );
+
+    // A different prevote is still refused
+    assert_double_sign(client.sign_vote(4, 0, VoteType::Prevote, b"other".to_vec()));
+}
+
+#[test]
+fn unreachable_signer() {
+    let dir = tempfile::tempdir().unwrap();
+    let address = SignerAddress::Unix(dir.path().join("missing.sock"));
+
+    let client = RemoteSignerClient::new(address, TIMEOUT);
+
+    match client.public_key() {
+        Err(Error::Io { .. }) => (),
+        other => panic!("expected I/O error, got {other:?}"),
+    }
+}
+
+#[test]
+fn parse_signer_address() {
+    assert_eq!(
+        "unix:///tmp/signer.sock".parse::<SignerAddress>(),
+        Ok(SignerAddress::Unix("/tmp/signer.sock".into()))
+    );
+    assert!("tcp://127.0.0.1:26659".parse::<SignerAddress>().is_err());
+    assert!("127.0.0.1:26659".parse::<SignerAddress>().is_err());
+    assert!("unix://".parse::<SignerAddress>().is_err());
+}
@@ -1,5 +1,7 @@
 use color_eyre::eyre::eyre;
+use malachitebft_starknet_host::codec::ProtobufCodec;
 use malachitebft_starknet_host::node::StarknetNode;
+use malachitebft_starknet_host::types::MockContext;
 use malachitebft_test_cli::args::{Args, Commands};
 use malachitebft_test_cli::{logging, runtime};
 use tracing::{error, info, trace};
@@ -88,6 +90,9 @@ pub fn main() -> color_eyre::Result<()> {
         Commands::DistributedTestnet(cmd) => cmd
             .run(node, &args.get_home_dir().unwrap(), logging)
             .map_err(|error| eyre!("Failed to run distributed testnet command {:?}", error)),
+        Commands::Wal(cmd) => cmd
+            .run::<MockContext, _>(&args.get_home_dir().unwrap(), &ProtobufCodec)
+            .map_err(|error| eyre!("Failed to run wal command {:?}", error)),
     }
 }
 
@@ -17,7 +17,7 @@ use malachitebft_engine::host::{LocallyProposedValue, ProposedValue};
 use malachitebft_engine::network::{NetworkMsg, NetworkRef};
 use malachitebft_engine::util::streaming::{StreamContent, StreamMessage};
 use malachitebft_metrics::Metrics;
-use malachitebft_sync::RawDecidedValue;
+use malachitebft_sync::{ApplySnapshotChunkResult, OfferSnapshotResult, RawDecidedValue};
 
 use crate::host::state::HostState;
 use crate::host::{Host as _, StarknetHost};
@@ -129,10 +129,13 @@ impl Host {
 
             HostMsg::GetHistoryMinHeight { reply_to } => on_get_history_min_height(state, reply_to),
 
+            // The consensus parameters never change, so the size of the blocks
+            // is already bounded by the one from the host parameters
             HostMsg::GetValue {
                 height,
                 round,
                 timeout,
+                max_block_size: _,
                 reply_to,
             } => on_get_value(state, &self.network, height, round, timeout, reply_to).await,
 
@@ -143,6 +146,10 @@ impl Host {
                 reply_to,
             } => on_extend_vote(state, height, round, value_id, reply_to).await,
 
+            HostMsg::ValidateProposal { proposal, reply_to } => {
+                on_validate_proposal(state, proposal, reply_to).await
+            }
+
             HostMsg::VerifyVoteExtension {
                 height,
                 round,
@@ -188,6 +195,16 @@ impl Host {
                 ..
             } => on_decided(state, &consensus, &self.mempool, certificate, &self.metrics).await,
 
+            HostMsg::MisbehaviorEvidence { evidence } => {
+                warn!(
+                    height = %evidence.height(),
+                    round = %evidence.round(),
+                    validator = %evidence.validator_address(),
+                    "Received evidence of misbehavior"
+                );
+                Ok(())
+            }
+
             HostMsg::GetDecidedValue { height, reply_to } => {
                 on_get_decided_block(height, state, reply_to).await
             }
@@ -200,6 +217,27 @@ impl Host {
                 reply_to,
             } => on_process_synced_value(value_bytes, height, round, validator_address, reply_to),
 
+            // This host does not take snapshots of its state, nor restore from them
+            HostMsg::ListSnapshots { reply_to } => {
+                reply_to.send(Vec::new())?;
+                Ok(())
+            }
+
+            HostMsg::LoadSnapshotChunk { reply_to, .. } => {
+                reply_to.send(None)?;
+                Ok(())
+            }
+
+            HostMsg::OfferSnapshot { reply_to, .. } => {
+                reply_to.send(OfferSnapshotResult::Reject)?;
+                Ok(())
+            }
+
+            HostMsg::ApplySnapshotChunk { reply_to, .. } => {
+                reply_to.send(ApplySnapshotChunkResult::RejectSnapshot)?;
+                Ok(())
+            }
+
             HostMsg::PeerJoined { peer_id } => {
                 debug!(%peer_id, "Peer joined the network");
                 Ok(())
@@ -209,6 +247,15 @@ impl Host {
                 debug!(%peer_id, "Peer left the network");
                 Ok(())
             }
+
+            HostMsg::SyncProgress { progress } => {
+                debug!(
+                    tip_height = %progress.tip_height,
+                    caught_up = %progress.caught_up,
+                    "Sync progress"
+                );
+                Ok(())
+            }
         }
     }
 }
@@ -385,6 +432,16 @@ async fn on_extend_vote(
     Ok(())
 }
 
+async fn on_validate_proposal(
+    _state: &mut HostState,
+    _proposal: Proposal,
+    reply_to: RpcReplyPort<Validity>,
+) -> Result<(), ActorProcessingErr> {
+    // TODO
+    reply_to.send(Validity::Valid)?;
+    Ok(())
+}
+
 async fn on_verify_vote_extension(
     _state: &mut HostState,
     _height: Height,
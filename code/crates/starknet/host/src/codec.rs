@@ -5,17 +5,22 @@ use prost::Message;
 
 use malachitebft_codec::Codec;
 use malachitebft_core_types::{
-    AggregatedSignature, CommitCertificate, CommitSignature, Round, SignedVote, Validity,
+    AggregatedSignature, CommitCertificate, CommitSignature, Evidence, Round, SignedProposal,
+    SignedVote, Validity,
 };
 use malachitebft_engine::util::streaming::{StreamContent, StreamMessage};
 use malachitebft_sync::{
-    self as sync, ValueRequest, ValueResponse, VoteSetRequest, VoteSetResponse,
+    self as sync, SnapshotChunkRequest, SnapshotChunkResponse, SnapshotListRequest,
+    SnapshotListResponse, ValueRangeRequest, ValueRangeResponse, ValueRequest, ValueResponse,
+    VoteSetRequest, VoteSetResponse,
 };
 
 use malachitebft_core_consensus::{PeerId, ProposedValue, SignedConsensusMsg};
 
 use crate::proto::{self as proto, Error as ProtoError, Protobuf};
-use crate::types::{self as p2p, Address, BlockHash, Height, MockContext, ProposalPart, Vote};
+use crate::types::{
+    self as p2p, Address, BlockHash, Hash, Height, MockContext, Proposal, ProposalPart, Vote,
+};
 
 trait MessageExt {
     fn encode_to_bytes(&self) -> Bytes;
@@ -216,12 +221,29 @@ pub fn decode_sync_request(
                 value_request.fork_id,
             )))
         }
+        proto::sync::sync_request::Messages::ValueRangeRequest(range_request) => {
+            sync::Request::ValueRangeRequest(ValueRangeRequest::new(
+                Height::new(range_request.start_block_number, range_request.fork_id),
+                Height::new(range_request.end_block_number, range_request.fork_id),
+                range_request.max_bytes,
+            ))
+        }
         proto::sync::sync_request::Messages::VoteSetRequest(vote_set_request) => {
             sync::Request::VoteSetRequest(VoteSetRequest::new(
                 Height::new(vote_set_request.block_number, vote_set_request.fork_id),
                 Round::new(vote_set_request.round),
             ))
         }
+        proto::sync::sync_request::Messages::SnapshotListRequest(_) => {
+            sync::Request::SnapshotListRequest(SnapshotListRequest)
+        }
+        proto::sync::sync_request::Messages::SnapshotChunkRequest(chunk_request) => {
+            sync::Request::SnapshotChunkRequest(SnapshotChunkRequest::new(
+                Height::new(chunk_request.block_number, chunk_request.fork_id),
+                chunk_request.format,
+                chunk_request.index,
+            ))
+        }
     };
 
     Ok(request)
@@ -239,6 +261,16 @@ pub fn encode_sync_request(
                 },
             )),
         },
+        sync::Request::ValueRangeRequest(range_request) => proto::sync::SyncRequest {
+            messages: Some(proto::sync::sync_request::Messages::ValueRangeRequest(
+                proto::sync::ValueRangeRequest {
+                    fork_id: range_request.start.fork_id,
+                    start_block_number: range_request.start.block_number,
+                    end_block_number: range_request.end.block_number,
+                    max_bytes: range_request.max_bytes,
+                },
+            )),
+        },
         sync::Request::VoteSetRequest(vote_set_request) => proto::sync::SyncRequest {
             messages: Some(proto::sync::sync_request::Messages::VoteSetRequest(
                 proto::sync::VoteSetRequest {
@@ -251,6 +283,21 @@ pub fn encode_sync_request(
                 },
             )),
         },
+        sync::Request::SnapshotListRequest(_) => proto::sync::SyncRequest {
+            messages: Some(proto::sync::sync_request::Messages::SnapshotListRequest(
+                proto::sync::SnapshotListRequest {},
+            )),
+        },
+        sync::Request::SnapshotChunkRequest(chunk_request) => proto::sync::SyncRequest {
+            messages: Some(proto::sync::sync_request::Messages::SnapshotChunkRequest(
+                proto::sync::SnapshotChunkRequest {
+                    fork_id: chunk_request.height.fork_id,
+                    block_number: chunk_request.height.block_number,
+                    format: chunk_request.format,
+                    index: chunk_request.index,
+                },
+            )),
+        },
     };
 
     Ok(proto)
@@ -282,6 +329,16 @@ pub fn decode_sync_response(
                 value_response.value.map(decode_synced_value).transpose()?,
             ))
         }
+        proto::sync::sync_response::Messages::ValueRangeResponse(range_response) => {
+            sync::Response::ValueRangeResponse(ValueRangeResponse::new(
+                Height::new(range_response.start_block_number, range_response.fork_id),
+                range_response
+                    .values
+                    .into_iter()
+                    .map(decode_synced_value)
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
         proto::sync::sync_response::Messages::VoteSetResponse(vote_set_response) => {
             let height = Height::new(vote_set_response.block_number, vote_set_response.fork_id);
             let round = Round::new(vote_set_response.round);
@@ -295,6 +352,23 @@ pub fn decode_sync_response(
                 decode_vote_set(vote_set)?,
             ))
         }
+        proto::sync::sync_response::Messages::SnapshotListResponse(list_response) => {
+            sync::Response::SnapshotListResponse(SnapshotListResponse::new(
+                list_response
+                    .snapshots
+                    .into_iter()
+                    .map(decode_snapshot)
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        proto::sync::sync_response::Messages::SnapshotChunkResponse(chunk_response) => {
+            sync::Response::SnapshotChunkResponse(SnapshotChunkResponse::new(
+                Height::new(chunk_response.block_number, chunk_response.fork_id),
+                chunk_response.format,
+                chunk_response.index,
+                chunk_response.chunk,
+            ))
+        }
     };
     Ok(response)
 }
@@ -316,6 +390,19 @@ pub fn encode_sync_response(
                 },
             )),
         },
+        sync::Response::ValueRangeResponse(range_response) => proto::sync::SyncResponse {
+            messages: Some(proto::sync::sync_response::Messages::ValueRangeResponse(
+                proto::sync::ValueRangeResponse {
+                    fork_id: range_response.start.fork_id,
+                    start_block_number: range_response.start.block_number,
+                    values: range_response
+                        .values
+                        .iter()
+                        .map(encode_synced_value)
+                        .collect::<Result<_, _>>()?,
+                },
+            )),
+        },
         sync::Response::VoteSetResponse(vote_set_response) => proto::sync::SyncResponse {
             messages: Some(proto::sync::sync_response::Messages::VoteSetResponse(
                 proto::sync::VoteSetResponse {
@@ -329,6 +416,28 @@ pub fn encode_sync_response(
                 },
             )),
         },
+        sync::Response::SnapshotListResponse(list_response) => proto::sync::SyncResponse {
+            messages: Some(proto::sync::sync_response::Messages::SnapshotListResponse(
+                proto::sync::SnapshotListResponse {
+                    snapshots: list_response
+                        .snapshots
+                        .iter()
+                        .map(encode_snapshot)
+                        .collect::<Result<_, _>>()?,
+                },
+            )),
+        },
+        sync::Response::SnapshotChunkResponse(chunk_response) => proto::sync::SyncResponse {
+            messages: Some(proto::sync::sync_response::Messages::SnapshotChunkResponse(
+                proto::sync::SnapshotChunkResponse {
+                    fork_id: chunk_response.height.fork_id,
+                    block_number: chunk_response.height.block_number,
+                    format: chunk_response.format,
+                    index: chunk_response.index,
+                    chunk: chunk_response.chunk.clone(),
+                },
+            )),
+        },
     };
 
     Ok(proto)
@@ -383,6 +492,70 @@ impl Codec<SignedConsensusMsg<MockContext>> for ProtobufCodec {
     }
 }
 
+impl Codec<Evidence<MockContext>> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<Evidence<MockContext>, Self::Error> {
+        let proto = proto::evidence::Evidence::decode(bytes)?;
+
+        let evidence = proto
+            .evidence
+            .ok_or_else(|| ProtoError::missing_field::<proto::evidence::Evidence>("evidence"))?;
+
+        match evidence {
+            proto::evidence::evidence::Evidence::DoubleVote(double_vote) => {
+                let decode = |vote: Option<proto::Vote>, field| {
+                    vote.and_then(decode_vote).ok_or_else(|| {
+                        ProtoError::missing_field::<proto::evidence::DoubleVote>(field)
+                    })
+                };
+
+                Ok(Evidence::DoubleVote {
+                    first: decode(double_vote.first, "first")?,
+                    second: decode(double_vote.second, "second")?,
+                })
+            }
+            proto::evidence::evidence::Evidence::DoubleProposal(double_proposal) => {
+                let decode = |proposal: Option<proto::evidence::Proposal>, field| {
+                    proposal.map(decode_proposal).unwrap_or_else(|| {
+                        Err(ProtoError::missing_field::<proto::evidence::DoubleProposal>(field))
+                    })
+                };
+
+                Ok(Evidence::DoubleProposal {
+                    first: decode(double_proposal.first, "first")?,
+                    second: decode(double_proposal.second, "second")?,
+                })
+            }
+        }
+    }
+
+    fn encode(&self, msg: &Evidence<MockContext>) -> Result<Bytes, Self::Error> {
+        let evidence = match msg {
+            Evidence::DoubleVote { first, second } => {
+                proto::evidence::evidence::Evidence::DoubleVote(proto::evidence::DoubleVote {
+                    first: Some(encode_vote(first)?),
+                    second: Some(encode_vote(second)?),
+                })
+            }
+            Evidence::DoubleProposal { first, second } => {
+                proto::evidence::evidence::Evidence::DoubleProposal(
+                    proto::evidence::DoubleProposal {
+                        first: Some(encode_proposal(first)?),
+                        second: Some(encode_proposal(second)?),
+                    },
+                )
+            }
+        };
+
+        let proto = proto::evidence::Evidence {
+            evidence: Some(evidence),
+        };
+
+        Ok(proto.encode_to_bytes())
+    }
+}
+
 impl<T> Codec<StreamMessage<T>> for ProtobufCodec
 where
     T: Protobuf,
@@ -535,6 +708,36 @@ impl Codec<CommitCertificate<MockContext>> for ProtobufCodec {
     }
 }
 
+pub fn encode_snapshot(
+    snapshot: &sync::Snapshot<MockContext>,
+) -> Result<proto::sync::Snapshot, ProtoError> {
+    Ok(proto::sync::Snapshot {
+        format: snapshot.format,
+        chunks: snapshot.chunks,
+        hash: snapshot.hash.clone(),
+        metadata: snapshot.metadata.clone(),
+        certificate: Some(encode_certificate(&snapshot.certificate)?),
+    })
+}
+
+pub fn decode_snapshot(
+    proto: proto::sync::Snapshot,
+) -> Result<sync::Snapshot<MockContext>, ProtoError> {
+    let Some(certificate) = proto.certificate else {
+        return Err(ProtoError::missing_field::<proto::sync::Snapshot>(
+            "certificate",
+        ));
+    };
+
+    Ok(sync::Snapshot::new(
+        proto.format,
+        proto.chunks,
+        proto.hash,
+        proto.metadata,
+        decode_certificate(certificate)?,
+    ))
+}
+
 pub fn encode_synced_value(
     synced_value: &sync::RawDecidedValue<MockContext>,
 ) -> Result<proto::sync::SyncedValue, ProtoError> {
@@ -605,3 +808,40 @@ pub(crate) fn decode_vote(msg: proto::Vote) -> Option<SignedVote<MockContext>> {
     let vote = Vote::from_proto(msg).ok()?;
     Some(SignedVote::new(vote, signature))
 }
+
+pub(crate) fn encode_proposal(
+    proposal: &SignedProposal<MockContext>,
+) -> Result<proto::evidence::Proposal, ProtoError> {
+    let proposal = &proposal.message;
+
+    Ok(proto::evidence::Proposal {
+        block_number: proposal.height.block_number,
+        fork_id: proposal.height.fork_id,
+        round: proposal.round.as_u32().expect("round should not be nil"),
+        pol_round: proposal.pol_round.as_u32(),
+        value_id: Some(proposal.value_id.to_proto()?),
+        proposer: Some(proposal.proposer.to_proto()?),
+    })
+}
+
+pub(crate) fn decode_proposal(
+    proto: proto::evidence::Proposal,
+) -> Result<SignedProposal<MockContext>, ProtoError> {
+    let value_id = proto
+        .value_id
+        .ok_or_else(|| ProtoError::missing_field::<proto::evidence::Proposal>("value_id"))?;
+
+    let proposer = proto
+        .proposer
+        .ok_or_else(|| ProtoError::missing_field::<proto::evidence::Proposal>("proposer"))?;
+
+    let proposal = Proposal::new(
+        Height::new(proto.block_number, proto.fork_id),
+        Round::new(proto.round),
+        Hash::from_proto(value_id)?,
+        Round::from(proto.pol_round),
+        Address::from_proto(proposer)?,
+    );
+
+    Ok(SignedProposal::new(proposal, Signature::dummy()))
+}
@@ -5,16 +5,20 @@ use libp2p_identity::ecdsa;
 use tokio::task::JoinHandle;
 use tracing::warn;
 
+use malachitebft_app::spawn::spawn_rpc_server;
 use malachitebft_config::{
     self as config, Config as NodeConfig, MempoolConfig, SyncConfig, TestConfig, TransportProtocol,
+    WalConfig,
 };
-use malachitebft_core_types::ValuePayload;
-use malachitebft_engine::consensus::{Consensus, ConsensusParams, ConsensusRef};
+use malachitebft_core_types::{SynchronyParams, ValuePayload};
+use malachitebft_engine::consensus::{Consensus, ConsensusParams, ConsensusRef, HeightParams};
 use malachitebft_engine::host::HostRef;
-use malachitebft_engine::network::{Network, NetworkRef};
+use malachitebft_engine::network::{AcceptAll, Network, NetworkRef};
 use malachitebft_engine::node::{Node, NodeRef};
 use malachitebft_engine::sync::{Params as SyncParams, Sync, SyncRef};
 use malachitebft_engine::util::events::TxEvent;
+use malachitebft_engine::util::sign_watermark::SignWatermark;
+use malachitebft_engine::util::timeouts::LinearTimeouts;
 use malachitebft_engine::wal::{Wal, WalRef};
 use malachitebft_metrics::{Metrics, SharedRegistry};
 use malachitebft_network::Keypair;
@@ -54,7 +58,7 @@ pub async fn spawn_node_actor(
         spawn_mempool_actor(mempool_network.clone(), &cfg.mempool, &cfg.test, &span).await;
 
     // Spawn consensus gossip
-    let network = spawn_network_actor(&cfg, &private_key, &registry, &span).await;
+    let network = spawn_network_actor(&cfg, &home_dir, &private_key, &registry, &span).await;
 
     // Spawn the host actor
     let host = spawn_host_actor(
@@ -76,11 +80,22 @@ pub async fn spawn_node_actor(
         host.clone(),
         &cfg.sync,
         &registry,
+        tx_event.clone(),
         &span,
     )
     .await;
 
-    let wal = spawn_wal_actor(&ctx, ProtobufCodec, &home_dir, &registry, &span).await;
+    let wal = spawn_wal_actor(
+        &ctx,
+        ProtobufCodec,
+        &home_dir,
+        &cfg.consensus.wal,
+        &registry,
+        &span,
+    )
+    .await;
+
+    let rpc_config = cfg.rpc.clone();
 
     // Spawn consensus
     let consensus = spawn_consensus_actor(
@@ -89,17 +104,20 @@ pub async fn spawn_node_actor(
         address,
         ctx,
         cfg,
+        &home_dir,
         signing_provider,
         network.clone(),
         host.clone(),
         wal.clone(),
         sync.clone(),
         metrics,
-        tx_event,
+        tx_event.clone(),
         &span,
     )
     .await;
 
+    spawn_rpc_server(&rpc_config, consensus.clone(), network.clone(), tx_event);
+
     // Spawn the node actor
     let node = Node::new(ctx, network, consensus, wal, sync, host, span);
 
@@ -112,6 +130,7 @@ async fn spawn_wal_actor(
     ctx: &MockContext,
     codec: ProtobufCodec,
     home_dir: &Path,
+    cfg: &WalConfig,
     registry: &SharedRegistry,
     span: &tracing::Span,
 ) -> WalRef<MockContext> {
@@ -119,7 +138,7 @@ async fn spawn_wal_actor(
     std::fs::create_dir_all(&wal_dir).unwrap();
     let wal_file = wal_dir.join("consensus.wal");
 
-    Wal::spawn(ctx, codec, wal_file, registry.clone(), span.clone())
+    Wal::spawn(ctx, codec, wal_file, *cfg, registry.clone(), span.clone())
         .await
         .unwrap()
 }
@@ -130,6 +149,7 @@ async fn spawn_sync_actor(
     host: HostRef<MockContext>,
     config: &SyncConfig,
     registry: &SharedRegistry,
+    tx_event: TxEvent<MockContext>,
     span: &tracing::Span,
 ) -> Option<SyncRef<MockContext>> {
     if !config.enabled {
@@ -139,10 +159,16 @@ async fn spawn_sync_actor(
     let params = SyncParams {
         status_update_interval: config.status_update_interval,
         request_timeout: config.request_timeout,
+        batch_size: config.batch_size,
+        parallel_requests: config.parallel_requests,
+        max_response_size: config.max_response_size.as_u64(),
+        peer_ban_duration: config.peer_ban_duration,
+        snapshot_sync: config.snapshot_sync,
+        snapshot_min_peers: config.snapshot_min_peers,
     };
 
     let metrics = sync::Metrics::register(registry);
-    let actor_ref = Sync::spawn(ctx, network, host, params, metrics, span.clone())
+    let actor_ref = Sync::spawn(ctx, network, host, params, metrics, tx_event, span.clone())
         .await
         .unwrap();
 
@@ -156,6 +182,7 @@ async fn spawn_consensus_actor(
     address: Address,
     ctx: MockContext,
     cfg: NodeConfig,
+    home_dir: &Path,
     signing_provider: EcdsaProvider,
     network: NetworkRef<MockContext>,
     host: HostRef<MockContext>,
@@ -165,19 +192,35 @@ async fn spawn_consensus_actor(
     tx_event: TxEvent<MockContext>,
     span: &tracing::Span,
 ) -> ConsensusRef<MockContext> {
+    // Like the value payload, the maximum block size and whether vote extensions are enabled
+    // are taken from the `test` section of the configuration
+    let height_params = HeightParams {
+        timeouts: cfg.consensus.timeouts,
+        max_block_size: cfg.test.max_block_size,
+        vote_extensions_enable_height: cfg.test.vote_extensions.enabled.then_some(initial_height),
+        threshold_params: Default::default(),
+    };
+
     let consensus_params = ConsensusParams {
         initial_height,
         initial_validator_set,
         address,
-        threshold_params: Default::default(),
+        threshold_params: height_params.threshold_params,
         value_payload: ValuePayload::PartsOnly,
+        synchrony: cfg.consensus.synchrony.map(|synchrony| SynchronyParams {
+            precision: synchrony.precision,
+            message_delay: synchrony.message_delay,
+        }),
+        vote_extensions_enable_height: height_params.vote_extensions_enable_height,
     };
 
     Consensus::spawn(
         ctx,
         consensus_params,
-        cfg.consensus.timeouts,
+        height_params,
+        Box::new(LinearTimeouts),
         Box::new(signing_provider),
+        SignWatermark::path_in(home_dir),
         network,
         host,
         wal,
@@ -192,6 +235,7 @@ async fn spawn_consensus_actor(
 
 async fn spawn_network_actor(
     cfg: &NodeConfig,
+    home_dir: &Path,
     private_key: &PrivateKey,
     registry: &SharedRegistry,
     span: &tracing::Span,
@@ -230,16 +274,33 @@ async fn spawn_network_actor(
             config::PubSubProtocol::Broadcast => gossip::PubSubProtocol::Broadcast,
         },
         gossipsub: match cfg.consensus.p2p.protocol {
-            config::PubSubProtocol::GossipSub(config) => gossip::GossipSubConfig {
-                mesh_n: config.mesh_n(),
-                mesh_n_high: config.mesh_n_high(),
-                mesh_n_low: config.mesh_n_low(),
-                mesh_outbound_min: config.mesh_outbound_min(),
-            },
+            config::PubSubProtocol::GossipSub(config) => {
+                let peer_scoring = config.peer_scoring();
+
+                gossip::GossipSubConfig {
+                    mesh_n: config.mesh_n(),
+                    mesh_n_high: config.mesh_n_high(),
+                    mesh_n_low: config.mesh_n_low(),
+                    mesh_outbound_min: config.mesh_outbound_min(),
+                    peer_scoring: peer_scoring.enabled.then_some(gossip::PeerScoringConfig {
+                        gossip_threshold: peer_scoring.gossip_threshold,
+                        publish_threshold: peer_scoring.publish_threshold,
+                        graylist_threshold: peer_scoring.graylist_threshold,
+                        invalid_message_weight: peer_scoring.invalid_message_weight,
+                    }),
+                }
+            }
             config::PubSubProtocol::Broadcast => gossip::GossipSubConfig::default(),
         },
         rpc_max_size: cfg.consensus.p2p.rpc_max_size.as_u64() as usize,
         pubsub_max_size: cfg.consensus.p2p.pubsub_max_size.as_u64() as usize,
+        address_book_path: Some(home_dir.join("data").join("address_book.json")),
+        allowed_peers: cfg
+            .consensus
+            .p2p
+            .allow_list
+            .enabled
+            .then(|| cfg.consensus.p2p.allow_list.peers.clone()),
     };
 
     let keypair = make_keypair(private_key);
@@ -250,6 +311,7 @@ async fn spawn_network_actor(
         config_gossip,
         registry.clone(),
         codec,
+        Box::new(AcceptAll),
         span.clone(),
     )
     .await
@@ -2,6 +2,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let protos = &[
         "./proto/sync.proto",
         "./proto/certificate.proto",
+        "./proto/evidence.proto",
         "./proto/p2p/proto/common.proto",
         "./proto/p2p/proto/transaction.proto",
         "./proto/p2p/proto/consensus/consensus.proto",
@@ -10,6 +10,11 @@ pub mod certificate {
     include!(concat!(env!("OUT_DIR"), "/certificate.rs"));
 }
 
+#[allow(clippy::module_inception)]
+pub mod evidence {
+    include!(concat!(env!("OUT_DIR"), "/evidence.rs"));
+}
+
 impl From<Uint128> for u128 {
     fn from(value: Uint128) -> Self {
         value.low as u128 | (value.high as u128) << 64
@@ -1,6 +1,6 @@
 use bytes::Bytes;
 
-use malachitebft_core_types::{Context, NilOrVal, Round, ValidatorSet as _};
+use malachitebft_core_types::{AggregatedSignature, Context, NilOrVal, Round, ValidatorSet as _};
 
 use crate::{Address, Ecdsa, Hash, Height, Proposal, ProposalPart, Validator, ValidatorSet, Vote};
 
@@ -26,6 +26,7 @@ impl Context for MockContext {
     type Vote = Vote;
     type Extension = Bytes;
     type SigningScheme = Ecdsa;
+    type CertificateSignature = AggregatedSignature<Self>;
 
     fn select_proposer<'a>(
         &self,
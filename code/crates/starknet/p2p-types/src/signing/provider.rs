@@ -2,12 +2,12 @@ use bytes::Bytes;
 use starknet_core::utils::starknet_keccak;
 
 use malachitebft_core_types::{
-    CertificateError, CommitCertificate, CommitSignature, NilOrVal, SignedExtension,
-    SignedProposal, SignedProposalPart, SignedVote, SigningProvider, VotingPower,
+    CertificateError, CommitCertificate, SignedExtension, SignedProposal, SignedProposalPart,
+    SignedVote, SigningError, SigningProvider, VotingPower,
 };
 
 use crate::{
-    MockContext, PrivateKey, Proposal, ProposalPart, PublicKey, Signature, Validator, Vote,
+    MockContext, PrivateKey, Proposal, ProposalPart, PublicKey, Signature, ValidatorSet, Vote,
 };
 
 #[derive(Debug)]
@@ -22,11 +22,11 @@ impl EcdsaProvider {
 }
 
 impl SigningProvider<MockContext> for EcdsaProvider {
-    fn sign_vote(&self, vote: Vote) -> SignedVote<MockContext> {
+    fn sign_vote(&self, vote: Vote) -> Result<SignedVote<MockContext>, SigningError> {
         // Votes are not signed for now
         // let hash = starknet_keccak(&vote.to_sign_bytes());
         // let signature = self.private_key.sign(&hash);
-        SignedVote::new(vote, Signature::dummy())
+        Ok(SignedVote::new(vote, Signature::dummy()))
     }
 
     fn verify_signed_vote(
@@ -41,9 +41,12 @@ impl SigningProvider<MockContext> for EcdsaProvider {
         // public_key.verify(&hash, signature)
     }
 
-    fn sign_proposal(&self, proposal: Proposal) -> SignedProposal<MockContext> {
+    fn sign_proposal(
+        &self,
+        proposal: Proposal,
+    ) -> Result<SignedProposal<MockContext>, SigningError> {
         // Proposals are never sent over the network
-        SignedProposal::new(proposal, Signature::dummy())
+        Ok(SignedProposal::new(proposal, Signature::dummy()))
     }
 
     fn verify_signed_proposal(
@@ -56,10 +59,13 @@ impl SigningProvider<MockContext> for EcdsaProvider {
         true
     }
 
-    fn sign_proposal_part(&self, proposal_part: ProposalPart) -> SignedProposalPart<MockContext> {
+    fn sign_proposal_part(
+        &self,
+        proposal_part: ProposalPart,
+    ) -> Result<SignedProposalPart<MockContext>, SigningError> {
         let hash = starknet_keccak(&proposal_part.to_sign_bytes());
         let signature = self.private_key.sign(&hash);
-        SignedProposalPart::new(proposal_part, signature)
+        Ok(SignedProposalPart::new(proposal_part, signature))
     }
 
     fn verify_signed_proposal_part(
@@ -72,10 +78,13 @@ impl SigningProvider<MockContext> for EcdsaProvider {
         public_key.verify(&hash, signature)
     }
 
-    fn sign_vote_extension(&self, extension: Bytes) -> SignedExtension<MockContext> {
+    fn sign_vote_extension(
+        &self,
+        extension: Bytes,
+    ) -> Result<SignedExtension<MockContext>, SigningError> {
         let hash = starknet_keccak(extension.as_ref());
         let signature = self.private_key.sign(&hash);
-        SignedExtension::new(extension, signature)
+        Ok(SignedExtension::new(extension, signature))
     }
 
     fn verify_signed_vote_extension(
@@ -88,27 +97,13 @@ impl SigningProvider<MockContext> for EcdsaProvider {
         public_key.verify(&hash, signature)
     }
 
-    fn verify_commit_signature(
+    fn verify_certificate_signature(
         &self,
         certificate: &CommitCertificate<MockContext>,
-        commit_sig: &CommitSignature<MockContext>,
-        validator: &Validator,
+        validator_set: &ValidatorSet,
     ) -> Result<VotingPower, CertificateError<MockContext>> {
-        use malachitebft_core_types::Validator;
-
-        // Reconstruct the vote that was signed
-        let vote = Vote::new_precommit(
-            certificate.height,
-            certificate.round,
-            NilOrVal::Val(certificate.value_id),
-            *validator.address(),
-        );
-
-        // Verify signature
-        if !self.verify_signed_vote(&vote, &commit_sig.signature, validator.public_key()) {
-            return Err(CertificateError::InvalidSignature(commit_sig.clone()));
-        }
-
-        Ok(validator.voting_power())
+        certificate
+            .aggregated_signature
+            .verify(certificate, validator_set, self)
     }
 }
@@ -153,6 +153,8 @@ impl TestRunner {
                         .collect(),
                     ..Default::default()
                 },
+                wal: WalConfig::default(),
+                synchrony: None,
             },
             mempool: MempoolConfig {
                 p2p: P2pConfig {
@@ -172,6 +174,7 @@ impl TestRunner {
                 enabled: true,
                 status_update_interval: Duration::from_secs(2),
                 request_timeout: Duration::from_secs(5),
+                ..Default::default()
             },
             metrics: MetricsConfig {
                 enabled: false,
@@ -179,6 +182,7 @@ impl TestRunner {
                     .parse()
                     .unwrap(),
             },
+            rpc: RpcConfig::default(),
             runtime: RuntimeConfig::single_threaded(),
             test: TestConfig {
                 value_payload: ValuePayload::PartsOnly,
@@ -1,5 +1,7 @@
 use core::marker::PhantomData;
+use std::time::Instant;
 
+use bytes::Bytes;
 use derive_where::derive_where;
 use thiserror::Error;
 use tracing::{debug, error, info, trace, warn};
@@ -8,8 +10,11 @@ use malachitebft_core_types::{CertificateError, CommitCertificate, Context, Heig
 
 use crate::co::Co;
 use crate::{
-    perform, InboundRequestId, Metrics, OutboundRequestId, PeerId, RawDecidedValue, Request, State,
-    Status, ValueRequest, ValueResponse, VoteSetRequest, VoteSetResponse,
+    perform, ApplySnapshotChunkResult, InboundRequestId, Metrics, OfferSnapshotResult,
+    OutboundRequestId, PeerId, RawDecidedValue, Request, Snapshot, SnapshotChunkRequest,
+    SnapshotChunkResponse, SnapshotDownload, SnapshotListRequest, SnapshotListResponse,
+    SnapshotPhase, State, Status, SyncProgress, ValueRangeRequest, ValueRangeResponse,
+    ValueRequest, ValueResponse, VoteSetRequest, VoteSetResponse,
 };
 
 #[derive_where(Debug)]
@@ -24,6 +29,9 @@ pub enum Error<Ctx: Context> {
 #[derive_where(Debug)]
 pub enum Resume<Ctx: Context> {
     Continue(PhantomData<Ctx>),
+
+    /// The request was sent out with the given id, or could not be sent
+    RequestSent(Option<OutboundRequestId>),
 }
 
 impl<Ctx: Context> Default for Resume<Ctx> {
@@ -37,17 +45,63 @@ pub enum Effect<Ctx: Context> {
     /// Broadcast our status to our direct peers
     BroadcastStatus(Ctx::Height),
 
-    /// Send a ValueSync request to a peer
-    SendValueRequest(PeerId, ValueRequest<Ctx>),
+    /// Send a request for a range of values to a peer
+    ///
+    /// Resumes with: [`Resume::RequestSent`]
+    SendValueRangeRequest(PeerId, ValueRangeRequest<Ctx>),
 
     /// Send a response to a ValueSync request
     SendValueResponse(InboundRequestId, ValueResponse<Ctx>),
 
+    /// Send a response to a request for a range of values
+    SendValueRangeResponse(InboundRequestId, ValueRangeResponse<Ctx>),
+
     /// Retrieve a value from the application
     GetDecidedValue(InboundRequestId, Ctx::Height),
 
+    /// Retrieve the values for a range of heights from the application,
+    /// stopping at the first missing one or once their total size exceeds `max_bytes`
+    GetDecidedValues(InboundRequestId, ValueRangeRequest<Ctx>),
+
     /// Send a VoteSet request to a peer
     SendVoteSetRequest(PeerId, VoteSetRequest<Ctx>),
+
+    /// Ask a peer for the snapshots it has
+    SendSnapshotListRequest(PeerId),
+
+    /// Send the snapshots we have in response to a peer's request
+    SendSnapshotListResponse(InboundRequestId, SnapshotListResponse<Ctx>),
+
+    /// Retrieve the snapshots available from the application
+    GetSnapshots(InboundRequestId),
+
+    /// Send a request for a snapshot chunk to a peer
+    SendSnapshotChunkRequest(PeerId, SnapshotChunkRequest<Ctx>),
+
+    /// Send a response to a request for a snapshot chunk
+    SendSnapshotChunkResponse(InboundRequestId, SnapshotChunkResponse<Ctx>),
+
+    /// Retrieve a snapshot chunk from the application
+    GetSnapshotChunk(InboundRequestId, SnapshotChunkRequest<Ctx>),
+
+    /// Offer a snapshot to the application, to restore its state from
+    OfferSnapshot(Snapshot<Ctx>),
+
+    /// Hand a chunk of the accepted snapshot, received from the given peer, to the application
+    ApplySnapshotChunk(PeerId, u32, Bytes),
+
+    /// The application state has been restored from the given snapshot,
+    /// consensus can start at the height following the one of the snapshot
+    SnapshotRestored(Snapshot<Ctx>),
+
+    /// Report our progress in catching up with our peers
+    ReportProgress(SyncProgress<Ctx>),
+
+    /// We just caught up with our peers
+    CaughtUp(SyncProgress<Ctx>),
+
+    /// We just fell behind our peers
+    FellBehind(SyncProgress<Ctx>),
 }
 
 #[derive_where(Debug)]
@@ -73,8 +127,18 @@ pub enum Input<Ctx: Context> {
     /// Got a response from the application to our `GetValue` request
     GotDecidedValue(InboundRequestId, Ctx::Height, Option<RawDecidedValue<Ctx>>),
 
+    /// A request for a range of values has been received from a peer
+    ValueRangeRequest(InboundRequestId, PeerId, ValueRangeRequest<Ctx>),
+
+    /// A response to our request for a range of values has been received
+    ValueRangeResponse(OutboundRequestId, PeerId, ValueRangeResponse<Ctx>),
+
+    /// Got a response from the application to our `GetDecidedValues` request,
+    /// with the values for consecutive heights starting at the given height
+    GotDecidedValues(InboundRequestId, Ctx::Height, Vec<RawDecidedValue<Ctx>>),
+
     /// A request for a value or vote set timed out
-    SyncRequestTimedOut(PeerId, Request<Ctx>),
+    SyncRequestTimedOut(OutboundRequestId, PeerId, Request<Ctx>),
 
     /// We received an invalid [`CommitCertificate`]
     InvalidCertificate(PeerId, CommitCertificate<Ctx>, CertificateError<Ctx>),
@@ -90,6 +154,30 @@ pub enum Input<Ctx: Context> {
 
     /// A VoteSet response has been received
     VoteSetResponse(OutboundRequestId, PeerId, VoteSetResponse<Ctx>),
+
+    /// A request for the snapshots we have has been received from a peer
+    SnapshotListRequest(InboundRequestId, PeerId, SnapshotListRequest),
+
+    /// A peer responded with the snapshots it has
+    SnapshotListResponse(OutboundRequestId, PeerId, SnapshotListResponse<Ctx>),
+
+    /// Got a response from the application to our `GetSnapshots` request
+    GotSnapshots(InboundRequestId, Vec<Snapshot<Ctx>>),
+
+    /// A request for a snapshot chunk has been received from a peer
+    SnapshotChunkRequest(InboundRequestId, PeerId, SnapshotChunkRequest<Ctx>),
+
+    /// A response to our request for a snapshot chunk has been received
+    SnapshotChunkResponse(OutboundRequestId, PeerId, SnapshotChunkResponse<Ctx>),
+
+    /// Got a response from the application to our `GetSnapshotChunk` request
+    GotSnapshotChunk(InboundRequestId, SnapshotChunkResponse<Ctx>),
+
+    /// The application accepted or rejected the snapshot offered to it
+    SnapshotOffered(Snapshot<Ctx>, OfferSnapshotResult),
+
+    /// The application applied the chunk at the given index, received from the given peer
+    SnapshotChunkApplied(PeerId, u32, ApplySnapshotChunkResult),
 }
 
 pub async fn handle<Ctx>(
@@ -119,8 +207,17 @@ where
         Input::GotDecidedValue(request_id, height, value) => {
             on_value(co, state, metrics, request_id, height, value).await
         }
-        Input::SyncRequestTimedOut(peer_id, request) => {
-            on_sync_request_timed_out(co, state, metrics, peer_id, request).await
+        Input::ValueRangeRequest(request_id, peer_id, request) => {
+            on_value_range_request(co, state, metrics, request_id, peer_id, request).await
+        }
+        Input::ValueRangeResponse(request_id, peer_id, response) => {
+            on_value_range_response(co, state, metrics, request_id, peer_id, response).await
+        }
+        Input::GotDecidedValues(request_id, start, values) => {
+            on_values(co, state, metrics, request_id, start, values).await
+        }
+        Input::SyncRequestTimedOut(request_id, peer_id, request) => {
+            on_sync_request_timed_out(co, state, metrics, request_id, peer_id, request).await
         }
         Input::InvalidCertificate(peer, certificate, error) => {
             on_invalid_certificate(co, state, metrics, peer, certificate, error).await
@@ -139,6 +236,38 @@ where
         Input::GotVoteSet(request_id, height, round) => {
             on_vote_set_response_sent(co, state, metrics, request_id, height, round).await
         }
+
+        Input::SnapshotListRequest(request_id, peer_id, _request) => {
+            on_snapshot_list_request(co, state, metrics, request_id, peer_id).await
+        }
+
+        Input::SnapshotListResponse(request_id, peer_id, response) => {
+            on_snapshot_list_response(co, state, metrics, request_id, peer_id, response).await
+        }
+
+        Input::GotSnapshots(request_id, snapshots) => {
+            on_snapshots(co, state, metrics, request_id, snapshots).await
+        }
+
+        Input::SnapshotChunkRequest(request_id, peer_id, request) => {
+            on_snapshot_chunk_request(co, state, metrics, request_id, peer_id, request).await
+        }
+
+        Input::SnapshotChunkResponse(request_id, peer_id, response) => {
+            on_snapshot_chunk_response(co, state, metrics, request_id, peer_id, response).await
+        }
+
+        Input::GotSnapshotChunk(request_id, response) => {
+            on_snapshot_chunk(co, state, metrics, request_id, response).await
+        }
+
+        Input::SnapshotOffered(snapshot, result) => {
+            on_snapshot_offered(co, state, metrics, snapshot, result).await
+        }
+
+        Input::SnapshotChunkApplied(peer_id, index, result) => {
+            on_snapshot_chunk_applied(co, state, metrics, peer_id, index, result).await
+        }
     }
 }
 
@@ -146,15 +275,35 @@ where
 pub async fn on_tick<Ctx>(
     co: Co<Ctx>,
     state: &mut State<Ctx>,
-    _metrics: &Metrics,
+    metrics: &Metrics,
 ) -> Result<(), Error<Ctx>>
 where
     Ctx: Context,
 {
     debug!(height = %state.tip_height, "Broadcasting status");
 
+    // If the value for the sync height was received but consensus has not moved on
+    // since the last tick, it most likely could not process it, so request it again.
+    if state.sync_height == state.last_tick_sync_height {
+        state.remove_received_value_request_containing(state.sync_height);
+    }
+
+    state.last_tick_sync_height = state.sync_height;
+
     perform!(co, Effect::BroadcastStatus(state.tip_height));
 
+    state
+        .sync_rate
+        .record(Instant::now(), state.tip_height.as_u64());
+
+    update_caught_up(&co, state).await?;
+    perform!(co, Effect::ReportProgress(state.progress()));
+
+    // Give peers until the next tick to tell us about their snapshots before picking one
+    if state.snapshot.phase == SnapshotPhase::Discovering {
+        offer_best_snapshot(co, state, metrics).await?;
+    }
+
     Ok(())
 }
 
@@ -176,9 +325,15 @@ where
 {
     debug!(%status.peer_id, %status.height, "Received peer status");
 
+    let peer_id = status.peer_id;
     let peer_height = status.height;
 
     state.update_status(status);
+    update_caught_up(&co, state).await?;
+
+    if state.snapshot.phase == SnapshotPhase::Discovering {
+        request_snapshots(&co, state, peer_id).await?;
+    }
 
     if peer_height > state.tip_height {
         info!(
@@ -190,7 +345,7 @@ where
 
         // We are lagging behind one of our peer at least,
         // request sync from any peer already at or above that peer's height.
-        request_value(co, state, metrics).await?;
+        request_values(co, state, metrics, None).await?;
     }
 
     Ok(())
@@ -236,6 +391,106 @@ where
     Ok(())
 }
 
+#[tracing::instrument(skip_all)]
+pub async fn on_value_range_request<Ctx>(
+    co: Co<Ctx>,
+    state: &mut State<Ctx>,
+    metrics: &Metrics,
+    request_id: InboundRequestId,
+    peer: PeerId,
+    request: ValueRangeRequest<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    debug!(start = %request.start, end = %request.end, %peer, "Received request for value range");
+
+    metrics.decided_value_request_received(request.start.as_u64());
+
+    if request.start > request.end {
+        warn!(start = %request.start, end = %request.end, %peer, "Invalid value range request");
+
+        perform!(
+            co,
+            Effect::SendValueRangeResponse(
+                request_id,
+                ValueRangeResponse::new(request.start, Vec::new())
+            )
+        );
+
+        return Ok(());
+    }
+
+    // Do not serve more values than we would request ourselves in a single request
+    let max_end = request
+        .start
+        .increment_by(state.params.batch_size.saturating_sub(1));
+
+    let request = ValueRangeRequest::new(
+        request.start,
+        request.end.min(max_end),
+        request.max_bytes.min(state.params.max_response_size),
+    );
+
+    perform!(co, Effect::GetDecidedValues(request_id, request));
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn on_value_range_response<Ctx>(
+    co: Co<Ctx>,
+    state: &mut State<Ctx>,
+    metrics: &Metrics,
+    request_id: OutboundRequestId,
+    peer: PeerId,
+    response: ValueRangeResponse<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let count = response.consecutive_values().len();
+
+    debug!(start = %response.start, %count, %request_id, %peer, "Received value range response");
+
+    if count < response.values.len() {
+        warn!(
+            start = %response.start, %peer,
+            "Received values for non-consecutive heights, ignoring the ones past the first gap"
+        );
+    }
+
+    metrics.decided_value_response_received(response.start.as_u64());
+    let received = state.value_range_received(&request_id, peer, response.start, count as u64);
+
+    // Keep the pipeline full, avoiding the peer if it did not send any of the requested values
+    let except = (!received).then_some(peer);
+    request_values(co, state, metrics, except).await
+}
+
+pub async fn on_values<Ctx>(
+    co: Co<Ctx>,
+    _state: &mut State<Ctx>,
+    metrics: &Metrics,
+    request_id: InboundRequestId,
+    start: Ctx::Height,
+    values: Vec<RawDecidedValue<Ctx>>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    debug!(%start, count = values.len(), "Received decided values");
+
+    perform!(
+        co,
+        Effect::SendValueRangeResponse(request_id, ValueRangeResponse::new(start, values))
+    );
+
+    metrics.decided_value_response_sent(start.as_u64());
+
+    Ok(())
+}
+
 pub async fn on_start_height<Ctx>(
     co: Co<Ctx>,
     state: &mut State<Ctx>,
@@ -248,16 +503,21 @@ where
     debug!(%height, "Starting new height");
 
     state.sync_height = height;
+    state.prune_pending_value_requests();
+
+    if state.snapshot.phase == SnapshotPhase::Waiting {
+        start_snapshot_sync(&co, state).await?;
+    }
 
     // Check if there is any peer already at or above the height we just started,
     // and request sync from that peer in order to catch up.
-    request_value(co, state, metrics).await?;
+    request_values(co, state, metrics, None).await?;
 
     Ok(())
 }
 
 pub async fn on_update_height<Ctx>(
-    _co: Co<Ctx>,
+    co: Co<Ctx>,
     state: &mut State<Ctx>,
     _metrics: &Metrics,
     height: Ctx::Height,
@@ -269,7 +529,7 @@ where
         debug!(%height, "Update height");
 
         state.tip_height = height;
-        state.remove_pending_decided_value_request(height);
+        update_caught_up(&co, state).await?;
     }
 
     Ok(())
@@ -315,22 +575,34 @@ where
 }
 
 pub async fn on_sync_request_timed_out<Ctx>(
-    _co: Co<Ctx>,
+    co: Co<Ctx>,
     state: &mut State<Ctx>,
     metrics: &Metrics,
+    request_id: OutboundRequestId,
     peer_id: PeerId,
     request: Request<Ctx>,
 ) -> Result<(), Error<Ctx>>
 where
     Ctx: Context,
 {
+    if state.record_failure(peer_id) {
+        warn!(%peer_id, "Banning peer after too many failed requests");
+    }
+
     match request {
         Request::ValueRequest(value_request) => {
             let height = value_request.height;
             warn!(%peer_id, %height, "Value request timed out");
-            state.remove_pending_decided_value_request(height);
             metrics.decided_value_request_timed_out(height.as_u64());
         }
+        Request::ValueRangeRequest(range_request) => {
+            let (start, end) = (range_request.start, range_request.end);
+            warn!(%peer_id, %start, %end, "Value range request timed out");
+            state.remove_pending_value_request(&request_id);
+            metrics.decided_value_request_timed_out(start.as_u64());
+
+            request_values(co, state, metrics, Some(peer_id)).await?;
+        }
         Request::VoteSetRequest(vote_set_request) => {
             let height = vote_set_request.height;
             let round = vote_set_request.round;
@@ -338,55 +610,83 @@ where
             state.remove_pending_vote_set_request(height, round);
             metrics.vote_set_request_timed_out(height.as_u64(), round.as_i64());
         }
+        Request::SnapshotListRequest(_) => {
+            warn!(%peer_id, "Snapshot list request timed out");
+            state.snapshot.pending.remove(&peer_id);
+        }
+        Request::SnapshotChunkRequest(chunk_request) => {
+            let index = chunk_request.index;
+            warn!(%peer_id, height = %chunk_request.height, %index, "Snapshot chunk request timed out");
+
+            let pending = state.snapshot.download.as_mut().filter(|download| {
+                download.next_chunk == index && download.pending == Some(peer_id)
+            });
+
+            if let Some(download) = pending {
+                download.pending = None;
+                request_snapshot_chunk(&co, state, Some(peer_id)).await?;
+            }
+        }
     };
 
     Ok(())
 }
 
-/// If there are no pending requests for the sync height,
-/// and there is peer at a higher height than our sync height,
-/// then sync from that peer.
-async fn request_value<Ctx>(
+/// Request the values for the heights at and above the sync height which have not been
+/// requested yet from peers which have them, keeping up to `parallel_requests` requests
+/// in flight at the same time, optionally avoiding the given peer.
+async fn request_values<Ctx>(
     co: Co<Ctx>,
     state: &mut State<Ctx>,
     metrics: &Metrics,
+    except: Option<PeerId>,
 ) -> Result<(), Error<Ctx>>
 where
     Ctx: Context,
 {
-    let sync_height = state.sync_height;
-
-    if state.has_pending_decided_value_request(&sync_height) {
-        debug!(sync.height = %sync_height, "Already have a pending value request for this height");
+    if state.snapshot.phase.is_active() {
+        debug!("Restoring a snapshot, not requesting values yet");
         return Ok(());
     }
 
-    if let Some(peer) = state.random_peer_with_value(sync_height) {
-        request_value_from_peer(co, state, metrics, sync_height, peer).await?;
-    }
-
-    Ok(())
-}
-
-async fn request_value_from_peer<Ctx>(
-    co: Co<Ctx>,
-    state: &mut State<Ctx>,
-    metrics: &Metrics,
-    height: Ctx::Height,
-    peer: PeerId,
-) -> Result<(), Error<Ctx>>
-where
-    Ctx: Context,
-{
-    info!(sync.height = %height, %peer, "Requesting sync value from peer");
+    while state.inflight_value_requests() < state.params.parallel_requests {
+        let (start, end) = state.next_range();
+
+        let peer = match except {
+            Some(except) => state.random_peer_with_value_except(start, except),
+            None => state.random_peer_with_value(start),
+        };
+
+        let Some(peer) = peer else {
+            debug!(%start, "No peer to request value from");
+            break;
+        };
+
+        // Do not request values past the height of the peer
+        let end = state
+            .peers
+            .get(&peer)
+            .map_or(end, |status| end.min(status.height));
+
+        info!(%start, %end, %peer, "Requesting sync values from peer");
+
+        let request_id = perform!(
+            co,
+            Effect::SendValueRangeRequest(
+                peer,
+                ValueRangeRequest::new(start, end, state.params.max_response_size)
+            ),
+            Resume::RequestSent(request_id) => request_id
+        );
 
-    perform!(
-        co,
-        Effect::SendValueRequest(peer, ValueRequest::new(height))
-    );
+        let Some(request_id) = request_id else {
+            warn!(%start, %end, %peer, "Failed to send value range request");
+            break;
+        };
 
-    metrics.decided_value_request_sent(height.as_u64());
-    state.store_pending_decided_value_request(height, peer);
+        metrics.decided_value_request_sent(start.as_u64());
+        state.store_pending_value_request(request_id, start, end, peer);
+    }
 
     Ok(())
 }
@@ -405,15 +705,14 @@ where
     error!(%error, %certificate.height, %certificate.round, "Received invalid certificate");
     trace!("Certificate: {certificate:#?}");
 
-    info!("Requesting sync from another peer");
-    state.remove_pending_decided_value_request(certificate.height);
+    if state.record_invalid_certificate(from) {
+        warn!(peer = %from, "Banning peer after receiving invalid certificates");
+    }
 
-    let Some(peer) = state.random_peer_with_value_except(certificate.height, from) else {
-        error!("No other peer to request sync from");
-        return Ok(());
-    };
+    info!("Requesting sync from another peer");
+    state.remove_pending_value_request_containing(certificate.height);
 
-    request_value_from_peer(co, state, metrics, certificate.height, peer).await
+    request_values(co, state, metrics, Some(from)).await
 }
 
 pub async fn on_get_vote_set<Ctx>(
@@ -527,3 +826,391 @@ where
 
     Ok(())
 }
+
+/// Notify the host when we catch up with our peers or fall behind them.
+async fn update_caught_up<Ctx>(co: &Co<Ctx>, state: &mut State<Ctx>) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let caught_up = state.is_caught_up();
+    if caught_up == state.caught_up {
+        return Ok(());
+    }
+
+    state.caught_up = caught_up;
+
+    if caught_up {
+        info!(tip.height = %state.tip_height, "Caught up with peers");
+        perform!(co, Effect::CaughtUp(state.progress()));
+    } else {
+        info!(tip.height = %state.tip_height, "Fell behind peers");
+        perform!(co, Effect::FellBehind(state.progress()));
+    }
+
+    Ok(())
+}
+
+/// Start bootstrapping the node from a snapshot, unless it has already decided some values,
+/// in which case its state is restored from its own store instead.
+async fn start_snapshot_sync<Ctx>(co: &Co<Ctx>, state: &mut State<Ctx>) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    if state.tip_height > Ctx::Height::ZERO {
+        debug!(tip_height = %state.tip_height, "Node already has state, skipping snapshot sync");
+        state.snapshot.phase = SnapshotPhase::Inactive;
+        return Ok(());
+    }
+
+    info!("Looking for a snapshot to bootstrap from");
+    state.snapshot.phase = SnapshotPhase::Discovering;
+
+    let peers = state.peers.keys().copied().collect::<Vec<_>>();
+    for peer in peers {
+        request_snapshots(co, state, peer).await?;
+    }
+
+    Ok(())
+}
+
+/// Ask the given peer for the snapshots it has, unless we already did.
+async fn request_snapshots<Ctx>(
+    co: &Co<Ctx>,
+    state: &mut State<Ctx>,
+    peer: PeerId,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    if !state.snapshot.requested.insert(peer) {
+        return Ok(());
+    }
+
+    debug!(%peer, "Requesting snapshots from peer");
+
+    perform!(co, Effect::SendSnapshotListRequest(peer));
+    state.snapshot.pending.insert(peer);
+
+    Ok(())
+}
+
+/// Offer the best snapshot available from our peers to the application,
+/// or fall back to syncing decided values if none of our peers has a usable one.
+async fn offer_best_snapshot<Ctx>(
+    co: Co<Ctx>,
+    state: &mut State<Ctx>,
+    metrics: &Metrics,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    if state.snapshot.is_exhausted(state.sync_height) {
+        warn!("No snapshot available from our peers, syncing decided values instead");
+        state.snapshot.phase = SnapshotPhase::Inactive;
+
+        return request_values(co, state, metrics, None).await;
+    }
+
+    let Some(best) = state.snapshot.best_snapshot(state.sync_height).cloned() else {
+        return Ok(());
+    };
+
+    info!(
+        height = %best.snapshot.height(), format = %best.snapshot.format,
+        chunks = %best.snapshot.chunks, peers = %best.peers.len(),
+        "Offering snapshot to the application"
+    );
+
+    state.snapshot.phase = SnapshotPhase::Offering;
+    state.snapshot.download = Some(SnapshotDownload {
+        snapshot: best.snapshot.clone(),
+        peers: best.peers,
+        next_chunk: 0,
+        pending: None,
+    });
+
+    perform!(co, Effect::OfferSnapshot(best.snapshot));
+
+    Ok(())
+}
+
+/// Request the next chunk of the snapshot being restored from one of the peers which have it,
+/// optionally avoiding the given peer, or abandon the snapshot if no peer can provide it.
+async fn request_snapshot_chunk<Ctx>(
+    co: &Co<Ctx>,
+    state: &mut State<Ctx>,
+    except: Option<PeerId>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let peer = state.random_peer_with_snapshot(except);
+
+    let Some(download) = state.snapshot.download.as_mut() else {
+        return Ok(());
+    };
+
+    let Some(peer) = peer else {
+        warn!(
+            height = %download.snapshot.height(),
+            "No peer left to fetch the snapshot from, abandoning it"
+        );
+
+        state.snapshot.abandon_download();
+        return Ok(());
+    };
+
+    let request = SnapshotChunkRequest::new(
+        download.snapshot.height(),
+        download.snapshot.format,
+        download.next_chunk,
+    );
+
+    debug!(height = %request.height, index = %request.index, %peer, "Requesting snapshot chunk");
+
+    download.pending = Some(peer);
+    perform!(co, Effect::SendSnapshotChunkRequest(peer, request));
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn on_snapshot_list_request<Ctx>(
+    co: Co<Ctx>,
+    _state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    request_id: InboundRequestId,
+    peer: PeerId,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    debug!(%request_id, %peer, "Received request for snapshots");
+
+    perform!(co, Effect::GetSnapshots(request_id));
+
+    Ok(())
+}
+
+pub async fn on_snapshots<Ctx>(
+    co: Co<Ctx>,
+    _state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    request_id: InboundRequestId,
+    snapshots: Vec<Snapshot<Ctx>>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    debug!(%request_id, count = snapshots.len(), "Sending available snapshots");
+
+    perform!(
+        co,
+        Effect::SendSnapshotListResponse(request_id, SnapshotListResponse::new(snapshots))
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn on_snapshot_list_response<Ctx>(
+    _co: Co<Ctx>,
+    state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    request_id: OutboundRequestId,
+    peer: PeerId,
+    response: SnapshotListResponse<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    debug!(%request_id, %peer, count = response.snapshots.len(), "Received snapshots");
+
+    state.snapshot.add_snapshots(peer, response.snapshots);
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn on_snapshot_chunk_request<Ctx>(
+    co: Co<Ctx>,
+    _state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    request_id: InboundRequestId,
+    peer: PeerId,
+    request: SnapshotChunkRequest<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    debug!(height = %request.height, index = %request.index, %peer, "Received request for snapshot chunk");
+
+    perform!(co, Effect::GetSnapshotChunk(request_id, request));
+
+    Ok(())
+}
+
+pub async fn on_snapshot_chunk<Ctx>(
+    co: Co<Ctx>,
+    _state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    request_id: InboundRequestId,
+    response: SnapshotChunkResponse<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    if response.chunk.is_none() {
+        debug!(height = %response.height, index = %response.index, "Snapshot chunk not found");
+    }
+
+    perform!(co, Effect::SendSnapshotChunkResponse(request_id, response));
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn on_snapshot_chunk_response<Ctx>(
+    co: Co<Ctx>,
+    state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    request_id: OutboundRequestId,
+    peer: PeerId,
+    response: SnapshotChunkResponse<Ctx>,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let Some(download) = state.snapshot.download.as_mut() else {
+        debug!(%request_id, %peer, "Received snapshot chunk while not restoring a snapshot");
+        return Ok(());
+    };
+
+    let expected = response.height == download.snapshot.height()
+        && response.format == download.snapshot.format
+        && response.index == download.next_chunk
+        && download.pending == Some(peer);
+
+    if !expected {
+        debug!(
+            %request_id, %peer, height = %response.height, index = %response.index,
+            "Ignoring unexpected snapshot chunk"
+        );
+        return Ok(());
+    }
+
+    let Some(chunk) = response.chunk else {
+        warn!(%peer, index = %response.index, "Peer does not have the requested snapshot chunk");
+
+        download.peers.remove(&peer);
+        download.pending = None;
+        state.record_failure(peer);
+
+        return request_snapshot_chunk(&co, state, Some(peer)).await;
+    };
+
+    debug!(%peer, index = %response.index, size = chunk.len(), "Received snapshot chunk");
+
+    perform!(co, Effect::ApplySnapshotChunk(peer, response.index, chunk));
+
+    Ok(())
+}
+
+pub async fn on_snapshot_offered<Ctx>(
+    co: Co<Ctx>,
+    state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    snapshot: Snapshot<Ctx>,
+    result: OfferSnapshotResult,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let offered = state.snapshot.phase == SnapshotPhase::Offering
+        && state
+            .snapshot
+            .download
+            .as_ref()
+            .is_some_and(|download| download.snapshot == snapshot);
+
+    if !offered {
+        debug!(height = %snapshot.height(), "Ignoring result of a stale snapshot offer");
+        return Ok(());
+    }
+
+    match result {
+        OfferSnapshotResult::Accept => {
+            info!(height = %snapshot.height(), chunks = %snapshot.chunks, "Restoring snapshot");
+
+            state.snapshot.phase = SnapshotPhase::Restoring;
+            request_snapshot_chunk(&co, state, None).await
+        }
+        OfferSnapshotResult::Reject => {
+            warn!(height = %snapshot.height(), "Application rejected the snapshot");
+
+            state.snapshot.abandon_download();
+            Ok(())
+        }
+    }
+}
+
+pub async fn on_snapshot_chunk_applied<Ctx>(
+    co: Co<Ctx>,
+    state: &mut State<Ctx>,
+    _metrics: &Metrics,
+    peer: PeerId,
+    index: u32,
+    result: ApplySnapshotChunkResult,
+) -> Result<(), Error<Ctx>>
+where
+    Ctx: Context,
+{
+    let Some(download) = state.snapshot.download.as_mut() else {
+        return Ok(());
+    };
+
+    if state.snapshot.phase != SnapshotPhase::Restoring || download.next_chunk != index {
+        debug!(%index, "Ignoring result of applying a stale snapshot chunk");
+        return Ok(());
+    }
+
+    download.pending = None;
+
+    match result {
+        ApplySnapshotChunkResult::Accept => {
+            download.next_chunk += 1;
+
+            if download.next_chunk < download.snapshot.chunks {
+                return request_snapshot_chunk(&co, state, None).await;
+            }
+
+            let snapshot = download.snapshot.clone();
+            info!(height = %snapshot.height(), "Restored snapshot, syncing decided values from there");
+
+            state.snapshot.phase = SnapshotPhase::Inactive;
+            state.snapshot.download = None;
+            state.tip_height = snapshot.height();
+            update_caught_up(&co, state).await?;
+
+            // Consensus will start the height following the snapshot,
+            // at which point we will request the values from there.
+            perform!(co, Effect::SnapshotRestored(snapshot));
+
+            Ok(())
+        }
+        ApplySnapshotChunkResult::Retry => {
+            warn!(%peer, %index, "Application asked to fetch snapshot chunk again");
+
+            download.peers.remove(&peer);
+            state.record_failure(peer);
+
+            request_snapshot_chunk(&co, state, Some(peer)).await
+        }
+        ApplySnapshotChunkResult::RejectSnapshot => {
+            warn!(%index, "Application rejected the snapshot while restoring it");
+
+            state.snapshot.abandon_download();
+            Ok(())
+        }
+    }
+}
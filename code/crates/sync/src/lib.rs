@@ -5,7 +5,19 @@ mod metrics;
 pub use metrics::Metrics;
 
 mod state;
-pub use state::State;
+pub use state::{PendingRange, RangeParams, State};
+
+mod progress;
+pub use progress::SyncRate;
+
+mod scoring;
+pub use scoring::{PeerScore, ScoringParams};
+
+mod snapshot;
+pub use snapshot::{
+    AvailableSnapshot, SnapshotDownload, SnapshotParams, SnapshotPhase, SnapshotSync,
+    MAX_SNAPSHOTS_PER_PEER,
+};
 
 mod types;
 pub use types::*;
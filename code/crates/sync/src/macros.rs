@@ -77,7 +77,6 @@ macro_rules! perform {
 
     // TODO: Add support for multiple patterns + if guards
     ($co:expr, $effect:expr, $pat:pat => $expr:expr $(,)?) => {
-        #[allow(unreachable_patterns)]
         match $co.yield_($effect).await {
             $pat => $expr,
             resume => {
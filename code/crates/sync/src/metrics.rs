@@ -213,7 +213,7 @@ impl Metrics {
         self.decided_values().request_timeouts.inc();
         self.decided_values()
             .instant_request_sent
-            .remove(&(height, 0));
+            .remove(&(height, -1));
     }
 
     pub fn vote_set_request_sent(&self, height: u64, round: i64) {
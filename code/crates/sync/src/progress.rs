@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+/// Weight of a new sample in the moving average of the sync rate
+const RATE_SAMPLE_WEIGHT: f64 = 0.2;
+
+/// Measures how fast the node moves through heights, to estimate when it will catch up with its peers.
+#[derive(Clone, Debug, Default)]
+pub struct SyncRate {
+    /// Moving average of the number of heights per second
+    heights_per_sec: Option<f64>,
+
+    /// Time and height of the last sample
+    last_sample: Option<(Instant, u64)>,
+}
+
+impl SyncRate {
+    /// Record that the node was at the given height at the given time.
+    pub fn record(&mut self, now: Instant, height: u64) {
+        if let Some((last_time, last_height)) = self.last_sample {
+            let elapsed = now.saturating_duration_since(last_time).as_secs_f64();
+
+            if elapsed > 0.0 {
+                let sample = height.saturating_sub(last_height) as f64 / elapsed;
+
+                self.heights_per_sec = Some(match self.heights_per_sec {
+                    None => sample,
+                    Some(average) => {
+                        average * (1.0 - RATE_SAMPLE_WEIGHT) + sample * RATE_SAMPLE_WEIGHT
+                    }
+                });
+            }
+        }
+
+        self.last_sample = Some((now, height));
+    }
+
+    /// Moving average of the number of heights per second, if measured yet.
+    pub fn heights_per_sec(&self) -> Option<f64> {
+        self.heights_per_sec
+    }
+
+    /// Estimated time to move through the given number of heights,
+    /// unless the rate is unknown or the node is not moving.
+    pub fn eta(&self, remaining: u64) -> Option<Duration> {
+        let rate = self.heights_per_sec.filter(|rate| *rate > 0.0)?;
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eta_from_rate() {
+        let start = Instant::now();
+        let mut rate = SyncRate::default();
+
+        rate.record(start, 10);
+        assert_eq!(rate.eta(100), None);
+
+        rate.record(start + Duration::from_secs(1), 20);
+        assert_eq!(rate.heights_per_sec(), Some(10.0));
+        assert_eq!(rate.eta(100), Some(Duration::from_secs(10)));
+
+        // Not moving anymore
+        rate.record(start + Duration::from_secs(2), 20);
+        assert_eq!(rate.heights_per_sec(), Some(8.0));
+
+        let mut stalled = SyncRate::default();
+        stalled.record(start, 10);
+        stalled.record(start + Duration::from_secs(1), 10);
+        assert_eq!(stalled.eta(100), None);
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::{Duration, Instant};
+
+/// Weight of a new latency sample in the moving average of a peer's latency
+const LATENCY_SAMPLE_WEIGHT: f64 = 0.2;
+
+/// Parameters controlling how peers are scored and when they get banned.
+#[derive(Copy, Clone, Debug)]
+pub struct ScoringParams {
+    /// Number of consecutive failed requests after which a peer is banned
+    pub max_consecutive_failures: u32,
+
+    /// Number of invalid certificates after which a peer is banned
+    pub max_invalid_certificates: u32,
+
+    /// How long a banned peer is not sent any request
+    pub ban_duration: Duration,
+}
+
+impl Default for ScoringParams {
+    fn default() -> Self {
+        Self {
+            max_consecutive_failures: 5,
+            max_invalid_certificates: 1,
+            ban_duration: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Quality of a peer as a source of decided values and vote sets.
+///
+/// Peers are picked at random, weighted by their [score](PeerScore::score),
+/// so that slow or unreliable peers are asked less often, and banned peers not at all.
+#[derive(Clone, Debug, Default)]
+pub struct PeerScore {
+    /// Moving average of the time it took the peer to respond to our requests
+    pub latency: Option<Duration>,
+
+    /// Number of requests which timed out or got an empty response since the last successful one
+    pub consecutive_failures: u32,
+
+    /// Number of invalid certificates received from the peer
+    pub invalid_certificates: u32,
+
+    /// The peer is banned until then
+    pub banned_until: Option<Instant>,
+}
+
+impl PeerScore {
+    /// Record a successful response from the peer, received after the given latency.
+    pub fn record_response(&mut self, latency: Duration) {
+        self.consecutive_failures = 0;
+
+        self.latency = Some(match self.latency {
+            None => latency,
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_SAMPLE_WEIGHT)
+                    + latency.mul_f64(LATENCY_SAMPLE_WEIGHT)
+            }
+        });
+    }
+
+    /// Record a request which timed out or got an empty response.
+    ///
+    /// Returns whether the peer got banned as a result.
+    pub fn record_failure(&mut self, params: &ScoringParams, now: Instant) -> bool {
+        self.consecutive_failures += 1;
+
+        if self.consecutive_failures >= params.max_consecutive_failures {
+            self.ban(params, now);
+            return true;
+        }
+
+        false
+    }
+
+    /// Record an invalid certificate received from the peer.
+    ///
+    /// Returns whether the peer got banned as a result.
+    pub fn record_invalid_certificate(&mut self, params: &ScoringParams, now: Instant) -> bool {
+        self.invalid_certificates += 1;
+
+        if self.invalid_certificates >= params.max_invalid_certificates {
+            self.ban(params, now);
+            return true;
+        }
+
+        false
+    }
+
+    /// Whether the peer is currently banned.
+    pub fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until.is_some_and(|until| now < until)
+    }
+
+    /// Score of the peer, from 1 for a peer which responds instantly and has never failed,
+    /// decreasing with its latency and number of consecutive failures, down to 0 when banned.
+    pub fn score(&self, now: Instant) -> f64 {
+        if self.is_banned(now) {
+            return 0.0;
+        }
+
+        let latency = self.latency.map_or(0.0, |latency| latency.as_secs_f64());
+
+        1.0 / (1.0 + latency) / (1.0 + f64::from(self.consecutive_failures))
+    }
+
+    /// Ban the peer for the configured duration, starting afresh once the ban expires.
+    fn ban(&mut self, params: &ScoringParams, now: Instant) {
+        self.banned_until = Some(now + params.ban_duration);
+        self.consecutive_failures = 0;
+        self.invalid_certificates = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slower_and_failing_peers_score_lower() {
+        let now = Instant::now();
+        let params = ScoringParams::default();
+
+        let mut fast = PeerScore::default();
+        fast.record_response(Duration::from_millis(10));
+
+        let mut slow = PeerScore::default();
+        slow.record_response(Duration::from_secs(2));
+
+        let mut failing = fast.clone();
+        failing.record_failure(&params, now);
+
+        assert!(fast.score(now) > slow.score(now));
+        assert!(fast.score(now) > failing.score(now));
+
+        // A successful response resets the consecutive failures
+        failing.record_response(Duration::from_millis(10));
+        assert_eq!(failing.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn ban_after_too_many_failures() {
+        let now = Instant::now();
+        let params = ScoringParams::default();
+
+        let mut peer = PeerScore::default();
+
+        for _ in 1..params.max_consecutive_failures {
+            assert!(!peer.record_failure(&params, now));
+        }
+
+        assert!(peer.record_failure(&params, now));
+        assert!(peer.is_banned(now));
+        assert_eq!(peer.score(now), 0.0);
+
+        // The ban expires after the configured duration
+        let later = now + params.ban_duration;
+        assert!(!peer.is_banned(later));
+        assert!(peer.score(later) > 0.0);
+    }
+
+    #[test]
+    fn ban_on_invalid_certificate() {
+        let now = Instant::now();
+        let params = ScoringParams::default();
+
+        let mut peer = PeerScore::default();
+
+        assert!(peer.record_invalid_certificate(&params, now));
+        assert!(peer.is_banned(now));
+    }
+}
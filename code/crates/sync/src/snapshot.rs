@@ -0,0 +1,177 @@
+use std::collections::BTreeSet;
+
+use derive_where::derive_where;
+
+use malachitebft_core_types::Context;
+use malachitebft_peer::PeerId;
+
+use crate::Snapshot;
+
+/// Maximum number of snapshots considered from a single peer, the most recent ones.
+pub const MAX_SNAPSHOTS_PER_PEER: usize = 8;
+
+/// Parameters controlling how a node without any state is bootstrapped from a snapshot.
+#[derive(Copy, Clone, Debug)]
+pub struct SnapshotParams {
+    /// Whether to bootstrap a node without any state from a snapshot offered by peers
+    pub enabled: bool,
+
+    /// Minimum number of peers which must offer the same snapshot
+    /// before it is offered to the application
+    pub min_peers: usize,
+}
+
+impl Default for SnapshotParams {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_peers: 2,
+        }
+    }
+}
+
+/// Progress of bootstrapping the node from a snapshot of the application state.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotPhase {
+    /// Snapshot sync is disabled or done, decided values are synced from peers instead
+    Inactive,
+
+    /// Waiting for consensus to start its first height, to find out whether
+    /// the node has any state yet, in which case snapshot sync is not needed
+    Waiting,
+
+    /// Collecting the snapshots available from our peers
+    Discovering,
+
+    /// Waiting for the application to accept or reject the snapshot offered to it
+    Offering,
+
+    /// Fetching the chunks of the accepted snapshot and applying them in order
+    Restoring,
+}
+
+impl SnapshotPhase {
+    /// Whether syncing decided values should be held off until the snapshot is restored.
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Discovering | Self::Offering | Self::Restoring)
+    }
+}
+
+/// A snapshot offered by some of our peers.
+#[derive_where(Clone, Debug)]
+pub struct AvailableSnapshot<Ctx: Context> {
+    pub snapshot: Snapshot<Ctx>,
+    pub peers: BTreeSet<PeerId>,
+}
+
+/// The snapshot being restored, and the chunk to fetch next.
+#[derive_where(Clone, Debug)]
+pub struct SnapshotDownload<Ctx: Context> {
+    pub snapshot: Snapshot<Ctx>,
+
+    /// Peers which offered the snapshot and have not failed to provide a chunk of it
+    pub peers: BTreeSet<PeerId>,
+
+    /// Index of the next chunk to apply
+    pub next_chunk: u32,
+
+    /// Peer the next chunk has been requested from, if any
+    pub pending: Option<PeerId>,
+}
+
+#[derive_where(Debug)]
+pub struct SnapshotSync<Ctx: Context> {
+    pub phase: SnapshotPhase,
+
+    /// Minimum number of peers which must offer the same snapshot
+    pub min_peers: usize,
+
+    /// Peers we have asked for the snapshots they have
+    pub requested: BTreeSet<PeerId>,
+
+    /// Peers we are still waiting on for the snapshots they have
+    pub pending: BTreeSet<PeerId>,
+
+    /// Snapshots offered by peers, which have not been rejected
+    pub available: Vec<AvailableSnapshot<Ctx>>,
+
+    /// The snapshot being offered to the application or restored
+    pub download: Option<SnapshotDownload<Ctx>>,
+}
+
+impl<Ctx: Context> SnapshotSync<Ctx> {
+    pub fn new(params: SnapshotParams) -> Self {
+        let phase = if params.enabled {
+            SnapshotPhase::Waiting
+        } else {
+            SnapshotPhase::Inactive
+        };
+
+        Self {
+            phase,
+            min_peers: params.min_peers.max(1),
+            requested: BTreeSet::new(),
+            pending: BTreeSet::new(),
+            available: Vec::new(),
+            download: None,
+        }
+    }
+
+    /// Record the snapshots offered by a peer we asked for them,
+    /// keeping at most [`MAX_SNAPSHOTS_PER_PEER`] of them, the most recent ones.
+    pub fn add_snapshots(&mut self, peer: PeerId, mut snapshots: Vec<Snapshot<Ctx>>) {
+        if !self.pending.remove(&peer) {
+            return;
+        }
+
+        snapshots.sort_by_key(|snapshot| core::cmp::Reverse(snapshot.height()));
+        snapshots.truncate(MAX_SNAPSHOTS_PER_PEER);
+
+        for snapshot in snapshots {
+            let existing = self
+                .available
+                .iter_mut()
+                .find(|a| a.snapshot.same_state(&snapshot));
+
+            match existing {
+                Some(available) => {
+                    available.peers.insert(peer);
+                }
+                None => self.available.push(AvailableSnapshot {
+                    snapshot,
+                    peers: BTreeSet::from([peer]),
+                }),
+            }
+        }
+    }
+
+    /// The most recent snapshot available at or above the given height
+    /// which is offered by at least `min_peers` peers, preferring the ones offered by more peers.
+    ///
+    /// As a node without any state cannot verify the certificate of a snapshot on its own,
+    /// a snapshot is only trusted once enough peers agree on it.
+    pub fn best_snapshot(&self, min_height: Ctx::Height) -> Option<&AvailableSnapshot<Ctx>> {
+        self.available
+            .iter()
+            .filter(|available| available.snapshot.height() >= min_height)
+            .filter(|available| available.peers.len() >= self.min_peers)
+            .max_by_key(|available| (available.snapshot.height(), available.peers.len()))
+    }
+
+    /// Stop restoring the current snapshot, forget about it and go back to discovering snapshots.
+    pub fn abandon_download(&mut self) {
+        if let Some(download) = self.download.take() {
+            self.available
+                .retain(|available| !available.snapshot.same_state(&download.snapshot));
+        }
+
+        self.phase = SnapshotPhase::Discovering;
+    }
+
+    /// Whether all the peers we asked responded without offering any usable snapshot.
+    pub fn is_exhausted(&self, min_height: Ctx::Height) -> bool {
+        !self.requested.is_empty()
+            && self.pending.is_empty()
+            && self.best_snapshot(min_height).is_none()
+    }
+}
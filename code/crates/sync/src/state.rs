@@ -1,12 +1,59 @@
 use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
-use rand::seq::IteratorRandom;
+use derive_where::derive_where;
+use rand::seq::SliceRandom;
 
 use malachitebft_core_types::{Context, Height, Round};
 use malachitebft_peer::PeerId;
 use tracing::warn;
 
-use crate::Status;
+use crate::progress::SyncRate;
+use crate::scoring::{PeerScore, ScoringParams};
+use crate::snapshot::{SnapshotParams, SnapshotSync};
+use crate::{OutboundRequestId, Status, SyncProgress};
+
+/// Parameters controlling how decided values are requested from peers.
+#[derive(Copy, Clone, Debug)]
+pub struct RangeParams {
+    /// Maximum number of values requested in a single request
+    pub batch_size: u64,
+
+    /// Maximum number of value requests in flight at the same time, across all peers
+    pub parallel_requests: usize,
+
+    /// Maximum size in bytes of the values included in a single response
+    pub max_response_size: u64,
+}
+
+impl Default for RangeParams {
+    fn default() -> Self {
+        Self {
+            batch_size: 50,
+            parallel_requests: 5,
+            max_response_size: 4 * 1024 * 1024, // 4 MiB
+        }
+    }
+}
+
+/// A request for the decided values of a range of heights which has been sent out to a peer.
+#[derive_where(Clone, Debug)]
+pub struct PendingRange<Ctx: Context> {
+    /// First height of the range
+    pub start: Ctx::Height,
+
+    /// Last height of the range, inclusive
+    pub end: Ctx::Height,
+
+    /// Peer the request was sent to
+    pub peer: PeerId,
+
+    /// Whether the values have been received and handed over to consensus
+    pub received: bool,
+
+    /// When the request was sent
+    pub sent_at: Instant,
+}
 
 pub struct State<Ctx>
 where
@@ -20,8 +67,18 @@ where
     /// Height currently syncing.
     pub sync_height: Ctx::Height,
 
-    /// Decided value requests for these heights have been sent out to peers.
-    pub pending_decided_value_requests: BTreeMap<Ctx::Height, PeerId>,
+    /// Sync height at the time of the last tick.
+    pub last_tick_sync_height: Ctx::Height,
+
+    /// Parameters of the value requests sent out to peers.
+    pub params: RangeParams,
+
+    /// Parameters for scoring and banning peers.
+    pub scoring_params: ScoringParams,
+
+    /// Decided value requests for these ranges of heights, indexed by the id of the request,
+    /// have been sent out to peers.
+    pub pending_value_requests: BTreeMap<OutboundRequestId, PendingRange<Ctx>>,
 
     /// Vote set requests for these heights and rounds have been sent out to peers.
     pub pending_vote_set_requests: BTreeMap<(Ctx::Height, Round), PeerId>,
@@ -29,20 +86,98 @@ where
     /// The set of peers we are connected to in order to get values, certificates and votes.
     /// TODO - For now value and vote sync peers are the same. Might need to revise in the future.
     pub peers: BTreeMap<PeerId, Status<Ctx>>,
+
+    /// Scores of the peers we have sent requests to, kept across disconnections
+    /// so that banned peers stay banned when they reconnect.
+    pub scores: BTreeMap<PeerId, PeerScore>,
+
+    /// Progress of bootstrapping the node from a snapshot of the application state.
+    pub snapshot: SnapshotSync<Ctx>,
+
+    /// Whether we were caught up with our peers when we last checked.
+    pub caught_up: bool,
+
+    /// How fast we move through heights.
+    pub sync_rate: SyncRate,
 }
 
 impl<Ctx> State<Ctx>
 where
     Ctx: Context,
 {
-    pub fn new(rng: Box<dyn rand::RngCore + Send>) -> Self {
+    pub fn new(
+        rng: Box<dyn rand::RngCore + Send>,
+        params: RangeParams,
+        scoring_params: ScoringParams,
+        snapshot_params: SnapshotParams,
+    ) -> Self {
         Self {
             rng,
             tip_height: Ctx::Height::ZERO,
             sync_height: Ctx::Height::ZERO,
-            pending_decided_value_requests: BTreeMap::new(),
+            last_tick_sync_height: Ctx::Height::ZERO,
+            params,
+            scoring_params,
+            pending_value_requests: BTreeMap::new(),
             pending_vote_set_requests: BTreeMap::new(),
             peers: BTreeMap::new(),
+            scores: BTreeMap::new(),
+            snapshot: SnapshotSync::new(snapshot_params),
+            caught_up: false,
+            sync_rate: SyncRate::default(),
+        }
+    }
+
+    /// Highest tip height reached by a majority of our peers which are not banned,
+    /// if we have heard from any.
+    ///
+    /// As peers report their own height, a minority of them claiming to be far ahead
+    /// can neither keep us from considering ourselves caught up nor skew our progress.
+    pub fn peers_tip_height(&self) -> Option<Ctx::Height> {
+        let mut heights = self
+            .peers
+            .values()
+            .filter(|status| !self.is_banned(status.peer_id))
+            .map(|status| status.height)
+            .collect::<Vec<_>>();
+
+        heights.sort_unstable_by(|a, b| b.cmp(a));
+        heights.get(heights.len() / 2).copied()
+    }
+
+    /// Whether we are at most one height behind most of our peers and not restoring a snapshot.
+    ///
+    /// Until we hear from a peer, we cannot tell and stick to what we last determined.
+    pub fn is_caught_up(&self) -> bool {
+        if self.snapshot.phase.is_active() {
+            return false;
+        }
+
+        match self.peers_tip_height() {
+            Some(peers_tip_height) => peers_tip_height <= self.tip_height.increment(),
+            None => self.caught_up,
+        }
+    }
+
+    /// Our progress in catching up with our peers.
+    pub fn progress(&self) -> SyncProgress<Ctx> {
+        let peers_tip_height = self.peers_tip_height();
+
+        let eta = if self.caught_up {
+            Some(Duration::ZERO)
+        } else {
+            peers_tip_height.and_then(|height| {
+                let remaining = height.as_u64().saturating_sub(self.tip_height.as_u64());
+                self.sync_rate.eta(remaining)
+            })
+        };
+
+        SyncProgress {
+            tip_height: self.tip_height,
+            sync_height: self.sync_height,
+            peers_tip_height,
+            eta,
+            caught_up: self.caught_up,
         }
     }
 
@@ -64,10 +199,7 @@ where
 
     /// Select at random a peer that that we know is at or above the given height.
     pub fn random_peer_with_value(&mut self, height: Ctx::Height) -> Option<PeerId> {
-        self.peers
-            .iter()
-            .filter_map(move |(&peer, status)| (status.height >= height).then_some(peer))
-            .choose_stable(&mut self.rng)
+        self.random_peer_with_value_where(height, |_| true)
     }
 
     /// Select at random a peer that that we know is at or above the given height,
@@ -77,24 +209,255 @@ where
         height: Ctx::Height,
         except: PeerId,
     ) -> Option<PeerId> {
-        self.peers
+        self.random_peer_with_value_where(height, |peer| peer != except)
+    }
+
+    /// Select at random a peer matching the given predicate that we know is at or above
+    /// the given height, excluding banned peers.
+    ///
+    /// Peers are weighted by their score, and by the number of value requests
+    /// already in flight to them, so as to spread the requests across good peers.
+    fn random_peer_with_value_where(
+        &mut self,
+        height: Ctx::Height,
+        predicate: impl Fn(PeerId) -> bool,
+    ) -> Option<PeerId> {
+        let now = Instant::now();
+
+        let candidates = self
+            .peers
+            .iter()
+            .filter(|(&peer, status)| status.height >= height && predicate(peer))
+            .map(|(&peer, _)| {
+                let score = self.scores.get(&peer).map_or(1.0, |score| score.score(now));
+                let inflight = self.inflight_value_requests_to(peer) as f64;
+                (peer, score / (1.0 + inflight))
+            })
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect::<Vec<_>>();
+
+        candidates
+            .choose_weighted(&mut self.rng, |(_, weight)| *weight)
+            .ok()
+            .map(|(peer, _)| *peer)
+    }
+
+    /// Select at random a connected peer which offered the snapshot being restored,
+    /// excluding banned peers and optionally the given one.
+    pub fn random_peer_with_snapshot(&mut self, except: Option<PeerId>) -> Option<PeerId> {
+        let download = self.snapshot.download.as_ref()?;
+
+        let candidates = download
+            .peers
+            .iter()
+            .copied()
+            .filter(|&peer| Some(peer) != except)
+            .filter(|peer| self.peers.contains_key(peer) && !self.is_banned(*peer))
+            .collect::<Vec<_>>();
+
+        candidates.choose(&mut self.rng).copied()
+    }
+
+    /// Whether the given peer is currently banned.
+    pub fn is_banned(&self, peer: PeerId) -> bool {
+        self.scores
+            .get(&peer)
+            .is_some_and(|score| score.is_banned(Instant::now()))
+    }
+
+    /// Record a request to the given peer which timed out or got an empty response.
+    ///
+    /// Returns whether the peer got banned as a result.
+    pub fn record_failure(&mut self, peer: PeerId) -> bool {
+        self.scores
+            .entry(peer)
+            .or_default()
+            .record_failure(&self.scoring_params, Instant::now())
+    }
+
+    /// Record an invalid certificate received from the given peer.
+    ///
+    /// Returns whether the peer got banned as a result.
+    pub fn record_invalid_certificate(&mut self, peer: PeerId) -> bool {
+        self.scores
+            .entry(peer)
+            .or_default()
+            .record_invalid_certificate(&self.scoring_params, Instant::now())
+    }
+
+    /// Number of value requests which have been sent out to the given peer and not yet answered.
+    fn inflight_value_requests_to(&self, peer: PeerId) -> usize {
+        self.pending_value_requests
+            .values()
+            .filter(|range| range.peer == peer && !range.received)
+            .count()
+    }
+
+    /// Number of value requests which have been sent out and not yet answered.
+    pub fn inflight_value_requests(&self) -> usize {
+        self.pending_value_requests
+            .values()
+            .filter(|range| !range.received)
+            .count()
+    }
+
+    /// The next range of heights to request, starting from the lowest height
+    /// at or above the sync height which is not covered by a pending request.
+    ///
+    /// The range holds at most `batch_size` heights and ends before the next pending request.
+    pub fn next_range(&self) -> (Ctx::Height, Ctx::Height) {
+        let mut ranges: Vec<_> = self.pending_value_requests.values().collect();
+        ranges.sort_by_key(|range| range.start);
+
+        // Skip over the pending ranges covering the sync height and the heights right after it
+        let mut start = self.sync_height;
+
+        for range in &ranges {
+            if range.start <= start && start <= range.end {
+                start = range.end.increment();
+            }
+        }
+
+        let mut end = start.increment_by(self.params.batch_size.saturating_sub(1));
+
+        if let Some(next) = ranges.iter().find(|range| range.start > start) {
+            if let Some(before_next) = next.start.decrement() {
+                end = end.min(before_next);
+            }
+        }
+
+        (start, end)
+    }
+
+    pub fn store_pending_value_request(
+        &mut self,
+        request_id: OutboundRequestId,
+        start: Ctx::Height,
+        end: Ctx::Height,
+        peer: PeerId,
+    ) {
+        let range = PendingRange {
+            start,
+            end,
+            peer,
+            received: false,
+            sent_at: Instant::now(),
+        };
+
+        self.pending_value_requests.insert(request_id, range);
+    }
+
+    /// Record that `count` values, starting at `start`, have been received from `peer`
+    /// in response to the given request, updating the score of the peer accordingly.
+    ///
+    /// If fewer values than requested were received, the rest of the range
+    /// is no longer considered pending and will be requested again.
+    /// If the values do not start at the first height of the requested range,
+    /// the request is considered failed and the whole range will be requested again.
+    ///
+    /// Returns whether any of the requested values were received.
+    pub fn value_range_received(
+        &mut self,
+        request_id: &OutboundRequestId,
+        peer: PeerId,
+        start: Ctx::Height,
+        count: u64,
+    ) -> bool {
+        let Some(range) = self.pending_value_requests.get_mut(request_id) else {
+            return false;
+        };
+
+        if range.peer != peer {
+            return false;
+        }
+
+        if range.start != start {
+            warn!(
+                %peer, expected = %range.start, received = %start,
+                "Received values for a range not matching the request"
+            );
+
+            self.pending_value_requests.remove(request_id);
+            self.record_failure(peer);
+
+            return false;
+        }
+
+        if count == 0 {
+            self.pending_value_requests.remove(request_id);
+
+            // Only hold it against the peer if it claims to have the requested values
+            let has_values = self
+                .peers
+                .get(&peer)
+                .is_some_and(|status| status.history_min_height <= start && start <= status.height);
+
+            if has_values {
+                self.record_failure(peer);
+            }
+
+            return false;
+        }
+
+        range.end = range.end.min(start.increment_by(count - 1));
+        range.received = true;
+
+        let latency = range.sent_at.elapsed();
+        self.scores
+            .entry(peer)
+            .or_default()
+            .record_response(latency);
+
+        true
+    }
+
+    /// Remove the given request, if it is still waiting for a response.
+    pub fn remove_pending_value_request(&mut self, request_id: &OutboundRequestId) {
+        if let Some(range) = self.pending_value_requests.get(request_id) {
+            if !range.received {
+                self.pending_value_requests.remove(request_id);
+            }
+        }
+    }
+
+    /// The id of the request for the range of heights containing the given height, if any.
+    fn value_request_containing(&self, height: Ctx::Height) -> Option<&OutboundRequestId> {
+        self.pending_value_requests
             .iter()
-            .filter_map(move |(&peer, status)| (status.height >= height).then_some(peer))
-            .filter(|&peer| peer != except)
-            .choose_stable(&mut self.rng)
+            .find(|(_, range)| range.start <= height && height <= range.end)
+            .map(|(request_id, _)| request_id)
     }
 
-    pub fn store_pending_decided_value_request(&mut self, height: Ctx::Height, peer: PeerId) {
-        self.pending_decided_value_requests.insert(height, peer);
+    /// Remove the request for the range of heights containing the given height, if any.
+    pub fn remove_pending_value_request_containing(
+        &mut self,
+        height: Ctx::Height,
+    ) -> Option<PendingRange<Ctx>> {
+        let request_id = self.value_request_containing(height)?.clone();
+        self.pending_value_requests.remove(&request_id)
     }
 
-    pub fn remove_pending_decided_value_request(&mut self, height: Ctx::Height) {
-        self.pending_decided_value_requests.remove(&height);
+    /// Remove the request for the range of heights containing the given height,
+    /// if its values have already been received.
+    pub fn remove_received_value_request_containing(&mut self, height: Ctx::Height) {
+        let received = self
+            .value_request_containing(height)
+            .and_then(|request_id| self.pending_value_requests.get(request_id))
+            .is_some_and(|range| range.received);
+
+        if received {
+            self.remove_pending_value_request_containing(height);
+        }
     }
 
-    pub fn has_pending_decided_value_request(&self, height: &Ctx::Height) -> bool {
-        self.pending_decided_value_requests.contains_key(height)
+    /// Remove the requests for ranges of heights which are all below the sync height.
+    pub fn prune_pending_value_requests(&mut self) {
+        let sync_height = self.sync_height;
+
+        self.pending_value_requests
+            .retain(|_, range| range.end >= sync_height);
     }
+
     pub fn store_pending_vote_set_request(
         &mut self,
         height: Ctx::Height,
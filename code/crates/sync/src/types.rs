@@ -1,10 +1,12 @@
+use std::time::Duration;
+
 use bytes::Bytes;
 use derive_where::derive_where;
 use displaydoc::Display;
 use libp2p::request_response;
 use serde::{Deserialize, Serialize};
 
-use malachitebft_core_types::{CommitCertificate, Context, Round, VoteSet};
+use malachitebft_core_types::{CommitCertificate, Context, Height, Round, VoteSet};
 pub use malachitebft_peer::PeerId;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
@@ -38,16 +40,42 @@ pub struct Status<Ctx: Context> {
     pub history_min_height: Ctx::Height,
 }
 
+/// Progress of this node in catching up with its peers.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct SyncProgress<Ctx: Context> {
+    /// Height of the latest value decided by this node
+    pub tip_height: Ctx::Height,
+
+    /// Height this node is currently deciding or syncing
+    pub sync_height: Ctx::Height,
+
+    /// Highest tip height reached by a majority of our peers, unless we have not heard from any peer yet
+    pub peers_tip_height: Option<Ctx::Height>,
+
+    /// Estimated time until this node catches up with its peers,
+    /// unless it does not move through heights fast enough to tell
+    pub eta: Option<Duration>,
+
+    /// Whether this node is caught up with its peers, ie. at most one height behind them
+    pub caught_up: bool,
+}
+
 #[derive_where(Clone, Debug, PartialEq, Eq)]
 pub enum Request<Ctx: Context> {
     ValueRequest(ValueRequest<Ctx>),
+    ValueRangeRequest(ValueRangeRequest<Ctx>),
     VoteSetRequest(VoteSetRequest<Ctx>),
+    SnapshotListRequest(SnapshotListRequest),
+    SnapshotChunkRequest(SnapshotChunkRequest<Ctx>),
 }
 
 #[derive_where(Clone, Debug, PartialEq, Eq)]
 pub enum Response<Ctx: Context> {
     ValueResponse(ValueResponse<Ctx>),
+    ValueRangeResponse(ValueRangeResponse<Ctx>),
     VoteSetResponse(VoteSetResponse<Ctx>),
+    SnapshotListResponse(SnapshotListResponse<Ctx>),
+    SnapshotChunkResponse(SnapshotChunkResponse<Ctx>),
 }
 
 #[derive_where(Clone, Debug, PartialEq, Eq)]
@@ -73,6 +101,53 @@ impl<Ctx: Context> ValueResponse<Ctx> {
     }
 }
 
+/// A request for the decided values of a range of heights, from `start` to `end` inclusive.
+///
+/// The peer may respond with fewer values than requested, either because it does not have
+/// all of them or because the total size of the values would exceed `max_bytes`.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct ValueRangeRequest<Ctx: Context> {
+    pub start: Ctx::Height,
+    pub end: Ctx::Height,
+    pub max_bytes: u64,
+}
+
+impl<Ctx: Context> ValueRangeRequest<Ctx> {
+    pub fn new(start: Ctx::Height, end: Ctx::Height, max_bytes: u64) -> Self {
+        Self {
+            start,
+            end,
+            max_bytes,
+        }
+    }
+}
+
+/// The decided values for consecutive heights, starting at `start`.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct ValueRangeResponse<Ctx: Context> {
+    pub start: Ctx::Height,
+    pub values: Vec<RawDecidedValue<Ctx>>,
+}
+
+impl<Ctx: Context> ValueRangeResponse<Ctx> {
+    pub fn new(start: Ctx::Height, values: Vec<RawDecidedValue<Ctx>>) -> Self {
+        Self { start, values }
+    }
+
+    /// The values of this response up to the first one which is not for the height
+    /// following the one of the previous value, starting at `start`.
+    pub fn consecutive_values(&self) -> &[RawDecidedValue<Ctx>] {
+        let len = self
+            .values
+            .iter()
+            .zip(0..)
+            .take_while(|(value, i)| value.certificate.height == self.start.increment_by(*i))
+            .count();
+
+        &self.values[..len]
+    }
+}
+
 #[derive_where(Clone, Debug, PartialEq, Eq)]
 pub struct RawDecidedValue<Ctx: Context> {
     pub value_bytes: Bytes,
@@ -136,3 +211,127 @@ impl<Ctx: Context> VoteSetResponse<Ctx> {
         }
     }
 }
+
+/// Metadata of a snapshot of the application state, taken right after the value
+/// certified by `certificate` was committed.
+///
+/// The snapshot itself is split into `chunks` opaque chunks, whose format and content
+/// are entirely up to the application, as are `hash` and `metadata`.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot<Ctx: Context> {
+    pub format: u32,
+    pub chunks: u32,
+    pub hash: Bytes,
+    pub metadata: Bytes,
+    pub certificate: CommitCertificate<Ctx>,
+}
+
+impl<Ctx: Context> Snapshot<Ctx> {
+    pub fn new(
+        format: u32,
+        chunks: u32,
+        hash: Bytes,
+        metadata: Bytes,
+        certificate: CommitCertificate<Ctx>,
+    ) -> Self {
+        Self {
+            format,
+            chunks,
+            hash,
+            metadata,
+            certificate,
+        }
+    }
+
+    /// Height at which the snapshot was taken
+    pub fn height(&self) -> Ctx::Height {
+        self.certificate.height
+    }
+
+    /// Whether both snapshots hold the same application state at the same height,
+    /// regardless of the signatures included in their certificates.
+    pub fn same_state(&self, other: &Self) -> bool {
+        self.height() == other.height()
+            && self.certificate.value_id == other.certificate.value_id
+            && self.format == other.format
+            && self.chunks == other.chunks
+            && self.hash == other.hash
+            && self.metadata == other.metadata
+    }
+}
+
+/// A request for the snapshots available from a peer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotListRequest;
+
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotListResponse<Ctx: Context> {
+    pub snapshots: Vec<Snapshot<Ctx>>,
+}
+
+impl<Ctx: Context> SnapshotListResponse<Ctx> {
+    pub fn new(snapshots: Vec<Snapshot<Ctx>>) -> Self {
+        Self { snapshots }
+    }
+}
+
+/// A request for the chunk at `index` of the snapshot taken at `height` in the given `format`.
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotChunkRequest<Ctx: Context> {
+    pub height: Ctx::Height,
+    pub format: u32,
+    pub index: u32,
+}
+
+impl<Ctx: Context> SnapshotChunkRequest<Ctx> {
+    pub fn new(height: Ctx::Height, format: u32, index: u32) -> Self {
+        Self {
+            height,
+            format,
+            index,
+        }
+    }
+}
+
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotChunkResponse<Ctx: Context> {
+    pub height: Ctx::Height,
+    pub format: u32,
+    pub index: u32,
+    pub chunk: Option<Bytes>,
+}
+
+impl<Ctx: Context> SnapshotChunkResponse<Ctx> {
+    pub fn new(height: Ctx::Height, format: u32, index: u32, chunk: Option<Bytes>) -> Self {
+        Self {
+            height,
+            format,
+            index,
+            chunk,
+        }
+    }
+}
+
+/// Decision of the application on a snapshot offered to it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OfferSnapshotResult {
+    /// Restore the application state from this snapshot
+    Accept,
+
+    /// Do not use this snapshot, eg. because its format is not supported
+    /// or its certificate cannot be trusted
+    Reject,
+}
+
+/// Outcome of applying a snapshot chunk to the application state.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ApplySnapshotChunkResult {
+    /// The chunk was applied, proceed with the next one
+    Accept,
+
+    /// The chunk is invalid, fetch it again from another peer
+    Retry,
+
+    /// The snapshot cannot be restored, abandon it and look for another one
+    RejectSnapshot,
+}
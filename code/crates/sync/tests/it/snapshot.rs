@@ -0,0 +1,75 @@
+use bytes::Bytes;
+
+use malachitebft_core_types::{AggregatedSignature, CommitCertificate, Round};
+use malachitebft_peer::PeerId;
+use malachitebft_test::{Height, TestContext, ValueId};
+
+use informalsystems_malachitebft_sync::{
+    Snapshot, SnapshotParams, SnapshotSync, MAX_SNAPSHOTS_PER_PEER,
+};
+
+fn new_sync(min_peers: usize) -> SnapshotSync<TestContext> {
+    SnapshotSync::new(SnapshotParams {
+        enabled: true,
+        min_peers,
+    })
+}
+
+/// A snapshot at the given height, with a certificate for the given round
+fn snapshot(height: u64, round: u32) -> Snapshot<TestContext> {
+    let certificate = CommitCertificate {
+        height: Height::new(height),
+        round: Round::new(round),
+        value_id: ValueId::new(height),
+        aggregated_signature: AggregatedSignature::new(Vec::new()),
+    };
+
+    Snapshot::new(
+        1,
+        3,
+        Bytes::from(height.to_be_bytes().to_vec()),
+        Bytes::new(),
+        certificate,
+    )
+}
+
+#[test]
+fn snapshot_is_offered_once_enough_peers_agree() {
+    let mut sync = new_sync(2);
+    let (a, b, c) = (PeerId::random(), PeerId::random(), PeerId::random());
+
+    sync.pending.extend([a, b]);
+
+    sync.add_snapshots(a, vec![snapshot(10, 0), snapshot(5, 0)]);
+    assert!(sync.best_snapshot(Height::new(1)).is_none());
+
+    // Snapshots from peers we did not ask are ignored
+    sync.add_snapshots(c, vec![snapshot(10, 0)]);
+    assert!(sync.best_snapshot(Height::new(1)).is_none());
+
+    // Peers agree on the state, even if their certificates differ
+    sync.add_snapshots(b, vec![snapshot(10, 1)]);
+
+    let best = sync.best_snapshot(Height::new(1)).unwrap();
+    assert_eq!(best.snapshot.height(), Height::new(10));
+    assert_eq!(best.peers.len(), 2);
+
+    assert!(sync.best_snapshot(Height::new(11)).is_none());
+}
+
+#[test]
+fn snapshots_per_peer_are_capped() {
+    let mut sync = new_sync(1);
+    let peer = PeerId::random();
+
+    sync.pending.insert(peer);
+    sync.add_snapshots(peer, (1..=20).map(|height| snapshot(height, 0)).collect());
+
+    assert_eq!(sync.available.len(), MAX_SNAPSHOTS_PER_PEER);
+
+    let lowest = 20 - MAX_SNAPSHOTS_PER_PEER as u64;
+    assert!(sync
+        .available
+        .iter()
+        .all(|available| available.snapshot.height() > Height::new(lowest)));
+}
@@ -0,0 +1,144 @@
+use rand::SeedableRng;
+
+use malachitebft_peer::PeerId;
+use malachitebft_test::{Height, TestContext};
+
+use informalsystems_malachitebft_sync::{
+    OutboundRequestId, RangeParams, ScoringParams, SnapshotParams, State, Status,
+};
+
+fn new_state(sync_height: u64) -> State<TestContext> {
+    let params = RangeParams {
+        batch_size: 10,
+        ..RangeParams::default()
+    };
+
+    let mut state = State::new(
+        Box::new(rand::rngs::StdRng::seed_from_u64(0x42)),
+        params,
+        ScoringParams::default(),
+        SnapshotParams::default(),
+    );
+
+    state.sync_height = Height::new(sync_height);
+    state
+}
+
+fn range(start: u64, end: u64) -> (Height, Height) {
+    (Height::new(start), Height::new(end))
+}
+
+#[test]
+fn next_range_skips_pending_ranges() {
+    let mut state = new_state(1);
+    let peer = PeerId::random();
+
+    assert_eq!(state.next_range(), range(1, 10));
+
+    state.store_pending_value_request(
+        OutboundRequestId::new(1),
+        Height::new(1),
+        Height::new(10),
+        peer,
+    );
+    assert_eq!(state.next_range(), range(11, 20));
+
+    // Stops before the next pending range
+    state.store_pending_value_request(
+        OutboundRequestId::new(2),
+        Height::new(15),
+        Height::new(24),
+        peer,
+    );
+    assert_eq!(state.next_range(), range(11, 14));
+
+    state.store_pending_value_request(
+        OutboundRequestId::new(3),
+        Height::new(11),
+        Height::new(14),
+        peer,
+    );
+    assert_eq!(state.next_range(), range(25, 34));
+
+    // Ranges below the sync height are not in the way anymore
+    state.sync_height = Height::new(12);
+    state.prune_pending_value_requests();
+    assert_eq!(state.next_range(), range(25, 34));
+
+    state.sync_height = Height::new(40);
+    state.prune_pending_value_requests();
+    assert!(state.pending_value_requests.is_empty());
+    assert_eq!(state.next_range(), range(40, 49));
+}
+
+#[test]
+fn partial_response_releases_rest_of_range() {
+    let mut state = new_state(1);
+    let peer = PeerId::random();
+    let request_id = OutboundRequestId::new(1);
+
+    state.store_pending_value_request(request_id.clone(), Height::new(1), Height::new(10), peer);
+
+    assert!(state.value_range_received(&request_id, peer, Height::new(1), 4));
+    assert_eq!(state.inflight_value_requests(), 0);
+    assert_eq!(state.next_range(), range(5, 14));
+}
+
+#[test]
+fn response_for_wrong_start_fails_request() {
+    let mut state = new_state(1);
+    let peer = PeerId::random();
+    let request_id = OutboundRequestId::new(1);
+
+    state.store_pending_value_request(request_id.clone(), Height::new(1), Height::new(10), peer);
+
+    // Responses from another peer or for another request are ignored
+    let other = PeerId::random();
+    assert!(!state.value_range_received(&request_id, other, Height::new(1), 10));
+    assert!(!state.value_range_received(&OutboundRequestId::new(2), peer, Height::new(1), 10));
+    assert_eq!(state.inflight_value_requests(), 1);
+
+    assert!(!state.value_range_received(&request_id, peer, Height::new(5), 6));
+
+    // The range is not pending anymore and will be requested again
+    assert!(state.pending_value_requests.is_empty());
+    assert_eq!(state.next_range(), range(1, 10));
+    assert_eq!(state.scores[&peer].consecutive_failures, 1);
+}
+
+fn add_peer(state: &mut State<TestContext>, height: u64) -> PeerId {
+    let peer_id = PeerId::random();
+
+    state.update_status(Status {
+        peer_id,
+        height: Height::new(height),
+        history_min_height: Height::new(1),
+    });
+
+    peer_id
+}
+
+#[test]
+fn peers_tip_height_ignores_outliers() {
+    let mut state = new_state(1);
+    state.tip_height = Height::new(9);
+
+    add_peer(&mut state, 10);
+    assert_eq!(state.peers_tip_height(), Some(Height::new(10)));
+
+    // A single peer claiming to be far ahead
+    let liar = add_peer(&mut state, 1_000_000);
+    add_peer(&mut state, 9);
+    assert_eq!(state.peers_tip_height(), Some(Height::new(10)));
+    assert!(state.is_caught_up());
+
+    // Unless most of our peers are ahead
+    add_peer(&mut state, 50);
+    add_peer(&mut state, 60);
+    assert_eq!(state.peers_tip_height(), Some(Height::new(50)));
+    assert!(!state.is_caught_up());
+
+    // Banned peers are not taken into account
+    while !state.record_failure(liar) {}
+    assert_eq!(state.peers_tip_height(), Some(Height::new(10)));
+}
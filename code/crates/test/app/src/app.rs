@@ -2,12 +2,12 @@ use std::time::Duration;
 
 use eyre::eyre;
 use tokio::time::sleep;
-use tracing::{error, info};
+use tracing::{debug, error, info, warn};
 
 use malachitebft_app_channel::app::streaming::StreamContent;
 use malachitebft_app_channel::app::types::codec::Codec;
 use malachitebft_app_channel::app::types::core::{Round, Validity};
-use malachitebft_app_channel::app::types::sync::RawDecidedValue;
+use malachitebft_app_channel::app::types::sync::{ApplySnapshotChunkResult, RawDecidedValue};
 use malachitebft_app_channel::app::types::ProposedValue;
 use malachitebft_app_channel::{AppMsg, Channels, ConsensusMsg, NetworkMsg};
 use malachitebft_test::codec::proto::ProtobufCodec;
@@ -70,6 +70,7 @@ pub async fn run(
                 height,
                 round,
                 timeout: _,
+                max_block_size: _,
                 reply,
             } => {
                 // NOTE: We can ignore the timeout as we are building the value right away.
@@ -273,6 +274,16 @@ pub async fn run(
                 }
             }
 
+            // Evidence of a validator equivocating, only logged by the test app
+            AppMsg::MisbehaviorEvidence { evidence } => {
+                warn!(
+                    height = %evidence.height(),
+                    round = %evidence.round(),
+                    validator = %evidence.validator_address(),
+                    "Received evidence of misbehavior: {evidence:?}"
+                );
+            }
+
             AppMsg::PeerJoined { peer_id } => {
                 info!(%peer_id, "Peer joined our local view of network");
 
@@ -287,12 +298,85 @@ pub async fn run(
                 state.peers.remove(&peer_id);
             }
 
+            AppMsg::SyncProgress { progress } => {
+                debug!(
+                    tip_height = %progress.tip_height,
+                    peers_tip_height = ?progress.peers_tip_height,
+                    eta = ?progress.eta,
+                    caught_up = %progress.caught_up,
+                    "Sync progress"
+                );
+            }
+
+            // When a peer is bootstrapping from a snapshot, the engine asks us for the snapshots
+            // we can serve, and then for the chunks of the one the peer picked.
+            AppMsg::ListSnapshots { reply } => {
+                let snapshots = state.list_snapshots().await;
+
+                if reply.send(snapshots).is_err() {
+                    error!("Failed to send ListSnapshots reply");
+                }
+            }
+
+            AppMsg::LoadSnapshotChunk {
+                height,
+                format,
+                index,
+                reply,
+            } => {
+                let chunk = state.load_snapshot_chunk(height, format, index).await;
+
+                if reply.send(chunk).is_err() {
+                    error!("Failed to send LoadSnapshotChunk reply");
+                }
+            }
+
+            // When we are the one bootstrapping from a snapshot, the engine offers us
+            // the best snapshot available from our peers, which we only accept if its
+            // certificate is valid, and then feeds us its chunks in order.
+            AppMsg::OfferSnapshot { snapshot, reply } => {
+                info!(height = %snapshot.height(), chunks = %snapshot.chunks, "Offered snapshot");
+
+                let result = state.offer_snapshot(snapshot);
+
+                if reply.send(result).is_err() {
+                    error!("Failed to send OfferSnapshot reply");
+                }
+            }
+
+            AppMsg::ApplySnapshotChunk {
+                index,
+                chunk,
+                from,
+                reply,
+            } => {
+                info!(%index, %from, "Applying snapshot chunk");
+
+                let result = match state.apply_snapshot_chunk(index, chunk).await {
+                    Ok(result) => result,
+                    Err(e) => {
+                        error!(%index, "Failed to apply snapshot chunk: {e}");
+                        ApplySnapshotChunkResult::RejectSnapshot
+                    }
+                };
+
+                if reply.send(result).is_err() {
+                    error!("Failed to send ApplySnapshotChunk reply");
+                }
+            }
+
             AppMsg::ExtendVote { reply, .. } => {
                 if reply.send(None).is_err() {
                     error!("Failed to send ExtendVote reply");
                 }
             }
 
+            AppMsg::ValidateProposal { reply, .. } => {
+                if reply.send(Validity::Valid).is_err() {
+                    error!("Failed to send ValidateProposal reply");
+                }
+            }
+
             AppMsg::VerifyVoteExtension { reply, .. } => {
                 if reply.send(Ok(())).is_err() {
                     error!("Failed to send VerifyVoteExtension reply");
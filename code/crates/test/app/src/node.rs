@@ -136,7 +136,7 @@ impl Node for App {
         let genesis = self.load_genesis()?;
 
         let (mut channels, engine_handle) = malachitebft_app_channel::start_engine(
-            ctx,
+            ctx.clone(),
             codec,
             self.clone(),
             self.config.clone(),
@@ -14,7 +14,12 @@ use malachitebft_app_channel::app::consensus::ProposedValue;
 use malachitebft_app_channel::app::streaming::{StreamContent, StreamId, StreamMessage};
 use malachitebft_app_channel::app::types::codec::Codec;
 use malachitebft_app_channel::app::types::config::Config; // TODO: Move into test app
-use malachitebft_app_channel::app::types::core::{CommitCertificate, Round, Validity};
+use malachitebft_app_channel::app::types::core::{
+    CommitCertificate, Round, SigningProviderExt, ThresholdParams, Validity,
+};
+use malachitebft_app_channel::app::types::sync::{
+    ApplySnapshotChunkResult, OfferSnapshotResult, Snapshot,
+};
 use malachitebft_app_channel::app::types::{LocallyProposedValue, PeerId};
 use malachitebft_test::codec::proto::ProtobufCodec;
 use malachitebft_test::{
@@ -28,6 +33,26 @@ use crate::streaming::{PartStreamsMap, ProposalParts};
 /// Number of historical values to keep in the store
 const HISTORY_LENGTH: u64 = 500;
 
+/// Format of the snapshots taken by this application
+const SNAPSHOT_FORMAT: u32 = 1;
+
+/// Snapshots are taken at the heights which are a multiple of this interval,
+/// so that peers at slightly different heights offer some of the same snapshots.
+const SNAPSHOT_INTERVAL: u64 = 5;
+
+/// Number of snapshots offered to peers, the most recent ones
+const SNAPSHOTS_KEPT: u64 = 2;
+
+/// Size of the chunks snapshots are split into.
+/// Kept very small so that restoring a snapshot spans several chunks.
+const SNAPSHOT_CHUNK_SIZE: usize = 4;
+
+/// A snapshot being restored, with the chunks applied so far
+struct SnapshotRestore {
+    snapshot: Snapshot<TestContext>,
+    data: Vec<u8>,
+}
+
 /// Represents the internal state of the application node
 /// Contains information about current height, round, proposals and blocks
 pub struct State {
@@ -43,6 +68,7 @@ pub struct State {
 
     signing_provider: Ed25519Provider,
     streams_map: PartStreamsMap,
+    restore: Option<SnapshotRestore>,
     rng: StdRng,
 }
 
@@ -80,6 +106,7 @@ impl State {
             current_round: Round::new(0),
             current_proposer: None,
             streams_map: PartStreamsMap::new(),
+            restore: None,
             rng: StdRng::seed_from_u64(seed_from_address(&address)),
             peers: HashSet::new(),
         }
@@ -206,6 +233,146 @@ impl State {
         Ok(())
     }
 
+    /// Returns the snapshots of our state at the most recent snapshot heights, if any.
+    ///
+    /// As the state of this application is just the last decided value,
+    /// the snapshot data is that value, and its certificate is the one the value was decided with.
+    pub async fn list_snapshots(&self) -> Vec<Snapshot<TestContext>> {
+        let Some(height) = self.store.max_decided_value_height().await else {
+            return Vec::new();
+        };
+
+        let latest = height.as_u64() - height.as_u64() % SNAPSHOT_INTERVAL;
+
+        let heights = (0..SNAPSHOTS_KEPT)
+            .filter_map(|i| latest.checked_sub(i * SNAPSHOT_INTERVAL))
+            .filter(|&height| height > 0);
+
+        let mut snapshots = Vec::new();
+
+        for height in heights {
+            let Some((decided_value, data)) = self.snapshot_data(Height::new(height)).await else {
+                continue;
+            };
+
+            let chunks = data.len().div_ceil(SNAPSHOT_CHUNK_SIZE) as u32;
+            let hash = Bytes::from(sha3::Keccak256::digest(&data).to_vec());
+
+            snapshots.push(Snapshot::new(
+                SNAPSHOT_FORMAT,
+                chunks,
+                hash,
+                Bytes::new(),
+                decided_value.certificate,
+            ));
+        }
+
+        snapshots
+    }
+
+    /// Returns the chunk at the given index of the snapshot taken at the given height
+    pub async fn load_snapshot_chunk(
+        &self,
+        height: Height,
+        format: u32,
+        index: u32,
+    ) -> Option<Bytes> {
+        if format != SNAPSHOT_FORMAT {
+            return None;
+        }
+
+        let (_, data) = self.snapshot_data(height).await?;
+
+        let start = index as usize * SNAPSHOT_CHUNK_SIZE;
+        if start >= data.len() {
+            return None;
+        }
+
+        let end = (start + SNAPSHOT_CHUNK_SIZE).min(data.len());
+        Some(data.slice(start..end))
+    }
+
+    async fn snapshot_data(&self, height: Height) -> Option<(DecidedValue, Bytes)> {
+        let decided_value = self.get_decided_value(height).await?;
+        let data = ProtobufCodec.encode(&decided_value.value).ok()?;
+        Some((decided_value, data))
+    }
+
+    /// Checks that a snapshot offered by our peers was taken at a height decided by our validators,
+    /// and if so, gets ready to restore it.
+    pub fn offer_snapshot(&mut self, snapshot: Snapshot<TestContext>) -> OfferSnapshotResult {
+        if snapshot.format != SNAPSHOT_FORMAT || snapshot.chunks == 0 {
+            return OfferSnapshotResult::Reject;
+        }
+
+        if let Err(e) = self.signing_provider.verify_certificate(
+            &snapshot.certificate,
+            self.get_validator_set(),
+            ThresholdParams::default(),
+        ) {
+            error!(height = %snapshot.height(), "Invalid certificate for snapshot: {e}");
+            return OfferSnapshotResult::Reject;
+        }
+
+        self.restore = Some(SnapshotRestore {
+            snapshot,
+            data: Vec::new(),
+        });
+
+        OfferSnapshotResult::Accept
+    }
+
+    /// Applies the next chunk of the snapshot being restored.
+    /// Once all chunks are applied, stores the decided value from the snapshot and moves to the next height.
+    pub async fn apply_snapshot_chunk(
+        &mut self,
+        index: u32,
+        chunk: Bytes,
+    ) -> eyre::Result<ApplySnapshotChunkResult> {
+        let Some(restore) = self.restore.as_mut() else {
+            return Ok(ApplySnapshotChunkResult::RejectSnapshot);
+        };
+
+        if index as usize * SNAPSHOT_CHUNK_SIZE != restore.data.len() {
+            return Ok(ApplySnapshotChunkResult::Retry);
+        }
+
+        restore.data.extend_from_slice(&chunk);
+
+        if index + 1 < restore.snapshot.chunks {
+            return Ok(ApplySnapshotChunkResult::Accept);
+        }
+
+        let SnapshotRestore { snapshot, data } = self.restore.take().unwrap();
+
+        if sha3::Keccak256::digest(&data).as_slice() != snapshot.hash.as_ref() {
+            error!(height = %snapshot.height(), "Snapshot hash mismatch");
+            return Ok(ApplySnapshotChunkResult::RejectSnapshot);
+        }
+
+        let value: Value = match ProtobufCodec.decode(Bytes::from(data)) {
+            Ok(value) => value,
+            Err(e) => {
+                error!(height = %snapshot.height(), "Failed to decode snapshot: {e}");
+                return Ok(ApplySnapshotChunkResult::RejectSnapshot);
+            }
+        };
+
+        if value.id() != snapshot.certificate.value_id {
+            error!(height = %snapshot.height(), "Snapshot does not match its certificate");
+            return Ok(ApplySnapshotChunkResult::RejectSnapshot);
+        }
+
+        self.store
+            .store_decided_value(&snapshot.certificate, value)
+            .await?;
+
+        self.current_height = snapshot.height().increment();
+        self.current_round = Round::new(0);
+
+        Ok(ApplySnapshotChunkResult::Accept)
+    }
+
     /// Retrieves a previously built proposal value for the given height
     pub async fn get_previously_built_value(
         &self,
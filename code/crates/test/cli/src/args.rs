@@ -17,6 +17,7 @@ use crate::cmd::distributed_testnet::DistributedTestnetCmd;
 use crate::cmd::init::InitCmd;
 use crate::cmd::start::StartCmd;
 use crate::cmd::testnet::TestnetCmd;
+use crate::cmd::wal::WalCmd;
 use crate::error::Error;
 
 const APP_FOLDER: &str = ".malachite";
@@ -56,6 +57,9 @@ pub enum Commands {
 
     /// Generate distributed testnet configuration
     DistributedTestnet(DistributedTestnetCmd),
+
+    /// Inspect, verify or truncate the write-ahead log
+    Wal(WalCmd),
 }
 
 impl Default for Commands {
@@ -113,9 +117,12 @@ impl Args {
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
     use malachitebft_config::LogLevel;
 
     use super::*;
+    use crate::cmd::wal::WalSubcommand;
 
     #[test]
     fn parse_args() {
@@ -136,6 +143,29 @@ mod tests {
         assert!(matches!(args.command, Commands::Start(_)));
     }
 
+    #[test]
+    fn parse_wal_args() {
+        let args = Args::parse_from(["test", "--home", "/tmp", "wal", "truncate", "--after", "3"]);
+        let Commands::Wal(cmd) = &args.command else {
+            panic!("expected wal command");
+        };
+        assert_eq!(cmd.command, WalSubcommand::Truncate { after: 3 });
+        assert_eq!(
+            cmd.wal_file(&args.get_home_dir().unwrap()),
+            PathBuf::from("/tmp/wal/consensus.wal")
+        );
+
+        let args = Args::parse_from(["test", "wal", "verify", "--file", "/data/consensus.wal"]);
+        let Commands::Wal(cmd) = &args.command else {
+            panic!("expected wal command");
+        };
+        assert_eq!(cmd.command, WalSubcommand::Verify);
+        assert_eq!(
+            cmd.wal_file(Path::new("/tmp")),
+            PathBuf::from("/data/consensus.wal")
+        );
+    }
+
     #[test]
     fn parse_home_path() {
         let args = Args::parse_from(["test", "start", "--home", "/tmp"]);
@@ -200,6 +200,7 @@ where
 const CONSENSUS_BASE_PORT: usize = 27000;
 const MEMPOOL_BASE_PORT: usize = 28000;
 const METRICS_BASE_PORT: usize = 29000;
+const RPC_BASE_PORT: usize = 30000;
 
 /// Generate configuration for node "index" out of "total" number of nodes.
 #[allow(clippy::too_many_arguments)]
@@ -222,6 +223,7 @@ fn generate_distributed_config(
     let consensus_port = CONSENSUS_BASE_PORT + (index / machines.len());
     let mempool_port = MEMPOOL_BASE_PORT + (index / machines.len());
     let metrics_port = METRICS_BASE_PORT + (index / machines.len());
+    let rpc_port = RPC_BASE_PORT + (index / machines.len());
 
     Config {
         moniker: format!("test-{}", index),
@@ -270,6 +272,8 @@ fn generate_distributed_config(
                 transport,
                 ..Default::default()
             },
+            wal: WalConfig::default(),
+            synchrony: None,
         },
         mempool: MempoolConfig {
             p2p: P2pConfig {
@@ -294,11 +298,16 @@ fn generate_distributed_config(
             enabled: false,
             status_update_interval: Duration::from_secs(0),
             request_timeout: Duration::from_secs(0),
+            ..Default::default()
         },
         metrics: MetricsConfig {
             enabled: true,
             listen_addr: format!("{machine}:{metrics_port}").parse().unwrap(),
         },
+        rpc: RpcConfig {
+            enabled: true,
+            listen_addr: format!("{machine}:{rpc_port}").parse().unwrap(),
+        },
         logging,
         runtime,
         test: TestConfig::default(),
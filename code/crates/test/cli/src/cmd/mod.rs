@@ -2,3 +2,4 @@ pub mod distributed_testnet;
 pub mod init;
 pub mod start;
 pub mod testnet;
+pub mod wal;
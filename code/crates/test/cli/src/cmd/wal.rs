@@ -0,0 +1,228 @@
+//! WAL command, to inspect the write-ahead log of a node while it is not running
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{self, eyre, Context as _};
+use serde_json::{json, Value};
+
+use malachitebft_app::consensus::SignedConsensusMsg;
+use malachitebft_app::wal::{WalCodec, WalEntry};
+use malachitebft_core_types::{
+    Context, Height, NilOrVal, Proposal, SigningScheme, Value as _, Vote,
+};
+use malachitebft_wal::{RawEntry, SegmentOptions, SegmentedLog};
+
+#[derive(Parser, Debug, Clone, PartialEq)]
+pub struct WalCmd {
+    /// Path to the WAL file (default: `<HOME_DIR>/wal/consensus.wal`)
+    #[clap(long, global = true, value_name = "WAL_FILE")]
+    pub file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: WalSubcommand,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum WalSubcommand {
+    /// Print the header and entries of the WAL as JSON, one object per line
+    Dump,
+
+    /// Check the integrity of all entries of the WAL and report the corrupted ones
+    Verify,
+
+    /// Discard all entries of the WAL past the first N ones
+    Truncate {
+        /// Number of entries to keep
+        #[clap(long, value_name = "N")]
+        after: usize,
+    },
+}
+
+impl WalCmd {
+    /// Path of the WAL file to operate on, given the home directory of the node
+    pub fn wal_file(&self, home_dir: &Path) -> PathBuf {
+        match &self.file {
+            Some(file) => file.clone(),
+            None => home_dir.join("wal").join("consensus.wal"),
+        }
+    }
+
+    /// Execute the wal command, decoding entries with the given codec.
+    ///
+    /// The WAL is opened for reading only when dumping or verifying it, which leaves it untouched.
+    /// Truncating it also discards any partially written entry at its end,
+    /// as would happen when the node restarts.
+    pub fn run<Ctx, Codec>(&self, home_dir: &Path, codec: &Codec) -> eyre::Result<()>
+    where
+        Ctx: Context,
+        Codec: WalCodec<Ctx>,
+    {
+        let path = self.wal_file(home_dir);
+
+        if !path.exists() {
+            return Err(eyre!("WAL file not found: {}", path.display()));
+        }
+
+        let options = SegmentOptions::default();
+
+        let log = match &self.command {
+            WalSubcommand::Dump | WalSubcommand::Verify => {
+                SegmentedLog::open_read_only_with(&path, options)
+            }
+            WalSubcommand::Truncate { .. } => SegmentedLog::open_with(&path, options),
+        };
+
+        let mut log = log.wrap_err_with(|| format!("Failed to open WAL at {}", path.display()))?;
+
+        match &self.command {
+            WalSubcommand::Dump => dump(&mut log, codec),
+            WalSubcommand::Verify => verify(&mut log, codec),
+            WalSubcommand::Truncate { after } => truncate(&mut log, *after),
+        }
+    }
+}
+
+fn dump<Ctx, Codec>(log: &mut SegmentedLog, codec: &Codec) -> eyre::Result<()>
+where
+    Ctx: Context,
+    Codec: WalCodec<Ctx>,
+{
+    let entries = log.raw_entries()?;
+
+    print_json(header_json(log, entries.len()));
+
+    for (index, raw) in entries.iter().enumerate() {
+        let mut json = raw_entry_json(index, raw);
+
+        json["entry"] = if raw.is_valid() {
+            match WalEntry::<Ctx>::decode(codec, raw.data.as_slice()) {
+                Ok(entry) => entry_json(&entry),
+                Err(e) => json!({ "error": format!("Failed to decode entry: {e}") }),
+            }
+        } else {
+            Value::Null
+        };
+
+        print_json(json);
+    }
+
+    Ok(())
+}
+
+fn verify<Ctx, Codec>(log: &mut SegmentedLog, codec: &Codec) -> eyre::Result<()>
+where
+    Ctx: Context,
+    Codec: WalCodec<Ctx>,
+{
+    let entries = log.raw_entries()?;
+    let mut corrupted = 0;
+
+    for (index, raw) in entries.iter().enumerate() {
+        let error = match raw.actual_crc {
+            None => Some("Failed to decompress entry".to_string()),
+            Some(_) if !raw.is_valid() => Some("CRC mismatch".to_string()),
+            Some(_) => WalEntry::<Ctx>::decode(codec, raw.data.as_slice())
+                .err()
+                .map(|e| format!("Failed to decode entry: {e}")),
+        };
+
+        if let Some(error) = error {
+            corrupted += 1;
+
+            let mut json = raw_entry_json(index, raw);
+            json["error"] = json!(error);
+            print_json(json);
+        }
+    }
+
+    let mut summary = header_json(log, entries.len());
+    summary["corrupted"] = json!(corrupted);
+    print_json(summary);
+
+    if corrupted > 0 {
+        return Err(eyre!(
+            "Found {corrupted} corrupted entries out of {}",
+            entries.len()
+        ));
+    }
+
+    Ok(())
+}
+
+fn truncate(log: &mut SegmentedLog, after: usize) -> eyre::Result<()> {
+    let before = log.len();
+
+    log.truncate(after)?;
+
+    print_json(json!({
+        "truncated": before.saturating_sub(log.len()),
+        "entries": log.len(),
+    }));
+
+    Ok(())
+}
+
+fn print_json(json: Value) {
+    println!("{json}");
+}
+
+fn header_json(log: &SegmentedLog, entries: usize) -> Value {
+    json!({
+        "path": log.path().display().to_string(),
+        "version": log.version() as u32,
+        "compression": format!("{:?}", log.compression()),
+        "sequence": log.sequence(),
+        "entries": entries,
+    })
+}
+
+fn raw_entry_json(index: usize, raw: &RawEntry) -> Value {
+    json!({
+        "index": index,
+        "offset": raw.offset,
+        "length": raw.length,
+        "compressed": raw.is_compressed,
+        "crc": format!("{:08x}", raw.crc),
+        "crc_valid": raw.is_valid(),
+    })
+}
+
+fn entry_json<Ctx: Context>(entry: &WalEntry<Ctx>) -> Value {
+    match entry {
+        WalEntry::ConsensusMsg(SignedConsensusMsg::Vote(vote)) => json!({
+            "type": "vote",
+            "vote_type": format!("{:?}", vote.vote_type()),
+            "height": vote.height().as_u64(),
+            "round": vote.round().as_i64(),
+            "value": match vote.value() {
+                NilOrVal::Nil => Value::Null,
+                NilOrVal::Val(id) => json!(id.to_string()),
+            },
+            "validator": vote.validator_address().to_string(),
+            "signature": signature_hex::<Ctx>(&vote.signature),
+        }),
+
+        WalEntry::ConsensusMsg(SignedConsensusMsg::Proposal(proposal)) => json!({
+            "type": "proposal",
+            "height": proposal.height().as_u64(),
+            "round": proposal.round().as_i64(),
+            "pol_round": proposal.pol_round().as_i64(),
+            "value": proposal.value().id().to_string(),
+            "proposer": proposal.validator_address().to_string(),
+            "signature": signature_hex::<Ctx>(&proposal.signature),
+        }),
+
+        WalEntry::Timeout(timeout) => json!({
+            "type": "timeout",
+            "kind": format!("{:?}", timeout.kind),
+            "round": timeout.round.as_i64(),
+        }),
+    }
+}
+
+fn signature_hex<Ctx: Context>(
+    signature: &<Ctx::SigningScheme as SigningScheme>::Signature,
+) -> String {
+    hex::encode(Ctx::SigningScheme::encode_signature(signature))
+}
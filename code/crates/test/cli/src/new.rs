@@ -17,6 +17,7 @@ const MAX_VOTING_POWER: u64 = 1;
 const CONSENSUS_BASE_PORT: usize = 27000;
 const MEMPOOL_BASE_PORT: usize = 28000;
 const METRICS_BASE_PORT: usize = 29000;
+const RPC_BASE_PORT: usize = 30000;
 
 /// Generate private keys. Random or deterministic for different use-cases.
 pub fn generate_private_keys<N>(
@@ -78,6 +79,7 @@ pub fn generate_config(
     let consensus_port = CONSENSUS_BASE_PORT + index;
     let mempool_port = MEMPOOL_BASE_PORT + index;
     let metrics_port = METRICS_BASE_PORT + index;
+    let rpc_port = RPC_BASE_PORT + index;
 
     Config {
         moniker: format!("test-{}", index),
@@ -121,6 +123,8 @@ pub fn generate_config(
                 transport,
                 ..Default::default()
             },
+            wal: WalConfig::default(),
+            synchrony: None,
         },
         mempool: MempoolConfig {
             p2p: P2pConfig {
@@ -151,6 +155,10 @@ pub fn generate_config(
             enabled: true,
             listen_addr: format!("127.0.0.1:{metrics_port}").parse().unwrap(),
         },
+        rpc: RpcConfig {
+            enabled: true,
+            listen_addr: format!("127.0.0.1:{rpc_port}").parse().unwrap(),
+        },
         logging,
         runtime,
         test: TestConfig::default(),
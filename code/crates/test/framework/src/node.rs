@@ -160,6 +160,18 @@ where
         })
     }
 
+    pub fn expect_caught_up(&mut self) -> &mut Self {
+        self.on_event(move |event, _| {
+            let Event::CaughtUp(height) = event else {
+                return Ok(HandlerResult::WaitForNextEvent);
+            };
+
+            info!("Caught up with peers at height {height}");
+
+            Ok(HandlerResult::ContinueTest)
+        })
+    }
+
     pub fn expect_vote_rebroadcast(&mut self, at_height: u64) -> &mut Self {
         self.on_event(move |event, _| {
             let Event::Rebroadcast(msg) = event else {
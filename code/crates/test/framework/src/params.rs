@@ -1,11 +1,14 @@
 use std::time::Duration;
 
 use bytesize::ByteSize;
-use malachitebft_config::{Config, PubSubProtocol, ValuePayload};
+use malachitebft_config::{Config, PubSubProtocol, SyncConfig, ValuePayload};
 
 #[derive(Copy, Clone, Debug)]
 pub struct TestParams {
     pub enable_sync: bool,
+    pub enable_snapshot_sync: bool,
+    pub sync_batch_size: u64,
+    pub sync_parallel_requests: usize,
     pub protocol: PubSubProtocol,
     pub block_size: ByteSize,
     pub tx_size: ByteSize,
@@ -20,6 +23,9 @@ impl Default for TestParams {
     fn default() -> Self {
         Self {
             enable_sync: false,
+            enable_snapshot_sync: false,
+            sync_batch_size: SyncConfig::default().batch_size,
+            sync_parallel_requests: SyncConfig::default().parallel_requests,
             protocol: PubSubProtocol::default(),
             block_size: ByteSize::mib(1),
             tx_size: ByteSize::kib(1),
@@ -35,6 +41,9 @@ impl Default for TestParams {
 impl TestParams {
     pub fn apply_to_config(&self, config: &mut Config) {
         config.sync.enabled = self.enable_sync;
+        config.sync.snapshot_sync = self.enable_snapshot_sync;
+        config.sync.batch_size = self.sync_batch_size;
+        config.sync.parallel_requests = self.sync_parallel_requests;
         config.consensus.p2p.protocol = self.protocol;
         config.consensus.timeouts.timeout_step = self.timeout_step;
         config.test.value_payload = self.value_payload;
@@ -2,11 +2,14 @@ use bytes::Bytes;
 use malachitebft_codec::Codec;
 
 use malachitebft_core_consensus::SignedConsensusMsg;
+use malachitebft_core_types::Evidence;
 use malachitebft_engine::util::streaming::StreamMessage;
 use malachitebft_sync::{Request, Response, Status};
 
 mod raw;
-use raw::{RawRequest, RawResponse, RawSignedConsensusMsg, RawStatus, RawStreamMessage};
+use raw::{
+    RawEvidence, RawRequest, RawResponse, RawSignedConsensusMsg, RawStatus, RawStreamMessage,
+};
 
 use crate::{ProposalPart, TestContext, Value};
 
@@ -49,6 +52,18 @@ impl Codec<SignedConsensusMsg<TestContext>> for JsonCodec {
     }
 }
 
+impl Codec<Evidence<TestContext>> for JsonCodec {
+    type Error = serde_json::Error;
+
+    fn decode(&self, bytes: Bytes) -> Result<Evidence<TestContext>, Self::Error> {
+        serde_json::from_slice::<RawEvidence>(&bytes).map(Into::into)
+    }
+
+    fn encode(&self, msg: &Evidence<TestContext>) -> Result<Bytes, Self::Error> {
+        serde_json::to_vec(&RawEvidence::from(msg.clone())).map(Bytes::from)
+    }
+}
+
 impl Codec<StreamMessage<ProposalPart>> for JsonCodec {
     type Error = serde_json::Error;
 
@@ -3,14 +3,15 @@ use bytes::Bytes;
 use ed25519_consensus::Signature;
 use malachitebft_core_consensus::SignedConsensusMsg;
 use malachitebft_core_types::{
-    AggregatedSignature, CommitCertificate, CommitSignature, Extension, Round, SignedExtension,
-    SignedProposal, SignedVote, VoteSet,
+    AggregatedSignature, CommitCertificate, CommitSignature, Evidence, Extension, Round,
+    SignedExtension, SignedProposal, SignedVote, VoteSet,
 };
 use malachitebft_engine::util::streaming::{StreamContent, StreamMessage};
 use malachitebft_proto::Protobuf;
 use malachitebft_sync::{
-    DecidedValue, PeerId, Request, Response, Status, ValueRequest, ValueResponse, VoteSetRequest,
-    VoteSetResponse,
+    DecidedValue, PeerId, Request, Response, Snapshot, SnapshotChunkRequest,
+    SnapshotChunkResponse, SnapshotListRequest, SnapshotListResponse, Status, ValueRangeRequest,
+    ValueRangeResponse, ValueRequest, ValueResponse, VoteSetRequest, VoteSetResponse,
 };
 use serde::{Deserialize, Serialize};
 
@@ -59,6 +60,68 @@ impl From<RawSignedConsensusMsg> for SignedConsensusMsg<TestContext> {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub enum RawEvidence {
+    DoubleVote {
+        first: RawSignedMessage,
+        second: RawSignedMessage,
+    },
+    DoubleProposal {
+        first: RawSignedMessage,
+        second: RawSignedMessage,
+    },
+}
+
+impl From<Evidence<TestContext>> for RawEvidence {
+    fn from(value: Evidence<TestContext>) -> Self {
+        let raw_vote = |vote: SignedVote<TestContext>| RawSignedMessage {
+            message: vote.message.to_bytes(),
+            signature: *vote.signature.inner(),
+        };
+
+        let raw_proposal = |proposal: SignedProposal<TestContext>| RawSignedMessage {
+            message: proposal.message.to_bytes(),
+            signature: *proposal.signature.inner(),
+        };
+
+        match value {
+            Evidence::DoubleVote { first, second } => Self::DoubleVote {
+                first: raw_vote(first),
+                second: raw_vote(second),
+            },
+            Evidence::DoubleProposal { first, second } => Self::DoubleProposal {
+                first: raw_proposal(first),
+                second: raw_proposal(second),
+            },
+        }
+    }
+}
+
+impl From<RawEvidence> for Evidence<TestContext> {
+    fn from(value: RawEvidence) -> Self {
+        let vote = |raw: RawSignedMessage| SignedVote {
+            message: Vote::from_bytes(&raw.message).unwrap(),
+            signature: raw.signature.into(),
+        };
+
+        let proposal = |raw: RawSignedMessage| SignedProposal {
+            message: Proposal::from_bytes(&raw.message).unwrap(),
+            signature: raw.signature.into(),
+        };
+
+        match value {
+            RawEvidence::DoubleVote { first, second } => Evidence::DoubleVote {
+                first: vote(first),
+                second: vote(second),
+            },
+            RawEvidence::DoubleProposal { first, second } => Evidence::DoubleProposal {
+                first: proposal(first),
+                second: proposal(second),
+            },
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RawStreamMessage {
     pub stream_id: u64,
@@ -130,6 +193,13 @@ pub struct ValueRawRequest {
     pub height: Height,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct ValueRangeRawRequest {
+    pub start: Height,
+    pub end: Height,
+    pub max_bytes: u64,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct VoteSetRawRequest {
     pub height: Height,
@@ -137,10 +207,20 @@ pub struct VoteSetRawRequest {
     pub round: Round,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotChunkRawRequest {
+    pub height: Height,
+    pub format: u32,
+    pub index: u32,
+}
+
 #[derive(Serialize, Deserialize)]
 pub enum RawRequest {
     SyncRequest(ValueRawRequest),
     VoteSetRequest(VoteSetRawRequest),
+    SyncRangeRequest(ValueRangeRawRequest),
+    SnapshotListRequest,
+    SnapshotChunkRequest(SnapshotChunkRawRequest),
 }
 
 impl From<Request<TestContext>> for RawRequest {
@@ -149,10 +229,25 @@ impl From<Request<TestContext>> for RawRequest {
             Request::ValueRequest(block_request) => Self::SyncRequest(ValueRawRequest {
                 height: block_request.height,
             }),
+            Request::ValueRangeRequest(range_request) => {
+                Self::SyncRangeRequest(ValueRangeRawRequest {
+                    start: range_request.start,
+                    end: range_request.end,
+                    max_bytes: range_request.max_bytes,
+                })
+            }
             Request::VoteSetRequest(vote_set_request) => Self::VoteSetRequest(VoteSetRawRequest {
                 height: vote_set_request.height,
                 round: vote_set_request.round,
             }),
+            Request::SnapshotListRequest(_) => Self::SnapshotListRequest,
+            Request::SnapshotChunkRequest(chunk_request) => {
+                Self::SnapshotChunkRequest(SnapshotChunkRawRequest {
+                    height: chunk_request.height,
+                    format: chunk_request.format,
+                    index: chunk_request.index,
+                })
+            }
         }
     }
 }
@@ -163,12 +258,27 @@ impl From<RawRequest> for Request<TestContext> {
             RawRequest::SyncRequest(block_raw_request) => Self::ValueRequest(ValueRequest {
                 height: block_raw_request.height,
             }),
+            RawRequest::SyncRangeRequest(range_raw_request) => {
+                Self::ValueRangeRequest(ValueRangeRequest {
+                    start: range_raw_request.start,
+                    end: range_raw_request.end,
+                    max_bytes: range_raw_request.max_bytes,
+                })
+            }
             RawRequest::VoteSetRequest(vote_set_raw_request) => {
                 Self::VoteSetRequest(VoteSetRequest {
                     height: vote_set_raw_request.height,
                     round: vote_set_raw_request.round,
                 })
             }
+            RawRequest::SnapshotListRequest => Self::SnapshotListRequest(SnapshotListRequest),
+            RawRequest::SnapshotChunkRequest(chunk_raw_request) => {
+                Self::SnapshotChunkRequest(SnapshotChunkRequest {
+                    height: chunk_raw_request.height,
+                    format: chunk_raw_request.format,
+                    index: chunk_raw_request.index,
+                })
+            }
         }
     }
 }
@@ -211,6 +321,78 @@ pub struct RawSyncedValue {
     pub certificate: RawCommitCertificate,
 }
 
+impl From<CommitCertificate<TestContext>> for RawCommitCertificate {
+    fn from(certificate: CommitCertificate<TestContext>) -> Self {
+        Self {
+            height: certificate.height,
+            round: certificate.round,
+            value_id: certificate.value_id,
+            aggregated_signature: RawAggregatedSignature {
+                signatures: certificate
+                    .aggregated_signature
+                    .signatures
+                    .iter()
+                    .map(|sig| RawCommitSignature {
+                        address: sig.address,
+                        signature: *sig.signature.inner(),
+                        extension: sig.extension.as_ref().map(|ext| RawSignedExtension {
+                            extension: RawExtension {
+                                data: ext.message.data.clone(),
+                            },
+                            signature: *ext.signature.inner(),
+                        }),
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl From<RawCommitCertificate> for CommitCertificate<TestContext> {
+    fn from(certificate: RawCommitCertificate) -> Self {
+        Self {
+            height: certificate.height,
+            round: certificate.round,
+            value_id: certificate.value_id,
+            aggregated_signature: AggregatedSignature {
+                signatures: certificate
+                    .aggregated_signature
+                    .signatures
+                    .iter()
+                    .map(|sig| CommitSignature {
+                        address: sig.address,
+                        signature: sig.signature.into(),
+                        extension: sig.extension.as_ref().map(|ext| SignedExtension {
+                            message: Extension {
+                                data: ext.extension.data.clone(),
+                            },
+                            signature: ext.signature.into(),
+                        }),
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl From<DecidedValue<TestContext>> for RawSyncedValue {
+    fn from(block: DecidedValue<TestContext>) -> Self {
+        Self {
+            value_bytes: block.value_bytes,
+            certificate: block.certificate.into(),
+        }
+    }
+}
+
+impl From<RawSyncedValue> for DecidedValue<TestContext> {
+    fn from(block: RawSyncedValue) -> Self {
+        Self {
+            value_bytes: block.value_bytes,
+            certificate: block.certificate.into(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ValueRawResponse {
     pub height: Height,
@@ -221,32 +403,7 @@ impl From<ValueResponse<TestContext>> for ValueRawResponse {
     fn from(value: ValueResponse<TestContext>) -> Self {
         Self {
             height: value.height,
-            block: value.value.map(|block| RawSyncedValue {
-                value_bytes: block.value_bytes,
-                certificate: RawCommitCertificate {
-                    height: block.certificate.height,
-                    round: block.certificate.round,
-                    value_id: block.certificate.value_id,
-                    aggregated_signature: RawAggregatedSignature {
-                        signatures: block
-                            .certificate
-                            .aggregated_signature
-                            .signatures
-                            .iter()
-                            .map(|sig| RawCommitSignature {
-                                address: sig.address,
-                                signature: *sig.signature.inner(),
-                                extension: sig.extension.as_ref().map(|ext| RawSignedExtension {
-                                    extension: RawExtension {
-                                        data: ext.message.data.clone(),
-                                    },
-                                    signature: *ext.signature.inner(),
-                                }),
-                            })
-                            .collect(),
-                    },
-                },
-            }),
+            block: value.value.map(Into::into),
         }
     }
 }
@@ -255,32 +412,31 @@ impl From<ValueRawResponse> for ValueResponse<TestContext> {
     fn from(value: ValueRawResponse) -> Self {
         Self {
             height: value.height,
-            value: value.block.map(|block| DecidedValue {
-                value_bytes: block.value_bytes,
-                certificate: CommitCertificate {
-                    height: block.certificate.height,
-                    round: block.certificate.round,
-                    value_id: block.certificate.value_id,
-                    aggregated_signature: AggregatedSignature {
-                        signatures: block
-                            .certificate
-                            .aggregated_signature
-                            .signatures
-                            .iter()
-                            .map(|sig| CommitSignature {
-                                address: sig.address,
-                                signature: sig.signature.into(),
-                                extension: sig.extension.as_ref().map(|ext| SignedExtension {
-                                    message: Extension {
-                                        data: ext.extension.data.clone(),
-                                    },
-                                    signature: ext.signature.into(),
-                                }),
-                            })
-                            .collect(),
-                    },
-                },
-            }),
+            value: value.block.map(Into::into),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ValueRangeRawResponse {
+    pub start: Height,
+    pub blocks: Vec<RawSyncedValue>,
+}
+
+impl From<ValueRangeResponse<TestContext>> for ValueRangeRawResponse {
+    fn from(value: ValueRangeResponse<TestContext>) -> Self {
+        Self {
+            start: value.start,
+            blocks: value.values.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ValueRangeRawResponse> for ValueRangeResponse<TestContext> {
+    fn from(value: ValueRangeRawResponse) -> Self {
+        Self {
+            start: value.start,
+            values: value.blocks.into_iter().map(Into::into).collect(),
         }
     }
 }
@@ -348,19 +504,94 @@ impl From<VoteSetRawResponse> for VoteSetResponse<TestContext> {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct RawSnapshot {
+    pub format: u32,
+    pub chunks: u32,
+    pub hash: Bytes,
+    pub metadata: Bytes,
+    pub certificate: RawCommitCertificate,
+}
+
+impl From<Snapshot<TestContext>> for RawSnapshot {
+    fn from(value: Snapshot<TestContext>) -> Self {
+        Self {
+            format: value.format,
+            chunks: value.chunks,
+            hash: value.hash,
+            metadata: value.metadata,
+            certificate: value.certificate.into(),
+        }
+    }
+}
+
+impl From<RawSnapshot> for Snapshot<TestContext> {
+    fn from(value: RawSnapshot) -> Self {
+        Self {
+            format: value.format,
+            chunks: value.chunks,
+            hash: value.hash,
+            metadata: value.metadata,
+            certificate: value.certificate.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotChunkRawResponse {
+    pub height: Height,
+    pub format: u32,
+    pub index: u32,
+    pub chunk: Option<Bytes>,
+}
+
+impl From<SnapshotChunkResponse<TestContext>> for SnapshotChunkRawResponse {
+    fn from(value: SnapshotChunkResponse<TestContext>) -> Self {
+        Self {
+            height: value.height,
+            format: value.format,
+            index: value.index,
+            chunk: value.chunk,
+        }
+    }
+}
+
+impl From<SnapshotChunkRawResponse> for SnapshotChunkResponse<TestContext> {
+    fn from(value: SnapshotChunkRawResponse) -> Self {
+        Self {
+            height: value.height,
+            format: value.format,
+            index: value.index,
+            chunk: value.chunk,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub enum RawResponse {
     ValueResponse(ValueRawResponse),
     VoteSetResponse(VoteSetRawResponse),
+    ValueRangeResponse(ValueRangeRawResponse),
+    SnapshotListResponse(Vec<RawSnapshot>),
+    SnapshotChunkResponse(SnapshotChunkRawResponse),
 }
 
 impl From<Response<TestContext>> for RawResponse {
     fn from(value: Response<TestContext>) -> Self {
         match value {
             Response::ValueResponse(block_response) => Self::ValueResponse(block_response.into()),
+            Response::ValueRangeResponse(range_response) => {
+                Self::ValueRangeResponse(range_response.into())
+            }
             Response::VoteSetResponse(vote_set_response) => {
                 Self::VoteSetResponse(vote_set_response.into())
             }
+            Response::SnapshotListResponse(list_response) => Self::SnapshotListResponse(
+                list_response.snapshots.into_iter().map(Into::into).collect(),
+            ),
+            Response::SnapshotChunkResponse(chunk_response) => {
+                Self::SnapshotChunkResponse(chunk_response.into())
+            }
         }
     }
 }
@@ -371,9 +602,20 @@ impl From<RawResponse> for Response<TestContext> {
             RawResponse::ValueResponse(block_raw_response) => {
                 Self::ValueResponse(block_raw_response.into())
             }
+            RawResponse::ValueRangeResponse(range_raw_response) => {
+                Self::ValueRangeResponse(range_raw_response.into())
+            }
             RawResponse::VoteSetResponse(vote_set_raw_response) => {
                 Self::VoteSetResponse(vote_set_raw_response.into())
             }
+            RawResponse::SnapshotListResponse(snapshots) => {
+                Self::SnapshotListResponse(SnapshotListResponse {
+                    snapshots: snapshots.into_iter().map(Into::into).collect(),
+                })
+            }
+            RawResponse::SnapshotChunkResponse(chunk_raw_response) => {
+                Self::SnapshotChunkResponse(chunk_raw_response.into())
+            }
         }
     }
 }
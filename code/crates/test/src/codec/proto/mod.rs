@@ -5,7 +5,7 @@ use malachitebft_app::streaming::{StreamContent, StreamId, StreamMessage};
 use malachitebft_codec::Codec;
 use malachitebft_core_consensus::{ProposedValue, SignedConsensusMsg};
 use malachitebft_core_types::{
-    AggregatedSignature, CommitCertificate, CommitSignature, Round, SignedExtension,
+    AggregatedSignature, CommitCertificate, CommitSignature, Evidence, Round, SignedExtension,
     SignedProposal, SignedVote, Validity, VoteSet,
 };
 use malachitebft_proto::{Error as ProtoError, Protobuf};
@@ -191,6 +191,20 @@ impl Codec<ProposedValue<TestContext>> for ProtobufCodec {
     }
 }
 
+impl Codec<Evidence<TestContext>> for ProtobufCodec {
+    type Error = ProtoError;
+
+    fn decode(&self, bytes: Bytes) -> Result<Evidence<TestContext>, Self::Error> {
+        let proto = proto::Evidence::decode(bytes.as_ref())?;
+        decode_evidence(proto)
+    }
+
+    fn encode(&self, msg: &Evidence<TestContext>) -> Result<Bytes, Self::Error> {
+        let proto = encode_evidence(msg)?;
+        Ok(Bytes::from(proto.encode_to_vec()))
+    }
+}
+
 impl Codec<sync::Status<TestContext>> for ProtobufCodec {
     type Error = ProtoError;
 
@@ -234,9 +248,24 @@ impl Codec<sync::Request<TestContext>> for ProtobufCodec {
             proto::sync_request::Request::ValueRequest(req) => Ok(sync::Request::ValueRequest(
                 sync::ValueRequest::new(Height::new(req.height)),
             )),
+            proto::sync_request::Request::ValueRangeRequest(req) => Ok(
+                sync::Request::ValueRangeRequest(sync::ValueRangeRequest::new(
+                    Height::new(req.start),
+                    Height::new(req.end),
+                    req.max_bytes,
+                )),
+            ),
             proto::sync_request::Request::VoteSetRequest(req) => Ok(sync::Request::VoteSetRequest(
                 sync::VoteSetRequest::new(Height::new(req.height), Round::new(req.round)),
             )),
+            proto::sync_request::Request::SnapshotListRequest(_) => Ok(
+                sync::Request::SnapshotListRequest(sync::SnapshotListRequest),
+            ),
+            proto::sync_request::Request::SnapshotChunkRequest(req) => {
+                Ok(sync::Request::SnapshotChunkRequest(
+                    sync::SnapshotChunkRequest::new(Height::new(req.height), req.format, req.index),
+                ))
+            }
         }
     }
 
@@ -249,6 +278,15 @@ impl Codec<sync::Request<TestContext>> for ProtobufCodec {
                     },
                 )),
             },
+            sync::Request::ValueRangeRequest(req) => proto::SyncRequest {
+                request: Some(proto::sync_request::Request::ValueRangeRequest(
+                    proto::ValueRangeRequest {
+                        start: req.start.as_u64(),
+                        end: req.end.as_u64(),
+                        max_bytes: req.max_bytes,
+                    },
+                )),
+            },
             sync::Request::VoteSetRequest(req) => proto::SyncRequest {
                 request: Some(proto::sync_request::Request::VoteSetRequest(
                     proto::VoteSetRequest {
@@ -257,6 +295,20 @@ impl Codec<sync::Request<TestContext>> for ProtobufCodec {
                     },
                 )),
             },
+            sync::Request::SnapshotListRequest(_) => proto::SyncRequest {
+                request: Some(proto::sync_request::Request::SnapshotListRequest(
+                    proto::SnapshotListRequest {},
+                )),
+            },
+            sync::Request::SnapshotChunkRequest(req) => proto::SyncRequest {
+                request: Some(proto::sync_request::Request::SnapshotChunkRequest(
+                    proto::SnapshotChunkRequest {
+                        height: req.height.as_u64(),
+                        format: req.format,
+                        index: req.index,
+                    },
+                )),
+            },
         };
 
         Ok(Bytes::from(proto.encode_to_vec()))
@@ -289,6 +341,16 @@ pub fn decode_sync_response(
                 value_response.value.map(decode_synced_value).transpose()?,
             ))
         }
+        proto::sync_response::Response::ValueRangeResponse(range_response) => {
+            sync::Response::ValueRangeResponse(sync::ValueRangeResponse::new(
+                Height::new(range_response.start),
+                range_response
+                    .values
+                    .into_iter()
+                    .map(decode_synced_value)
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
         proto::sync_response::Response::VoteSetResponse(vote_set_response) => {
             let height = Height::new(vote_set_response.height);
             let round = Round::new(vote_set_response.round);
@@ -302,6 +364,23 @@ pub fn decode_sync_response(
                 decode_vote_set(vote_set)?,
             ))
         }
+        proto::sync_response::Response::SnapshotListResponse(list_response) => {
+            sync::Response::SnapshotListResponse(sync::SnapshotListResponse::new(
+                list_response
+                    .snapshots
+                    .into_iter()
+                    .map(decode_snapshot)
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        proto::sync_response::Response::SnapshotChunkResponse(chunk_response) => {
+            sync::Response::SnapshotChunkResponse(sync::SnapshotChunkResponse::new(
+                Height::new(chunk_response.height),
+                chunk_response.format,
+                chunk_response.index,
+                chunk_response.chunk,
+            ))
+        }
     };
     Ok(response)
 }
@@ -322,6 +401,18 @@ pub fn encode_sync_response(
                 },
             )),
         },
+        sync::Response::ValueRangeResponse(range_response) => proto::SyncResponse {
+            response: Some(proto::sync_response::Response::ValueRangeResponse(
+                proto::ValueRangeResponse {
+                    start: range_response.start.as_u64(),
+                    values: range_response
+                        .values
+                        .iter()
+                        .map(encode_synced_value)
+                        .collect::<Result<_, _>>()?,
+                },
+            )),
+        },
         sync::Response::VoteSetResponse(vote_set_response) => proto::SyncResponse {
             response: Some(proto::sync_response::Response::VoteSetResponse(
                 proto::VoteSetResponse {
@@ -334,11 +425,58 @@ pub fn encode_sync_response(
                 },
             )),
         },
+        sync::Response::SnapshotListResponse(list_response) => proto::SyncResponse {
+            response: Some(proto::sync_response::Response::SnapshotListResponse(
+                proto::SnapshotListResponse {
+                    snapshots: list_response
+                        .snapshots
+                        .iter()
+                        .map(encode_snapshot)
+                        .collect::<Result<_, _>>()?,
+                },
+            )),
+        },
+        sync::Response::SnapshotChunkResponse(chunk_response) => proto::SyncResponse {
+            response: Some(proto::sync_response::Response::SnapshotChunkResponse(
+                proto::SnapshotChunkResponse {
+                    height: chunk_response.height.as_u64(),
+                    format: chunk_response.format,
+                    index: chunk_response.index,
+                    chunk: chunk_response.chunk.clone(),
+                },
+            )),
+        },
     };
 
     Ok(proto)
 }
 
+pub fn encode_snapshot(
+    snapshot: &sync::Snapshot<TestContext>,
+) -> Result<proto::Snapshot, ProtoError> {
+    Ok(proto::Snapshot {
+        format: snapshot.format,
+        chunks: snapshot.chunks,
+        hash: snapshot.hash.clone(),
+        metadata: snapshot.metadata.clone(),
+        certificate: Some(encode_certificate(&snapshot.certificate)?),
+    })
+}
+
+pub fn decode_snapshot(proto: proto::Snapshot) -> Result<sync::Snapshot<TestContext>, ProtoError> {
+    let certificate = proto
+        .certificate
+        .ok_or_else(|| ProtoError::missing_field::<proto::Snapshot>("certificate"))?;
+
+    Ok(sync::Snapshot::new(
+        proto.format,
+        proto.chunks,
+        proto.hash,
+        proto.metadata,
+        decode_certificate(certificate)?,
+    ))
+}
+
 pub fn encode_synced_value(
     synced_value: &sync::RawDecidedValue<TestContext>,
 ) -> Result<proto::SyncedValue, ProtoError> {
@@ -501,6 +639,85 @@ pub fn decode_vote(msg: proto::SignedMessage) -> Option<SignedVote<TestContext>>
     Some(SignedVote::new(vote, signature))
 }
 
+pub fn encode_proposal(
+    proposal: &SignedProposal<TestContext>,
+) -> Result<proto::SignedMessage, ProtoError> {
+    Ok(proto::SignedMessage {
+        message: Some(proto::signed_message::Message::Proposal(
+            proposal.message.to_proto()?,
+        )),
+        signature: Some(encode_signature(&proposal.signature)),
+    })
+}
+
+pub fn decode_proposal(msg: proto::SignedMessage) -> Option<SignedProposal<TestContext>> {
+    let signature = msg.signature?;
+    let proposal = match msg.message {
+        Some(proto::signed_message::Message::Proposal(p)) => Some(p),
+        _ => None,
+    }?;
+
+    let signature = decode_signature(signature).ok()?;
+    let proposal = Proposal::from_proto(proposal).ok()?;
+    Some(SignedProposal::new(proposal, signature))
+}
+
+pub fn encode_evidence(evidence: &Evidence<TestContext>) -> Result<proto::Evidence, ProtoError> {
+    let evidence = match evidence {
+        Evidence::DoubleVote { first, second } => {
+            proto::evidence::Evidence::DoubleVote(proto::DoubleVote {
+                first: Some(encode_vote(first)?),
+                second: Some(encode_vote(second)?),
+            })
+        }
+        Evidence::DoubleProposal { first, second } => {
+            proto::evidence::Evidence::DoubleProposal(proto::DoubleProposal {
+                first: Some(encode_proposal(first)?),
+                second: Some(encode_proposal(second)?),
+            })
+        }
+    };
+
+    Ok(proto::Evidence {
+        evidence: Some(evidence),
+    })
+}
+
+pub fn decode_evidence(evidence: proto::Evidence) -> Result<Evidence<TestContext>, ProtoError> {
+    let evidence = evidence
+        .evidence
+        .ok_or_else(|| ProtoError::missing_field::<proto::Evidence>("evidence"))?;
+
+    match evidence {
+        proto::evidence::Evidence::DoubleVote(double_vote) => {
+            let first = double_vote
+                .first
+                .and_then(decode_vote)
+                .ok_or_else(|| ProtoError::missing_field::<proto::DoubleVote>("first"))?;
+
+            let second = double_vote
+                .second
+                .and_then(decode_vote)
+                .ok_or_else(|| ProtoError::missing_field::<proto::DoubleVote>("second"))?;
+
+            Ok(Evidence::DoubleVote { first, second })
+        }
+        proto::evidence::Evidence::DoubleProposal(double_proposal) => {
+            let first = double_proposal
+                .first
+                .and_then(decode_proposal)
+                .ok_or_else(|| ProtoError::missing_field::<proto::DoubleProposal>("first"))?;
+
+            let second = double_proposal
+                .second
+                .and_then(decode_proposal)
+                .ok_or_else(|| ProtoError::missing_field::<proto::DoubleProposal>("second"))?;
+
+            Ok(Evidence::DoubleProposal { first, second })
+        }
+    }
+}
+
 pub fn encode_signature(signature: &Signature) -> proto::Signature {
     proto::Signature {
         bytes: Bytes::copy_from_slice(signature.to_bytes().as_ref()),
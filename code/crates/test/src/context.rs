@@ -1,22 +1,51 @@
+use std::fmt;
+use std::sync::Arc;
+
 use bytes::Bytes;
 
-use malachitebft_core_types::{Context, NilOrVal, Round, ValidatorSet as _};
+use malachitebft_core_types::{AggregatedSignature, Context, NilOrVal, ProposerSelector, Round};
 
 use crate::address::*;
 use crate::height::*;
 use crate::proposal::*;
 use crate::proposal_part::*;
+use crate::proposer_selector::RotateProposer;
 use crate::signing::*;
 use crate::validator_set::*;
 use crate::value::*;
 use crate::vote::*;
 
-#[derive(Copy, Clone, Debug, Default)]
-pub struct TestContext;
+#[derive(Clone)]
+pub struct TestContext {
+    proposer_selector: Arc<dyn ProposerSelector<Self>>,
+}
 
 impl TestContext {
+    /// A context where the proposer rotates amongst the validators at each height and round.
     pub fn new() -> Self {
-        Self
+        Self::with_proposer_selector(RotateProposer)
+    }
+
+    /// A context which selects the proposers with the given selector,
+    /// eg. [`WeightedRoundRobin`](crate::proposer_selector::WeightedRoundRobin).
+    pub fn with_proposer_selector(
+        proposer_selector: impl ProposerSelector<Self> + 'static,
+    ) -> Self {
+        Self {
+            proposer_selector: Arc::new(proposer_selector),
+        }
+    }
+}
+
+impl Default for TestContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for TestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestContext").finish_non_exhaustive()
     }
 }
 
@@ -31,6 +60,7 @@ impl Context for TestContext {
     type Vote = Vote;
     type Extension = Bytes;
     type SigningScheme = Ed25519;
+    type CertificateSignature = AggregatedSignature<Self>;
 
     fn select_proposer<'a>(
         &self,
@@ -38,19 +68,9 @@ impl Context for TestContext {
         height: Self::Height,
         round: Round,
     ) -> &'a Self::Validator {
-        assert!(validator_set.count() > 0);
-        assert!(round != Round::Nil && round.as_i64() >= 0);
-
-        let proposer_index = {
-            let height = height.as_u64() as usize;
-            let round = round.as_i64() as usize;
-
-            (height - 1 + round) % validator_set.count()
-        };
-
-        validator_set
-            .get_by_index(proposer_index)
-            .expect("proposer_index is valid")
+        self.proposer_selector
+            .select_proposer(validator_set, height, round)
+            .expect("validator set is not empty")
     }
 
     fn new_proposal(
@@ -1,46 +1,26 @@
-use malachitebft_core_types::{Context, Round};
-
-use crate::{Address, Height, TestContext, ValidatorSet};
-
-/// Defines how to select a proposer amongst a validator set for a given round.
-pub trait ProposerSelector<Ctx>
-where
-    Self: Send + Sync,
-    Ctx: Context,
-{
-    /// Select a proposer from the given validator set for the given round.
-    ///
-    /// This function is called at the beginning of each round to select the proposer for that
-    /// round. The proposer is responsible for proposing a value for the round.
-    ///
-    /// # Important
-    /// This function must be deterministic!
-    /// For a given round and validator set, it must always return the same proposer.
-    fn select_proposer(
-        &self,
-        height: Ctx::Height,
-        round: Round,
-        validator_set: &Ctx::ValidatorSet,
-    ) -> Ctx::Address;
-}
+use malachitebft_core_types::Round;
+
+pub use malachitebft_core_types::{ProposerSelector, WeightedRoundRobin};
+
+use crate::{Address, Height, TestContext, Validator, ValidatorSet};
 
 #[derive(Copy, Clone, Debug, Default)]
 pub struct RotateProposer;
 
 impl ProposerSelector<TestContext> for RotateProposer {
-    fn select_proposer(
+    fn select_proposer<'a>(
         &self,
+        validator_set: &'a ValidatorSet,
         height: Height,
         round: Round,
-        validator_set: &ValidatorSet,
-    ) -> Address {
+    ) -> Option<&'a Validator> {
         assert!(round != Round::Nil && round.as_i64() >= 0);
 
         let height = height.as_u64() as usize;
         let round = round.as_i64() as usize;
 
-        let proposer_index = (height - 1 + round) % validator_set.validators.len();
-        validator_set.validators[proposer_index].address
+        let proposer_index = (height - 1 + round).checked_rem(validator_set.validators.len())?;
+        validator_set.validators.get(proposer_index)
     }
 }
 
@@ -56,12 +36,156 @@ impl FixedProposer {
 }
 
 impl ProposerSelector<TestContext> for FixedProposer {
-    fn select_proposer(
+    fn select_proposer<'a>(
         &self,
+        validator_set: &'a ValidatorSet,
         _height: Height,
         _round: Round,
-        _validator_set: &ValidatorSet,
-    ) -> Address {
-        self.proposer
+    ) -> Option<&'a Validator> {
+        validator_set.get_by_address(&self.proposer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utils::validators::make_validators;
+
+    fn proposers<const N: usize>(
+        voting_powers: [u64; N],
+        heights: u64,
+    ) -> (ValidatorSet, Vec<Address>) {
+        let validators = make_validators(voting_powers).map(|(v, _)| v);
+        let validator_set = ValidatorSet::new(validators);
+        let proposers = proposers_of(&validator_set, 1..=heights);
+
+        (validator_set, proposers)
+    }
+
+    fn proposers_of(
+        validator_set: &ValidatorSet,
+        heights: impl IntoIterator<Item = u64>,
+    ) -> Vec<Address> {
+        heights
+            .into_iter()
+            .map(|height| {
+                WeightedRoundRobin::<TestContext>::new()
+                    .select_proposer(validator_set, Height::new(height), Round::new(0))
+                    .unwrap()
+                    .address
+            })
+            .collect()
+    }
+
+    fn count(proposers: &[Address], address: &Address) -> usize {
+        proposers.iter().filter(|&p| p == address).count()
+    }
+
+    #[test]
+    fn weighted_round_robin_by_voting_power() {
+        let (validator_set, proposers) = proposers([10, 1, 1], 24);
+
+        let big = &validator_set.validators[0].address;
+        let small = &validator_set.validators[1].address;
+
+        assert_eq!(count(&proposers, big), 20);
+        assert_eq!(count(&proposers, small), 2);
+
+        // The small validators do not have to wait for the big one to propose 10 times in a row
+        assert!(proposers.windows(7).all(|window| window.contains(big)));
+        assert!(proposers.windows(7).any(|window| window.contains(small)));
+    }
+
+    #[test]
+    fn weighted_round_robin_with_equal_voting_power() {
+        let (validator_set, proposers) = proposers([5, 5, 5, 5], 8);
+
+        let mut addresses = validator_set
+            .validators
+            .iter()
+            .map(|v| v.address)
+            .collect::<Vec<_>>();
+
+        addresses.sort();
+
+        assert_eq!(proposers[..4], addresses);
+        assert_eq!(proposers[4..], addresses);
+    }
+
+    #[test]
+    fn weighted_round_robin_rounds_are_steps() {
+        let validators = make_validators([3, 2, 1]).map(|(v, _)| v);
+        let validator_set = ValidatorSet::new(validators);
+
+        let select = |height, round| {
+            WeightedRoundRobin::<TestContext>::new()
+                .select_proposer(&validator_set, Height::new(height), Round::new(round))
+                .unwrap()
+                .address
+        };
+
+        assert_eq!(select(1, 2), select(3, 0));
+        assert_eq!(select(2, 5), select(1, 0));
+        assert_eq!(select(7, 0), select(1, 0));
+    }
+
+    #[test]
+    fn weighted_round_robin_with_large_coprime_voting_powers() {
+        let voting_powers = [1_000_000_007, 998_244_353, 1_000_000_009];
+        let (validator_set, proposers) = proposers(voting_powers, 300);
+
+        // Each validator proposes about as often as the others, rather than in long streaks
+        for validator in &validator_set.validators {
+            assert!((99..=101).contains(&count(&proposers, &validator.address)));
+        }
+
+        assert!(proposers.windows(2).all(|pair| pair[0] != pair[1]));
+
+        // One cycle later, the same validators propose in the same order
+        let cycle = voting_powers.iter().sum::<u64>();
+        let next_cycle = proposers_of(&validator_set, (1..=300).map(|h| h + cycle));
+        assert_eq!(proposers, next_cycle);
+    }
+
+    #[test]
+    fn weighted_round_robin_ignores_common_divisor() {
+        let (_, small) = proposers([3, 2, 1], 12);
+        let (_, large) = proposers([3_000_009, 2_000_006, 1_000_003], 12);
+
+        assert_eq!(small, large);
+    }
+
+    #[test]
+    fn context_selects_proposer_with_given_selector() {
+        use malachitebft_core_types::Context;
+
+        let validators = make_validators([3, 2, 1]).map(|(v, _)| v);
+        let validator_set = ValidatorSet::new(validators);
+        let ctx = TestContext::with_proposer_selector(WeightedRoundRobin::new());
+
+        let proposers = (1..=6)
+            .map(|height| {
+                ctx.select_proposer(&validator_set, Height::new(height), Round::new(0))
+                    .address
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(proposers, proposers_of(&validator_set, 1..=6));
+    }
+
+    #[test]
+    fn weighted_round_robin_with_empty_validator_set() {
+        let validator_set = ValidatorSet {
+            validators: Default::default(),
+        };
+
+        let proposer = WeightedRoundRobin::<TestContext>::new().select_proposer(
+            &validator_set,
+            Height::new(1),
+            Round::new(0),
+        );
+
+        assert!(proposer.is_none());
     }
 }
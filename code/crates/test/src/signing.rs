@@ -1,13 +1,18 @@
 use bytes::Bytes;
 use malachitebft_core_types::{
-    CertificateError, CommitCertificate, CommitSignature, NilOrVal, SignedExtension,
-    SignedProposal, SignedProposalPart, SignedVote, SigningProvider, VotingPower,
+    CertificateError, CommitCertificate, SignedExtension, SignedProposal, SignedProposalPart,
+    SignedVote, SigningError, SigningProvider, VotingPower,
 };
 
-use crate::{Proposal, ProposalPart, TestContext, Validator, Vote};
+use malachitebft_signing_remote::{domain_separated, PROPOSAL_PART_DOMAIN, VOTE_EXTENSION_DOMAIN};
+
+use crate::{Proposal, ProposalPart, TestContext, ValidatorSet, Vote};
 
 pub use malachitebft_signing_ed25519::*;
 
+mod remote;
+pub use remote::RemoteSigningProvider;
+
 pub trait Hashable {
     type Output;
     fn hash(&self) -> Self::Output;
@@ -48,9 +53,9 @@ impl Ed25519Provider {
 }
 
 impl SigningProvider<TestContext> for Ed25519Provider {
-    fn sign_vote(&self, vote: Vote) -> SignedVote<TestContext> {
+    fn sign_vote(&self, vote: Vote) -> Result<SignedVote<TestContext>, SigningError> {
         let signature = self.sign(&vote.to_sign_bytes());
-        SignedVote::new(vote, signature)
+        Ok(SignedVote::new(vote, signature))
     }
 
     fn verify_signed_vote(
@@ -62,9 +67,12 @@ impl SigningProvider<TestContext> for Ed25519Provider {
         public_key.verify(&vote.to_sign_bytes(), signature).is_ok()
     }
 
-    fn sign_proposal(&self, proposal: Proposal) -> SignedProposal<TestContext> {
+    fn sign_proposal(
+        &self,
+        proposal: Proposal,
+    ) -> Result<SignedProposal<TestContext>, SigningError> {
         let signature = self.private_key.sign(&proposal.to_sign_bytes());
-        SignedProposal::new(proposal, signature)
+        Ok(SignedProposal::new(proposal, signature))
     }
 
     fn verify_signed_proposal(
@@ -78,9 +86,14 @@ impl SigningProvider<TestContext> for Ed25519Provider {
             .is_ok()
     }
 
-    fn sign_proposal_part(&self, proposal_part: ProposalPart) -> SignedProposalPart<TestContext> {
-        let signature = self.private_key.sign(&proposal_part.to_sign_bytes());
-        SignedProposalPart::new(proposal_part, signature)
+    fn sign_proposal_part(
+        &self,
+        proposal_part: ProposalPart,
+    ) -> Result<SignedProposalPart<TestContext>, SigningError> {
+        let signature = self
+            .private_key
+            .sign(&proposal_part_sign_bytes(&proposal_part));
+        Ok(SignedProposalPart::new(proposal_part, signature))
     }
 
     fn verify_signed_proposal_part(
@@ -90,13 +103,20 @@ impl SigningProvider<TestContext> for Ed25519Provider {
         public_key: &PublicKey,
     ) -> bool {
         public_key
-            .verify(&proposal_part.to_sign_bytes(), signature)
+            .verify(&proposal_part_sign_bytes(proposal_part), signature)
             .is_ok()
     }
 
-    fn sign_vote_extension(&self, extension: Bytes) -> SignedExtension<TestContext> {
-        let signature = self.private_key.sign(extension.as_ref());
-        malachitebft_core_types::SignedMessage::new(extension, signature)
+    fn sign_vote_extension(
+        &self,
+        extension: Bytes,
+    ) -> Result<SignedExtension<TestContext>, SigningError> {
+        let signature = self
+            .private_key
+            .sign(&vote_extension_sign_bytes(&extension));
+        Ok(malachitebft_core_types::SignedMessage::new(
+            extension, signature,
+        ))
     }
 
     fn verify_signed_vote_extension(
@@ -105,30 +125,110 @@ impl SigningProvider<TestContext> for Ed25519Provider {
         signature: &Signature,
         public_key: &PublicKey,
     ) -> bool {
-        public_key.verify(extension.as_ref(), signature).is_ok()
+        public_key
+            .verify(&vote_extension_sign_bytes(extension), signature)
+            .is_ok()
+    }
+
+    fn verify_batch(&self, votes: &[(&Vote, &Signature, &PublicKey)]) -> Vec<bool> {
+        verify_vote_batch(votes)
     }
 
-    fn verify_commit_signature(
+    fn verify_certificate_signature(
         &self,
         certificate: &CommitCertificate<TestContext>,
-        commit_sig: &CommitSignature<TestContext>,
-        validator: &Validator,
+        validator_set: &ValidatorSet,
     ) -> Result<VotingPower, CertificateError<TestContext>> {
-        use malachitebft_core_types::Validator;
-
-        // Reconstruct the vote that was signed
-        let vote = Vote::new_precommit(
-            certificate.height,
-            certificate.round,
-            NilOrVal::Val(certificate.value_id),
-            *validator.address(),
-        );
+        certificate
+            .aggregated_signature
+            .verify(certificate, validator_set, self)
+    }
+}
+
+/// The bytes signed for a proposal part, tagged with their domain
+/// so that they cannot be confused with the sign bytes of a vote or proposal.
+fn proposal_part_sign_bytes(proposal_part: &ProposalPart) -> Vec<u8> {
+    domain_separated(PROPOSAL_PART_DOMAIN, &proposal_part.to_sign_bytes())
+}
 
-        // Verify signature
-        if !self.verify_signed_vote(&vote, &commit_sig.signature, validator.public_key()) {
-            return Err(CertificateError::InvalidSignature(commit_sig.clone()));
-        }
+/// The bytes signed for a vote extension, tagged with their domain
+/// so that they cannot be confused with the sign bytes of a vote or proposal.
+fn vote_extension_sign_bytes(extension: &Bytes) -> Vec<u8> {
+    domain_separated(VOTE_EXTENSION_DOMAIN, extension)
+}
+
+/// Verify the signatures of a batch of votes at once, falling back
+/// to verifying them one by one if at least one of them is invalid.
+fn verify_vote_batch(votes: &[(&Vote, &Signature, &PublicKey)]) -> Vec<bool> {
+    let sign_bytes = votes
+        .iter()
+        .map(|(vote, _, _)| vote.to_sign_bytes())
+        .collect::<Vec<_>>();
+
+    let mut batch = BatchVerifier::new();
+    for ((_, signature, public_key), bytes) in votes.iter().zip(&sign_bytes) {
+        batch.queue(public_key, bytes, signature);
+    }
 
-        Ok(validator.voting_power())
+    if batch.verify(rand::thread_rng()).is_ok() {
+        return vec![true; votes.len()];
+    }
+
+    // At least one signature is invalid, verify them one by one to find out which ones
+    votes
+        .iter()
+        .zip(&sign_bytes)
+        .map(|((_, signature, public_key), bytes)| public_key.verify(bytes, signature).is_ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use malachitebft_core_types::{NilOrVal, Round};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    use crate::{Address, Height, ValueId};
+
+    #[test]
+    fn verify_batch_reports_invalid_signatures() {
+        let mut rng = StdRng::seed_from_u64(0x42);
+
+        let providers = (0..4)
+            .map(|_| Ed25519Provider::new(PrivateKey::generate(&mut rng)))
+            .collect::<Vec<_>>();
+
+        let votes = providers
+            .iter()
+            .map(|provider| {
+                let public_key = provider.private_key().public_key();
+                let vote = Vote::new_precommit(
+                    Height::new(1),
+                    Round::new(0),
+                    NilOrVal::Val(ValueId::new(42)),
+                    Address::from_public_key(&public_key),
+                );
+                (provider.sign_vote(vote).unwrap(), public_key)
+            })
+            .collect::<Vec<_>>();
+
+        let batch = votes
+            .iter()
+            .map(|(vote, pk)| (&vote.message, &vote.signature, pk))
+            .collect::<Vec<_>>();
+
+        assert_eq!(providers[0].verify_batch(&batch), vec![true; 4]);
+
+        // Swap the public keys of the last two votes
+        let mut batch = batch;
+        batch[2].2 = &votes[3].1;
+        batch[3].2 = &votes[2].1;
+
+        assert_eq!(
+            providers[0].verify_batch(&batch),
+            vec![true, true, false, false]
+        );
     }
 }
@@ -0,0 +1,260 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use malachitebft_core_types::{
+    CertificateError, CommitCertificate, Round, SignedExtension, SignedProposal,
+    SignedProposalPart, SignedVote, SigningError, SigningProvider, VoteType, VotingPower,
+};
+use malachitebft_signing_remote::{proto, RemoteSignerClient, SignerAddress};
+
+use crate::{Proposal, ProposalPart, TestContext, ValidatorSet, Vote};
+
+use super::{
+    proposal_part_sign_bytes, verify_vote_batch, vote_extension_sign_bytes, PublicKey, Signature,
+};
+
+/// A signing provider which delegates signing to a remote signer,
+/// such that the private key never lives in the node's process.
+///
+/// Signatures are verified locally.
+///
+/// Requests to the signer block the calling thread until the signer replies,
+/// the engine therefore signs messages on the blocking thread pool.
+pub struct RemoteSigningProvider {
+    client: RemoteSignerClient,
+}
+
+impl RemoteSigningProvider {
+    pub fn new(address: SignerAddress, timeout: Duration) -> Self {
+        Self {
+            client: RemoteSignerClient::new(address, timeout),
+        }
+    }
+
+    pub fn client(&self) -> &RemoteSignerClient {
+        &self.client
+    }
+
+    /// Get the public key of the validator whose private key is held by the remote signer.
+    pub fn public_key(&self) -> Result<PublicKey, SigningError> {
+        let bytes = self.client.public_key().map_err(SigningError::new)?;
+
+        let bytes = <[u8; 32]>::try_from(bytes.as_slice())
+            .map_err(|_| SigningError::new("invalid public key length"))?;
+
+        let key = ed25519_consensus::VerificationKey::try_from(bytes).map_err(SigningError::new)?;
+
+        Ok(PublicKey::new(key))
+    }
+}
+
+fn round_to_u32(round: Round) -> Result<u32, SigningError> {
+    round
+        .as_u32()
+        .ok_or_else(|| SigningError::new("cannot sign a message for a nil round"))
+}
+
+fn decode_signature(
+    result: Result<Vec<u8>, impl core::fmt::Display>,
+) -> Result<Signature, SigningError> {
+    let bytes = result.map_err(SigningError::new)?;
+    Signature::try_from(bytes.as_slice()).map_err(SigningError::new)
+}
+
+impl SigningProvider<TestContext> for RemoteSigningProvider {
+    fn sign_vote(&self, vote: Vote) -> Result<SignedVote<TestContext>, SigningError> {
+        let vote_type = match vote.typ {
+            VoteType::Prevote => proto::VoteType::Prevote,
+            VoteType::Precommit => proto::VoteType::Precommit,
+        };
+
+        let signature = decode_signature(self.client.sign_vote(
+            vote.height.as_u64(),
+            round_to_u32(vote.round)?,
+            vote_type,
+            vote.to_sign_bytes().to_vec(),
+        ))?;
+
+        Ok(SignedVote::new(vote, signature))
+    }
+
+    fn verify_signed_vote(
+        &self,
+        vote: &Vote,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> bool {
+        public_key.verify(&vote.to_sign_bytes(), signature).is_ok()
+    }
+
+    fn sign_proposal(
+        &self,
+        proposal: Proposal,
+    ) -> Result<SignedProposal<TestContext>, SigningError> {
+        let signature = decode_signature(self.client.sign_proposal(
+            proposal.height.as_u64(),
+            round_to_u32(proposal.round)?,
+            proposal.to_sign_bytes().to_vec(),
+        ))?;
+
+        Ok(SignedProposal::new(proposal, signature))
+    }
+
+    fn sign_implicit_proposal(
+        &self,
+        proposal: Proposal,
+    ) -> Result<SignedProposal<TestContext>, SigningError> {
+        let signature = decode_signature(
+            self.client
+                .sign_implicit_proposal(proposal.to_sign_bytes().to_vec()),
+        )?;
+
+        Ok(SignedProposal::new(proposal, signature))
+    }
+
+    fn verify_signed_proposal(
+        &self,
+        proposal: &Proposal,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> bool {
+        public_key
+            .verify(&proposal.to_sign_bytes(), signature)
+            .is_ok()
+    }
+
+    fn sign_proposal_part(
+        &self,
+        proposal_part: ProposalPart,
+    ) -> Result<SignedProposalPart<TestContext>, SigningError> {
+        let signature = decode_signature(
+            self.client
+                .sign_proposal_part(proposal_part.to_sign_bytes().to_vec()),
+        )?;
+
+        Ok(SignedProposalPart::new(proposal_part, signature))
+    }
+
+    fn verify_signed_proposal_part(
+        &self,
+        proposal_part: &ProposalPart,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> bool {
+        public_key
+            .verify(&proposal_part_sign_bytes(proposal_part), signature)
+            .is_ok()
+    }
+
+    fn sign_vote_extension(
+        &self,
+        extension: Bytes,
+    ) -> Result<SignedExtension<TestContext>, SigningError> {
+        let signature = decode_signature(self.client.sign_vote_extension(extension.to_vec()))?;
+
+        Ok(SignedExtension::new(extension, signature))
+    }
+
+    fn verify_signed_vote_extension(
+        &self,
+        extension: &Bytes,
+        signature: &Signature,
+        public_key: &PublicKey,
+    ) -> bool {
+        public_key
+            .verify(&vote_extension_sign_bytes(extension), signature)
+            .is_ok()
+    }
+
+    fn verify_batch(&self, votes: &[(&Vote, &Signature, &PublicKey)]) -> Vec<bool> {
+        verify_vote_batch(votes)
+    }
+
+    fn verify_certificate_signature(
+        &self,
+        certificate: &CommitCertificate<TestContext>,
+        validator_set: &ValidatorSet,
+    ) -> Result<VotingPower, CertificateError<TestContext>> {
+        certificate
+            .aggregated_signature
+            .verify(certificate, validator_set, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use malachitebft_core_types::NilOrVal;
+    use malachitebft_signing_remote::{SignStateFile, SignerListener, SignerServer};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    use crate::{Address, Ed25519Provider, Height, PrivateKey, ProposalData, Value, ValueId};
+
+    #[test]
+    fn sign_with_remote_signer() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let private_key = PrivateKey::generate(StdRng::seed_from_u64(0x42));
+        let public_key = private_key.public_key();
+
+        let state = SignStateFile::load(dir.path().join("state.json")).unwrap();
+        let listener =
+            SignerListener::bind(&SignerAddress::Unix(dir.path().join("signer.sock"))).unwrap();
+        let address = listener.local_addr().unwrap();
+
+        thread::spawn(move || listener.run(SignerServer::new(private_key, state)));
+
+        let provider = RemoteSigningProvider::new(address, Duration::from_secs(5));
+        assert_eq!(provider.public_key().unwrap(), public_key);
+
+        let vote = Vote::new_precommit(
+            Height::new(1),
+            Round::new(0),
+            NilOrVal::Val(ValueId::new(42)),
+            Address::from_public_key(&public_key),
+        );
+
+        let signed_vote = provider.sign_vote(vote.clone()).unwrap();
+        assert!(provider.verify_signed_vote(&vote, &signed_vote.signature, &public_key));
+
+        // The signer refuses to sign a conflicting precommit
+        let conflicting = Vote::new_precommit(
+            Height::new(1),
+            Round::new(0),
+            NilOrVal::Nil,
+            Address::from_public_key(&public_key),
+        );
+
+        assert!(provider.sign_vote(conflicting).is_err());
+
+        // An implicit proposal can still be signed after voting in its round
+        let proposal = Proposal::new(
+            Height::new(1),
+            Round::new(0),
+            Value::new(42),
+            Round::Nil,
+            Address::from_public_key(&public_key),
+        );
+
+        assert!(provider.sign_implicit_proposal(proposal).is_ok());
+
+        // Proposal parts and vote extensions signed remotely verify like the ones signed locally
+        let local = Ed25519Provider::new(PrivateKey::generate(StdRng::seed_from_u64(0x42)));
+
+        let part = ProposalPart::Data(ProposalData::new(42));
+        let signed_part = provider.sign_proposal_part(part.clone()).unwrap();
+        assert!(local.verify_signed_proposal_part(&part, &signed_part.signature, &public_key));
+
+        let extension = Bytes::from_static(b"extension");
+        let signed_extension = provider.sign_vote_extension(extension.clone()).unwrap();
+        assert!(local.verify_signed_vote_extension(
+            &extension,
+            &signed_extension.signature,
+            &public_key
+        ));
+    }
+}
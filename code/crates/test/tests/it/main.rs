@@ -142,6 +142,8 @@ impl TestRunner {
                         .collect(),
                     ..Default::default()
                 },
+                wal: WalConfig::default(),
+                synchrony: None,
             },
             mempool: MempoolConfig {
                 p2p: P2pConfig {
@@ -161,6 +163,7 @@ impl TestRunner {
                 enabled: true,
                 status_update_interval: Duration::from_secs(2),
                 request_timeout: Duration::from_secs(5),
+                ..Default::default()
             },
             metrics: MetricsConfig {
                 enabled: false,
@@ -168,6 +171,7 @@ impl TestRunner {
                     .parse()
                     .unwrap(),
             },
+            rpc: RpcConfig::default(),
             runtime: RuntimeConfig::single_threaded(),
             test: TestConfig::default(),
         }
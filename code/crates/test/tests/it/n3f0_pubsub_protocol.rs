@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use bytesize::ByteSize;
 
-use malachitebft_config::{GossipSubConfig, PubSubProtocol};
+use malachitebft_config::{GossipSubConfig, PeerScoringConfig, PubSubProtocol};
 
 use crate::{TestBuilder, TestParams};
 
@@ -75,3 +75,21 @@ pub async fn gossip_custom_config_2ktx() {
 
     run_test(params).await
 }
+
+#[tokio::test]
+pub async fn gossip_peer_scoring() {
+    let peer_scoring = PeerScoringConfig {
+        enabled: true,
+        ..Default::default()
+    };
+
+    let params = TestParams {
+        enable_sync: false,
+        protocol: PubSubProtocol::GossipSub(
+            GossipSubConfig::default().with_peer_scoring(peer_scoring),
+        ),
+        ..Default::default()
+    };
+
+    run_test(params).await
+}
@@ -122,6 +122,47 @@ pub async fn crash_restart_from_latest() {
         .await
 }
 
+#[tokio::test]
+pub async fn crash_restart_from_start_small_batches() {
+    const HEIGHT: u64 = 15;
+
+    let mut test = TestBuilder::<()>::new();
+
+    test.add_node()
+        .with_voting_power(10)
+        .start()
+        .wait_until(HEIGHT)
+        .success();
+    test.add_node()
+        .with_voting_power(10)
+        .start()
+        .wait_until(HEIGHT)
+        .success();
+
+    test.add_node()
+        .with_voting_power(5)
+        .start()
+        .wait_until(2)
+        .crash()
+        .reset_db()
+        // Wait long enough for the other nodes to be several batches ahead
+        .restart_after(Duration::from_secs(10))
+        .wait_until(HEIGHT)
+        .success();
+
+    test.build()
+        .run_with_params(
+            Duration::from_secs(60),
+            TestParams {
+                enable_sync: true,
+                sync_batch_size: 2,        // Request two values at a time...
+                sync_parallel_requests: 3, // ...from up to three requests in flight
+                ..Default::default()
+            },
+        )
+        .await
+}
+
 #[tokio::test]
 pub async fn aggressive_pruning() {
     const HEIGHT: u64 = 15;
@@ -196,3 +237,78 @@ pub async fn start_late() {
         )
         .await
 }
+
+#[tokio::test]
+pub async fn start_late_from_snapshot() {
+    const HEIGHT: u64 = 5;
+
+    let mut test = TestBuilder::<()>::new();
+
+    test.add_node()
+        .with_voting_power(10)
+        .start()
+        .wait_until(HEIGHT * 4)
+        .success();
+
+    test.add_node()
+        .with_voting_power(10)
+        .start()
+        .wait_until(HEIGHT * 4)
+        .success();
+
+    // The node restores the state at the latest height decided by its peers,
+    // instead of syncing all the values decided since genesis
+    test.add_node()
+        .with_voting_power(5)
+        .start_after(1, Duration::from_secs(10))
+        .wait_until(HEIGHT * 4)
+        .success();
+
+    test.build()
+        .run_with_params(
+            Duration::from_secs(60),
+            TestParams {
+                enable_sync: true,
+                enable_snapshot_sync: true,
+                ..Default::default()
+            },
+        )
+        .await
+}
+
+#[tokio::test]
+pub async fn report_caught_up() {
+    const HEIGHT: u64 = 10;
+
+    let mut test = TestBuilder::<()>::new();
+
+    test.add_node()
+        .with_voting_power(10)
+        .start()
+        .wait_until(HEIGHT * 2)
+        .success();
+
+    test.add_node()
+        .with_voting_power(10)
+        .start()
+        .wait_until(HEIGHT * 2)
+        .success();
+
+    // The node reports that it caught up with its peers once done syncing
+    test.add_node()
+        .with_voting_power(5)
+        .start_after(1, Duration::from_secs(10))
+        .expect_caught_up()
+        .wait_until(HEIGHT * 2)
+        .success();
+
+    test.build()
+        .run_with_params(
+            Duration::from_secs(60),
+            TestParams {
+                enable_sync: true,
+                ..Default::default()
+            },
+        )
+        .await
+}
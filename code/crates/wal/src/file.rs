@@ -37,6 +37,17 @@ impl Storage for File {
         Ok(file)
     }
 
+    fn open_read_only(path: impl AsRef<Path>, _: ()) -> io::Result<Self> {
+        let file = File::open(&path)?;
+
+        // Fail rather than read entries while they are being written
+        AdvisoryFileLock::try_lock(&file, FileLockMode::Shared).map_err(|e| {
+            io::Error::other(format!("Failed to acquire shared advisory lock: {e}"))
+        })?;
+
+        Ok(file)
+    }
+
     fn size_bytes(&self) -> io::Result<u64> {
         File::metadata(self).map(|m| m.len())
     }
@@ -0,0 +1,191 @@
+use std::io::{self, Read, Write};
+
+use cfg_if::cfg_if;
+
+use crate::ext::{read_u32, read_u64, write_u32, write_u64};
+use crate::Version;
+
+/// Magic bytes at the start of a WAL file, from [`Version::V2`] onwards
+pub const MAGIC: [u8; 4] = *b"MWAL";
+
+/// Mask of the header flags holding the compression algorithm
+const COMPRESSION_MASK: u32 = 0xFF;
+
+/// Compression algorithm used for the compressed entries of the WAL.
+///
+/// Whether a given entry is compressed or not is recorded in the entry itself,
+/// and only entries which get smaller when compressed are stored compressed.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Entries are never compressed
+    None = 0,
+
+    /// Entries are compressed with the LZ4 algorithm
+    Lz4 = 1,
+}
+
+impl Compression {
+    /// The compression algorithm implied by the given version of the format,
+    /// for versions which do not record it in their header.
+    fn implied_by(version: Version) -> Self {
+        match version {
+            Version::V1 => Self::Lz4,
+            Version::V2 => Self::default(),
+        }
+    }
+}
+
+impl Default for Compression {
+    /// LZ4 if the `compression` feature is enabled, none otherwise
+    fn default() -> Self {
+        cfg_if! {
+            if #[cfg(feature = "compression")] {
+                Self::Lz4
+            } else {
+                Self::None
+            }
+        }
+    }
+}
+
+impl TryFrom<u8> for Compression {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Lz4),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Header of a WAL file.
+///
+/// # Format on disk
+///
+/// Version 1:
+///
+/// ```text
+/// +-----------------+-----------------+
+/// |     Version     |     Sequence    |
+/// |    (4 bytes)    |    (8 bytes)    |
+/// +-----------------+-----------------+
+/// ```
+///
+/// Version 2:
+///
+/// ```text
+/// +-----------------+-----------------+-----------------+-----------------+
+/// |      Magic      |     Version     |      Flags      |     Sequence    |
+/// |    (4 bytes)    |    (4 bytes)    |    (4 bytes)    |    (8 bytes)    |
+/// +-----------------+-----------------+-----------------+-----------------+
+/// ```
+///
+/// The lowest byte of the flags holds the [`Compression`] algorithm,
+/// the remaining bits are reserved and must be zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub version: Version,
+    pub compression: Compression,
+    pub sequence: u64,
+}
+
+impl Header {
+    /// A header in the current version of the format, with the default compression algorithm
+    pub fn new(sequence: u64) -> Self {
+        Self {
+            version: Version::CURRENT,
+            compression: Compression::default(),
+            sequence,
+        }
+    }
+
+    /// A header for the given version of the format, with the compression algorithm it implies
+    pub fn with_version(version: Version, sequence: u64) -> Self {
+        Self {
+            version,
+            compression: Compression::implied_by(version),
+            sequence,
+        }
+    }
+
+    /// Size of the header on disk, in bytes
+    pub fn size(&self) -> u64 {
+        match self.version {
+            Version::V1 => 4 + 8,
+            Version::V2 => 4 + 4 + 4 + 8,
+        }
+    }
+
+    /// Reads a header in any supported version of the format
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let first = read_u32(reader)?;
+
+        if first.to_be_bytes() != MAGIC {
+            // Version 1 has no magic bytes and starts with the version number
+            let version = Version::try_from(first)
+                .ok()
+                .filter(|version| *version == Version::V1)
+                .ok_or_else(|| invalid_data("Invalid WAL version"))?;
+
+            let sequence = read_sequence(reader)?;
+
+            return Ok(Self::with_version(version, sequence));
+        }
+
+        let version = read_u32(reader)?;
+        let version = Version::try_from(version)
+            .ok()
+            .filter(|version| *version >= Version::V2)
+            .ok_or_else(|| invalid_data("Invalid WAL version"))?;
+
+        let flags = read_u32(reader)?;
+        if flags & !COMPRESSION_MASK != 0 {
+            return Err(invalid_data(format!(
+                "Unsupported WAL header flags: {flags:#010x}"
+            )));
+        }
+
+        let compression = Compression::try_from((flags & COMPRESSION_MASK) as u8)
+            .map_err(|_| invalid_data("Unsupported WAL compression algorithm"))?;
+
+        let sequence = read_sequence(reader)?;
+
+        Ok(Self {
+            version,
+            compression,
+            sequence,
+        })
+    }
+
+    /// Writes the header in its version of the format
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self.version {
+            Version::V1 => {
+                write_u32(writer, self.version as u32)?;
+                write_u64(writer, self.sequence)
+            }
+            Version::V2 => {
+                writer.write_all(&MAGIC)?;
+                write_u32(writer, self.version as u32)?;
+                write_u32(writer, self.compression as u32)?;
+                write_u64(writer, self.sequence)
+            }
+        }
+    }
+}
+
+fn read_sequence<R: Read>(reader: &mut R) -> io::Result<u64> {
+    read_u64(reader).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Failed to read sequence number",
+        )
+    })
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
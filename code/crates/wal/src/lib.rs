@@ -3,12 +3,19 @@
 //! Write-Ahead Log (WAL) implementation
 
 mod file;
+mod header;
+mod segmented;
 mod storage;
 mod version;
 
 pub mod log;
 
 pub use file::{Log, LogEntry, LogIter};
+pub use header::{Compression, Header, MAGIC};
+pub use log::RawEntry;
+pub use segmented::{
+    SegmentOptions, SegmentedFile, SegmentedLog, SegmentedLogEntry, SegmentedLogIter,
+};
 pub use storage::Storage;
 pub use version::Version;
 
@@ -9,6 +9,7 @@ use std::path::{Path, PathBuf};
 use cfg_if::cfg_if;
 
 use crate::ext::{read_u32, read_u64, read_u8, write_u32, write_u64, write_u8};
+use crate::header::{Compression, Header};
 use crate::{Storage, Version};
 
 /// Represents a single entry in the Write-Ahead Log (WAL).
@@ -18,12 +19,17 @@ use crate::{Storage, Version};
 /// ```text
 /// +-----------------|-----------------+----------------+-----------------+
 /// |  Is compressed  |     Length      |      CRC       |      Data       |
-/// |     (1 byte)    |    (4 bytes)    |   (4 bytes)    | ($length bytes) |
+/// |     (1 byte)    |    (8 bytes)    |   (4 bytes)    | ($length bytes) |
 /// +-----------------|-----------------+----------------+-----------------+
 /// ```
+///
+/// See [`Version`] for the data covered by the CRC.
 pub struct LogEntry<'a, S> {
     /// Reference to the parent WAL
     log: &'a mut Log<S>,
+
+    /// Index of the entry in the WAL
+    index: usize,
 }
 
 impl<S> LogEntry<'_, S>
@@ -63,36 +69,18 @@ where
         let mut data = vec![0; length];
         self.log.storage.read_exact(&mut data)?;
 
-        #[cfg(not(feature = "compression"))]
-        if is_compressed {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Entry is compressed but compression is disabled",
-            ));
-        }
+        let (actual_crc, data) = self.log.unpack(is_compressed, data);
 
-        #[cfg(feature = "compression")]
-        if is_compressed {
-            data = lz4_flex::decompress_size_prepended(&data).map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Failed to decompress entry: {e}"),
-                )
-            })?;
-        }
-
-        let actual_crc = compute_crc(&data);
-
-        if expected_crc != actual_crc {
+        if actual_crc.is_some_and(|actual_crc| actual_crc != expected_crc) {
             return Err(io::Error::new(io::ErrorKind::InvalidData, "CRC mismatch"));
         }
 
-        writer.write_all(&data)?;
+        writer.write_all(&data?)?;
 
-        let pos = self.log.storage.stream_position()?;
-        let len = self.log.storage.size_bytes()?;
+        // Stop at the last complete entry, ignoring a partially written one past it
+        self.index += 1;
 
-        if pos < len {
+        if self.index < self.log.len {
             Ok(Some(self))
         } else {
             Ok(None)
@@ -100,6 +88,37 @@ where
     }
 }
 
+/// An entry of the Write-Ahead Log (WAL) as stored on disk,
+/// along with the outcome of its integrity check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawEntry {
+    /// Offset of the entry from the start of the log, in bytes
+    pub offset: u64,
+
+    /// Whether the entry data is compressed on disk
+    pub is_compressed: bool,
+
+    /// Length of the entry data on disk, in bytes
+    pub length: u64,
+
+    /// CRC of the entry data, as stored on disk, see [`Version`] for the data it covers
+    pub crc: u32,
+
+    /// CRC of the entry data, as computed when reading it,
+    /// or `None` if the data could not be decompressed
+    pub actual_crc: Option<u32>,
+
+    /// The entry data, decompressed if possible
+    pub data: Vec<u8>,
+}
+
+impl RawEntry {
+    /// Whether the entry could be decompressed and its CRC matches its data
+    pub fn is_valid(&self) -> bool {
+        self.actual_crc == Some(self.crc)
+    }
+}
+
 /// Write-Ahead Log (WAL)
 ///
 /// A Write-Ahead Log is a sequential log of records that provides durability and atomicity
@@ -108,27 +127,28 @@ where
 /// # Format on disk
 ///
 /// ```text
-/// +-----------------+-----------------+-----------------+-----------------+-----------------+
-/// |     Version     |     Sequence    |    Entry #1     |       ...       |     Entry #n    |
-/// |    (4 bytes)    |    (8 bytes)    |    (variable)   |                 |    (variable)   |
-/// +-----------------+-----------------+-----------------+-----------------+-----------------+
+/// +-----------------+-----------------+-----------------+-----------------+
+/// |     Header      |    Entry #1     |       ...       |     Entry #n    |
+/// |   (see below)   |    (variable)   |                 |    (variable)   |
+/// +-----------------+-----------------+-----------------+-----------------+
 /// ```
+///
+/// The layout of the header depends on the version of the format, see [`Header`].
+///
+/// Logs written in an older version of the format are read transparently. They are upgraded
+/// to the current version right away if they hold no entries, and otherwise when the log
+/// is next restarted, so that existing entries are never rewritten in place.
 #[derive(Debug)]
 pub struct Log<S> {
     storage: S,
     path: PathBuf,
     version: Version,
+    compression: Compression,
     sequence: u64,
     len: usize,
 }
 
-const VERSION_SIZE: u64 = size_of::<Version>() as u64;
-const SEQUENCE_SIZE: u64 = size_of::<u64>() as u64;
-const HEADER_SIZE: u64 = VERSION_SIZE + SEQUENCE_SIZE;
-
-const VERSION_OFFSET: u64 = 0;
-const SEQUENCE_OFFSET: u64 = VERSION_OFFSET + VERSION_SIZE;
-const FIRST_ENTRY_OFFSET: u64 = HEADER_SIZE;
+const HEADER_OFFSET: u64 = 0;
 
 const ENTRY_LENGTH_SIZE: u64 = size_of::<u64>() as u64;
 const ENTRY_CRC_SIZE: u64 = size_of::<u32>() as u64;
@@ -173,6 +193,14 @@ impl WriteEntry<'_> {
         }
     }
 
+    /// CRC of the entry, computed over the data covered by the given version of the format
+    fn crc(&self, version: Version) -> u32 {
+        match version {
+            Version::V1 => self.uncompressed_crc(),
+            Version::V2 => compute_crc(self.data()),
+        }
+    }
+
     fn is_compressed(&self) -> bool {
         cfg_if! {
             if #[cfg(feature = "compression")] {
@@ -202,6 +230,13 @@ where
     pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
         Self::open_with(path, ())
     }
+
+    /// Opens an existing Write-Ahead Log file at the specified path, for reading only.
+    ///
+    /// See [`Log::open_read_only_with`].
+    pub fn open_read_only(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::open_read_only_with(path, ())
+    }
 }
 
 impl<S> Log<S>
@@ -228,82 +263,92 @@ where
 
         // If file exists and has content
         if size > 0 {
-            // Read and validate version number
-            let version = Version::try_from(read_u32(&mut storage)?)
-                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid WAL version"))?;
-
-            // Read sequence number
-            let sequence = read_u64(&mut storage).map_err(|_| {
-                io::Error::new(
-                    io::ErrorKind::UnexpectedEof,
-                    "Failed to read sequence number",
-                )
-            })?;
-
-            // Track current position and entry count
-            let mut pos = FIRST_ENTRY_OFFSET; // Start after header
-            let mut len = 0;
-
-            // Scan through entries to validate and count them
-            while size.saturating_sub(pos) > ENTRY_HEADER_SIZE - ENTRY_CRC_SIZE {
-                // Skip over compression flag
-                read_u8(&mut storage)?;
-
-                // Read entry length
-                let data_length = read_u64(&mut storage)?;
-
-                // Calculate total entry size including CRC
-                let Some(entry_length) = data_length.checked_add(ENTRY_CRC_SIZE) else {
-                    break; // Integer overflow, file is corrupt
-                };
-
-                // Check if enough bytes remain for full entry
-                if size.saturating_sub(pos) < entry_length {
-                    break; // Partial/corrupt entry
-                }
-
-                // Skip to next entry
-                pos = storage.seek(SeekFrom::Current(entry_length.try_into().unwrap()))?;
-                len += 1;
-            }
+            let (header, pos, len) = scan(&mut storage, size)?;
 
             // Truncate any partial entries at the end
             storage.truncate_to(pos)?;
             storage.sync_all()?;
 
-            return Ok(Self {
-                version,
+            let mut log = Self {
+                version: header.version,
+                compression: header.compression,
                 storage,
                 path,
-                sequence,
+                sequence: header.sequence,
                 len,
-            });
+            };
+
+            // Upgrade an empty log written in an older version of the format right away
+            if log.version < Version::CURRENT && log.is_empty() {
+                log.restart(log.sequence)?;
+            }
+
+            return Ok(log);
         }
 
         // Creating new WAL file
-        let version = Version::V1;
+        let header = Header::new(0);
 
-        // Write header: version (4 bytes)
-        write_u32(&mut storage, version as u32)?;
-
-        // Write header: sequence (8 bytes)
-        write_u64(&mut storage, 0)?;
+        // Write header
+        header.write(&mut storage)?;
 
         // Ensure file is exactly header size
-        storage.truncate_to(HEADER_SIZE)?;
+        storage.truncate_to(header.size())?;
 
         // Ensure header is persisted to disk
         storage.sync_all()?;
 
         Ok(Self {
-            version,
+            version: header.version,
+            compression: header.compression,
             storage,
             path,
-            sequence: 0,
+            sequence: header.sequence,
             len: 0,
         })
     }
 
+    /// Opens an existing Write-Ahead Log file at the specified path, for reading only.
+    ///
+    /// Unlike [`Log::open_with`], this never modifies the log: a partially written entry
+    /// at its end is ignored rather than discarded, and a log written in an older version
+    /// of the format is not upgraded. This makes it suitable for inspecting a WAL offline.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the WAL file to open
+    ///
+    /// # Returns
+    /// * `Ok(Wal)` - Successfully opened WAL
+    /// * `Err` - If the WAL does not exist, is empty or invalid, or if file operations fail
+    pub fn open_read_only_with(
+        path: impl AsRef<Path>,
+        options: S::OpenOptions,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+
+        let mut storage = S::open_read_only(&path, options)?;
+
+        let size = storage.size_bytes()?;
+
+        if size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "WAL file is empty",
+            ));
+        }
+
+        let (header, _, len) = scan(&mut storage, size)?;
+
+        Ok(Self {
+            version: header.version,
+            compression: header.compression,
+            storage,
+            path,
+            sequence: header.sequence,
+            len,
+        })
+    }
+
     /// Writes a new entry to the WAL.
     ///
     /// The entry is appended to the end of the log with length, CRC and data.
@@ -342,11 +387,14 @@ where
         self.write_entry(WriteEntry::Raw(data.as_ref()))
     }
 
-    /// Writes a new entry to the WAL, compressing it with the LZ4 algorithm.
+    /// Writes a new entry to the WAL, compressing it with the compression algorithm of the log.
     ///
     /// The entry is appended to the end of the log with length, CRC and data.
     /// If writing fails, the WAL is truncated to remove the partial write.
     ///
+    /// The entry is written uncompressed if the log has no compression algorithm,
+    /// or if compressing it does not make it smaller.
+    ///
     /// # Arguments
     /// * `data` - The data to write as a new WAL entry
     ///
@@ -357,16 +405,19 @@ where
     #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
     pub fn write_compressed(&mut self, data: impl AsRef<[u8]>) -> io::Result<()> {
         let data = data.as_ref();
-        let compressed = lz4_flex::compress_prepend_size(data);
+
+        let compressed = match self.compression {
+            Compression::None => None,
+            Compression::Lz4 => Some(lz4_flex::compress_prepend_size(data)),
+        };
 
         // Only use compression if it actually helps
-        let entry = if compressed.len() < data.len() {
-            WriteEntry::Compressed {
-                compressed: &compressed,
+        let entry = match &compressed {
+            Some(compressed) if compressed.len() < data.len() => WriteEntry::Compressed {
+                compressed,
                 uncompressed: data,
-            }
-        } else {
-            WriteEntry::Raw(data)
+            },
+            _ => WriteEntry::Raw(data),
         };
 
         // Rest of write logic...
@@ -383,8 +434,8 @@ where
             // Write length of (compressed) data
             write_u64(&mut self.storage, entry.len() as u64)?;
 
-            // Write CRC of (uncompressed) data
-            write_u32(&mut self.storage, entry.uncompressed_crc())?;
+            // Write CRC of the data, as covered by the version of the log
+            write_u32(&mut self.storage, entry.crc(self.version))?;
 
             // Write (compressed) entry data
             self.storage.write_all(entry.data())?;
@@ -422,9 +473,12 @@ where
         }
 
         // Seek to the first entry after the header
-        self.storage.seek(SeekFrom::Start(FIRST_ENTRY_OFFSET))?;
+        self.storage.seek(SeekFrom::Start(self.header().size()))?;
 
-        Ok(Some(LogEntry { log: self }))
+        Ok(Some(LogEntry {
+            log: self,
+            index: 0,
+        }))
     }
 
     /// Returns an iterator over all entries in the WAL.
@@ -438,10 +492,88 @@ where
         })
     }
 
+    /// Reads all entries in the WAL, including those which fail their integrity check.
+    ///
+    /// Unlike [`Log::iter`], which stops at the first corrupted entry, this carries on
+    /// reading past entries whose CRC does not match their data, which makes it suitable
+    /// for inspecting a WAL offline.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<RawEntry>)` - All entries in the WAL, in order
+    /// * `Err` - If reading fails
+    pub fn raw_entries(&mut self) -> io::Result<Vec<RawEntry>> {
+        let mut entries = Vec::with_capacity(self.len);
+        let mut offset = self.storage.seek(SeekFrom::Start(self.header().size()))?;
+
+        for _ in 0..self.len {
+            let is_compressed = read_u8(&mut self.storage)? != 0;
+            let length = read_u64(&mut self.storage)?;
+            let crc = read_u32(&mut self.storage)?;
+
+            let mut data = vec![0; length as usize];
+            self.storage.read_exact(&mut data)?;
+
+            let (actual_crc, data) = match self.unpack(is_compressed, data.clone()) {
+                (actual_crc, Ok(decompressed)) => (actual_crc, decompressed),
+                (_, Err(_)) => (None, data),
+            };
+
+            entries.push(RawEntry {
+                offset,
+                is_compressed,
+                length,
+                crc,
+                actual_crc,
+                data,
+            });
+
+            offset = self.storage.stream_position()?;
+        }
+
+        Ok(entries)
+    }
+
+    /// Truncates the WAL to its first `len` entries, discarding all entries after these.
+    ///
+    /// Does nothing if the WAL has `len` entries or less.
+    ///
+    /// # Arguments
+    /// * `len` - Number of entries to keep
+    ///
+    /// # Returns
+    /// * `Ok(())` - WAL was successfully truncated
+    /// * `Err` - If file operations fail
+    pub fn truncate(&mut self, len: usize) -> io::Result<()> {
+        if len >= self.len {
+            return Ok(());
+        }
+
+        let mut pos = self.storage.seek(SeekFrom::Start(self.header().size()))?;
+
+        for _ in 0..len {
+            // Skip over compression flag
+            read_u8(&mut self.storage)?;
+
+            // Skip over the CRC and data
+            let data_length = read_u64(&mut self.storage)?;
+            pos = skip_entry(&mut self.storage, data_length)?;
+        }
+
+        self.storage.truncate_to(pos)?;
+        self.storage.sync_all()?;
+
+        self.len = len;
+
+        Ok(())
+    }
+
     /// Restarts the WAL with a new sequence number.
     ///
     /// This truncates all existing entries and resets the WAL to an empty state
-    /// with the specified sequence number.
+    /// with the specified sequence number, in the current version of the format.
+    ///
+    /// If the backing storage supports it, the existing entries are archived first,
+    /// see [`Storage::archive`].
     ///
     /// # Arguments
     /// * `sequence` - New sequence number to start from
@@ -450,18 +582,26 @@ where
     /// * `Ok(())` - WAL was successfully restarted
     /// * `Err` - If file operations fail
     pub fn restart(&mut self, sequence: u64) -> io::Result<()> {
-        // Reset sequence number and entry count
-        self.sequence = sequence;
+        // Give the storage a chance to archive the entries of the previous sequence
+        if !self.is_empty() {
+            self.storage.archive(self.sequence)?;
+        }
+
+        // Reset header and entry count, upgrading the log to the current version if needed
+        let header = Header::new(sequence);
+        self.version = header.version;
+        self.compression = header.compression;
+        self.sequence = header.sequence;
         self.len = 0;
 
-        // Seek to start of sequence number
-        self.storage.seek(SeekFrom::Start(SEQUENCE_OFFSET))?;
+        // Seek to start of header, which may be missing if the storage was archived
+        self.storage.seek(SeekFrom::Start(HEADER_OFFSET))?;
 
-        // Write new sequence number
-        write_u64(&mut self.storage, sequence)?;
+        // Write header with the new sequence number
+        header.write(&mut self.storage)?;
 
         // Truncate all entries
-        self.storage.truncate_to(HEADER_SIZE)?;
+        self.storage.truncate_to(header.size())?;
 
         // Sync changes to disk
         self.storage.sync_all()?;
@@ -495,11 +635,14 @@ where
         sequence: u64,
         len: usize,
     ) -> Self {
+        let header = Header::with_version(version, sequence);
+
         Self {
             storage: file,
             path,
-            version,
-            sequence,
+            version: header.version,
+            compression: header.compression,
+            sequence: header.sequence,
             len,
         }
     }
@@ -516,6 +659,42 @@ impl<S> Log<S> {
         self.version
     }
 
+    /// Returns the compression algorithm used for compressed entries.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    fn header(&self) -> Header {
+        Header {
+            version: self.version,
+            compression: self.compression,
+            sequence: self.sequence,
+        }
+    }
+
+    /// Reads back the data of an entry as stored on disk, decompressing it if needed.
+    ///
+    /// Returns the CRC to check against the one stored in the entry, computed over
+    /// the data covered by the version of the log, along with the (decompressed) data.
+    /// The CRC is `None` if it covers the uncompressed data, which could not be decompressed.
+    fn unpack(&self, is_compressed: bool, data: Vec<u8>) -> (Option<u32>, io::Result<Vec<u8>>) {
+        let stored_crc = match self.version {
+            Version::V1 => None,
+            Version::V2 => Some(compute_crc(&data)),
+        };
+
+        let data = if is_compressed {
+            decompress(self.compression, &data)
+        } else {
+            Ok(data)
+        };
+
+        match stored_crc {
+            Some(crc) => (Some(crc), data),
+            None => (data.as_deref().ok().map(compute_crc), data),
+        }
+    }
+
     /// Returns the current sequence number.
     pub fn sequence(&self) -> u64 {
         self.sequence
@@ -576,6 +755,85 @@ where
     }
 }
 
+/// Decompresses the data of an entry which was compressed with the given algorithm
+/// Reads the header of the log and scans through its entries to validate and count them.
+///
+/// Returns the header, the position right after the last complete entry and the number of
+/// complete entries. A partially written entry at the end of the log is not counted.
+fn scan<S: Storage>(storage: &mut S, size: u64) -> io::Result<(Header, u64, usize)> {
+    // Read and validate header
+    let header = Header::read(storage)?;
+
+    // Track current position and entry count
+    let mut pos = header.size(); // Start after header
+    let mut len = 0;
+
+    while size.saturating_sub(pos) > ENTRY_HEADER_SIZE - ENTRY_CRC_SIZE {
+        // Skip over compression flag
+        read_u8(storage)?;
+
+        // Read entry length
+        let data_length = read_u64(storage)?;
+
+        // Calculate total entry size including CRC
+        let Some(entry_length) = data_length.checked_add(ENTRY_CRC_SIZE) else {
+            break; // Integer overflow, file is corrupt
+        };
+
+        // Check if enough bytes remain for full entry
+        if size.saturating_sub(pos) < entry_length {
+            break; // Partial/corrupt entry
+        }
+
+        pos = skip_entry(storage, data_length)?;
+        len += 1;
+    }
+
+    Ok((header, pos, len))
+}
+
+/// Skips over the CRC and data of an entry with the given data length,
+/// returning the position of the next entry.
+fn skip_entry<S: Storage>(storage: &mut S, data_length: u64) -> io::Result<u64> {
+    let offset = data_length
+        .checked_add(ENTRY_CRC_SIZE)
+        .and_then(|entry_length| i64::try_from(entry_length).ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid entry length: {data_length}"),
+            )
+        })?;
+
+    storage.seek(SeekFrom::Current(offset))
+}
+
+fn decompress(compression: Compression, data: &[u8]) -> io::Result<Vec<u8>> {
+    if compression == Compression::None {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Entry is compressed but the log has no compression algorithm",
+        ));
+    }
+
+    cfg_if! {
+        if #[cfg(feature = "compression")] {
+            lz4_flex::decompress_size_prepended(data).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Failed to decompress entry: {e}"),
+                )
+            })
+        } else {
+            let _ = data;
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Entry is compressed but compression is disabled",
+            ))
+        }
+    }
+}
+
 /// Computes the CRC32 checksum of the provided data
 ///
 /// # Arguments
@@ -0,0 +1,498 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use advisory_lock::{AdvisoryFileLock, FileLockMode};
+
+use crate::storage::Storage;
+
+/// Write-Ahead Log (WAL) backed by a [`SegmentedFile`]
+pub type SegmentedLog = crate::log::Log<SegmentedFile>;
+
+/// Write-Ahead Log (WAL) entry, backed by a [`SegmentedFile`]
+pub type SegmentedLogEntry<'a> = crate::log::LogEntry<'a, SegmentedFile>;
+
+/// Iterator over the WAL entries, backed by a [`SegmentedFile`]
+pub type SegmentedLogIter<'a> = crate::log::LogIter<'a, SegmentedFile>;
+
+/// Options for opening a [`SegmentedFile`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SegmentOptions {
+    /// Maximum size of a segment in bytes, once reached writes roll over to a new segment
+    pub max_segment_size: u64,
+
+    /// Number of past logs to retain after the log is restarted, eg. for post-mortem debugging
+    pub retain_logs: usize,
+
+    /// Maximum total size in bytes of the retained logs, the oldest ones are pruned first
+    pub max_retained_size: Option<u64>,
+}
+
+impl Default for SegmentOptions {
+    fn default() -> Self {
+        Self {
+            max_segment_size: 64 * 1024 * 1024,
+            retain_logs: 0,
+            max_retained_size: None,
+        }
+    }
+}
+
+struct Segment {
+    file: File,
+    len: u64,
+}
+
+/// Storage for the Write-Ahead Log, made of a set of segment files which
+/// are presented as a single contiguous file.
+///
+/// Given a path `consensus.wal`, the segments of the current log are stored at
+/// `consensus.wal`, `consensus.wal.1`, `consensus.wal.2`, etc. A log consisting of a
+/// single, plain file is therefore a valid segmented log.
+///
+/// When the log is restarted, its segments are renamed to `consensus.wal.h<sequence>`,
+/// `consensus.wal.h<sequence>.1`, etc., where `sequence` is the sequence number of the
+/// log before it was restarted, and retained according to the [`SegmentOptions`].
+///
+/// Exclusive access to the log is guarded by an advisory lock on `consensus.wal.lock`.
+pub struct SegmentedFile {
+    path: PathBuf,
+    options: SegmentOptions,
+    _lock: Option<File>,
+    segments: Vec<Segment>,
+    pos: u64,
+}
+
+impl SegmentedFile {
+    /// Returns the number of segments of the current log.
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Returns the sequence numbers of the retained logs, in ascending order.
+    pub fn retained(&self) -> io::Result<Vec<u64>> {
+        let mut sequences = list_retained(&self.path)?
+            .into_iter()
+            .map(|(sequence, _)| sequence)
+            .collect::<Vec<_>>();
+
+        sequences.sort_unstable();
+        sequences.dedup();
+
+        Ok(sequences)
+    }
+
+    /// Returns the paths of the segments of the retained log with the given sequence number, in order.
+    pub fn retained_segments(&self, sequence: u64) -> io::Result<Vec<PathBuf>> {
+        let mut segments = list_retained(&self.path)?
+            .into_iter()
+            .filter(|(seq, _)| *seq == sequence)
+            .map(|(_, index)| retained_segment_path(&self.path, sequence, index))
+            .collect::<Vec<_>>();
+
+        segments.sort_by_key(|path| segment_index_of(path));
+
+        Ok(segments)
+    }
+
+    fn size(&self) -> u64 {
+        self.segments.iter().map(|s| s.len).sum()
+    }
+
+    /// Finds the segment containing the given position, along with the offset within that segment.
+    ///
+    /// At the end of the log, returns the last segment if it still has room,
+    /// or the index of the segment to create otherwise.
+    fn locate(&self, pos: u64) -> (usize, u64) {
+        let mut start = 0;
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            if pos < start + segment.len {
+                return (index, pos - start);
+            }
+
+            start += segment.len;
+        }
+
+        match self.segments.last() {
+            Some(last) if last.len < self.options.max_segment_size => {
+                (self.segments.len() - 1, pos - (start - last.len))
+            }
+            _ => (self.segments.len(), 0),
+        }
+    }
+
+    fn open_segment(&self, index: usize) -> io::Result<File> {
+        open_file(&segment_path(&self.path, index))
+    }
+
+    /// Moves the segments of the current log aside, so that they are retained
+    /// under the given sequence number, and starts over with an empty log.
+    fn move_aside(&mut self, sequence: u64) -> io::Result<()> {
+        while let Some(segment) = self.segments.pop() {
+            segment.file.sync_all()?;
+
+            let index = self.segments.len();
+            fs::rename(
+                segment_path(&self.path, index),
+                retained_segment_path(&self.path, sequence, index),
+            )?;
+        }
+
+        let file = self.open_segment(0)?;
+        self.segments.push(Segment { file, len: 0 });
+        self.pos = 0;
+
+        sync_dir(&self.path)
+    }
+
+    /// Deletes the oldest retained logs, until at most `retain_logs` of them remain,
+    /// and they take up at most `max_retained_size` bytes.
+    fn prune(&self) -> io::Result<()> {
+        let retained = list_retained(&self.path)?;
+
+        let mut sequences = retained.iter().map(|(seq, _)| *seq).collect::<Vec<_>>();
+        sequences.sort_unstable();
+        sequences.dedup();
+
+        let size_of = |sequence: u64| -> u64 {
+            retained
+                .iter()
+                .filter(|(seq, _)| *seq == sequence)
+                .filter_map(|(_, index)| {
+                    fs::metadata(retained_segment_path(&self.path, sequence, *index)).ok()
+                })
+                .map(|m| m.len())
+                .sum()
+        };
+
+        let mut total_size = sequences.iter().map(|seq| size_of(*seq)).sum::<u64>();
+        let max_size = self.options.max_retained_size.unwrap_or(u64::MAX);
+
+        for (i, sequence) in sequences.iter().enumerate() {
+            let remaining = sequences.len() - i;
+
+            if remaining <= self.options.retain_logs && total_size <= max_size {
+                break;
+            }
+
+            total_size -= size_of(*sequence);
+
+            for (_, index) in retained.iter().filter(|(seq, _)| seq == sequence) {
+                fs::remove_file(retained_segment_path(&self.path, *sequence, *index))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Storage for SegmentedFile {
+    type OpenOptions = SegmentOptions;
+
+    fn open_with(path: impl AsRef<Path>, options: SegmentOptions) -> io::Result<Self> {
+        if options.max_segment_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Maximum segment size must be greater than zero",
+            ));
+        }
+
+        let path = path.as_ref().to_owned();
+
+        let lock = open_file(&lock_path(&path))?;
+
+        AdvisoryFileLock::try_lock(&lock, FileLockMode::Exclusive).map_err(|e| {
+            io::Error::other(format!("Failed to acquire exclusive advisory lock: {e}"))
+        })?;
+
+        let segments = open_segments(&path, open_file)?;
+
+        Ok(Self {
+            path,
+            options,
+            _lock: Some(lock),
+            segments,
+            pos: 0,
+        })
+    }
+
+    fn open_read_only(path: impl AsRef<Path>, options: SegmentOptions) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+
+        // Fail rather than read entries while they are being written,
+        // without creating the lock file if the log was never opened for writing
+        let lock = match File::open(lock_path(&path)) {
+            Ok(lock) => Some(lock),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        if let Some(lock) = &lock {
+            AdvisoryFileLock::try_lock(lock, FileLockMode::Shared).map_err(|e| {
+                io::Error::other(format!("Failed to acquire shared advisory lock: {e}"))
+            })?;
+        }
+
+        let segments = open_segments(&path, open_file_read_only)?;
+
+        Ok(Self {
+            path,
+            options,
+            _lock: lock,
+            segments,
+            pos: 0,
+        })
+    }
+
+    fn size_bytes(&self) -> io::Result<u64> {
+        Ok(self.size())
+    }
+
+    fn truncate_to(&mut self, size: u64) -> io::Result<()> {
+        let mut start = 0;
+        let mut keep = 0;
+
+        for segment in &mut self.segments {
+            keep += 1;
+
+            if size <= start + segment.len {
+                segment.len = size - start;
+                segment.file.set_len(segment.len)?;
+                break;
+            }
+
+            start += segment.len;
+        }
+
+        // Always keep the first segment
+        let keep = keep.max(1);
+
+        while self.segments.len() > keep {
+            let index = self.segments.len() - 1;
+            self.segments.pop();
+            fs::remove_file(segment_path(&self.path, index))?;
+        }
+
+        self.pos = self.pos.min(self.size());
+
+        Ok(())
+    }
+
+    fn sync_all(&mut self) -> io::Result<()> {
+        for segment in &mut self.segments {
+            segment.file.sync_all()?;
+        }
+
+        Ok(())
+    }
+
+    fn archive(&mut self, sequence: u64) -> io::Result<()> {
+        if self.options.retain_logs == 0 {
+            return Ok(());
+        }
+
+        self.move_aside(sequence)?;
+        self.prune()
+    }
+}
+
+impl Read for SegmentedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (index, offset) = self.locate(self.pos);
+
+        let Some(segment) = self.segments.get_mut(index) else {
+            return Ok(0);
+        };
+
+        let available = segment.len.saturating_sub(offset);
+        let len = buf.len().min(available as usize);
+
+        segment.file.seek(SeekFrom::Start(offset))?;
+        let read = segment.file.read(&mut buf[..len])?;
+
+        self.pos += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl Write for SegmentedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.pos > self.size() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Cannot write past the end of the log",
+            ));
+        }
+
+        let (index, offset) = self.locate(self.pos);
+
+        if index == self.segments.len() {
+            let file = self.open_segment(index)?;
+            self.segments.push(Segment { file, len: 0 });
+        }
+
+        let is_last = index == self.segments.len() - 1;
+        let segment = &mut self.segments[index];
+
+        // Only the last segment may grow, up to the maximum segment size
+        let capacity = if is_last {
+            segment.len.max(self.options.max_segment_size)
+        } else {
+            segment.len
+        };
+
+        let len = buf.len().min((capacity - offset) as usize);
+
+        segment.file.seek(SeekFrom::Start(offset))?;
+        let written = segment.file.write(&buf[..len])?;
+
+        segment.len = segment.len.max(offset + written as u64);
+        self.pos += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for segment in &mut self.segments {
+            segment.file.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Seek for SegmentedFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size().checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+
+        let new_pos = new_pos.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative or overflowing position",
+            )
+        })?;
+
+        self.pos = new_pos;
+
+        Ok(new_pos)
+    }
+}
+
+/// Opens the segments of the log at the given path, which must be numbered contiguously.
+fn open_segments(
+    path: &Path,
+    open: impl Fn(&Path) -> io::Result<File>,
+) -> io::Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+
+    for index in 0.. {
+        let segment_path = segment_path(path, index);
+
+        // The first segment is always opened, and created if needed
+        if index > 0 && !segment_path.exists() {
+            break;
+        }
+
+        let file = open(&segment_path)?;
+        let len = file.metadata()?.len();
+        segments.push(Segment { file, len });
+    }
+
+    Ok(segments)
+}
+
+fn open_file(path: &Path) -> io::Result<File> {
+    fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+}
+
+fn open_file_read_only(path: &Path) -> io::Result<File> {
+    File::open(path)
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!("{}.lock", file_name(path)))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn segment_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        path.to_owned()
+    } else {
+        path.with_file_name(format!("{}.{index}", file_name(path)))
+    }
+}
+
+fn retained_segment_path(path: &Path, sequence: u64, index: usize) -> PathBuf {
+    if index == 0 {
+        path.with_file_name(format!("{}.h{sequence}", file_name(path)))
+    } else {
+        path.with_file_name(format!("{}.h{sequence}.{index}", file_name(path)))
+    }
+}
+
+fn segment_index_of(path: &Path) -> usize {
+    file_name(path)
+        .rsplit_once('.')
+        .and_then(|(_, index)| index.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Lists the segments of the retained logs, as pairs of sequence number and segment index.
+fn list_retained(path: &Path) -> io::Result<Vec<(u64, usize)>> {
+    let prefix = format!("{}.h", file_name(path));
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut retained = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+
+        let Some(suffix) = name.strip_prefix(&prefix) else {
+            continue;
+        };
+
+        let parsed = match suffix.split_once('.') {
+            None => suffix.parse().ok().map(|sequence| (sequence, 0)),
+            Some((sequence, index)) => sequence.parse().ok().zip(index.parse().ok()),
+        };
+
+        if let Some(segment) = parsed {
+            retained.push(segment);
+        }
+    }
+
+    Ok(retained)
+}
+
+fn sync_dir(path: &Path) -> io::Result<()> {
+    cfg_if::cfg_if! {
+        if #[cfg(unix)] {
+            match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+                _ => Ok(()),
+            }
+        } else {
+            let _ = path;
+            Ok(())
+        }
+    }
+}
@@ -14,6 +14,18 @@ pub trait Storage: Read + Write + Seek + Sized {
     /// Open the backing storage for the Write-Ahead Log at the given path.
     fn open_with(path: impl AsRef<Path>, options: Self::OpenOptions) -> io::Result<Self>;
 
+    /// Open the existing backing storage at the given path for reading only, without modifying it.
+    ///
+    /// By default, opening a storage for reading only is not supported.
+    fn open_read_only(path: impl AsRef<Path>, options: Self::OpenOptions) -> io::Result<Self> {
+        let _ = (path, options);
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Storage cannot be opened for reading only",
+        ))
+    }
+
     /// Returns the size of the file in bytes.
     fn size_bytes(&self) -> io::Result<u64>;
 
@@ -22,4 +34,14 @@ pub trait Storage: Read + Write + Seek + Sized {
 
     /// Synchronizes all in-memory data to the underlying storage device.
     fn sync_all(&mut self) -> io::Result<()>;
+
+    /// Archives the current contents of the storage before the log is restarted,
+    /// where `sequence` is the sequence number of the log being archived.
+    ///
+    /// Storages which archive their contents must be left empty afterwards.
+    /// By default, nothing is archived and the contents are simply discarded on restart.
+    fn archive(&mut self, sequence: u64) -> io::Result<()> {
+        let _ = sequence;
+        Ok(())
+    }
 }
@@ -1,11 +1,27 @@
 /// Version identifier for the Write-Ahead Log (WAL) format
 ///
-/// Currently only supports version 1 (V1) of the format.
+/// Logs in an older version of the format can still be opened, and are upgraded
+/// to the [current version](Version::CURRENT) when restarted.
 #[repr(u32)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Version {
     /// Version 1 of the WAL format
+    ///
+    /// The header holds no magic bytes nor flags, and the CRC of each entry
+    /// is computed over its uncompressed data.
     V1 = 1,
+
+    /// Version 2 of the WAL format
+    ///
+    /// The header starts with magic bytes and holds flags, such as the compression
+    /// algorithm of the log, and the CRC of each entry is computed over its data
+    /// as stored on disk, ie. after compression.
+    V2 = 2,
+}
+
+impl Version {
+    /// The version of the format in which new logs are written
+    pub const CURRENT: Self = Self::V2;
 }
 
 impl TryFrom<u32> for Version {
@@ -22,6 +38,7 @@ impl TryFrom<u32> for Version {
     fn try_from(value: u32) -> Result<Self, Self::Error> {
         match value {
             1 => Ok(Self::V1),
+            2 => Ok(Self::V2),
             _ => Err(()),
         }
     }
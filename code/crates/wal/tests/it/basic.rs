@@ -43,7 +43,7 @@ fn setup_wal(path: &Path, entries: &[&str]) -> io::Result<Log> {
 
     let version = wal.version();
     let sequence = wal.sequence();
-    assert_eq!(version, Version::V1);
+    assert_eq!(version, Version::V2);
     assert_eq!(sequence, 0);
 
     for entry in entries {
@@ -64,7 +64,7 @@ fn new_wal() -> io::Result<()> {
     let wal = Log::open(path)?;
     println!("Path: {}", wal.path().display());
 
-    assert_eq!(wal.version(), Version::V1);
+    assert_eq!(wal.version(), Version::V2);
     assert_eq!(wal.sequence(), 0);
     assert_eq!(wal.len(), 0);
     assert_eq!(wal.is_empty(), true);
@@ -143,6 +143,34 @@ fn restart() -> io::Result<()> {
 
     Ok(())
 }
+#[test]
+fn truncate() -> io::Result<()> {
+    let path = testwal!();
+
+    {
+        let mut wal = setup_wal(&path, ENTRIES_1)?;
+        wal.truncate(2)?;
+        assert_eq!(wal.len(), 2);
+
+        // Truncating to more entries than there are does nothing
+        wal.truncate(10)?;
+        assert_eq!(wal.len(), 2);
+
+        wal.append(ENTRIES_2[0])?;
+        wal.flush()?;
+    }
+
+    let mut wal = Log::open(&path)?;
+    let entries = wal
+        .iter()?
+        .map(|entry| entry.map(|bytes| String::from_utf8(bytes).unwrap()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    assert_eq!(entries, [ENTRIES_1[0], ENTRIES_1[1], ENTRIES_2[0]]);
+
+    Ok(())
+}
+
 #[test]
 fn corrupted_wal() -> io::Result<()> {
     let path = testwal!();
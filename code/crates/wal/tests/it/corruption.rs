@@ -35,8 +35,8 @@ fn corrupted_crc() -> io::Result<()> {
     {
         let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
 
-        // Skip version (4 bytes) + sequence (8 bytes) + first entry
-        file.seek(SeekFrom::Start(12))?;
+        // Skip magic (4 bytes) + version (4 bytes) + flags (4 bytes) + sequence (8 bytes) + first entry
+        file.seek(SeekFrom::Start(20))?;
         read_u8(&mut file)?; // Skip compression flag
         let first_entry_len = read_u64(&mut file)?;
         file.seek(SeekFrom::Current(first_entry_len as i64 + 4))?; // +4 for CRC
@@ -66,6 +66,51 @@ fn corrupted_crc() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+fn raw_entries_past_corrupted_crc() -> io::Result<()> {
+    let path = testwal!();
+
+    {
+        let mut wal = Log::open(&path)?;
+        wal.append(b"entry1")?;
+        wal.append(b"entry2")?;
+        wal.append(b"entry3")?;
+        wal.flush()?;
+    }
+
+    // Corrupt the CRC of the second entry
+    {
+        let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
+
+        // Skip magic (4 bytes) + version (4 bytes) + flags (4 bytes) + sequence (8 bytes) + first entry
+        file.seek(SeekFrom::Start(20))?;
+        read_u8(&mut file)?; // Skip compression flag
+        let first_entry_len = read_u64(&mut file)?;
+        file.seek(SeekFrom::Current(first_entry_len as i64 + 4))?; // +4 for CRC
+
+        // Now at the start of second entry, skip compression flag and length
+        file.seek(SeekFrom::Current(1 + 8))?;
+
+        // Write incorrect CRC
+        write_u32(&mut file, 0xdeadbeef)?;
+    }
+
+    // All entries are read, and only the second one is reported as corrupted
+    {
+        let mut wal = Log::open(&path)?;
+        let entries = wal.raw_entries()?;
+
+        let valid = entries.iter().map(|e| e.is_valid()).collect::<Vec<_>>();
+        assert_eq!(valid, vec![true, false, true]);
+
+        assert_eq!(entries[1].crc, 0xdeadbeef);
+        assert_eq!(entries[1].data, b"entry2");
+        assert_eq!(entries[2].data, b"entry3");
+    }
+
+    Ok(())
+}
+
 #[test]
 fn incomplete_entries() -> io::Result<()> {
     let path = testwal!();
@@ -83,13 +128,13 @@ fn incomplete_entries() -> io::Result<()> {
         let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
 
         // Skip header
-        file.seek(SeekFrom::Start(12))?;
+        file.seek(SeekFrom::Start(20))?;
 
         read_u8(&mut file)?; // Skip compression flag
         let first_entry_len = read_u64(&mut file)?;
 
         // header + compression flag + length + CRC + data + partial second entry
-        let truncate_pos = 12 + 1 + 8 + 4 + first_entry_len + 3;
+        let truncate_pos = 20 + 1 + 8 + 4 + first_entry_len + 3;
 
         // Seek to middle of second entry
         file.set_len(truncate_pos)?;
@@ -185,15 +230,15 @@ fn multiple_corruptions() -> io::Result<()> {
         let mut file = OpenOptions::new().read(true).write(true).open(&path)?;
 
         // Corrupt sequence number
-        file.seek(SeekFrom::Start(4))?;
+        file.seek(SeekFrom::Start(12))?;
         write_u64(&mut file, u64::MAX)?;
 
         // Corrupt entry length
-        file.seek(SeekFrom::Start(12 + 1))?;
+        file.seek(SeekFrom::Start(20 + 1))?;
         write_u64(&mut file, u64::MAX - 1)?;
 
         // Corrupt CRC of another entry
-        file.seek(SeekFrom::Start(50 + 1))?;
+        file.seek(SeekFrom::Start(58 + 1))?;
         write_u32(&mut file, 0xdeadbeef)?;
     }
 
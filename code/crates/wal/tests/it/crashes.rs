@@ -134,7 +134,7 @@ fn system_crash_during_write() -> io::Result<()> {
 
         // Open WAL with failing file
         let storage = FailingFile::open_with(&path, crash_point)?;
-        let mut wal = FailingLog::from_raw_parts(storage, path.clone(), Version::V1, 0, 0);
+        let mut wal = FailingLog::from_raw_parts(storage, path.clone(), Version::V2, 0, 0);
 
         // Attempt to write entries
         let result = (|| -> io::Result<()> {
@@ -227,7 +227,7 @@ fn power_failure_simulation() -> io::Result<()> {
     {
         // Use `from_raw_parts` to avoid calling `sync` during initialization
         let storage = FailingSync::open_with(&path, true)?;
-        let mut wal = FailingSyncLog::from_raw_parts(storage, path.to_owned(), Version::V1, 0, 0);
+        let mut wal = FailingSyncLog::from_raw_parts(storage, path.to_owned(), Version::V2, 0, 0);
 
         wal.append(b"entry1")?;
 
@@ -319,7 +319,7 @@ fn concurrent_crash_recovery() -> io::Result<()> {
 
             // Simulate crash by truncating file
             if let Ok(file) = OpenOptions::new().write(true).open(&path2) {
-                let _ = file.set_len(12); // Truncate to header size
+                let _ = file.set_len(20); // Truncate to header size
             }
         }
         running.store(false, Ordering::SeqCst);
@@ -0,0 +1,135 @@
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use informalsystems_malachitebft_wal::ext::*;
+use informalsystems_malachitebft_wal::{Header, Log, Version, MAGIC};
+
+/// Write a log in version 1 of the format, as written by older nodes
+fn write_v1_log(path: &Path, sequence: u64, entries: &[&[u8]]) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .read(true)
+        .write(true)
+        .open(path)?;
+
+    Header::with_version(Version::V1, sequence).write(&mut file)?;
+
+    let mut wal = Log::from_raw_parts(file, path.to_owned(), Version::V1, sequence, 0);
+    for entry in entries {
+        wal.append(entry)?;
+    }
+
+    wal.flush()
+}
+
+fn read_all(wal: &mut Log) -> io::Result<Vec<Vec<u8>>> {
+    wal.iter()?.collect()
+}
+
+fn starts_with_magic(path: &Path) -> io::Result<bool> {
+    Ok(fs::read(path)?.starts_with(&MAGIC))
+}
+
+#[test]
+fn reads_v1_log_and_upgrades_on_restart() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    write_v1_log(&path, 5, &[b"entry1", b"entry2"])?;
+
+    // Entries of the V1 log are read back as-is, and new ones keep its format
+    let mut wal = Log::open(&path)?;
+    assert_eq!(wal.version(), Version::V1);
+    assert_eq!(wal.sequence(), 5);
+    assert_eq!(
+        read_all(&mut wal)?,
+        vec![b"entry1".to_vec(), b"entry2".to_vec()]
+    );
+
+    wal.append(b"entry3")?;
+    wal.flush()?;
+    drop(wal);
+
+    let mut wal = Log::open(&path)?;
+    assert_eq!(wal.version(), Version::V1);
+    assert_eq!(wal.len(), 3);
+    assert!(!starts_with_magic(&path)?);
+
+    // Restarting the log upgrades it to the current version
+    wal.restart(6)?;
+    assert_eq!(wal.version(), Version::CURRENT);
+    assert!(starts_with_magic(&path)?);
+
+    wal.append(b"entry4")?;
+    wal.flush()?;
+    drop(wal);
+
+    let mut wal = Log::open(&path)?;
+    assert_eq!(wal.version(), Version::CURRENT);
+    assert_eq!(wal.sequence(), 6);
+    assert_eq!(read_all(&mut wal)?, vec![b"entry4".to_vec()]);
+
+    Ok(())
+}
+
+#[test]
+fn upgrades_empty_v1_log_on_open() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    write_v1_log(&path, 7, &[])?;
+
+    let wal = Log::open(&path)?;
+    assert_eq!(wal.version(), Version::CURRENT);
+    assert_eq!(wal.sequence(), 7);
+    assert!(wal.is_empty());
+    assert!(starts_with_magic(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn read_only_open_does_not_upgrade_v1_log() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    write_v1_log(&path, 7, &[])?;
+    let before = fs::read(&path)?;
+
+    let wal = Log::open_read_only(&path)?;
+    assert_eq!(wal.version(), Version::V1);
+    assert_eq!(wal.sequence(), 7);
+    assert!(wal.is_empty());
+    drop(wal);
+
+    assert_eq!(fs::read(&path)?, before);
+
+    Ok(())
+}
+
+#[test]
+fn rejects_unsupported_header_flags() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&path)?;
+        io::Write::write_all(&mut file, &MAGIC)?;
+        write_u32(&mut file, Version::V2 as u32)?;
+        write_u32(&mut file, 0x100)?; // flags
+        write_u64(&mut file, 0)?; // sequence
+    }
+
+    match Log::open(&path) {
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+        Ok(_) => panic!("Expected error when opening WAL with unsupported flags"),
+    }
+
+    Ok(())
+}
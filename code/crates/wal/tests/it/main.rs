@@ -1,6 +1,8 @@
 pub mod basic;
 pub mod corruption;
 pub mod crashes;
+pub mod format;
+pub mod segmented;
 pub mod stress;
 
 #[cfg(all(feature = "compression", not(feature = "force-compression")))]
@@ -0,0 +1,217 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use informalsystems_malachitebft_wal::{Log, SegmentOptions, SegmentedLog};
+
+fn options(max_segment_size: u64, retain_logs: usize) -> SegmentOptions {
+    SegmentOptions {
+        max_segment_size,
+        retain_logs,
+        max_retained_size: None,
+    }
+}
+
+fn entries(count: usize) -> Vec<Vec<u8>> {
+    (0..count)
+        .map(|i| format!("entry #{i}: {}", "x".repeat(i * 10)).into_bytes())
+        .collect()
+}
+
+fn read_all(wal: &mut SegmentedLog) -> io::Result<Vec<Vec<u8>>> {
+    wal.iter()?.collect()
+}
+
+fn exists(dir: &Path, name: &str) -> bool {
+    dir.join(name).exists()
+}
+
+#[test]
+fn rotates_segments() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    let mut wal = SegmentedLog::open_with(&path, options(64, 0))?;
+    let entries = entries(10);
+
+    for entry in &entries {
+        wal.append(entry)?;
+    }
+
+    wal.flush()?;
+
+    assert_eq!(wal.len(), entries.len());
+    assert!(exists(dir.path(), "consensus.wal.1"));
+    assert!(exists(dir.path(), "consensus.wal.2"));
+    assert_eq!(read_all(&mut wal)?, entries);
+
+    let size = wal.size_bytes()?;
+    drop(wal);
+
+    // Reopening the log reads the entries back from all segments
+    let mut wal = SegmentedLog::open_with(&path, options(64, 0))?;
+    assert_eq!(wal.len(), entries.len());
+    assert_eq!(wal.size_bytes()?, size);
+    assert_eq!(read_all(&mut wal)?, entries);
+
+    // Restarting the log without retention removes all segments but the first
+    wal.restart(1)?;
+    assert!(wal.is_empty());
+    assert!(!exists(dir.path(), "consensus.wal.1"));
+    assert!(!exists(dir.path(), "consensus.wal.h0"));
+
+    Ok(())
+}
+
+#[test]
+fn reads_plain_log() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+    let entries = entries(5);
+
+    let mut wal = Log::open(&path)?;
+    wal.restart(7)?;
+    for entry in &entries {
+        wal.append(entry)?;
+    }
+    wal.flush()?;
+    drop(wal);
+
+    // A log made of a single plain file is a valid segmented log
+    let mut wal = SegmentedLog::open_with(&path, options(32, 0))?;
+    assert_eq!(wal.sequence(), 7);
+    assert_eq!(read_all(&mut wal)?, entries);
+
+    // New entries roll over to new segments
+    wal.append(b"one more")?;
+    assert!(exists(dir.path(), "consensus.wal.1"));
+    assert_eq!(wal.len(), entries.len() + 1);
+
+    Ok(())
+}
+
+#[test]
+fn retains_previous_logs() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    let mut wal = SegmentedLog::open_with(&path, options(64, 2))?;
+
+    for height in 1..=4 {
+        wal.restart(height)?;
+
+        for entry in entries(6) {
+            wal.append(entry)?;
+        }
+
+        wal.flush()?;
+    }
+
+    wal.restart(5)?;
+    assert!(wal.is_empty());
+    assert_eq!(wal.sequence(), 5);
+
+    // Only the last two logs are retained
+    assert!(exists(dir.path(), "consensus.wal.h3"));
+    assert!(exists(dir.path(), "consensus.wal.h4"));
+    assert!(!exists(dir.path(), "consensus.wal.h1"));
+    assert!(!exists(dir.path(), "consensus.wal.h2"));
+    assert!(exists(dir.path(), "consensus.wal.h4.1"));
+
+    // Retained logs can be opened and read as regular segmented logs
+    drop(wal);
+    let mut retained =
+        SegmentedLog::open_with(dir.path().join("consensus.wal.h4"), options(64, 0))?;
+    assert_eq!(retained.sequence(), 4);
+    assert_eq!(read_all(&mut retained)?, entries(6));
+
+    Ok(())
+}
+
+#[test]
+fn prunes_retained_logs_by_size() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    // Entry data which does not compress, so that sizes are the same with compression enabled
+    let entry = (0..100).map(|i| (i * 37 % 251) as u8).collect::<Vec<_>>();
+
+    let options = SegmentOptions {
+        max_segment_size: 1024,
+        retain_logs: 10,
+        max_retained_size: Some(300),
+    };
+
+    let mut wal = SegmentedLog::open_with(&path, options)?;
+
+    for height in 1..=5 {
+        wal.restart(height)?;
+        wal.append(&entry)?;
+        wal.append(&entry)?;
+    }
+
+    wal.restart(6)?;
+
+    // Each retained log takes up more than 200 bytes, so only the last one fits
+    assert!(exists(dir.path(), "consensus.wal.h5"));
+    assert!(!exists(dir.path(), "consensus.wal.h4"));
+
+    Ok(())
+}
+
+#[test]
+fn exclusive_access() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    let _wal = SegmentedLog::open_with(&path, options(64, 0))?;
+    assert!(SegmentedLog::open_with(&path, options(64, 0)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn read_only_access() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("consensus.wal");
+
+    let mut wal = SegmentedLog::open_with(&path, options(64, 0))?;
+    let entries = entries(5);
+
+    for entry in &entries {
+        wal.append(entry)?;
+    }
+
+    wal.flush()?;
+
+    // Cannot read the log while it is open for writing
+    assert!(SegmentedLog::open_read_only_with(&path, options(64, 0)).is_err());
+    drop(wal);
+
+    // Simulate a partially written entry at the end of the last segment
+    let last = (1..)
+        .map(|index| dir.path().join(format!("consensus.wal.{index}")))
+        .take_while(|path| path.exists())
+        .last()
+        .unwrap();
+    let mut bytes = fs::read(&last)?;
+    bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 100]);
+    fs::write(&last, &bytes)?;
+
+    // The partial entry is ignored, but left in place
+    let mut wal = SegmentedLog::open_read_only_with(&path, options(64, 0))?;
+    assert_eq!(wal.len(), entries.len());
+    assert_eq!(read_all(&mut wal)?, entries);
+    assert!(wal.append(b"entry").is_err());
+    drop(wal);
+
+    assert_eq!(fs::read(&last)?, bytes);
+
+    // A missing log is not created
+    let missing = dir.path().join("missing.wal");
+    assert!(SegmentedLog::open_read_only_with(&missing, options(64, 0)).is_err());
+    assert!(!exists(dir.path(), "missing.wal"));
+    assert!(!exists(dir.path(), "missing.wal.lock"));
+
+    Ok(())
+}
@@ -2,12 +2,14 @@ use std::time::Duration;
 
 use eyre::eyre;
 use tokio::time::sleep;
-use tracing::{error, info};
+use tracing::{debug, error, info, warn};
 
 use malachitebft_app_channel::app::streaming::StreamContent;
 use malachitebft_app_channel::app::types::codec::Codec;
 use malachitebft_app_channel::app::types::core::{Height as _, Round, Validity};
-use malachitebft_app_channel::app::types::sync::RawDecidedValue;
+use malachitebft_app_channel::app::types::sync::{
+    ApplySnapshotChunkResult, OfferSnapshotResult, RawDecidedValue,
+};
 use malachitebft_app_channel::app::types::ProposedValue;
 use malachitebft_app_channel::{AppMsg, Channels, ConsensusMsg, NetworkMsg};
 use malachitebft_test::codec::proto::ProtobufCodec;
@@ -66,6 +68,7 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                 height,
                 round,
                 timeout: _,
+                max_block_size: _,
                 reply,
             } => {
                 // NOTE: We can ignore the timeout as we are building the value right away.
@@ -118,6 +121,17 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                 }
             }
 
+            // A proposal from another validator has been received, before its value.
+            // This is where the application can reject it early on its own grounds,
+            // eg. a stale timestamp, in which case consensus will prevote nil for it.
+            // The example application has nothing to check in the proposal itself,
+            // and only judges the validity of the value once it is fully received.
+            AppMsg::ValidateProposal { proposal: _, reply } => {
+                if reply.send(Validity::Valid).is_err() {
+                    error!("Failed to send ValidateProposal reply");
+                }
+            }
+
             AppMsg::VerifyVoteExtension {
                 height: _,
                 round: _,
@@ -285,6 +299,49 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                 }
             }
 
+            // A validator signed two conflicting messages. An application would typically
+            // include this evidence in a block to slash the validator, here we just log it.
+            AppMsg::MisbehaviorEvidence { evidence } => {
+                warn!(
+                    height = %evidence.height(),
+                    round = %evidence.round(),
+                    validator = %evidence.validator_address(),
+                    "Received evidence of misbehavior: {evidence:?}"
+                );
+            }
+
+            // Nodes joining the network without any state may bootstrap from a snapshot
+            // of the application state offered by their peers, if enabled in the sync config.
+            // This example does not take snapshots of its state, so it has none to serve...
+            AppMsg::ListSnapshots { reply } => {
+                if reply.send(Vec::new()).is_err() {
+                    error!("Failed to send ListSnapshots reply");
+                }
+            }
+
+            AppMsg::LoadSnapshotChunk { reply, .. } => {
+                if reply.send(None).is_err() {
+                    error!("Failed to send LoadSnapshotChunk reply");
+                }
+            }
+
+            // ...and does not restore its state from snapshots either,
+            // instead syncing every decided value from its peers.
+            AppMsg::OfferSnapshot { reply, .. } => {
+                if reply.send(OfferSnapshotResult::Reject).is_err() {
+                    error!("Failed to send OfferSnapshot reply");
+                }
+            }
+
+            AppMsg::ApplySnapshotChunk { reply, .. } => {
+                if reply
+                    .send(ApplySnapshotChunkResult::RejectSnapshot)
+                    .is_err()
+                {
+                    error!("Failed to send ApplySnapshotChunk reply");
+                }
+            }
+
             AppMsg::PeerJoined { peer_id } => {
                 info!(%peer_id, "Peer joined our local view of network");
 
@@ -298,6 +355,19 @@ pub async fn run(state: &mut State, channels: &mut Channels<TestContext>) -> eyr
                 // Remove the peer from tracking
                 state.peers.remove(&peer_id);
             }
+
+            AppMsg::SyncProgress { progress } => {
+                debug!(
+                    tip_height = %progress.tip_height,
+                    peers_tip_height = ?progress.peers_tip_height,
+                    eta = ?progress.eta,
+                    caught_up = %progress.caught_up,
+                    "Sync progress"
+                );
+
+                // You might want to only serve requests from your clients once caught up,
+                // as the state of a lagging node is likely to be stale.
+            }
         }
     }
 
@@ -4,11 +4,13 @@ use eyre::{eyre, Result};
 use tracing::{info, trace};
 
 use malachitebft_app_channel::app::Node;
-use malachitebft_test::Height;
+use malachitebft_test::codec::proto::ProtobufCodec;
+use malachitebft_test::{Height, TestContext};
 use malachitebft_test_cli::args::{Args, Commands};
 use malachitebft_test_cli::cmd::init::InitCmd;
 use malachitebft_test_cli::cmd::start::StartCmd;
 use malachitebft_test_cli::cmd::testnet::TestnetCmd;
+use malachitebft_test_cli::cmd::wal::WalCmd;
 use malachitebft_test_cli::{config, logging, runtime};
 
 mod app;
@@ -54,6 +56,7 @@ fn main() -> Result<()> {
         Commands::Start(cmd) => start(&args, cmd, logging),
         Commands::Init(cmd) => init(&args, cmd, logging),
         Commands::Testnet(cmd) => testnet(&args, cmd, logging),
+        Commands::Wal(cmd) => wal(&args, cmd),
         _ => unimplemented!(),
     }
 }
@@ -125,3 +128,8 @@ fn testnet(args: &Args, cmd: &TestnetCmd, logging: config::LoggingConfig) -> Res
     cmd.run(&app, &args.get_home_dir()?, logging)
         .map_err(|error| eyre!("Failed to run testnet command {:?}", error))
 }
+
+fn wal(args: &Args, cmd: &WalCmd) -> Result<()> {
+    cmd.run::<TestContext, _>(&args.get_home_dir()?, &ProtobufCodec)
+        .map_err(|error| eyre!("Failed to run wal command: {error:?}"))
+}